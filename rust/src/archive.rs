@@ -0,0 +1,382 @@
+use std::{fmt, fs, io};
+use std::io::Write;
+use std::os::unix::fs::FileExt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::snapshot::SystemSnapshot;
+use crate::utils::{*};
+
+// every record starts with the magic, the timestamp in milliseconds, the compression and the payload length
+const RECORD_MAGIC: &[u8; 4] = b"SSNP";
+const RECORD_HEADER_LENGTH: u64 = 17;
+
+// index entries are the record timestamp and offset, both little endian `u64`
+const INDEX_ENTRY_LENGTH: usize = 16;
+
+/// Compression of the snapshots stored in a `SnapshotArchive`
+///
+/// Gzip needs the `gzip` feature and zstd the `zstd` one, both to write and to read the records
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd
+}
+
+impl Compression {
+    pub fn fromString(compression: &str) -> Compression {
+        match compression {
+            "gzip" => Compression::Gzip,
+            "zstd" => Compression::Zstd,
+            _ => Compression::None
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            Compression::None => String::from("none"),
+            Compression::Gzip => String::from("gzip"),
+            Compression::Zstd => String::from("zstd")
+        }
+    }
+
+    fn code(&self) -> u8 {
+        match self {
+            Compression::None => 0,
+            Compression::Gzip => 1,
+            Compression::Zstd => 2
+        }
+    }
+
+    fn fromCode(code: u8) -> Option<Compression> {
+        match code {
+            0 => Some(Compression::None),
+            1 => Some(Compression::Gzip),
+            2 => Some(Compression::Zstd),
+            _ => None
+        }
+    }
+
+    fn compress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data.to_vec()),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::<u8>::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            },
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::encode_all(data, 0),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unsupported())
+        }
+    }
+
+    fn decompress(&self, data: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data.to_vec()),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                let mut decompressed = Vec::<u8>::new();
+                io::Read::read_to_end(&mut flate2::read::GzDecoder::new(data), &mut decompressed)?;
+                Ok(decompressed)
+            },
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::decode_all(data),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unsupported())
+        }
+    }
+
+    fn unsupported(&self) -> io::Error {
+        return io::Error::new(
+            io::ErrorKind::Unsupported, format!("{} compression needs the `{}` feature", self, self.toString())
+        );
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Position of a snapshot in a `SnapshotArchive`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArchiveEntry {
+    /// When the snapshot was appended
+    pub timestamp: SystemTime,
+    /// Offset of the record in the archive file
+    pub offset: u64
+}
+
+fn indexPath(path: &Path) -> PathBuf {
+    let mut indexPath = path.as_os_str().to_owned();
+    indexPath.push(".index");
+
+    return PathBuf::from(indexPath);
+}
+
+fn toMillis(timestamp: SystemTime) -> u64 {
+    return timestamp.duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_millis() as u64).unwrap_or(0);
+}
+
+// reads the header of the record at `offset`, `None` if there is no complete record there
+fn readHeader(data: &fs::File, offset: u64, dataLength: u64) -> Option<(SystemTime, Compression, u32)> {
+    let mut header = [0_u8; RECORD_HEADER_LENGTH as usize];
+    data.read_exact_at(&mut header, offset).ok()?;
+
+    if &header[0..4] != RECORD_MAGIC {
+        return None;
+    }
+
+    let timestamp = UNIX_EPOCH + Duration::from_millis(u64::from_le_bytes(header[4..12].try_into().ok()?));
+    let compression = Compression::fromCode(header[12])?;
+    let length = u32::from_le_bytes(header[13..17].try_into().ok()?);
+
+    if offset + RECORD_HEADER_LENGTH + length as u64 > dataLength {
+        return None;
+    }
+
+    return Some((timestamp, compression, length));
+}
+
+// reads the index, then recovers the records appended after the last indexed one (e.g. after a crash
+// between the two writes), returns the entries and the end of the last complete record
+fn loadEntries(data: &fs::File, indexPath: &Path) -> io::Result<(Vec<ArchiveEntry>, u64)> {
+    let dataLength = data.metadata()?.len();
+    let index = fs::read(indexPath).unwrap_or_default();
+
+    let mut entries = Vec::<ArchiveEntry>::new();
+    let mut end = 0_u64;
+
+    for chunk in index.chunks_exact(INDEX_ENTRY_LENGTH) {
+        let offset = u64::from_le_bytes(chunk[8..16].try_into().unwrap_or_default());
+
+        // entries must follow each other, anything else means the index is damaged
+        if offset != end {
+            break;
+        }
+
+        let Some((timestamp, _, length)) = readHeader(data, offset, dataLength) else {
+            break;
+        };
+
+        entries.push(ArchiveEntry {
+            timestamp: timestamp,
+            offset: offset
+        });
+        end = offset + RECORD_HEADER_LENGTH + length as u64;
+    }
+
+    while let Some((timestamp, _, length)) = readHeader(data, end, dataLength) {
+        entries.push(ArchiveEntry {
+            timestamp: timestamp,
+            offset: end
+        });
+        end += RECORD_HEADER_LENGTH + length as u64;
+    }
+
+    return Ok((entries, end));
+}
+
+fn writeIndex(indexPath: &Path, entries: &[ArchiveEntry]) -> io::Result<()> {
+    let mut index = Vec::<u8>::with_capacity(entries.len() * INDEX_ENTRY_LENGTH);
+
+    for entry in entries {
+        index.extend_from_slice(&toMillis(entry.timestamp).to_le_bytes());
+        index.extend_from_slice(&entry.offset.to_le_bytes());
+    }
+
+    return fs::write(indexPath, index);
+}
+
+/// Appends snapshots to a file, each one compressed on its own, with an index of their timestamps in `<path>.index`
+///
+/// Records are stored as the JSON of `exportJson()`, so archives stay readable across versions of the crate; values the
+/// export leaves out (e.g. IPv4 netmasks) are not stored.
+/// Records left incomplete by a crash are dropped when the archive is opened again
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use sysutil::SysInfo;
+/// use sysutil::archive::{Compression, SnapshotArchive};
+/// use sysutil::monitor::Monitor;
+///
+/// let mut archive = SnapshotArchive::open("/var/lib/telemetry/snapshots", Compression::Zstd).unwrap();
+/// let monitor = Monitor::withSections(Duration::from_secs(60), SysInfo::builder().cpu().ram().sensors());
+///
+/// monitor.onUpdate(move |snapshot| {
+///     archive.append(snapshot).unwrap();
+/// });
+/// ```
+#[derive(Debug)]
+pub struct SnapshotArchive {
+    path: PathBuf,
+    data: fs::File,
+    index: fs::File,
+    compression: Compression,
+    end: u64
+}
+
+impl SnapshotArchive {
+    /// Opens the archive at `path`, creating it if missing, new snapshots are stored with `compression`
+    pub fn open<P: AsRef<Path>>(path: P, compression: Compression) -> io::Result<SnapshotArchive> {
+        let path = path.as_ref().to_path_buf();
+        let indexPath = indexPath(&path);
+
+        let data = fs::OpenOptions::new().read(true).append(true).create(true).open(&path)?;
+        let (entries, end) = loadEntries(&data, &indexPath)?;
+
+        // drops a record left incomplete, then rewrites the index if it missed some records
+        if data.metadata()?.len() > end {
+            traceEvent!(debug, path = %path.display(), "dropping incomplete archive record");
+            data.set_len(end)?;
+        }
+
+        if fs::metadata(&indexPath).map(|metadata| metadata.len()).unwrap_or(0) != (entries.len() * INDEX_ENTRY_LENGTH) as u64 {
+            writeIndex(&indexPath, &entries)?;
+        }
+
+        let index = fs::OpenOptions::new().append(true).create(true).open(&indexPath)?;
+
+        return Ok(SnapshotArchive {
+            path: path,
+            data: data,
+            index: index,
+            compression: compression,
+            end: end
+        });
+    }
+
+    /// Compresses and appends `snapshot`, timestamped with the current time
+    pub fn append(&mut self, snapshot: &SystemSnapshot) -> io::Result<ArchiveEntry> {
+        // timestamps are stored in milliseconds, the returned entry matches the stored one
+        let timestamp = UNIX_EPOCH + Duration::from_millis(toMillis(SystemTime::now()));
+        let payload = self.compression.compress(snapshot.toJson().toString().as_bytes())?;
+
+        let length = u32::try_from(payload.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "snapshot too large"))?;
+
+        let mut record = Vec::<u8>::with_capacity(RECORD_HEADER_LENGTH as usize + payload.len());
+        record.extend_from_slice(RECORD_MAGIC);
+        record.extend_from_slice(&toMillis(timestamp).to_le_bytes());
+        record.push(self.compression.code());
+        record.extend_from_slice(&length.to_le_bytes());
+        record.extend_from_slice(&payload);
+
+        self.data.write_all(&record)?;
+
+        let mut indexEntry = [0_u8; INDEX_ENTRY_LENGTH];
+        indexEntry[0..8].copy_from_slice(&toMillis(timestamp).to_le_bytes());
+        indexEntry[8..16].copy_from_slice(&self.end.to_le_bytes());
+        self.index.write_all(&indexEntry)?;
+
+        let entry = ArchiveEntry {
+            timestamp: timestamp,
+            offset: self.end
+        };
+
+        self.end += record.len() as u64;
+        return Ok(entry);
+    }
+
+    /// Opens a reader over the snapshots appended so far
+    pub fn reader(&self) -> io::Result<ArchiveReader> {
+        return ArchiveReader::open(&self.path);
+    }
+}
+
+/// Reads the snapshots of an archive written by `SnapshotArchive`
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::archive::ArchiveReader;
+///
+/// let archive = ArchiveReader::open("/var/lib/telemetry/snapshots").unwrap();
+///
+/// for (timestamp, snapshot) in archive.snapshots() {
+///     println!("{:?}: {:?}", timestamp, snapshot.ok().and_then(|snapshot| snapshot.ram));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ArchiveReader {
+    data: fs::File,
+    entries: Vec<ArchiveEntry>
+}
+
+impl ArchiveReader {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<ArchiveReader> {
+        let data = fs::File::open(path.as_ref())?;
+        let (entries, _) = loadEntries(&data, &indexPath(path.as_ref()))?;
+
+        return Ok(ArchiveReader {
+            data: data,
+            entries: entries
+        });
+    }
+
+    /// Returns the position of every snapshot, oldest first
+    pub fn entries(&self) -> Vec<ArchiveEntry> {
+        return self.entries.clone();
+    }
+
+    /// Returns the position of the snapshots appended between `from` and `to`, both included
+    pub fn between(&self, from: SystemTime, to: SystemTime) -> Vec<ArchiveEntry> {
+        return self.entries.iter().filter(|entry| entry.timestamp >= from && entry.timestamp <= to).copied().collect();
+    }
+
+    /// Reads and decompresses the snapshot at `entry`
+    pub fn read(&self, entry: &ArchiveEntry) -> io::Result<SystemSnapshot> {
+        let dataLength = self.data.metadata()?.len();
+        let Some((_, compression, length)) = readHeader(&self.data, entry.offset, dataLength) else {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "no archive record at this offset"));
+        };
+
+        let mut payload = vec![0_u8; length as usize];
+        self.data.read_exact_at(&mut payload, entry.offset + RECORD_HEADER_LENGTH)?;
+
+        let json = String::from_utf8(compression.decompress(&payload)?).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        return SystemSnapshot::fromJson(&json).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed snapshot"));
+    }
+
+    /// Iterates over every snapshot, oldest first, reading each one when reached
+    pub fn snapshots(&self) -> impl Iterator<Item = (SystemTime, io::Result<SystemSnapshot>)> + '_ {
+        return self.entries.iter().map(|entry| (entry.timestamp, self.read(entry)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SysInfo, SysPaths};
+
+    #[test]
+    fn recoversIncompleteRecords() {
+        let path = std::env::temp_dir().join(format!("sysutil-archive-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(indexPath(&path));
+
+        let root = SysPaths::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/root/desktop"));
+        let snapshot = root.scope(|| SysInfo::builder().ram().collect());
+
+        let mut archive = SnapshotArchive::open(&path, Compression::None).unwrap();
+        archive.append(&snapshot).unwrap();
+        archive.append(&snapshot).unwrap();
+        drop(archive);
+
+        // a crash while appending leaves a partial record and no index entry
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"SSNP\x01\x02").unwrap();
+
+        let mut archive = SnapshotArchive::open(&path, Compression::None).unwrap();
+        let last = archive.append(&snapshot).unwrap();
+
+        let reader = archive.reader().unwrap();
+        assert_eq!(reader.entries().len(), 3);
+        assert_eq!(reader.entries()[2], last);
+        assert_eq!(reader.read(&last).unwrap().ram.map(|ram| ram.usage), Some(25_f32));
+
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_file(indexPath(&path));
+    }
+}
@@ -1,8 +1,164 @@
 use std::collections::HashMap;
+use std::{fmt, fs, mem, path, thread};
+use std::io::Read;
+use std::num::ParseIntError;
+use std::sync::mpsc;
+use std::time::Duration;
 use crate::utils::{*};
 
+pub const KEY_ESC: u16 = 1;
+pub const KEY_Q: u16 = 16;
+pub const KEY_ENTER: u16 = 28;
+pub const KEY_A: u16 = 30;
+pub const KEY_S: u16 = 31;
+pub const KEY_Z: u16 = 44;
+pub const KEY_SPACE: u16 = 57;
+pub const KEY_MUTE: u16 = 113;
+pub const KEY_VOLUMEDOWN: u16 = 114;
+pub const KEY_VOLUMEUP: u16 = 115;
+pub const KEY_POWER: u16 = 116;
+pub const KEY_SLEEP: u16 = 142;
+pub const KEY_WAKEUP: u16 = 143;
+pub const KEY_BRIGHTNESSDOWN: u16 = 224;
+pub const KEY_BRIGHTNESSUP: u16 = 225;
+pub const BTN_LEFT: u16 = 0x110;
+pub const BTN_RIGHT: u16 = 0x111;
+pub const BTN_MIDDLE: u16 = 0x112;
+pub const BTN_JOYSTICK: u16 = 0x120;
+pub const BTN_GAMEPAD: u16 = 0x130;
+pub const BTN_TOOL_PEN: u16 = 0x140;
+pub const BTN_TOOL_FINGER: u16 = 0x145;
+pub const BTN_TOUCH: u16 = 0x14a;
+
+/// Input event types (`EV_*`) a device can generate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventType {
+    Synchronization,
+    Key,
+    Relative,
+    Absolute,
+    Miscellaneous,
+    Switch,
+    Led,
+    Sound,
+    Repeat,
+    ForceFeedback,
+    Power,
+    ForceFeedbackStatus
+}
+
+impl EventType {
+    const ALL: [EventType; 12] = [
+        EventType::Synchronization, EventType::Key, EventType::Relative, EventType::Absolute,
+        EventType::Miscellaneous, EventType::Switch, EventType::Led, EventType::Sound,
+        EventType::Repeat, EventType::ForceFeedback, EventType::Power, EventType::ForceFeedbackStatus
+    ];
+
+    /// Returns the kernel code of the event type, i.e. its bit in the `EV` bitmap
+    pub fn code(&self) -> u16 {
+        match self {
+            EventType::Synchronization => 0x00,
+            EventType::Key => 0x01,
+            EventType::Relative => 0x02,
+            EventType::Absolute => 0x03,
+            EventType::Miscellaneous => 0x04,
+            EventType::Switch => 0x05,
+            EventType::Led => 0x11,
+            EventType::Sound => 0x12,
+            EventType::Repeat => 0x14,
+            EventType::ForceFeedback => 0x15,
+            EventType::Power => 0x16,
+            EventType::ForceFeedbackStatus => 0x17
+        }
+    }
+
+    pub fn fromCode(code: u16) -> Option<EventType> {
+        return EventType::ALL.into_iter().find(|eventType| eventType.code() == code);
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            EventType::Synchronization => String::from("EV_SYN"),
+            EventType::Key => String::from("EV_KEY"),
+            EventType::Relative => String::from("EV_REL"),
+            EventType::Absolute => String::from("EV_ABS"),
+            EventType::Miscellaneous => String::from("EV_MSC"),
+            EventType::Switch => String::from("EV_SW"),
+            EventType::Led => String::from("EV_LED"),
+            EventType::Sound => String::from("EV_SND"),
+            EventType::Repeat => String::from("EV_REP"),
+            EventType::ForceFeedback => String::from("EV_FF"),
+            EventType::Power => String::from("EV_PWR"),
+            EventType::ForceFeedbackStatus => String::from("EV_FF_STATUS")
+        }
+    }
+}
+
+impl fmt::Display for EventType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Input device properties (`INPUT_PROP_*`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputProperty {
+    Pointer,
+    Direct,
+    ButtonPad,
+    SemiMultitouch,
+    TopButtonPad,
+    PointingStick,
+    Accelerometer
+}
+
+impl InputProperty {
+    const ALL: [InputProperty; 7] = [
+        InputProperty::Pointer, InputProperty::Direct, InputProperty::ButtonPad, InputProperty::SemiMultitouch,
+        InputProperty::TopButtonPad, InputProperty::PointingStick, InputProperty::Accelerometer
+    ];
+
+    /// Returns the kernel code of the property, i.e. its bit in the `PROP` bitmap
+    pub fn code(&self) -> u16 {
+        match self {
+            InputProperty::Pointer => 0x00,
+            InputProperty::Direct => 0x01,
+            InputProperty::ButtonPad => 0x02,
+            InputProperty::SemiMultitouch => 0x03,
+            InputProperty::TopButtonPad => 0x04,
+            InputProperty::PointingStick => 0x05,
+            InputProperty::Accelerometer => 0x06
+        }
+    }
+
+    pub fn fromCode(code: u16) -> Option<InputProperty> {
+        return InputProperty::ALL.into_iter().find(|property| property.code() == code);
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            InputProperty::Pointer => String::from("INPUT_PROP_POINTER"),
+            InputProperty::Direct => String::from("INPUT_PROP_DIRECT"),
+            InputProperty::ButtonPad => String::from("INPUT_PROP_BUTTONPAD"),
+            InputProperty::SemiMultitouch => String::from("INPUT_PROP_SEMI_MT"),
+            InputProperty::TopButtonPad => String::from("INPUT_PROP_TOPBUTTONPAD"),
+            InputProperty::PointingStick => String::from("INPUT_PROP_POINTING_STICK"),
+            InputProperty::Accelerometer => String::from("INPUT_PROP_ACCELEROMETER")
+        }
+    }
+}
+
+impl fmt::Display for InputProperty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
 /// Contains the information regarding a bus input
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BusInput {
     pub bus: u16,
     pub vendor: u16,
@@ -39,52 +195,109 @@ impl BusInput {
             keys: Vec::<String>::new()
         }
     }
-}
 
-fn hexToUsize(hexadecimal: String) -> usize {
-    let mut hexaTable: HashMap<&str, usize> = HashMap::new();
-    hexaTable.insert("0", 0);
-    hexaTable.insert("1", 1);
-    hexaTable.insert("2", 2);
-    hexaTable.insert("3", 3);
-    hexaTable.insert("4", 4);
-    hexaTable.insert("5", 5);
-    hexaTable.insert("6", 6);
-    hexaTable.insert("7", 7);
-    hexaTable.insert("8", 8);
-    hexaTable.insert("9", 9);
-    hexaTable.insert("a", 10);
-    hexaTable.insert("b", 11);
-    hexaTable.insert("c", 12);
-    hexaTable.insert("d", 13);
-    hexaTable.insert("e", 14);
-    hexaTable.insert("f", 15);
-    hexaTable.insert("A", 10);
-    hexaTable.insert("B", 11);
-    hexaTable.insert("C", 12);
-    hexaTable.insert("D", 13);
-    hexaTable.insert("E", 14);
-    hexaTable.insert("F", 15);
+    /// Returns the event types the device can generate
+    pub fn eventTypes(&self) -> Vec<EventType> {
+        let mut types = Vec::<EventType>::new();
 
-    let hex = hexadecimal.clone();
+        for eventType in EventType::ALL {
+            if self.supportsEvent(eventType) {
+                types.push(eventType);
+            }
+        }
+
+        return types;
+    }
+
+    pub fn supportsEvent(&self, eventType: EventType) -> bool {
+        return bitIsSet(self.events, eventType.code());
+    }
+
+    /// Returns the properties of the device
+    pub fn inputProperties(&self) -> Vec<InputProperty> {
+        let mut properties = Vec::<InputProperty>::new();
+
+        for property in InputProperty::ALL {
+            if self.hasProperty(property) {
+                properties.push(property);
+            }
+        }
+
+        return properties;
+    }
+
+    pub fn hasProperty(&self, property: InputProperty) -> bool {
+        return bitIsSet(self.properties, property.code());
+    }
+
+    /// Checks whether the device can report the key or button with the specified code (e.g. `KEY_POWER`, `BTN_LEFT`)
+    pub fn supportsKey(&self, key: u16) -> bool {
+        // the bitmap is a list of `long` words, the most significant one first
+        let wordBits = usize::BITS as usize;
+        let wordIndex = key as usize / wordBits;
+
+        if wordIndex >= self.keys.len() {
+            return false;
+        }
+
+        let word = &self.keys[self.keys.len() - 1 - wordIndex];
+        return match usize::from_str_radix(word, 16) {
+            Ok(value) => bitIsSet(value, (key as usize % wordBits) as u16),
+            Err(_) => false
+        };
+    }
+
+    /// Checks whether the device is a keyboard, i.e. it reports every key from `KEY_ESC` to `KEY_S`
+    pub fn isKeyboard(&self) -> bool {
+        return self.supportsEvent(EventType::Key) && (KEY_ESC..=KEY_S).all(|key| self.supportsKey(key));
+    }
+
+    pub fn isMouse(&self) -> bool {
+        return self.supportsEvent(EventType::Relative) && self.supportsKey(BTN_LEFT);
+    }
+
+    pub fn isTouchpad(&self) -> bool {
+        return self.supportsEvent(EventType::Absolute) && self.supportsKey(BTN_TOOL_FINGER) &&
+            !self.supportsKey(BTN_TOOL_PEN) && !self.hasProperty(InputProperty::Direct);
+    }
 
-    let mut power = hex.len();
-    let mut res: usize = 0;
+    pub fn isTouchscreen(&self) -> bool {
+        return self.supportsEvent(EventType::Absolute) && self.supportsKey(BTN_TOUCH) &&
+            self.hasProperty(InputProperty::Direct);
+    }
 
-    for chr in hex.chars() {
-        let char = chr.to_string();
+    pub fn isJoystick(&self) -> bool {
+        return self.supportsEvent(EventType::Absolute) &&
+            (self.supportsKey(BTN_JOYSTICK) || self.supportsKey(BTN_GAMEPAD));
+    }
+}
 
-        res += hexaTable.get(char.as_str()).unwrap() * (16_i32.pow(power as u32) as usize);
-        power -= 1;
+fn bitIsSet(bitmap: usize, bit: u16) -> bool {
+    if bit as u32 >= usize::BITS {
+        return false;
     }
 
-    return res
+    return bitmap & (1 << bit) != 0;
+}
+
+/// Parses the lowest word of a `B: ...=` bitmap line, which holds every `EV`, `PROP`, `MSC` and `LED` bit
+fn parseBitmap(bitmap: String) -> usize {
+    let lowest = bitmap.split_whitespace().last().unwrap_or("0");
+    return hexToUsize(lowest).unwrap_or(0);
+}
+
+fn hexToUsize(hexadecimal: &str) -> Result<usize, ParseIntError> {
+    return usize::from_str_radix(hexadecimal.trim().trim_start_matches("0x"), 16);
 }
 
 /// Returns a vector containing all the bus inputs found in procfs
 pub fn busInput() -> Vec<BusInput> {
+    linuxCheck();
+    return parseInputDevices(&readFile("/proc/bus/input/devices"));
+}
+
+fn parseInputDevices(fileContent: &str) -> Vec<BusInput> {
     let mut inputs = Vec::<BusInput>::new();
-    let fileContent = readFile("/proc/bus/input/devices");
 
     for chunk in fileContent.split("\n\n") {
         if chunk.trim().is_empty() {
@@ -97,16 +310,16 @@ pub fn busInput() -> Vec<BusInput> {
             if line.contains("I: ") {
                 for block in line.trim().split(" ") {
                     if block.contains("Bus=") {
-                        bus.bus = hexToUsize(block.replace("Bus=", "")) as u16;
+                        bus.bus = hexToUsize(&block.replace("Bus=", "")).unwrap_or(0) as u16;
 
                     } else if block.contains("Vendor=") {
-                        bus.vendor = hexToUsize(block.replace("Vendor=", "")) as u16;
+                        bus.vendor = hexToUsize(&block.replace("Vendor=", "")).unwrap_or(0) as u16;
 
                     } else if block.contains("Version=") {
-                        bus.version = hexToUsize(block.replace("Version=", "")) as u16;
+                        bus.version = hexToUsize(&block.replace("Version=", "")).unwrap_or(0) as u16;
 
                     } else if block.contains("Product=") {
-                        bus.product = hexToUsize(block.replace("Product=", "")) as u16;
+                        bus.product = hexToUsize(&block.replace("Product=", "")).unwrap_or(0) as u16;
                     }
                 }
             } else if line.contains("N: Name=") {
@@ -137,16 +350,16 @@ pub fn busInput() -> Vec<BusInput> {
                 };
 
             } else if line.contains("B: PROP=") {
-                bus.properties = hexToUsize(line.replace("B: PROP=", ""));
+                bus.properties = parseBitmap(line.replace("B: PROP=", ""));
 
             } else if line.contains("B: EV=") {
-                bus.events = hexToUsize(line.replace("B: EV=", ""));
+                bus.events = parseBitmap(line.replace("B: EV=", ""));
 
             } else if line.contains("B: KEY=") {
                 bus.keys = {
                     let mut binding = Vec::<String>::new();
 
-                    for key in line.replace("B: KEY=", "").split(" ") {
+                    for key in line.replace("B: KEY=", "").split_whitespace() {
                         binding.push(key.to_string());
                     }
 
@@ -154,10 +367,10 @@ pub fn busInput() -> Vec<BusInput> {
                 };
 
             } else if line.contains("B: MSC=") {
-                bus.miscellaneousEvents = hexToUsize(line.replace("B: MSC=", ""));
+                bus.miscellaneousEvents = parseBitmap(line.replace("B: MSC=", ""));
 
             } else if line.contains("B: LED=") {
-                bus.led = hexToUsize(line.replace("B: LED=", ""));
+                bus.led = parseBitmap(line.replace("B: LED=", ""));
             }
         }
 
@@ -166,3 +379,657 @@ pub fn busInput() -> Vec<BusInput> {
 
     return inputs
 }
+
+/// Decoded input event read from an evdev node
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum InputEventKind {
+    /// Key or button, `value` is 0 when released, 1 when pressed and 2 on autorepeat
+    Key { code: u16, value: i32 },
+    Relative { code: u16, value: i32 },
+    Absolute { code: u16, value: i32 },
+    Switch { code: u16, value: i32 },
+    Other { eventType: u16, code: u16, value: i32 }
+}
+
+/// Contains an input event and the time it was generated at, as elapsed time since the epoch
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InputEvent {
+    pub time: Duration,
+    pub kind: InputEventKind
+}
+
+/// Receives the events of an input device, read by a background thread
+///
+/// The thread exits at the first event read after the watcher has been dropped
+pub struct InputWatcher {
+    pub devicePath: String,
+    receiver: mpsc::Receiver<InputEvent>
+}
+
+impl InputWatcher {
+    /// Blocks until the next event, returns `None` once the device has been removed
+    pub fn recv(&self) -> Option<InputEvent> {
+        return self.receiver.recv().ok();
+    }
+
+    /// Non-blocking variant of `recv()`, returns `None` when no event is pending
+    pub fn tryRecv(&self) -> Option<InputEvent> {
+        return self.receiver.try_recv().ok();
+    }
+
+    /// Returns every pending event without blocking
+    pub fn pending(&self) -> Vec<InputEvent> {
+        return self.receiver.try_iter().collect();
+    }
+}
+
+impl Iterator for InputWatcher {
+    type Item = InputEvent;
+
+    fn next(&mut self) -> Option<InputEvent> {
+        return self.recv();
+    }
+}
+
+fn decodeInputEvent(buffer: &[u8]) -> Option<InputEvent> {
+    // `struct input_event` is a `timeval` (two `long`s) followed by type, code and value
+    let size = buffer.len();
+    let longSize = (size - 8) / 2;
+
+    let long = |offset: usize| -> i64 {
+        match longSize {
+            8 => i64::from_ne_bytes(buffer[offset..offset + 8].try_into().unwrap()),
+            _ => i32::from_ne_bytes(buffer[offset..offset + 4].try_into().unwrap()) as i64
+        }
+    };
+
+    let seconds = long(0);
+    let microseconds = long(longSize);
+
+    let eventType = u16::from_ne_bytes([buffer[size - 8], buffer[size - 7]]);
+    let code = u16::from_ne_bytes([buffer[size - 6], buffer[size - 5]]);
+    let value = i32::from_ne_bytes(buffer[size - 4..].try_into().unwrap());
+
+    let kind = match EventType::fromCode(eventType) {
+        // synchronization events only delimit packets of events, they carry no information
+        Some(EventType::Synchronization) => return None,
+        Some(EventType::Key) => InputEventKind::Key { code, value },
+        Some(EventType::Relative) => InputEventKind::Relative { code, value },
+        Some(EventType::Absolute) => InputEventKind::Absolute { code, value },
+        Some(EventType::Switch) => InputEventKind::Switch { code, value },
+        _ => InputEventKind::Other { eventType, code, value }
+    };
+
+    return Some(InputEvent {
+        time: Duration::from_secs(seconds.max(0) as u64) + Duration::from_micros(microseconds.max(0) as u64),
+        kind: kind
+    });
+}
+
+/// Opens the `/dev/input/eventN` node of the device and streams its events through an `InputWatcher`
+///
+/// Returns `None` if the device has no event handler or the node cannot be opened,
+/// which usually requires root privileges or membership of the `input` group
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::bus::{busInput, watchInput, InputEventKind, KEY_POWER};
+///
+/// let keyboard = busInput().into_iter().find(|input| input.isKeyboard()).unwrap();
+///
+/// for event in watchInput(&keyboard).unwrap() {
+///     if event.kind == (InputEventKind::Key { code: KEY_POWER, value: 1 }) {
+///         println!("power button pressed");
+///     }
+/// }
+/// ```
+pub fn watchInput(device: &BusInput) -> Option<InputWatcher> {
+    linuxCheck();
+
+    let handler = device.handles.iter().find(|handler| handler.starts_with("event"))?;
+    let devicePath = format!("/dev/input/{}", handler);
+
+    let mut file = match fs::File::open(&devicePath) {
+        Ok(file) => file,
+        Err(_error) => {
+            traceEvent!(debug, path = devicePath, error = %_error, "failed to open input device");
+            return None;
+        }
+    };
+
+    let (sender, receiver) = mpsc::channel::<InputEvent>();
+    thread::spawn(move || {
+        let mut buffer = vec![0_u8; mem::size_of::<libc::input_event>()];
+
+        while file.read_exact(&mut buffer).is_ok() {
+            if let Some(event) = decodeInputEvent(&buffer) {
+                if sender.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    return Some(InputWatcher {
+        devicePath: devicePath,
+        receiver: receiver
+    });
+}
+
+/// Contains the information regarding a device attached to an I2C bus
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct I2cDevice {
+    pub address: u16,
+    pub name: String,
+    pub driver: Option<String>
+}
+
+/// Contains the information regarding an I2C bus (adapter) and its attached devices
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct I2cBus {
+    pub number: usize,
+    pub name: String,
+    pub devices: Vec<I2cDevice>
+}
+
+impl fmt::Display for I2cBus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "i2c-{} ({}), {} devices", self.number, self.name, self.devices.len())
+    }
+}
+
+/// Returns a vector containing all the I2C buses found in sysfs, each with its attached devices
+pub fn i2cBuses() -> Vec<I2cBus> {
+    linuxCheck();
+
+    let mut buses = Vec::<I2cBus>::new();
+    let mut devices = Vec::<(usize, I2cDevice)>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/bus/i2c/devices")) else {
+        return buses;
+    };
+
+    for entry in entries.flatten() {
+        let entryName = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+
+        // adapters are named `i2c-<bus>`, devices `<bus>-<4 digit hex address>`
+        if let Some(number) = entryName.strip_prefix("i2c-") {
+            let Ok(number) = number.parse::<usize>() else {
+                continue;
+            };
+
+            buses.push(I2cBus {
+                number: number,
+                name: readFile(path.join("name")),
+                devices: Vec::<I2cDevice>::new()
+            });
+
+        } else if let Some((bus, address)) = entryName.split_once('-') {
+            let (Ok(bus), Ok(address)) = (bus.parse::<usize>(), u16::from_str_radix(address, 16)) else {
+                continue;
+            };
+
+            devices.push((bus, I2cDevice {
+                address: address,
+                name: readFile(path.join("name")),
+                driver: fs::read_link(path.join("driver")).ok()
+                    .and_then(|driver| driver.file_name().map(|name| name.to_string_lossy().to_string()))
+            }));
+        }
+    }
+
+    for (number, device) in devices {
+        if let Some(bus) = buses.iter_mut().find(|bus| bus.number == number) {
+            bus.devices.push(device);
+        }
+    }
+
+    buses.sort_by_key(|bus| bus.number);
+    for bus in buses.iter_mut() {
+        bus.devices.sort_by_key(|device| device.address);
+    }
+
+    return buses;
+}
+
+/// Authorization state of a Thunderbolt/USB4 device
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThunderboltAuthorization {
+    Unauthorized,
+    Authorized,
+    SecureAuthorized,
+    Other(String)
+}
+
+impl ThunderboltAuthorization {
+    pub fn fromString(authorized: &str) -> ThunderboltAuthorization {
+        match authorized {
+            "0" => ThunderboltAuthorization::Unauthorized,
+            "1" => ThunderboltAuthorization::Authorized,
+            "2" => ThunderboltAuthorization::SecureAuthorized,
+            other => ThunderboltAuthorization::Other(other.to_string())
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            ThunderboltAuthorization::Unauthorized => String::from("unauthorized"),
+            ThunderboltAuthorization::Authorized => String::from("authorized"),
+            ThunderboltAuthorization::SecureAuthorized => String::from("secure authorized"),
+            ThunderboltAuthorization::Other(authorized) => authorized.clone()
+        }
+    }
+}
+
+impl fmt::Display for ThunderboltAuthorization {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains the information regarding a Thunderbolt/USB4 device, the host router included
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThunderboltDevice {
+    pub route: String,
+    pub name: String,
+    pub vendor: String,
+    pub uniqueIdentifier: String,
+    pub authorized: ThunderboltAuthorization,
+    pub generation: Option<usize>,
+    pub rxSpeedGbps: Option<f32>,
+    pub txSpeedGbps: Option<f32>,
+    pub rxLanes: Option<usize>,
+    pub txLanes: Option<usize>
+}
+
+impl fmt::Display for ThunderboltDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} ({})", self.route, self.vendor, self.name, self.authorized)?;
+
+        if let (Some(speed), Some(lanes)) = (self.rxSpeedGbps, self.rxLanes) {
+            write!(f, ", {} x {} Gb/s", lanes, speed)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a vector containing all the Thunderbolt/USB4 devices found in sysfs
+pub fn thunderboltDevices() -> Vec<ThunderboltDevice> {
+    linuxCheck();
+
+    let mut devices = Vec::<ThunderboltDevice>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/bus/thunderbolt/devices")) else {
+        return devices;
+    };
+
+    for entry in entries.flatten() {
+        let route = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+
+        // routers are named `<domain>-<route>`, domains (`domainN`) and services (`<router>:<port>.<index>`) are skipped
+        if !route.contains('-') || route.contains(':') || route.starts_with("domain") {
+            continue;
+        }
+
+        let speed = |name: &str| -> Option<f32> {
+            readFile(path.join(name)).split(' ').next()?.parse::<f32>().ok()
+        };
+
+        devices.push(ThunderboltDevice {
+            name: readFile(path.join("device_name")),
+            vendor: readFile(path.join("vendor_name")),
+            uniqueIdentifier: readFile(path.join("unique_id")),
+            authorized: ThunderboltAuthorization::fromString(&readFile(path.join("authorized"))),
+            generation: readFile(path.join("generation")).parse::<usize>().ok(),
+            rxSpeedGbps: speed("rx_speed"),
+            txSpeedGbps: speed("tx_speed"),
+            rxLanes: readFile(path.join("rx_lanes")).parse::<usize>().ok(),
+            txLanes: readFile(path.join("tx_lanes")).parse::<usize>().ok(),
+            route: route
+        });
+    }
+
+    devices.sort_by(|first, second| first.route.cmp(&second.route));
+    return devices;
+}
+
+pub use crate::utils::PcieLink;
+
+/// Contains the Advanced Error Reporting counters of a PCIe device
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AerErrors {
+    pub correctable: u64,
+    pub nonFatal: u64,
+    pub fatal: u64,
+    /// Every single correctable counter (e.g. `BadTLP`, `RxErr`), as named by the kernel
+    pub correctableCounters: HashMap<String, u64>,
+    /// Every single non fatal counter (e.g. `CmpltTO`), the severities share some names such as `Undefined`
+    pub nonFatalCounters: HashMap<String, u64>,
+    pub fatalCounters: HashMap<String, u64>
+}
+
+impl AerErrors {
+    pub fn total(&self) -> u64 {
+        return self.correctable.saturating_add(self.nonFatal).saturating_add(self.fatal);
+    }
+}
+
+/// Contains the information regarding a PCI device
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PciDevice {
+    pub address: String,
+    pub vendor: u16,
+    pub device: u16,
+    pub class: u32,
+    pub driver: Option<String>,
+    /// `None` for conventional PCI devices and for those not exposing link information
+    pub link: Option<PcieLink>,
+    /// `None` when the device or the kernel does not support AER
+    pub aer: Option<AerErrors>
+}
+
+impl fmt::Display for PciDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {:04x}:{:04x}", self.address, self.vendor, self.device)?;
+
+        if let Some(driver) = &self.driver {
+            write!(f, " ({})", driver)?;
+        }
+
+        if let Some(link) = &self.link {
+            write!(f, ", {}", link)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn parseHex<T: TryFrom<u64>>(value: String) -> Option<T> {
+    let value = u64::from_str_radix(value.trim_start_matches("0x"), 16).ok()?;
+    return T::try_from(value).ok();
+}
+
+fn readAerCounters(path: &path::Path, counters: &mut HashMap<String, u64>) -> Option<u64> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut total = None;
+
+    for line in content.lines() {
+        let Some((name, value)) = line.split_once(' ') else {
+            continue;
+        };
+
+        let Ok(value) = value.trim().parse::<u64>() else {
+            continue;
+        };
+
+        if name.starts_with("TOTAL_ERR_") {
+            total = Some(value);
+        } else {
+            counters.insert(name.to_string(), value);
+        }
+    }
+
+    return total;
+}
+
+/// Returns a vector containing all the PCI devices found in sysfs, with their PCIe link status and AER counters
+pub fn pciDevices() -> Vec<PciDevice> {
+    linuxCheck();
+
+    let mut devices = Vec::<PciDevice>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/bus/pci/devices")) else {
+        return devices;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        let link = readPcieLink(&path);
+
+        let mut correctableCounters = HashMap::<String, u64>::new();
+        let mut nonFatalCounters = HashMap::<String, u64>::new();
+        let mut fatalCounters = HashMap::<String, u64>::new();
+
+        let correctable = readAerCounters(&path.join("aer_dev_correctable"), &mut correctableCounters);
+        let nonFatal = readAerCounters(&path.join("aer_dev_nonfatal"), &mut nonFatalCounters);
+        let fatal = readAerCounters(&path.join("aer_dev_fatal"), &mut fatalCounters);
+
+        let aer = match (correctable, nonFatal, fatal) {
+            (None, None, None) => None,
+            _ => Some(AerErrors {
+                correctable: correctable.unwrap_or(0),
+                nonFatal: nonFatal.unwrap_or(0),
+                fatal: fatal.unwrap_or(0),
+                correctableCounters: correctableCounters,
+                nonFatalCounters: nonFatalCounters,
+                fatalCounters: fatalCounters
+            })
+        };
+
+        devices.push(PciDevice {
+            address: entry.file_name().to_string_lossy().to_string(),
+            vendor: parseHex(readFile(path.join("vendor"))).unwrap_or(0),
+            device: parseHex(readFile(path.join("device"))).unwrap_or(0),
+            class: parseHex(readFile(path.join("class"))).unwrap_or(0),
+            driver: fs::read_link(path.join("driver")).ok()
+                .and_then(|driver| driver.file_name().map(|name| name.to_string_lossy().to_string())),
+            link: link,
+            aer: aer
+        });
+    }
+
+    devices.sort_by(|first, second| first.address.cmp(&second.address));
+    return devices;
+}
+
+/// Capabilities of a V4L2 device node
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoCapabilities {
+    pub capture: bool,
+    pub output: bool,
+    pub overlay: bool,
+    pub metadata: bool,
+    pub memoryToMemory: bool,
+    pub streaming: bool
+}
+
+/// Contains the information regarding a video4linux device (webcam, capture card, codec)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VideoDevice {
+    pub device: String,
+    pub name: String,
+    pub index: Option<usize>,
+    pub driver: Option<String>,
+    /// `None` when the device node cannot be opened, usually for lack of permissions
+    pub capabilities: Option<VideoCapabilities>,
+    /// Subsystem of the parent device, e.g. `usb` or `pci`
+    pub parentBus: Option<String>,
+    pub parentAddress: Option<String>
+}
+
+impl fmt::Display for VideoDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.device, self.name)?;
+
+        if let (Some(bus), Some(address)) = (&self.parentBus, &self.parentAddress) {
+            write!(f, " ({} {})", bus, address)?;
+        }
+
+        Ok(())
+    }
+}
+
+// from linux/videodev2.h
+const VIDIOC_QUERYCAP: libc::c_ulong = 0x80685600;
+const V4L2_CAP_VIDEO_CAPTURE: u32 = 0x00000001;
+const V4L2_CAP_VIDEO_OUTPUT: u32 = 0x00000002;
+const V4L2_CAP_VIDEO_OVERLAY: u32 = 0x00000004;
+const V4L2_CAP_VIDEO_CAPTURE_MPLANE: u32 = 0x00001000;
+const V4L2_CAP_VIDEO_OUTPUT_MPLANE: u32 = 0x00002000;
+const V4L2_CAP_VIDEO_M2M_MPLANE: u32 = 0x00004000;
+const V4L2_CAP_VIDEO_M2M: u32 = 0x00008000;
+const V4L2_CAP_META_CAPTURE: u32 = 0x00800000;
+const V4L2_CAP_STREAMING: u32 = 0x04000000;
+const V4L2_CAP_DEVICE_CAPS: u32 = 0x80000000;
+
+#[repr(C)]
+struct V4l2Capability {
+    driver: [u8; 16],
+    card: [u8; 32],
+    busInfo: [u8; 32],
+    version: u32,
+    capabilities: u32,
+    deviceCaps: u32,
+    reserved: [u32; 3]
+}
+
+fn queryVideoCapabilities(device: &str) -> Option<(String, VideoCapabilities)> {
+    let file = fs::File::open(device).ok()?;
+    let mut capability: V4l2Capability = unsafe { mem::zeroed() };
+
+    if unsafe { libc::ioctl(std::os::fd::AsRawFd::as_raw_fd(&file), VIDIOC_QUERYCAP as _, &mut capability) } != 0 {
+        return None;
+    }
+
+    // `capabilities` describes the whole physical device, `deviceCaps` only this node
+    let flags = if capability.capabilities & V4L2_CAP_DEVICE_CAPS != 0 {
+        capability.deviceCaps
+    } else {
+        capability.capabilities
+    };
+
+    let driverLength = capability.driver.iter().position(|byte| *byte == 0).unwrap_or(capability.driver.len());
+    let driver = String::from_utf8_lossy(&capability.driver[..driverLength]).to_string();
+
+    return Some((driver, VideoCapabilities {
+        capture: flags & (V4L2_CAP_VIDEO_CAPTURE | V4L2_CAP_VIDEO_CAPTURE_MPLANE) != 0,
+        output: flags & (V4L2_CAP_VIDEO_OUTPUT | V4L2_CAP_VIDEO_OUTPUT_MPLANE) != 0,
+        overlay: flags & V4L2_CAP_VIDEO_OVERLAY != 0,
+        metadata: flags & V4L2_CAP_META_CAPTURE != 0,
+        memoryToMemory: flags & (V4L2_CAP_VIDEO_M2M | V4L2_CAP_VIDEO_M2M_MPLANE) != 0,
+        streaming: flags & V4L2_CAP_STREAMING != 0
+    }));
+}
+
+/// Returns a vector containing all the video4linux devices found in sysfs
+pub fn videoDevices() -> Vec<VideoDevice> {
+    linuxCheck();
+
+    let mut devices = Vec::<VideoDevice>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/class/video4linux")) else {
+        return devices;
+    };
+
+    for entry in entries.flatten() {
+        let nodeName = entry.file_name().to_string_lossy().to_string();
+        let path = entry.path();
+        let device = format!("/dev/{}", nodeName);
+
+        let linkName = |link: path::PathBuf| -> Option<String> {
+            Some(fs::read_link(link).ok()?.file_name()?.to_string_lossy().to_string())
+        };
+
+        let parentBus = linkName(path.join("device/subsystem"));
+        let parentAddress = linkName(path.join("device")).map(|address| {
+            match parentBus.as_deref() {
+                // the parent of a USB camera is the interface (`1-2:1.0`), report the device (`1-2`)
+                Some("usb") => address.split(':').next().unwrap_or(&address).to_string(),
+                _ => address
+            }
+        });
+
+        let query = queryVideoCapabilities(&device);
+
+        devices.push(VideoDevice {
+            name: readFile(path.join("name")),
+            index: readFile(path.join("index")).parse::<usize>().ok(),
+            driver: query.as_ref().map(|(driver, _)| driver.clone())
+                .or(linkName(path.join("device/driver"))),
+            capabilities: query.map(|(_, capabilities)| capabilities),
+            parentBus: parentBus,
+            parentAddress: parentAddress,
+            device: device
+        });
+    }
+
+    devices.sort_by(|first, second| first.device.cmp(&second.device));
+    return devices;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEVICES: &str = include_str!("../tests/fixtures/proc_bus_input_devices.txt");
+
+    #[test]
+    fn hexToUsizeParsesWithoutScaling() {
+        assert_eq!(hexToUsize("0019"), Ok(0x19));
+        assert_eq!(hexToUsize("ab83"), Ok(0xab83));
+        assert_eq!(hexToUsize("0x10"), Ok(0x10));
+        assert!(hexToUsize("").is_err());
+        assert!(hexToUsize("12g4").is_err());
+    }
+
+    #[test]
+    fn parsesIdentifiers() {
+        let inputs = parseInputDevices(DEVICES);
+        assert_eq!(inputs.len(), 4);
+
+        let keyboard = &inputs[1];
+        assert_eq!(keyboard.name, "AT Translated Set 2 keyboard");
+        assert_eq!(keyboard.bus, 0x11);
+        assert_eq!(keyboard.vendor, 0x1);
+        assert_eq!(keyboard.product, 0x1);
+        assert_eq!(keyboard.version, 0xab83);
+        assert_eq!(keyboard.handles, vec!["sysrq", "kbd", "leds", "event3"]);
+        assert_eq!(keyboard.events, 0x120013);
+        assert_eq!(keyboard.led, 0x7);
+
+        let mouse = &inputs[3];
+        assert_eq!(mouse.vendor, 0x046d);
+        assert_eq!(mouse.product, 0xc077);
+        assert_eq!(mouse.sysfsPath, "/sys/devices/pci0000:00/0000:00:14.0/usb1/1-2/1-2:1.0/0003:046D:C077.0003/input/input15");
+    }
+
+    #[test]
+    fn classifiesDevices() {
+        let inputs = parseInputDevices(DEVICES);
+
+        assert!(inputs[0].supportsKey(KEY_POWER));
+        assert!(!inputs[0].isKeyboard());
+
+        assert!(inputs[1].isKeyboard());
+        assert!(!inputs[1].isMouse());
+
+        assert!(inputs[2].isTouchpad());
+        assert!(inputs[2].hasProperty(InputProperty::ButtonPad));
+
+        assert!(inputs[3].isMouse());
+        assert_eq!(inputs[3].eventTypes(), vec![EventType::Synchronization, EventType::Key, EventType::Relative, EventType::Miscellaneous]);
+    }
+
+    #[test]
+    fn toleratesMalformedValues() {
+        let inputs = parseInputDevices("I: Bus=zz Vendor=0001 Product= Version=0000\nB: EV=not-hex\n");
+
+        assert_eq!(inputs.len(), 1);
+        assert_eq!(inputs[0].bus, 0);
+        assert_eq!(inputs[0].vendor, 1);
+        assert_eq!(inputs[0].events, 0);
+    }
+}
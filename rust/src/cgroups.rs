@@ -0,0 +1,916 @@
+use std::{fmt, fs, io, mem, path};
+use std::ffi::CString;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::time::Duration;
+use crate::utils::{*};
+
+const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+// kubelet pod resources API, gRPC over HTTP/2 without TLS
+const POD_RESOURCES_SOCKET: &str = "/var/lib/kubelet/pod-resources/kubelet.sock";
+const POD_RESOURCES_LIST: &str = "/v1.PodResourcesLister/List";
+const POD_RESOURCES_TIMEOUT: Duration = Duration::from_secs(2);
+
+const CGROUP2_SUPER_MAGIC: i64 = 0x63677270;
+const TMPFS_MAGIC: i64 = 0x01021994;
+
+/// Layout of the cgroup hierarchies mounted on the system
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CgroupMode {
+    /// cgroup v2 only, mounted on `/sys/fs/cgroup`
+    Unified,
+    /// v1 controllers, with an empty v2 hierarchy on `/sys/fs/cgroup/unified`
+    Hybrid,
+    /// v1 controllers only
+    Legacy,
+    Unknown
+}
+
+impl CgroupMode {
+    pub fn fromString(mode: &str) -> CgroupMode {
+        match mode {
+            "unified" => CgroupMode::Unified,
+            "hybrid" => CgroupMode::Hybrid,
+            "legacy" => CgroupMode::Legacy,
+            _ => CgroupMode::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            CgroupMode::Unified => "unified",
+            CgroupMode::Hybrid => "hybrid",
+            CgroupMode::Legacy => "legacy",
+            CgroupMode::Unknown => "unknown"
+        })
+    }
+}
+
+impl fmt::Display for CgroupMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+fn filesystemType(path: &str) -> Option<i64> {
+    let path = CString::new(path).ok()?;
+    let mut stat: libc::statfs = unsafe { mem::zeroed() };
+
+    if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+
+    return Some(stat.f_type as i64);
+}
+
+/// Returns the cgroup hierarchy layout, detected from the filesystem mounted on `/sys/fs/cgroup`
+pub fn cgroupMode() -> CgroupMode {
+    linuxCheck();
+
+    match filesystemType(CGROUP_ROOT) {
+        Some(CGROUP2_SUPER_MAGIC) => CgroupMode::Unified,
+        Some(TMPFS_MAGIC) => {
+            if filesystemType(&format!("{}/unified", CGROUP_ROOT)) == Some(CGROUP2_SUPER_MAGIC) {
+                CgroupMode::Hybrid
+            } else {
+                CgroupMode::Legacy
+            }
+        },
+        _ => CgroupMode::Unknown
+    }
+}
+
+/// Contains the resource usage of a cgroup, read from the v2 hierarchy or from the v1 controllers
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CgroupUsage {
+    /// Path of the cgroup relative to the hierarchy root, e.g. `/system.slice/sshd.service`
+    pub path: String,
+    /// CPU time consumed by the tasks of the cgroup
+    pub cpuTime: Duration,
+    pub memory: ByteSize,
+    /// `None` when the cgroup has no memory limit
+    pub memoryLimit: Option<ByteSize>,
+    pub readBytes: u64,
+    pub writtenBytes: u64,
+    /// Number of tasks, `None` when the pids controller is not enabled
+    pub pids: Option<u64>
+}
+
+impl fmt::Display for CgroupUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {:.1}s CPU, {} memory", self.path, self.cpuTime.as_secs_f64(), self.memory)?;
+
+        if let Some(limit) = &self.memoryLimit {
+            write!(f, " of {}", limit)?;
+        }
+
+        Ok(())
+    }
+}
+
+// sums the `rbytes=` and `wbytes=` fields of every device line in `io.stat`
+fn parseIoStat(content: &str) -> (u64, u64) {
+    let mut read = 0_u64;
+    let mut written = 0_u64;
+
+    for field in content.split_whitespace() {
+        if let Some(bytes) = field.strip_prefix("rbytes=") {
+            read += bytes.parse::<u64>().unwrap_or(0);
+        } else if let Some(bytes) = field.strip_prefix("wbytes=") {
+            written += bytes.parse::<u64>().unwrap_or(0);
+        }
+    }
+
+    return (read, written);
+}
+
+// sums the `<major>:<minor> Read <bytes>` and `Write` lines of `blkio.throttle.io_service_bytes`
+fn parseBlkio(content: &str) -> (u64, u64) {
+    let mut read = 0_u64;
+    let mut written = 0_u64;
+
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        if fields.len() != 3 {
+            continue;
+        }
+
+        let bytes = fields[2].parse::<u64>().unwrap_or(0);
+
+        match fields[1] {
+            "Read" => read += bytes,
+            "Write" => written += bytes,
+            _ => {}
+        }
+    }
+
+    return (read, written);
+}
+
+fn unifiedUsage(cgroup: &str) -> Option<CgroupUsage> {
+    let directory = sysPath(CGROUP_ROOT).join(cgroup.trim_start_matches('/'));
+
+    if !directory.is_dir() {
+        return None;
+    }
+
+    let cpuMicroseconds = readFile(directory.join("cpu.stat")).lines()
+        .find_map(|line| line.strip_prefix("usage_usec "))
+        .and_then(|usage| usage.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+
+    let (readBytes, writtenBytes) = parseIoStat(&readFile(directory.join("io.stat")));
+
+    return Some(CgroupUsage {
+        path: cgroup.to_string(),
+        cpuTime: Duration::from_micros(cpuMicroseconds),
+        memory: ByteSize::fromBytes(readFile(directory.join("memory.current")).parse::<u64>().unwrap_or(0)),
+        // "max" when unlimited
+        memoryLimit: readFile(directory.join("memory.max")).parse::<u64>().ok().map(ByteSize::fromBytes),
+        readBytes: readBytes,
+        writtenBytes: writtenBytes,
+        pids: readFile(directory.join("pids.current")).parse::<u64>().ok()
+    });
+}
+
+// v1 controllers may be co-mounted, e.g. `cpu,cpuacct`
+fn controllerDirectory(controllers: &[&str], cgroup: &str) -> Option<path::PathBuf> {
+    return controllers.iter()
+        .map(|controller| sysPath(CGROUP_ROOT).join(controller).join(cgroup.trim_start_matches('/')))
+        .find(|directory| directory.is_dir());
+}
+
+fn legacyUsage(cgroup: &str) -> Option<CgroupUsage> {
+    let cpuacct = controllerDirectory(&["cpuacct", "cpu,cpuacct", "cpuacct,cpu"], cgroup);
+    let memory = controllerDirectory(&["memory"], cgroup);
+    let blkio = controllerDirectory(&["blkio"], cgroup);
+    let pids = controllerDirectory(&["pids"], cgroup);
+
+    if cpuacct.is_none() && memory.is_none() && blkio.is_none() && pids.is_none() {
+        return None;
+    }
+
+    let cpuNanoseconds = cpuacct.map(|directory| readFile(directory.join("cpuacct.usage")))
+        .and_then(|usage| usage.parse::<u64>().ok())
+        .unwrap_or(0);
+
+    // v1 reports an unlimited cgroup with a page aligned value close to i64::MAX
+    let memoryLimit = memory.as_ref().map(|directory| readFile(directory.join("memory.limit_in_bytes")))
+        .and_then(|limit| limit.parse::<u64>().ok())
+        .filter(|limit| *limit < (i64::MAX as u64) / 2)
+        .map(ByteSize::fromBytes);
+
+    let (readBytes, writtenBytes) = blkio.map(|directory| parseBlkio(&readFile(directory.join("blkio.throttle.io_service_bytes"))))
+        .unwrap_or((0, 0));
+
+    return Some(CgroupUsage {
+        path: cgroup.to_string(),
+        cpuTime: Duration::from_nanos(cpuNanoseconds),
+        memory: ByteSize::fromBytes(
+            memory.map(|directory| readFile(directory.join("memory.usage_in_bytes")))
+                .and_then(|usage| usage.parse::<u64>().ok())
+                .unwrap_or(0)
+        ),
+        memoryLimit: memoryLimit,
+        readBytes: readBytes,
+        writtenBytes: writtenBytes,
+        pids: pids.and_then(|directory| readFile(directory.join("pids.current")).parse::<u64>().ok())
+    });
+}
+
+/// Returns the resource usage of a cgroup, `cgroup` being its path relative to the hierarchy root (e.g. `/user.slice`)
+///
+/// The v2 interface files are read on unified systems, the cpuacct, memory, blkio and pids controllers on hybrid and legacy ones
+pub fn cgroupUsage(cgroup: &str) -> Option<CgroupUsage> {
+    linuxCheck();
+
+    match cgroupMode() {
+        CgroupMode::Unified => unifiedUsage(cgroup),
+        CgroupMode::Hybrid | CgroupMode::Legacy => legacyUsage(cgroup),
+        CgroupMode::Unknown => None
+    }
+}
+
+/// Returns the cgroup path of a process, as used by `cgroupUsage()`
+///
+/// On hybrid and legacy systems the path in the memory hierarchy is returned,
+/// which container runtimes keep aligned with the other controllers
+pub fn processCgroup(pid: u32) -> Option<String> {
+    linuxCheck();
+
+    // every line is `<hierarchy id>:<controllers>:<path>`, the v2 hierarchy has id 0 and no controllers
+    let content = readFile(format!("/proc/{}/cgroup", pid));
+    let entries: Vec<(&str, &str)> = content.lines()
+        .filter_map(|line| line.split_once(':'))
+        .filter_map(|(_, rest)| rest.split_once(':'))
+        .collect();
+
+    let controller = match cgroupMode() {
+        CgroupMode::Unified => "",
+        _ => "memory"
+    };
+
+    return entries.iter()
+        .find(|(controllers, _)| {
+            if controller.is_empty() {
+                controllers.is_empty()
+            } else {
+                controllers.split(',').any(|name| name == controller)
+            }
+        })
+        .map(|(_, path)| path.to_string());
+}
+
+/// Container runtime owning a cgroup
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContainerRuntime {
+    Docker,
+    Containerd,
+    CriO,
+    Podman,
+    Lxc,
+    Unknown
+}
+
+impl ContainerRuntime {
+    pub fn fromString(runtime: &str) -> ContainerRuntime {
+        match runtime {
+            "docker" => ContainerRuntime::Docker,
+            "containerd" | "cri-containerd" => ContainerRuntime::Containerd,
+            "crio" | "cri-o" => ContainerRuntime::CriO,
+            "podman" | "libpod" => ContainerRuntime::Podman,
+            "lxc" => ContainerRuntime::Lxc,
+            _ => ContainerRuntime::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Containerd => "containerd",
+            ContainerRuntime::CriO => "cri-o",
+            ContainerRuntime::Podman => "podman",
+            ContainerRuntime::Lxc => "lxc",
+            ContainerRuntime::Unknown => "unknown"
+        })
+    }
+}
+
+impl fmt::Display for ContainerRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Kubernetes quality of service class of a pod
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum QosClass {
+    Guaranteed,
+    Burstable,
+    BestEffort
+}
+
+impl QosClass {
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            QosClass::Guaranteed => "Guaranteed",
+            QosClass::Burstable => "Burstable",
+            QosClass::BestEffort => "BestEffort"
+        })
+    }
+}
+
+impl fmt::Display for QosClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains the Kubernetes pod a container belongs to
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KubernetesPod {
+    pub uid: String,
+    pub qosClass: QosClass,
+    /// Filled by `resolveNames()`, the cgroup path only carries the pod UID
+    pub namespace: Option<String>,
+    pub name: Option<String>,
+    pub containerName: Option<String>
+}
+
+impl KubernetesPod {
+    /// Looks up namespace, pod name and container name, returns `false` if the pod was not found
+    ///
+    /// Names come from the kubelet pod resources API (`/var/lib/kubelet/pod-resources/kubelet.sock`). The API
+    /// reports neither pod UIDs nor container IDs, so the pod is told apart from the others by the log paths the
+    /// kubelet derives from them: `/var/log/pods/<namespace>_<name>_<uid>` and
+    /// `/var/log/containers/<name>_<namespace>_<container>-<id>.log`. Both the socket and the log directories
+    /// usually require root privileges
+    ///
+    /// When the socket can not be queried (kubelet older than 1.20, or not running), the names are parsed from the
+    /// entries of the log directories instead
+    pub fn resolveNames(&mut self, containerId: &str) -> bool {
+        if let Ok(pods) = podResources() {
+            return self.matchPodResources(&pods, containerId);
+        }
+
+        return self.resolveLogNames(containerId);
+    }
+
+    fn matchPodResources(&mut self, pods: &[PodResources], containerId: &str) -> bool {
+        for pod in pods {
+            if !sysPath(format!("/var/log/pods/{}_{}_{}", pod.namespace, pod.name, self.uid)).exists() {
+                continue;
+            }
+
+            self.namespace = Some(pod.namespace.clone());
+            self.name = Some(pod.name.clone());
+            self.containerName = pod.containers.iter().find(|container| sysPath(format!(
+                "/var/log/containers/{}_{}_{}-{}.log", pod.name, pod.namespace, container, containerId
+            )).exists()).cloned();
+
+            return true;
+        }
+
+        return false;
+    }
+
+    // fallback of `resolveNames()`, parses the names out of the kubelet log directories
+    fn resolveLogNames(&mut self, containerId: &str) -> bool {
+        for entry in fs::read_dir(sysPath("/var/log/containers")).into_iter().flatten().flatten() {
+            let Some((namespace, name, container)) = parseContainerLog(&entry.file_name().to_string_lossy(), containerId) else {
+                continue;
+            };
+
+            self.namespace = Some(namespace);
+            self.name = Some(name);
+            self.containerName = Some(container);
+
+            return true;
+        }
+
+        let Ok(entries) = fs::read_dir(sysPath("/var/log/pods")) else {
+            return false;
+        };
+
+        for entry in entries.flatten() {
+            let directory = entry.file_name().to_string_lossy().to_string();
+
+            let Some(rest) = directory.strip_suffix(&format!("_{}", self.uid)) else {
+                continue;
+            };
+
+            let Some((namespace, name)) = rest.split_once('_') else {
+                continue;
+            };
+
+            self.namespace = Some(namespace.to_string());
+            self.name = Some(name.to_string());
+
+            return true;
+        }
+
+        return false;
+    }
+}
+
+// splits `<name>_<namespace>_<container>-<id>.log` into namespace, pod name and container name,
+// none of them can contain `_` as they are DNS labels
+fn parseContainerLog(fileName: &str, containerId: &str) -> Option<(String, String, String)> {
+    let rest = fileName.strip_suffix(&format!("-{}.log", containerId))?;
+
+    let mut parts = rest.splitn(3, '_');
+    let name = parts.next()?;
+    let namespace = parts.next()?;
+    let container = parts.next()?;
+
+    return Some((namespace.to_string(), name.to_string(), container.to_string()));
+}
+
+// a pod as listed by the kubelet pod resources API
+#[derive(Debug, PartialEq)]
+struct PodResources {
+    name: String,
+    namespace: String,
+    containers: Vec<String>
+}
+
+// lists the pods running on the node through the kubelet pod resources API
+fn podResources() -> io::Result<Vec<PodResources>> {
+    let response = grpcCall(&sysPath(POD_RESOURCES_SOCKET), POD_RESOURCES_LIST)?;
+    return Ok(parsePodResources(&response));
+}
+
+// `ListPodResourcesResponse`: `repeated PodResources pod_resources = 1`, with `PodResources` made of
+// `string name = 1`, `string namespace = 2` and `repeated ContainerResources containers = 3`, whose name is field 1
+fn parsePodResources(response: &[u8]) -> Vec<PodResources> {
+    let mut pods = Vec::<PodResources>::new();
+
+    for (_, pod) in protobufFields(response).into_iter().filter(|(field, _)| *field == 1) {
+        let mut resources = PodResources { name: String::new(), namespace: String::new(), containers: Vec::new() };
+
+        for (field, value) in protobufFields(pod) {
+            let value = String::from_utf8_lossy(value).to_string();
+
+            match field {
+                1 => resources.name = value,
+                2 => resources.namespace = value,
+                _ => {}
+            }
+        }
+
+        for (field, container) in protobufFields(pod) {
+            if field != 3 {
+                continue;
+            }
+
+            if let Some((_, name)) = protobufFields(container).into_iter().find(|(field, _)| *field == 1) {
+                resources.containers.push(String::from_utf8_lossy(name).to_string());
+            }
+        }
+
+        pods.push(resources);
+    }
+
+    return pods;
+}
+
+fn readVarint(message: &[u8], offset: &mut usize) -> Option<u64> {
+    let mut value: u64 = 0;
+
+    for shift in (0..64).step_by(7) {
+        let byte = *message.get(*offset)?;
+        *offset += 1;
+
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    return None;
+}
+
+// returns the length-delimited fields of a protobuf message, skipping scalars and stopping at the first malformed one
+fn protobufFields(message: &[u8]) -> Vec<(u64, &[u8])> {
+    let mut fields = Vec::<(u64, &[u8])>::new();
+    let mut offset = 0;
+
+    while offset < message.len() {
+        let Some(key) = readVarint(message, &mut offset) else {
+            break;
+        };
+
+        let length = match key & 0x7 {
+            0 => match readVarint(message, &mut offset) {
+                Some(_) => continue,
+                None => break
+            },
+            1 => 8,
+            5 => 4,
+            2 => match readVarint(message, &mut offset) {
+                Some(length) => length as usize,
+                None => break
+            },
+            _ => break
+        };
+
+        let Some(value) = offset.checked_add(length).and_then(|end| message.get(offset..end)) else {
+            break;
+        };
+        offset += length;
+
+        if key & 0x7 == 2 {
+            fields.push((key >> 3, value));
+        }
+    }
+
+    return fields;
+}
+
+const FRAME_DATA: u8 = 0x0;
+const FRAME_HEADERS: u8 = 0x1;
+const FRAME_RST_STREAM: u8 = 0x3;
+const FRAME_SETTINGS: u8 = 0x4;
+const FRAME_PING: u8 = 0x6;
+const FRAME_GOAWAY: u8 = 0x7;
+const FRAME_WINDOW_UPDATE: u8 = 0x8;
+
+const FLAG_END_STREAM: u8 = 0x1;
+const FLAG_ACK: u8 = 0x1;
+const FLAG_END_HEADERS: u8 = 0x4;
+const FLAG_PADDED: u8 = 0x8;
+
+fn writeFrame(stream: &mut UnixStream, frameType: u8, flags: u8, streamId: u32, payload: &[u8]) -> io::Result<()> {
+    let mut frame = (payload.len() as u32).to_be_bytes()[1..].to_vec();
+    frame.push(frameType);
+    frame.push(flags);
+    frame.extend_from_slice(&streamId.to_be_bytes());
+    frame.extend_from_slice(payload);
+
+    return stream.write_all(&frame);
+}
+
+// HPACK integer with a `prefix` bits prefix, the first byte carrying `pattern` in its other bits
+fn hpackInteger(block: &mut Vec<u8>, pattern: u8, prefix: u32, value: usize) {
+    let limit = (1usize << prefix) - 1;
+
+    if value < limit {
+        block.push(pattern | value as u8);
+        return;
+    }
+
+    block.push(pattern | limit as u8);
+    let mut rest = value - limit;
+    while rest >= 0x80 {
+        block.push((rest % 0x80) as u8 | 0x80);
+        rest /= 0x80;
+    }
+    block.push(rest as u8);
+}
+
+fn hpackString(block: &mut Vec<u8>, value: &str) {
+    hpackInteger(block, 0, 7, value.len());
+    block.extend_from_slice(value.as_bytes());
+}
+
+// request headers of a gRPC call, literals without indexing and without Huffman coding
+fn grpcHeaders(method: &str) -> Vec<u8> {
+    // `:method: POST` and `:scheme: http` from the static table
+    let mut block = vec![0x83, 0x86];
+
+    // `:path` and `:authority`, with their names from the static table
+    hpackInteger(&mut block, 0, 4, 4);
+    hpackString(&mut block, method);
+    hpackInteger(&mut block, 0, 4, 1);
+    hpackString(&mut block, "localhost");
+
+    for (name, value) in [("content-type", "application/grpc"), ("te", "trailers")] {
+        block.push(0);
+        hpackString(&mut block, name);
+        hpackString(&mut block, value);
+    }
+
+    return block;
+}
+
+// makes a unary gRPC call with an empty request message over HTTP/2 on a unix socket, returns the response message
+fn grpcCall(socket: &path::Path, method: &str) -> io::Result<Vec<u8>> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(POD_RESOURCES_TIMEOUT))?;
+    stream.set_write_timeout(Some(POD_RESOURCES_TIMEOUT))?;
+
+    stream.write_all(b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n")?;
+
+    // no server push, and windows large enough for the whole response
+    let window: u32 = 0x7fffffff;
+    let mut settings = vec![0x00, 0x02, 0, 0, 0, 0, 0x00, 0x04];
+    settings.extend_from_slice(&window.to_be_bytes());
+    writeFrame(&mut stream, FRAME_SETTINGS, 0, 0, &settings)?;
+    writeFrame(&mut stream, FRAME_WINDOW_UPDATE, 0, 0, &(window - 65535).to_be_bytes())?;
+
+    writeFrame(&mut stream, FRAME_HEADERS, FLAG_END_HEADERS, 1, &grpcHeaders(method))?;
+    // uncompressed, zero-length message
+    writeFrame(&mut stream, FRAME_DATA, FLAG_END_STREAM, 1, &[0, 0, 0, 0, 0])?;
+
+    let mut body = Vec::<u8>::new();
+    loop {
+        let mut header = [0u8; 9];
+        stream.read_exact(&mut header)?;
+
+        let length = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+        let (frameType, flags) = (header[3], header[4]);
+        let streamId = u32::from_be_bytes([header[5], header[6], header[7], header[8]]) & 0x7fffffff;
+
+        let mut payload = vec![0u8; length];
+        stream.read_exact(&mut payload)?;
+
+        match frameType {
+            FRAME_SETTINGS if flags & FLAG_ACK == 0 => writeFrame(&mut stream, FRAME_SETTINGS, FLAG_ACK, 0, &[])?,
+            FRAME_PING if flags & FLAG_ACK == 0 => writeFrame(&mut stream, FRAME_PING, FLAG_ACK, 0, &payload)?,
+            FRAME_RST_STREAM | FRAME_GOAWAY => {
+                return Err(io::Error::new(io::ErrorKind::ConnectionAborted, "call refused by the server"));
+            },
+            FRAME_DATA if streamId == 1 => {
+                let data = match flags & FLAG_PADDED {
+                    0 => &payload[..],
+                    _ => {
+                        let padding = *payload.first().unwrap_or(&0) as usize;
+                        payload.get(1..length.saturating_sub(padding)).unwrap_or_default()
+                    }
+                };
+                body.extend_from_slice(data);
+            },
+            _ => {}
+        }
+
+        if streamId == 1 && flags & FLAG_END_STREAM != 0 && (frameType == FRAME_DATA || frameType == FRAME_HEADERS) {
+            break;
+        }
+    }
+
+    // a failed call only carries its status in the trailers, without any message
+    if body.len() < 5 || body[0] != 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "no uncompressed response message"));
+    }
+
+    let length = u32::from_be_bytes([body[1], body[2], body[3], body[4]]) as usize;
+    return match body.get(5..5 + length) {
+        Some(message) => Ok(message.to_vec()),
+        None => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated response message"))
+    };
+}
+
+/// Contains the container a process runs in
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContainerInfo {
+    pub runtime: ContainerRuntime,
+    /// Container ID, or container name for LXC
+    pub id: String,
+    /// Kubernetes pod, when the container is managed by the kubelet
+    pub pod: Option<KubernetesPod>
+}
+
+impl fmt::Display for ContainerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.runtime, &self.id[..self.id.len().min(12)])?;
+
+        if let Some(pod) = &self.pod {
+            match (&pod.namespace, &pod.name) {
+                (Some(namespace), Some(name)) => write!(f, " in pod {}/{}", namespace, name)?,
+                _ => write!(f, " in pod {}", pod.uid)?
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// splits a systemd scope name such as `cri-containerd-<id>.scope` into runtime and ID
+fn parseScope(segment: &str) -> Option<(ContainerRuntime, String)> {
+    let scope = segment.strip_suffix(".scope")?;
+
+    for prefix in ["cri-containerd", "docker", "crio", "libpod"] {
+        if let Some(id) = scope.strip_prefix(prefix).and_then(|rest| rest.strip_prefix('-')) {
+            // `crio-conmon-<id>.scope` holds the monitor process, not the container
+            if id.starts_with("conmon-") {
+                return None;
+            }
+
+            return Some((ContainerRuntime::fromString(prefix), id.to_string()));
+        }
+    }
+
+    return None;
+}
+
+fn isContainerId(segment: &str) -> bool {
+    return segment.len() == 64 && segment.chars().all(|character| character.is_ascii_hexdigit());
+}
+
+/// Parses a cgroup path into the container it belongs to, following the layouts of
+/// the kubelet (cgroupfs and systemd drivers), Docker, Podman and LXC
+fn parseContainer(cgroup: &str) -> Option<ContainerInfo> {
+    let segments: Vec<&str> = cgroup.split('/').filter(|segment| !segment.is_empty()).collect();
+
+    if segments.first().map(|first| first.starts_with("kubepods")).unwrap_or(false) {
+        // `kubepods/burstable/pod<uid>/<id>` or `kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod<uid>.slice/<scope>`
+        let qosClass = if cgroup.contains("besteffort") {
+            QosClass::BestEffort
+        } else if cgroup.contains("burstable") {
+            QosClass::Burstable
+        } else {
+            QosClass::Guaranteed
+        };
+
+        let podSegment = segments.iter().find_map(|segment| {
+            let pod = segment.strip_suffix(".slice").unwrap_or(segment);
+            pod.rsplit_once("-pod").map(|(_, uid)| uid).or_else(|| pod.strip_prefix("pod"))
+        })?;
+
+        let last = segments.last()?;
+        let (runtime, id) = match parseScope(last) {
+            Some(container) => container,
+            None if isContainerId(last) => (ContainerRuntime::Unknown, last.to_string()),
+            None => return None
+        };
+
+        return Some(ContainerInfo {
+            runtime: runtime,
+            id: id,
+            pod: Some(KubernetesPod {
+                // the systemd driver replaces the dashes of the UID with underscores
+                uid: podSegment.replace('_', "-"),
+                qosClass: qosClass,
+                namespace: None,
+                name: None,
+                containerName: None
+            })
+        });
+    }
+
+    for (index, segment) in segments.iter().enumerate() {
+        if let Some((runtime, id)) = parseScope(segment) {
+            return Some(ContainerInfo { runtime: runtime, id: id, pod: None });
+        }
+
+        if *segment == "docker" {
+            if let Some(id) = segments.get(index + 1).filter(|id| isContainerId(id)) {
+                return Some(ContainerInfo { runtime: ContainerRuntime::Docker, id: id.to_string(), pod: None });
+            }
+        }
+
+        let lxcName = segment.strip_prefix("lxc.payload.")
+            .or_else(|| if *segment == "lxc" { segments.get(index + 1).copied() } else { None });
+
+        if let Some(name) = lxcName {
+            return Some(ContainerInfo { runtime: ContainerRuntime::Lxc, id: name.to_string(), pod: None });
+        }
+    }
+
+    return None;
+}
+
+/// Returns the container a process runs in, `None` for processes on the host
+///
+/// Kubernetes pods only carry their UID, use `KubernetesPod::resolveNames()` to look up namespace and names
+pub fn containerInfo(pid: u32) -> Option<ContainerInfo> {
+    return parseContainer(&processCgroup(pid)?);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsesIoStat() {
+        let content = "8:0 rbytes=4096 wbytes=8192 rios=1 wios=2 dbytes=0 dios=0\n259:0 rbytes=1000 wbytes=0 rios=3 wios=0 dbytes=0 dios=0";
+        assert_eq!(parseIoStat(content), (5096, 8192));
+    }
+
+    #[test]
+    fn parsesBlkio() {
+        let content = "8:0 Read 4096\n8:0 Write 8192\n8:0 Sync 0\n8:0 Async 12288\n8:0 Total 12288\nTotal 12288";
+        assert_eq!(parseBlkio(content), (4096, 8192));
+    }
+
+    #[test]
+    fn parsesKubernetesCgroups() {
+        let id = "4e6bc0d2a7f1e4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9";
+
+        let cgroupfs = parseContainer(&format!("/kubepods/burstable/pod0a1b2c3d-1111-2222-3333-444455556666/{}", id)).unwrap();
+        let pod = cgroupfs.pod.unwrap();
+        assert_eq!(cgroupfs.id, id);
+        assert_eq!(pod.uid, "0a1b2c3d-1111-2222-3333-444455556666");
+        assert_eq!(pod.qosClass, QosClass::Burstable);
+
+        let systemd = parseContainer(&format!(
+            "/kubepods.slice/kubepods-pod0a1b2c3d_1111_2222_3333_444455556666.slice/cri-containerd-{}.scope", id
+        )).unwrap();
+        let pod = systemd.pod.unwrap();
+        assert_eq!(systemd.runtime, ContainerRuntime::Containerd);
+        assert_eq!(pod.uid, "0a1b2c3d-1111-2222-3333-444455556666");
+        assert_eq!(pod.qosClass, QosClass::Guaranteed);
+    }
+
+    #[test]
+    fn parsesContainerLogNames() {
+        let id = "4e6bc0d2a7f1e4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9";
+
+        assert_eq!(
+            parseContainerLog(&format!("coredns-5d78c9869d-x2k4p_kube-system_coredns-{}.log", id), id),
+            Some((String::from("kube-system"), String::from("coredns-5d78c9869d-x2k4p"), String::from("coredns")))
+        );
+        assert_eq!(parseContainerLog(&format!("web-0_default_nginx-{}.log", "0".repeat(64)), id), None);
+    }
+
+    // length-delimited protobuf field
+    fn field(number: u8, value: &[u8]) -> Vec<u8> {
+        let mut encoded = vec![number << 3 | 2, value.len() as u8];
+        encoded.extend_from_slice(value);
+        return encoded;
+    }
+
+    #[test]
+    fn parsesPodResources() {
+        // a container with a device, cpu ids 2 and 3 (packed) and a scalar, unknown field
+        let mut container = field(1, b"nginx");
+        container.extend(field(2, &[field(1, b"example.com/gpu"), field(2, b"gpu0")].concat()));
+        container.extend(field(3, &[2, 3]));
+        container.extend([6 << 3, 0x96, 0x01]);
+
+        let pod = [field(1, b"web-0"), field(2, b"default"), field(3, &container), field(3, &field(1, b"sidecar"))].concat();
+        let response = [field(1, &pod), field(1, &[field(1, b"coredns-x2k4p"), field(2, b"kube-system")].concat())].concat();
+
+        assert_eq!(parsePodResources(&response), vec![
+            PodResources {
+                name: String::from("web-0"),
+                namespace: String::from("default"),
+                containers: vec![String::from("nginx"), String::from("sidecar")]
+            },
+            PodResources { name: String::from("coredns-x2k4p"), namespace: String::from("kube-system"), containers: vec![] }
+        ]);
+
+        // truncated messages keep the complete fields
+        assert_eq!(parsePodResources(&response[..response.len() - 3]).len(), 1);
+    }
+
+    #[test]
+    fn encodesHpackIntegers() {
+        // RFC 7541, C.1
+        let mut block = Vec::<u8>::new();
+        hpackInteger(&mut block, 0, 5, 10);
+        hpackInteger(&mut block, 0, 5, 1337);
+        assert_eq!(block, vec![0x0a, 0x1f, 0x9a, 0x0a]);
+
+        assert_eq!(&grpcHeaders(POD_RESOURCES_LIST)[..4], &[0x83, 0x86, 0x04, 27]);
+    }
+
+    #[test]
+    fn matchesPodResourcesByLogPaths() {
+        let id = "4e6bc0d2a7f1e4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9";
+        let uid = "0a1b2c3d-1111-2222-3333-444455556666";
+
+        let root = std::env::temp_dir().join(format!("sysutil-pods-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join(format!("var/log/pods/default_web-0_{}", uid))).unwrap();
+        fs::create_dir_all(root.join("var/log/containers")).unwrap();
+        fs::write(root.join(format!("var/log/containers/web-0_default_sidecar-{}.log", id)), "").unwrap();
+
+        let pods = vec![
+            PodResources { name: String::from("web-1"), namespace: String::from("default"), containers: vec![String::from("nginx")] },
+            PodResources {
+                name: String::from("web-0"),
+                namespace: String::from("default"),
+                containers: vec![String::from("nginx"), String::from("sidecar")]
+            }
+        ];
+
+        let mut pod = KubernetesPod { uid: uid.to_string(), qosClass: QosClass::Burstable, namespace: None, name: None, containerName: None };
+        let found = crate::SysPaths::new(&root).scope(|| pod.matchPodResources(&pods, id));
+        let _ = fs::remove_dir_all(&root);
+
+        assert!(found);
+        assert_eq!(pod.name.as_deref(), Some("web-0"));
+        assert_eq!(pod.namespace.as_deref(), Some("default"));
+        assert_eq!(pod.containerName.as_deref(), Some("sidecar"));
+    }
+
+    #[test]
+    fn parsesContainerCgroups() {
+        let id = "4e6bc0d2a7f1e4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9";
+
+        assert_eq!(parseContainer(&format!("/docker/{}", id)).unwrap().runtime, ContainerRuntime::Docker);
+        assert_eq!(parseContainer(&format!("/system.slice/docker-{}.scope", id)).unwrap().id, id);
+        assert_eq!(parseContainer(&format!("/machine.slice/libpod-{}.scope", id)).unwrap().runtime, ContainerRuntime::Podman);
+        assert_eq!(parseContainer("/lxc.payload.web/init.scope").unwrap().id, "web");
+        assert!(parseContainer("/user.slice/user-1000.slice/session-2.scope").is_none());
+    }
+}
@@ -1,6 +1,6 @@
-use std::{fs, path, thread};
+use std::{fmt, fs, io, mem, path, thread};
 use std::collections::HashMap;
-use std::process::Command;
+use std::sync::Mutex;
 use std::time::Duration;
 use crate::utils::{*};
 use regex;
@@ -8,6 +8,7 @@ use regex::Regex;
 
 /// Contains the average CPU usage and the discrete usage for each processor
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CpuUsage {
     pub average: ProcessorUsage,
     pub processors: Vec<ProcessorUsage>,
@@ -15,6 +16,7 @@ pub struct CpuUsage {
 
 /// Encloses the different parameters relative to processor usage
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProcessorUsage {
     pub total: f32,
     pub user: f32,
@@ -41,18 +43,184 @@ impl ProcessorUsage {
     }
 }
 
+/// CPU architecture word size
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Architecture {
+    Bits64,
+    Bits32,
+    Other(String)
+}
+
+impl Architecture {
+    pub fn fromString(architecture: &str) -> Architecture {
+        match architecture {
+            "64 bit" => Architecture::Bits64,
+            "32 bit" => Architecture::Bits32,
+            other => Architecture::Other(other.to_string())
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            Architecture::Bits64 => String::from("64 bit"),
+            Architecture::Bits32 => String::from("32 bit"),
+            Architecture::Other(architecture) => architecture.clone()
+        }
+    }
+}
+
+/// CPU byte order
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    Little,
+    Big,
+    Other(String)
+}
+
+impl Endianness {
+    pub fn fromString(byteOrder: &str) -> Endianness {
+        match byteOrder {
+            "Little Endian" => Endianness::Little,
+            "Big Endian" => Endianness::Big,
+            other => Endianness::Other(other.to_string())
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            Endianness::Little => String::from("Little Endian"),
+            Endianness::Big => String::from("Big Endian"),
+            Endianness::Other(byteOrder) => byteOrder.clone()
+        }
+    }
+}
+
+/// CPU frequency scaling governor
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Governor {
+    Performance,
+    Powersave,
+    Schedutil,
+    Ondemand,
+    Conservative,
+    Userspace,
+    Other(String)
+}
+
+impl Governor {
+    pub fn fromString(governor: &str) -> Governor {
+        match governor {
+            "performance" => Governor::Performance,
+            "powersave" => Governor::Powersave,
+            "schedutil" => Governor::Schedutil,
+            "ondemand" => Governor::Ondemand,
+            "conservative" => Governor::Conservative,
+            "userspace" => Governor::Userspace,
+            other => Governor::Other(other.to_string())
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            Governor::Performance => String::from("performance"),
+            Governor::Powersave => String::from("powersave"),
+            Governor::Schedutil => String::from("schedutil"),
+            Governor::Ondemand => String::from("ondemand"),
+            Governor::Conservative => String::from("conservative"),
+            Governor::Userspace => String::from("userspace"),
+            Governor::Other(governor) => governor.clone()
+        }
+    }
+}
+
+impl fmt::Display for Architecture {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+impl fmt::Display for Endianness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+impl fmt::Display for Governor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Core type on hybrid CPUs (e.g. Intel P-cores and E-cores, ARM big.LITTLE)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CoreType {
+    Performance,
+    Efficiency
+}
+
+impl CoreType {
+    pub fn toString(&self) -> String {
+        match self {
+            CoreType::Performance => String::from("performance"),
+            CoreType::Efficiency => String::from("efficiency")
+        }
+    }
+}
+
+impl fmt::Display for CoreType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains the position of a processor (logical CPU) in the CPU topology
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessorTopology {
+    pub processorID: usize,
+    pub packageId: Option<usize>,
+    pub dieId: Option<usize>,
+    pub coreId: Option<usize>,
+    /// `None` on non-hybrid CPUs
+    pub coreType: Option<CoreType>
+}
+
 /// Contains base information relative to the CPU
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CpuInfo {
     pub modelName: String,
     pub cores: usize,
     pub threads: usize,
     pub dies: usize,
-    pub governors: Vec<String>,
+    pub governors: Vec<Governor>,
     pub maxFrequencyMHz: f32,
     pub clockBoost: Option<bool>,
-    pub architecture: String,
-    pub byteOrder: String
+    pub architecture: Architecture,
+    pub byteOrder: Endianness,
+    /// Number of performance cores, `None` on non-hybrid CPUs
+    pub performanceCores: Option<usize>,
+    /// Number of efficiency cores, `None` on non-hybrid CPUs
+    pub efficiencyCores: Option<usize>
+}
+
+impl fmt::Display for CpuInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{}, {}c/{}t @ {:.1} GHz",
+            self.modelName, self.cores, self.threads, self.maxFrequencyMHz / 1000_f32
+        )?;
+
+        if let (Some(performance), Some(efficiency)) = (self.performanceCores, self.efficiencyCores) {
+            write!(f, " ({}P + {}E)", performance, efficiency)?;
+        }
+
+        Ok(())
+    }
 }
 
 /// Encloses all CPU-related data available in the library
@@ -65,6 +233,7 @@ pub struct CpuInfo {
 /// cpu.update();
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CPU {
     pub info: CpuInfo,
     pub averageUsage: ProcessorUsage,
@@ -76,7 +245,16 @@ pub struct CPU {
 
 impl CPU {
     pub fn new() -> CPU {
-        let cpuUsage = cpuUsage();
+        return CPU::withUsage(cpuUsage());
+    }
+
+    /// Same as `CPU::new()`, but samples the usage with `cpuUsageAsync()`
+    #[cfg(feature = "async")]
+    pub async fn newAsync() -> CPU {
+        return CPU::withUsage(cpuUsageAsync().await);
+    }
+
+    fn withUsage(cpuUsage: CpuUsage) -> CPU {
         let frequency = cpuFrequency();
 
         CPU {
@@ -89,6 +267,12 @@ impl CPU {
         }
     }
 
+    /// Reads again the static CPU information (model, topology, governors), which is otherwise cached
+    pub fn refresh(&mut self) {
+        self.info = refreshCpuInfo();
+        self.update();
+    }
+
     pub fn update(&mut self) {
         self.schedulerPolicies = schedulerInfo();
         let cpuUsage = cpuUsage();
@@ -98,37 +282,17 @@ impl CPU {
     }
 }
 
-/// Frequency data structure implements direct conversion for frequencies
-/// in various size orders
-/// ```rust
-/// use sysutil::cpuFrequency;
-/// let frequency = cpuFrequency().average;
-///
-/// frequency.khz(); // returns the frequency in Kilo Hertz
-/// frequency.mhz(); // returns the frequency in Mega Hertz
-/// frequency.ghz(); // return the frequency in Giga Hertz
-/// ```
-#[derive(Debug, Clone)]
-pub struct Frequency {
-    pub khz: usize
-}
-
-impl Frequency {
-    pub fn khz(&self) -> f32 {
-        return self.khz as f32;
-    }
-
-    pub fn mhz(&self) -> f32 {
-        return self.khz as f32 / 1000_f32;
-    }
-
-    pub fn ghz(&self) -> f32 {
-        return self.khz as f32 / 1000_000_f32;
+impl fmt::Display for CPU {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {:.1}% used at {}", self.info, self.averageUsage.total, self.averageFrequency)
     }
 }
 
+pub use crate::utils::Frequency;
+
 /// Contains processor id and its frequency
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProcessorFrequency {
     pub processorID: String,
     pub frequency: Frequency
@@ -136,6 +300,7 @@ pub struct ProcessorFrequency {
 
 /// Contains cpu frequencies, both average and processor wise
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CpuFrequency {
     pub average: Frequency,
     pub processors: Vec<ProcessorFrequency>
@@ -143,6 +308,7 @@ pub struct CpuFrequency {
 
 /// Contains currently active clock source and the available ones
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClockSource {
     pub current: String,
     pub available: Vec<String>
@@ -151,27 +317,37 @@ pub struct ClockSource {
 
 /// Contains scheduler information relative to a processor in the system
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SchedulerPolicy {
     pub name: String,
     pub scalingGovernor: String,
     pub scalingDriver: String,
-    pub minimumScalingMHz: f32,
-    pub maximumScalingMHz: f32,
+    pub minimumScaling: Frequency,
+    pub maximumScaling: Frequency,
 }
 
 /// Holds data structure for average load
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Load {
     pub oneMinute: f32,
     pub fiveMinutes: f32,
     pub fifteenMinutes: f32
 }
 
-fn getStats() -> Vec<Vec<usize>> {
-    linuxCheck();
+impl fmt::Display for Load {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.2} {:.2} {:.2}", self.oneMinute, self.fiveMinutes, self.fifteenMinutes)
+    }
+}
 
-    let fileContent = readFile("/proc/stat");
+fn getStats() -> Vec<Vec<u64>> {
+    linuxCheck();
+    return parseStats(&readFile("/proc/stat"));
+}
 
+// parses the `cpu` lines of `/proc/stat`, the aggregate line first
+fn parseStats(fileContent: &str) -> Vec<Vec<u64>> {
     let lines = fileContent.split("\n");
     let mut strLines = Vec::<String>::new();
 
@@ -181,16 +357,21 @@ fn getStats() -> Vec<Vec<usize>> {
         }
     }
 
-    let mut uLines = Vec::<Vec<usize>>::new();
+    let mut uLines = Vec::<Vec<u64>>::new();
     for line in strLines {
         let splittedLine = line.split(' ');
-        let mut fixedLine = Vec::<usize>::new();
+        let mut fixedLine = Vec::<u64>::new();
 
         for chunk in splittedLine {
             if !chunk.is_empty() && !chunk.contains("cpu") {
-                fixedLine.push(chunk.parse().unwrap());
+                fixedLine.push(chunk.parse().unwrap_or(0));
             }
         }
+
+        // guarantees the columns used by `cpuUsage` exist, even on old kernels
+        while fixedLine.len() < 7 {
+            fixedLine.push(0);
+        }
         uLines.push(fixedLine);
     }
 
@@ -199,60 +380,139 @@ fn getStats() -> Vec<Vec<usize>> {
 
 /// Returns CPU usage, both average and processor-wise, each value is in percentage
 pub fn cpuUsage() -> CpuUsage {
+    return cpuUsageWithInterval(Duration::from_millis(250));
+}
+
+/// Same as `cpuUsage()`, but samples over `interval` instead of 250 ms
+///
+/// Longer intervals give smoother readings, shorter ones react faster but are noisier
+pub fn cpuUsageWithInterval(interval: Duration) -> CpuUsage {
     linuxCheck();
+    traceSpan!("cpuUsage");
 
     let before = getStats();
-    thread::sleep(Duration::from_millis(250));
+    thread::sleep(interval);
     let after = getStats();
 
+    return usageBetween(&before, &after);
+}
+
+/// Same as `cpuUsage()`, but waits between the two reads with `tokio::time::sleep` instead of blocking the thread
+#[cfg(feature = "async")]
+pub async fn cpuUsageAsync() -> CpuUsage {
+    linuxCheck();
+
+    let before = parseStats(&tokio::fs::read_to_string(sysPath("/proc/stat")).await.unwrap_or_default());
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    let after = parseStats(&tokio::fs::read_to_string(sysPath("/proc/stat")).await.unwrap_or_default());
+
+    return usageBetween(&before, &after);
+}
+
+/// Contains the CPU time counters read at some point, used as starting point by `cpuUsageSince()`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuBaseline {
+    stats: Vec<Vec<u64>>
+}
+
+impl CpuBaseline {
+    /// Reads the current CPU time counters
+    pub fn capture() -> CpuBaseline {
+        return CpuBaseline {
+            stats: getStats()
+        };
+    }
+}
+
+/// Returns CPU usage since `baseline` was captured, without sleeping, then moves `baseline` to the current counters
+///
+/// Calling it periodically with the same baseline returns the usage between consecutive calls
+/// ## Example
+/// ```rust,no_run
+/// let mut baseline = sysutil::cpu::CpuBaseline::capture();
+///
+/// loop {
+///     std::thread::sleep(std::time::Duration::from_secs(1));
+///     println!("{}", sysutil::cpu::cpuUsageSince(&mut baseline).average.total);
+/// }
+/// ```
+pub fn cpuUsageSince(baseline: &mut CpuBaseline) -> CpuUsage {
+    let current = getStats();
+    let usage = usageBetween(&baseline.stats, &current);
+
+    baseline.stats = current;
+    return usage;
+}
+
+fn usageBetween(before: &[Vec<u64>], after: &[Vec<u64>]) -> CpuUsage {
     let mut processors = Vec::<ProcessorUsage>::new();
-    for i in 0..before.len() {
+    // a processor going offline between the two reads shortens the second list
+    for i in 0..before.len().min(after.len()) {
         let beforeLine = &before[i];
         let beforeSum = {
-            let mut sum = 0;
+            let mut sum = 0_u64;
 
             for element in beforeLine {
-                sum += element;
+                sum = sum.wrapping_add(*element);
             }
             sum
         };
 
         let afterLine = &after[i];
         let afterSum = {
-            let mut sum = 0;
+            let mut sum = 0_u64;
 
             for element in afterLine {
-                sum += element;
+                sum = sum.wrapping_add(*element);
             }
             sum
         };
 
-        let delta: f32 = (afterSum - beforeSum) as f32;
+        // counters going backwards (e.g. wraparound on 32 bit systems) count as no activity
+        let column = |index: usize| -> f32 {
+            return afterLine[index].saturating_sub(beforeLine[index]) as f32;
+        };
+
+        let delta: f32 = afterSum.saturating_sub(beforeSum) as f32;
+        if delta == 0_f32 {
+            processors.push(ProcessorUsage {
+                total: 0_f32,
+                user: 0_f32,
+                nice: 0_f32,
+                system: 0_f32,
+                idle: 100_f32,
+                iowait: 0_f32,
+                interrupt: 0_f32,
+                soft_interrupt: 0_f32,
+            });
+            continue;
+        }
 
         processors.push(ProcessorUsage {
             total: {
-                100_f32 - (afterLine[3] - beforeLine[3]) as f32 * 100_f32 / delta
+                (100_f32 - column(3) * 100_f32 / delta).max(0_f32)
             },
             user: {
-                (afterLine[0] - beforeLine[0]) as f32 * 100_f32 / delta
+                column(0) * 100_f32 / delta
             },
             nice: {
-                (afterLine[1] - beforeLine[1]) as f32 * 100_f32 / delta
+                column(1) * 100_f32 / delta
             },
             system: {
-                (afterLine[2] - beforeLine[2]) as f32 * 100_f32 / delta
+                column(2) * 100_f32 / delta
             },
             idle: {
-                (afterLine[3] - beforeLine[3]) as f32 * 100_f32 / delta
+                column(3) * 100_f32 / delta
             },
             iowait: {
-                (afterLine[4] - beforeLine[4]) as f32 * 100_f32 / delta
+                column(4) * 100_f32 / delta
             },
             interrupt: {
-                (afterLine[5] - beforeLine[5]) as f32 * 100_f32 / delta
+                column(5) * 100_f32 / delta
             },
             soft_interrupt: {
-                (afterLine[6] - beforeLine[6]) as f32 * 100_f32 / delta
+                column(6) * 100_f32 / delta
             },
         });
     }
@@ -263,9 +523,42 @@ pub fn cpuUsage() -> CpuUsage {
     };
 }
 
+static CPU_INFO: Mutex<Option<CpuInfo>> = Mutex::new(None);
+
 /// Returns CPU base information, enclosed in the `CpuInfo` data structure
+///
+/// Since this information does not change at runtime it is read once and cached,
+/// use `refreshCpuInfo()` to read it again (e.g. after CPU hotplug)
+pub fn cpuInfo() -> CpuInfo {
+    let mut cache = CPU_INFO.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    match cache.as_ref() {
+        Some(info) => info.clone(),
+        None => {
+            let info = readCpuInfo();
+            *cache = Some(info.clone());
+            info
+        }
+    }
+}
+
+/// Same as `cpuInfo()`, but returns an error instead of incomplete information when `/proc/cpuinfo` is not readable
+pub fn tryCpuInfo() -> Result<CpuInfo, SysError> {
+    linuxCheck();
 
-fn cpuInfo() -> CpuInfo {
+    tryReadFile("/proc/cpuinfo")?;
+    return Ok(cpuInfo());
+}
+
+/// Reads again CPU base information, updating the cached value returned by `cpuInfo()`
+pub fn refreshCpuInfo() -> CpuInfo {
+    let info = readCpuInfo();
+    *CPU_INFO.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(info.clone());
+
+    return info;
+}
+
+fn readCpuInfo() -> CpuInfo {
     linuxCheck();
 
     let infoFile = readFile("/proc/cpuinfo");
@@ -280,61 +573,24 @@ fn cpuInfo() -> CpuInfo {
         name.trim().to_string()
     };
 
-    let baseDir = path::Path::new("/sys/devices/system/cpu");
-
-    let mut coreCount: usize = 0;
-    let mut dieCount: usize = 0;
-
-    for processor in fs::read_dir(baseDir).unwrap() {
-        let processorPath = processor.unwrap().path();
-        let path = processorPath.to_str().unwrap();
-
-        if path.contains("cpu") && !path.contains("cpufreq") && !path.contains("cpuidle")
-        {
-            let coreId = readFile(format!("{path}/topology/core_id").as_str());
-            let dieId = readFile(format!("{path}/topology/die_id").as_str());
-
-            if !coreId.is_empty() {
-                match coreId.parse::<usize>() {
-                    Err(_) => (),
-                    Ok(uCoreId) => {
-                        if uCoreId > coreCount {
-                            coreCount = uCoreId;
-                        }
-                    }
-                }
-            }
-
-            if !dieId.is_empty() {
-                match dieId.parse::<usize>() {
-                    Err(_) => (),
-                    Ok(uDieId) => {
-                        if uDieId > dieCount {
-                            dieCount = uDieId;
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    if coreCount % 2 == 1 {
-        coreCount += 1;
-    }
-    dieCount += 1;
+    let topology = cpuTopology();
+    let (coreCount, dieCount) = topologyCounts(&topology);
 
     let cpuInfoFile = readFile("/proc/cpuinfo");
     let threadCount = cpuInfoFile.matches("processor").count();
 
-    let mut governors = Vec::<String>::new();
-    let policiesPath = path::Path::new("/sys/devices/system/cpu/cpufreq/");
+    let mut governors = Vec::<Governor>::new();
+    let policiesPath = sysPath("/sys/devices/system/cpu/cpufreq/");
 
     let mut maxFrequency: usize = 0;
     let mut clockBoost: Option<bool> = None;
 
-    for dir in fs::read_dir(policiesPath).unwrap() {
-        let path = dir.unwrap().path();
-        let sPath = path.to_str().unwrap();
+    // cpufreq is missing in most virtual machines
+    for dir in fs::read_dir(policiesPath).into_iter().flatten().flatten() {
+        let path = dir.path();
+        let Some(sPath) = path.to_str() else {
+            continue;
+        };
 
         if sPath.contains("policy") {
             let localGovernors = readFile(format!("{sPath}/scaling_available_governors").as_str());
@@ -352,8 +608,10 @@ fn cpuInfo() -> CpuInfo {
             }
 
             for governor in localGovernors.split(" ") {
-                if !governors.contains(&governor.to_string()) {
-                    governors.push(governor.to_string());
+                let governor = Governor::fromString(governor);
+
+                if !governors.contains(&governor) {
+                    governors.push(governor);
                 }
             }
         } else if sPath.contains("boost") {
@@ -372,22 +630,18 @@ fn cpuInfo() -> CpuInfo {
 
     let arch = {
         if maxInteger as u128 == 2_u128.pow(64) - 1 {
-            String::from("64 bit")
+            Architecture::Bits64
         } else if maxInteger as u128 == 2_u128.pow(32) - 1 {
-            String::from("32 bit")
+            Architecture::Bits32
         } else {
-            String::new()
+            Architecture::Other(String::new())
         }
     };
 
-    let pipe = Command::new("sh").arg("-c").args(
-        ["echo -n I | od -t o2 | head -n 1 | cut -f 2 -d \" \" | cut -c 6"]
-    ).output().unwrap().stdout;
-
-    let byteOrder = match String::from_utf8(pipe).unwrap().trim() {
-        "1" => String::from("Little Endian"),
-        "0" => String::from("Big Endian"),
-        _ => String::new(),
+    let byteOrder = if cfg!(target_endian = "little") {
+        Endianness::Little
+    } else {
+        Endianness::Big
     };
 
     return CpuInfo {
@@ -400,45 +654,230 @@ fn cpuInfo() -> CpuInfo {
         clockBoost: clockBoost,
         architecture: arch,
         byteOrder: byteOrder,
+        performanceCores: coreTypeCount(&topology, CoreType::Performance),
+        efficiencyCores: coreTypeCount(&topology, CoreType::Efficiency),
     };
 }
 
-/// Returns scheduler information for each processor
+/// Counts physical cores as unique (package, die, core) triples and dies as unique (package, die) pairs
+///
+/// Core ids are neither contiguous nor unique across packages and dies, so they cannot be used as a count
+fn topologyCounts(topology: &Vec<ProcessorTopology>) -> (usize, usize) {
+    let mut cores = Vec::<(Option<usize>, Option<usize>, Option<usize>)>::new();
+    let mut dies = Vec::<(Option<usize>, Option<usize>)>::new();
+
+    for processor in topology {
+        let core = (processor.packageId, processor.dieId, processor.coreId);
+        let die = (processor.packageId, processor.dieId);
+
+        if !cores.contains(&core) {
+            cores.push(core);
+        }
+
+        if !dies.contains(&die) {
+            dies.push(die);
+        }
+    }
+
+    return (cores.len(), dies.len().max(1));
+}
+
+/// Counts the physical cores of the specified type, `None` if the CPU is not hybrid
+fn coreTypeCount(topology: &Vec<ProcessorTopology>, coreType: CoreType) -> Option<usize> {
+    if topology.iter().all(|processor| processor.coreType.is_none()) {
+        return None;
+    }
+
+    let mut cores = Vec::<(Option<usize>, Option<usize>, Option<usize>)>::new();
+    for processor in topology {
+        let core = (processor.packageId, processor.dieId, processor.coreId);
+
+        if processor.coreType == Some(coreType) && !cores.contains(&core) {
+            cores.push(core);
+        }
+    }
+
+    return Some(cores.len());
+}
+
+/// Parses a CPU list in the kernel format, e.g. `0-3,8,10-11`
+fn parseCpuList(list: &str) -> Vec<usize> {
+    let mut processors = Vec::<usize>::new();
+
+    for range in list.trim().split(',') {
+        match range.split_once('-') {
+            Some((first, last)) => {
+                if let (Ok(first), Ok(last)) = (first.parse::<usize>(), last.parse::<usize>()) {
+                    processors.extend(first..=last);
+                }
+            },
+            None => {
+                if let Ok(processor) = range.parse::<usize>() {
+                    processors.push(processor);
+                }
+            }
+        }
+    }
+
+    return processors;
+}
+
+/// Reads the CPU topology from a sysfs `devices` directory (`/sys/devices` on a live system)
+fn readTopology(devices: &path::Path) -> Vec<ProcessorTopology> {
+    let mut topology = Vec::<ProcessorTopology>::new();
+
+    let Ok(entries) = fs::read_dir(devices.join("system/cpu")) else {
+        return topology;
+    };
+
+    let mut capacities = Vec::<(usize, usize)>::new();
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Some(Ok(processorID)) = name.strip_prefix("cpu").map(|id| id.parse::<usize>()) else {
+            continue;
+        };
+
+        let path = entry.path();
+        let id = |name: &str| readFile(path.join("topology").join(name)).parse::<usize>().ok();
+
+        if let Ok(capacity) = readFile(path.join("cpu_capacity")).parse::<usize>() {
+            capacities.push((processorID, capacity));
+        }
+
+        topology.push(ProcessorTopology {
+            processorID: processorID,
+            packageId: id("physical_package_id"),
+            dieId: id("die_id"),
+            coreId: id("core_id"),
+            coreType: None
+        });
+    }
+
+    topology.sort_by_key(|processor| processor.processorID);
+
+    // hybrid Intel CPUs register a PMU for each core type
+    let performance = parseCpuList(&readFile(devices.join("cpu_core/cpus")));
+    let efficiency = parseCpuList(&readFile(devices.join("cpu_atom/cpus")));
+
+    assignCoreTypes(&mut topology, &performance, &efficiency, &capacities);
+    return topology;
+}
+
+/// Sets the core type of each processor from the hybrid PMU CPU lists or, without them, from the scheduler capacities
+fn assignCoreTypes(topology: &mut [ProcessorTopology], performance: &[usize], efficiency: &[usize], capacities: &[(usize, usize)]) {
+    if !performance.is_empty() && !efficiency.is_empty() {
+        for processor in topology.iter_mut() {
+            if performance.contains(&processor.processorID) {
+                processor.coreType = Some(CoreType::Performance);
+            } else if efficiency.contains(&processor.processorID) {
+                processor.coreType = Some(CoreType::Efficiency);
+            }
+        }
+
+        return;
+    }
+
+    // otherwise processors are clustered by the capacity the scheduler gives them, exactly two clusters mean a hybrid
+    // CPU; maximum frequencies alone are not enough, as favored cores of Turbo Boost Max and binning spread them too
+    let mut clusters = capacities.iter().map(|(_, capacity)| *capacity).collect::<Vec<usize>>();
+    clusters.sort();
+    clusters.dedup();
+
+    if clusters.len() == 2 {
+        for processor in topology.iter_mut() {
+            let capacity = capacities.iter().find(|(id, _)| *id == processor.processorID);
+
+            processor.coreType = match capacity {
+                Some((_, capacity)) if *capacity == clusters[1] => Some(CoreType::Performance),
+                Some(_) => Some(CoreType::Efficiency),
+                None => None
+            };
+        }
+    }
+}
+
+/// Returns the topology of each processor, including its core type on hybrid CPUs
+pub fn cpuTopology() -> Vec<ProcessorTopology> {
+    linuxCheck();
+    return readTopology(&sysPath("/sys/devices"));
+}
+
+/// Returns the processors the process (or thread) `pid` is allowed to run on, `0` meaning the calling thread
+///
+/// The IDs match `ProcessorTopology::processorID`
+pub fn getAffinity(pid: u32) -> io::Result<Vec<usize>> {
+    linuxCheck();
+
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+    if unsafe { libc::sched_getaffinity(pid as libc::pid_t, mem::size_of::<libc::cpu_set_t>(), &mut set) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    return Ok((0..libc::CPU_SETSIZE as usize).filter(|processor| unsafe { libc::CPU_ISSET(*processor, &set) }).collect());
+}
+
+/// Restricts the process (or thread) `pid` to the given processors, `0` meaning the calling thread
+///
+/// Pinning another user's process requires `CAP_SYS_NICE`, processors which are offline or outside the
+/// cpuset of the process are rejected by the kernel
+pub fn setAffinity(pid: u32, cpus: &[usize]) -> io::Result<()> {
+    linuxCheck();
+
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+
+    for processor in cpus {
+        if *processor >= libc::CPU_SETSIZE as usize {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("processor {} out of range", processor)));
+        }
+
+        unsafe { libc::CPU_SET(*processor, &mut set) };
+    }
+
+    if unsafe { libc::sched_setaffinity(pid as libc::pid_t, mem::size_of::<libc::cpu_set_t>(), &set) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    return Ok(());
+}
+
+/// Returns scheduler information for each processor, empty if it is not available (e.g. no cpufreq driver)
 pub fn schedulerInfo() -> Vec<SchedulerPolicy> {
+    return trySchedulerInfo().unwrap_or_default();
+}
+
+/// Same as `schedulerInfo()`, but returns an error when the cpufreq policies can not be read
+pub fn trySchedulerInfo() -> Result<Vec<SchedulerPolicy>, SysError> {
     linuxCheck();
 
-    let schedulerDir = path::Path::new("/sys/devices/system/cpu/cpufreq/");
+    let schedulerDir = sysPath("/sys/devices/system/cpu/cpufreq/");
     let mut policies = Vec::<SchedulerPolicy>::new();
 
-    for dir in fs::read_dir(schedulerDir).unwrap() {
-        let path = dir.unwrap().path();
-        let sPath = path.to_str().unwrap();
+    for dir in tryReadDir(schedulerDir)? {
+        let policyName = dir.file_name().to_string_lossy().to_string();
 
-        if sPath.contains("policy") {
-            let policyName = sPath.split("/").last().unwrap().to_string();
+        if policyName.contains("policy") {
+            let path = dir.path();
 
-            let scalingGovernor = readFile(format!("{sPath}/scaling_governor").as_str());
-            let scalingDriver = readFile(format!("{sPath}/scaling_driver").as_str());
+            let scalingGovernor = readFile(path.join("scaling_governor"));
+            let scalingDriver = readFile(path.join("scaling_driver"));
 
-            let maxScalingFrequency = readFile(
-                format!("{sPath}/scaling_max_freq").as_str()
-            ).parse::<f32>().unwrap() / 1000_f32;
+            let maxPath = path.join("scaling_max_freq");
+            let maxScalingFrequency = Frequency::fromKhz(tryParse(&maxPath, &tryReadFile(&maxPath)?)?);
 
-            let minScalingFrequency = readFile(
-                format!("{sPath}/scaling_min_freq").as_str()
-            ).parse::<f32>().unwrap() / 1000_f32;
+            let minPath = path.join("scaling_min_freq");
+            let minScalingFrequency = Frequency::fromKhz(tryParse(&minPath, &tryReadFile(&minPath)?)?);
 
             policies.push(SchedulerPolicy {
                 name: policyName,
                 scalingGovernor: scalingGovernor,
                 scalingDriver: scalingDriver,
-                minimumScalingMHz: minScalingFrequency,
-                maximumScalingMHz: maxScalingFrequency,
+                minimumScaling: minScalingFrequency,
+                maximumScaling: maxScalingFrequency,
             });
         }
     }
 
-    return policies;
+    return Ok(policies);
 }
 
 /// Returns the currently active clock source and the different ones available, enclosed in `ClockSource` struct
@@ -464,13 +903,21 @@ pub fn clockSource() -> ClockSource {
     }
 }
 
-/// Returns cpu frequency, both average and processor wise
+/// Returns cpu frequency, both average and processor wise, zero if it is not reported
 pub fn cpuFrequency() -> CpuFrequency {
+    return tryCpuFrequency().unwrap_or(CpuFrequency {
+        average: Frequency::fromKhz(0),
+        processors: Vec::<ProcessorFrequency>::new()
+    });
+}
+
+/// Same as `cpuFrequency()`, but returns an error when `/proc/cpuinfo` is not readable or reports no frequency
+pub fn tryCpuFrequency() -> Result<CpuFrequency, SysError> {
     linuxCheck();
     let mut totalFreq: f32 = 0_f32;
     let mut frequencies: Vec<ProcessorFrequency> = Vec::new();
 
-    let fileContent = readFile("/proc/cpuinfo");
+    let fileContent = tryReadFile("/proc/cpuinfo")?;
     for chunk in fileContent.split("\n\n") {
 
         if chunk.is_empty() {
@@ -481,11 +928,15 @@ pub fn cpuFrequency() -> CpuFrequency {
         let mut freq: f32 = 0_f32;
 
         for line in chunk.split("\n") {
+            let Some((_, value)) = line.split_once(":") else {
+                continue
+            };
+
             if line.contains("processor") {
-                id = line.trim().split(":").last().unwrap().trim().to_string();
+                id = value.trim().to_string();
 
             } else if line.contains("cpu MHz") {
-                freq = line.trim().split(":").last().unwrap().trim().parse::<f32>().unwrap();
+                freq = tryParse("/proc/cpuinfo", value)?;
             }
         }
 
@@ -496,30 +947,166 @@ pub fn cpuFrequency() -> CpuFrequency {
         totalFreq += freq;
         frequencies.push(ProcessorFrequency{
             processorID: id,
-            frequency: Frequency {
-                khz: (freq * 1000.0) as usize
-            }
+            frequency: Frequency::fromKhz((freq * 1000.0) as usize)
         });
     }
 
-    CpuFrequency {
-        average: Frequency {
-            khz: (totalFreq * 1000.0) as usize / frequencies.len()
-        },
-        processors: frequencies
+    // e.g. ARM systems do not report `cpu MHz`
+    if frequencies.is_empty() {
+        return Err(SysError::parse("/proc/cpuinfo", "no cpu MHz field"));
     }
+
+    Ok(CpuFrequency {
+        average: Frequency::fromKhz((totalFreq * 1000.0) as usize / frequencies.len()),
+        processors: frequencies
+    })
 }
 
-/// Returns the average load for the last one, five and fifteen minutes
+/// Returns the average load for the last one, five and fifteen minutes, zero if it is not readable
 pub fn getLoad() -> Load {
-    let fileContent = readFile("/proc/loadavg");
+    return tryGetLoad().unwrap_or(Load {
+        oneMinute: 0_f32,
+        fiveMinutes: 0_f32,
+        fifteenMinutes: 0_f32
+    });
+}
+
+/// Same as `getLoad()`, but returns an error when `/proc/loadavg` is not readable
+pub fn tryGetLoad() -> Result<Load, SysError> {
+    let fileContent = tryReadFile("/proc/loadavg")?;
     let binding = fileContent.split(" ").collect::<Vec<&str>>();
 
-    Load {
-        oneMinute: binding.get(0).unwrap().parse::<f32>().unwrap(),
-        fiveMinutes: binding.get(1).unwrap().parse::<f32>().unwrap(),
-        fifteenMinutes: binding.get(2).unwrap().parse::<f32>().unwrap()
+    let average = |index: usize| -> Result<f32, SysError> {
+        tryParse("/proc/loadavg", binding.get(index).unwrap_or(&""))
+    };
+
+    Ok(Load {
+        oneMinute: average(0)?,
+        fiveMinutes: average(1)?,
+        fifteenMinutes: average(2)?
+    })
+}
+
+/// Contains scheduler statistics of a processor, counters are cumulative since boot
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessorSchedStats {
+    pub processorID: String,
+    /// Time spent running tasks
+    pub runningTime: Duration,
+    /// Time tasks spent waiting in the run queue before running
+    pub waitingTime: Duration,
+    pub timeslices: u64,
+    /// Tasks pulled to this processor by load balancing, `None` if the schedstat version is not known
+    pub migrations: Option<u64>,
+    /// Current run queue length, only available when the scheduler debug file is readable (usually root only)
+    pub runQueueLength: Option<usize>
+}
+
+/// Contains scheduler statistics of every processor, as reported by `/proc/schedstat`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchedStats {
+    pub version: usize,
+    /// Tasks currently runnable in the whole system
+    pub runnableTasks: Option<usize>,
+    pub processors: Vec<ProcessorSchedStats>
+}
+
+fn parseSchedStat(content: &str) -> Option<SchedStats> {
+    let mut version = None;
+    let mut processors = Vec::<ProcessorSchedStats>::new();
+
+    for line in content.lines() {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        let Some(name) = fields.first() else {
+            continue;
+        };
+
+        if *name == "version" {
+            version = fields.get(1).and_then(|value| value.parse::<usize>().ok());
+
+        } else if name.starts_with("cpu") {
+            let value = |index: usize| fields.get(index).and_then(|value| value.parse::<u64>().ok()).unwrap_or(0);
+
+            // cpuN <yld_count> <legacy> <schedule> <goidle> <ttwu> <ttwu_local> <run time> <wait time> <timeslices>
+            processors.push(ProcessorSchedStats {
+                processorID: name.to_string(),
+                runningTime: Duration::from_nanos(value(7)),
+                waitingTime: Duration::from_nanos(value(8)),
+                timeslices: value(9),
+                migrations: None,
+                runQueueLength: None
+            });
+
+        } else if name.starts_with("domain") && version == Some(15) {
+            // `lb_gained` of each of the three idle types, the cpumask is at index 1
+            let Some(processor) = processors.last_mut() else {
+                continue;
+            };
+
+            let mut gained = 0_u64;
+            for index in [6, 14, 22] {
+                gained += fields.get(index).and_then(|value| value.parse::<u64>().ok()).unwrap_or(0);
+            }
+
+            processor.migrations = Some(processor.migrations.unwrap_or(0) + gained);
+        }
+    }
+
+    return Some(SchedStats {
+        version: version?,
+        runnableTasks: None,
+        processors: processors
+    });
+}
+
+fn runQueueLengths() -> HashMap<String, usize> {
+    let mut lengths = HashMap::<String, usize>::new();
+
+    let mut content = readFile("/sys/kernel/debug/sched/debug");
+    if content.is_empty() {
+        content = readFile("/proc/sched_debug");
+    }
+
+    let mut processor = None;
+    for line in content.lines() {
+        if let Some(header) = line.strip_prefix("cpu#") {
+            processor = header.split(',').next().map(|id| format!("cpu{}", id.trim()));
+
+        } else if line.trim_start().starts_with(".nr_running") {
+            let (Some(id), Some(value)) = (&processor, line.split(':').nth(1)) else {
+                continue;
+            };
+
+            if let Ok(length) = value.trim().parse::<usize>() {
+                // the first `.nr_running` of each processor belongs to its run queue, the others to its classes
+                lengths.entry(id.clone()).or_insert(length);
+            }
+        }
+    }
+
+    return lengths;
+}
+
+/// Returns scheduler statistics for each processor, `None` if the kernel does not expose `/proc/schedstat`
+pub fn schedStats() -> Option<SchedStats> {
+    linuxCheck();
+
+    let mut stats = parseSchedStat(&readFile("/proc/schedstat"))?;
+
+    let lengths = runQueueLengths();
+    for processor in stats.processors.iter_mut() {
+        processor.runQueueLength = lengths.get(&processor.processorID).copied();
     }
+
+    for line in readFile("/proc/stat").lines() {
+        if let Some(running) = line.strip_prefix("procs_running ") {
+            stats.runnableTasks = running.trim().parse::<usize>().ok();
+        }
+    }
+
+    return Some(stats);
 }
 
 /// Returns a HashMap containing the size (in ByteSize) for each cache level
@@ -529,8 +1116,8 @@ pub fn cacheLevels() -> HashMap<String, ByteSize> {
 
     let pattern = regex::Regex::new(r"cpu[0-9]{1,3}").unwrap();
 
-    for element in std::fs::read_dir(baseDir).unwrap() {
-        let name = element.unwrap().file_name().to_str().unwrap().to_string();
+    for element in tryReadDir(baseDir).unwrap_or_default() {
+        let name = element.file_name().to_string_lossy().to_string();
         match pattern.captures(&name) {
             Some(_) => {
                 cpus.push(name);
@@ -549,8 +1136,8 @@ pub fn cacheLevels() -> HashMap<String, ByteSize> {
 
         let pattern = regex::Regex::new(r"index[0-9]{1}").unwrap();
 
-        for element in std::fs::read_dir(format!("{}/{}/cache/", baseDir, cpu)).unwrap() {
-            let name = element.unwrap().file_name().to_str().unwrap().to_string();
+        for element in tryReadDir(format!("{}/{}/cache/", baseDir, cpu)).unwrap_or_default() {
+            let name = element.file_name().to_string_lossy().to_string();
             match pattern.captures(&name) {
                 Some(_) => {
                     cacheDirs.push(name);
@@ -586,24 +1173,113 @@ pub fn cacheLevels() -> HashMap<String, ByteSize> {
 
         let chunks = cacheLevels.get(level);
         for chunk in chunks {
-            let (size, _) = chunk.first().unwrap();
+            let Some((size, _)) = chunk.first() else {
+                continue
+            };
 
             let intSize = {
                 if size.contains("K") {
-                    size.replace("K", "").parse::<usize>().unwrap() * 1024
+                    size.replace("K", "").parse::<usize>().unwrap_or(0) * 1024
 
                 } else if size.contains("M") {
-                    size.replace("M", "").parse::<usize>().unwrap() * 1024
+                    size.replace("M", "").parse::<usize>().unwrap_or(0) * 1024
                 } else {
-                    size.parse::<usize>().unwrap()
+                    size.parse::<usize>().unwrap_or(0)
                 }
             };
 
             totalSize += intSize;
         }
 
-        levels.insert(level.to_string(), ByteSize::fromBytes(totalSize));
+        levels.insert(level.to_string(), ByteSize::fromBytes(totalSize as u64));
     }
 
     return levels;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // one processor for each (package, die, core) triple, numbered in order
+    fn processors(ids: &[(usize, usize, usize)]) -> Vec<ProcessorTopology> {
+        return ids.iter().enumerate().map(|(processorID, (package, die, core))| ProcessorTopology {
+            processorID: processorID,
+            packageId: Some(*package),
+            dieId: Some(*die),
+            coreId: Some(*core),
+            coreType: None
+        }).collect();
+    }
+
+    #[test]
+    fn readsCapturedTopology() {
+        let devices = path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/sysfs/vm-1c");
+        let topology = readTopology(&devices);
+
+        assert_eq!(topology.len(), 1);
+        assert_eq!(topologyCounts(&topology), (1, 1));
+        assert_eq!(topology[0].coreType, None);
+    }
+
+    #[test]
+    fn countsSparseCoreIds() {
+        // two threads per core, with gaps in the core ids
+        let topology = processors(&[0, 1, 2, 4, 5, 6, 0, 1, 2, 4, 5, 6].map(|core| (0, 0, core)));
+
+        assert_eq!(topologyCounts(&topology), (6, 1));
+        assert_eq!(coreTypeCount(&topology, CoreType::Performance), None);
+    }
+
+    #[test]
+    fn countsCoresAcrossPackages() {
+        // core ids restart on each package
+        let topology = processors(&[(0, 0, 0), (0, 0, 1), (0, 0, 2), (0, 0, 3), (1, 0, 0), (1, 0, 1), (1, 0, 2), (1, 0, 3)]);
+
+        assert_eq!(topologyCounts(&topology), (8, 2));
+    }
+
+    #[test]
+    fn countsDiesPerPackage() {
+        let topology = processors(&[(0, 0, 0), (0, 0, 1), (0, 1, 0), (0, 1, 1)]);
+
+        assert_eq!(topologyCounts(&topology), (4, 2));
+    }
+
+    #[test]
+    fn detectsHybridPmus() {
+        // 6 performance cores with two threads each, then 4 efficiency cores
+        let mut topology = processors(&[0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 7, 8, 9].map(|core| (0, 0, core)));
+        assignCoreTypes(&mut topology, &parseCpuList("0-11"), &parseCpuList("12-15"), &[]);
+
+        assert_eq!(topologyCounts(&topology), (10, 1));
+        assert_eq!(topology[0].coreType, Some(CoreType::Performance));
+        assert_eq!(topology[15].coreType, Some(CoreType::Efficiency));
+        assert_eq!(coreTypeCount(&topology, CoreType::Performance), Some(6));
+        assert_eq!(coreTypeCount(&topology, CoreType::Efficiency), Some(4));
+    }
+
+    #[test]
+    fn clustersByCapacity() {
+        let mut topology = processors(&[0, 1, 2, 3, 4, 5, 6, 7].map(|core| (0, 0, core)));
+        let capacities = [446, 446, 446, 446, 1024, 1024, 1024, 1024].iter().copied().enumerate().collect::<Vec<(usize, usize)>>();
+        assignCoreTypes(&mut topology, &[], &[], &capacities);
+
+        assert_eq!(topology[0].coreType, Some(CoreType::Efficiency));
+        assert_eq!(topology[7].coreType, Some(CoreType::Performance));
+        assert_eq!(coreTypeCount(&topology, CoreType::Performance), Some(4));
+
+        // a single capacity cluster is not a hybrid CPU, whatever the maximum frequencies of its cores
+        let mut topology = processors(&[0, 1, 2, 3].map(|core| (0, 0, core)));
+        assignCoreTypes(&mut topology, &[], &[], &[(0, 1024), (1, 1024), (2, 1024), (3, 1024)]);
+
+        assert_eq!(topologyCounts(&topology), (4, 1));
+        assert_eq!(coreTypeCount(&topology, CoreType::Performance), None);
+    }
+
+    #[test]
+    fn parsesCpuLists() {
+        assert_eq!(parseCpuList("0-3,8,10-11"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parseCpuList(""), Vec::<usize>::new());
+    }
+}
@@ -0,0 +1,261 @@
+use std::{fmt, fs, io};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{cpu, ram, network, sensors};
+use crate::sensors::CategorizedTemperature;
+use crate::utils::{*};
+
+/// Flat export formats, comma separated or tab separated values
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CsvFormat {
+    Csv,
+    Tsv
+}
+
+impl CsvFormat {
+    pub fn fromString(format: &str) -> CsvFormat {
+        match format {
+            "tsv" => CsvFormat::Tsv,
+            _ => CsvFormat::Csv
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            CsvFormat::Csv => String::from("csv"),
+            CsvFormat::Tsv => String::from("tsv")
+        }
+    }
+
+    pub fn separator(&self) -> char {
+        match self {
+            CsvFormat::Csv => ',',
+            CsvFormat::Tsv => '\t'
+        }
+    }
+}
+
+impl fmt::Display for CsvFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains a single row of numeric metrics, usages are in percentage, rates in bytes per second and temperatures in °C
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MetricsRow {
+    pub timestamp: SystemTime,
+    pub cpuUsage: f32,
+    pub processorUsage: Vec<f32>,
+    pub ramUsage: f32,
+    pub download: f32,
+    pub upload: f32,
+    /// Column name and value of every temperature channel, e.g. `temperature.k10temp.tctl`
+    pub temperatures: Vec<(String, f32)>
+}
+
+impl MetricsRow {
+    /// Samples the metrics, takes about 750 ms because of the CPU usage and network rate sampling
+    pub fn capture() -> MetricsRow {
+        let usage = cpu::cpuUsage();
+        let rate = network::networkRate();
+
+        return MetricsRow {
+            timestamp: SystemTime::now(),
+            cpuUsage: usage.average.total,
+            processorUsage: usage.processors.iter().map(|processor| processor.total).collect(),
+            ramUsage: ram::ramUsage(),
+            download: rate.download,
+            upload: rate.upload,
+            temperatures: temperatureColumns(&sensors::categorizedTemperatures())
+        };
+    }
+
+    /// Returns the column names, stable as long as the processors and temperature channels do not change
+    pub fn columns(&self) -> Vec<String> {
+        let mut columns = vec![String::from("timestamp"), String::from("cpu.usage")];
+
+        for processor in 0..self.processorUsage.len() {
+            columns.push(format!("cpu{}.usage", processor));
+        }
+
+        columns.push(String::from("ram.usage"));
+        columns.push(String::from("network.download"));
+        columns.push(String::from("network.upload"));
+
+        for (column, _) in &self.temperatures {
+            columns.push(column.clone());
+        }
+
+        return columns;
+    }
+
+    /// Returns the values in the same order as `columns()`, the timestamp in seconds since the Unix epoch
+    pub fn values(&self) -> Vec<String> {
+        let timestamp = self.timestamp.duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0);
+
+        let mut values = vec![timestamp.to_string(), format!("{:.2}", self.cpuUsage)];
+
+        for usage in &self.processorUsage {
+            values.push(format!("{:.2}", usage));
+        }
+
+        values.push(format!("{:.2}", self.ramUsage));
+        values.push(format!("{:.0}", self.download));
+        values.push(format!("{:.0}", self.upload));
+
+        for (_, temperature) in &self.temperatures {
+            values.push(format!("{:.1}", temperature));
+        }
+
+        return values;
+    }
+
+    /// Returns the header line, without the line terminator
+    pub fn header(&self, format: CsvFormat) -> String {
+        return joinFields(&self.columns(), format);
+    }
+
+    /// Returns the row line, without the line terminator
+    pub fn toLine(&self, format: CsvFormat) -> String {
+        return joinFields(&self.values(), format);
+    }
+}
+
+// names temperature columns after chip and label, channels sharing both get a `-2`, `-3`, ... suffix
+fn temperatureColumns(temperatures: &[CategorizedTemperature]) -> Vec<(String, f32)> {
+    let mut columns = Vec::<(String, f32)>::new();
+
+    for temperature in temperatures {
+        let base = format!(
+            "temperature.{}.{}",
+            temperature.chip.trim().to_lowercase().replace(' ', "-"),
+            temperature.label.trim().to_lowercase().replace(' ', "-")
+        );
+
+        let mut column = base.clone();
+        let mut occurrence = 1;
+
+        while columns.iter().any(|(existing, _)| *existing == column) {
+            occurrence += 1;
+            column = format!("{}-{}", base, occurrence);
+        }
+
+        columns.push((column, temperature.temperature));
+    }
+
+    return columns;
+}
+
+// quotes fields containing the separator, quotes or line breaks, doubling the quotes as RFC 4180 does
+fn escapeField(field: &str, separator: char) -> String {
+    if field.contains([separator, '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+
+    return field.to_string();
+}
+
+fn joinFields(fields: &[String], format: CsvFormat) -> String {
+    return fields.iter()
+        .map(|field| escapeField(field, format.separator()))
+        .collect::<Vec<String>>()
+        .join(&format.separator().to_string());
+}
+
+/// Returns the header and a freshly sampled row of metrics, each followed by a line break
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::csv::{exportCsv, CsvFormat};
+///
+/// print!("{}", exportCsv(CsvFormat::Csv));
+/// ```
+pub fn exportCsv(format: CsvFormat) -> String {
+    linuxCheck();
+
+    let row = MetricsRow::capture();
+    return format!("{}\n{}\n", row.header(format), row.toLine(format));
+}
+
+/// Samples the metrics and appends a row to the file at `path`, writing the header first if the file is missing or empty
+///
+/// Fails with `InvalidData` when the existing header differs from the current columns (e.g. a sensor appeared or
+/// disappeared), so rows never end up under the wrong column; start a new file in that case
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::csv::{appendCsv, CsvFormat};
+///
+/// loop {
+///     appendCsv("metrics.tsv", CsvFormat::Tsv).unwrap();
+///     std::thread::sleep(std::time::Duration::from_secs(60));
+/// }
+/// ```
+pub fn appendCsv<P: AsRef<Path>>(path: P, format: CsvFormat) -> io::Result<MetricsRow> {
+    linuxCheck();
+
+    let row = MetricsRow::capture();
+    let header = row.header(format);
+
+    let mut file = fs::OpenOptions::new().create(true).read(true).append(true).open(path)?;
+
+    let mut existingHeader = String::new();
+    BufReader::new(&file).read_line(&mut existingHeader)?;
+
+    if existingHeader.is_empty() {
+        file.write_all(format!("{}\n{}\n", header, row.toLine(format)).as_bytes())?;
+
+    } else if existingHeader.trim_end_matches(['\r', '\n']) != header {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData, "the file header does not match the current columns"
+        ));
+
+    } else {
+        file.write_all(format!("{}\n", row.toLine(format)).as_bytes())?;
+    }
+
+    return Ok(row);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sensors::SensorCategory;
+
+    fn temperature(chip: &str, label: &str, value: f32) -> CategorizedTemperature {
+        CategorizedTemperature {
+            category: SensorCategory::Other(format!("{}/{}", chip, label)),
+            device: String::from("hwmon0"),
+            chip: chip.to_string(),
+            label: label.to_string(),
+            temperature: value
+        }
+    }
+
+    #[test]
+    fn flattensRow() {
+        let row = MetricsRow {
+            timestamp: UNIX_EPOCH + std::time::Duration::from_secs(1700000000),
+            cpuUsage: 12.5,
+            processorUsage: vec![20_f32, 5_f32],
+            ramUsage: 40_f32,
+            download: 1024_f32,
+            upload: 0_f32,
+            temperatures: temperatureColumns(&[
+                temperature("nvme", "Composite", 38_f32),
+                temperature("nvme", "Composite", 41.3),
+                temperature("acpitz", "temp1, zone", 30_f32)
+            ])
+        };
+
+        assert_eq!(
+            row.header(CsvFormat::Csv),
+            "timestamp,cpu.usage,cpu0.usage,cpu1.usage,ram.usage,network.download,network.upload,\
+            temperature.nvme.composite,temperature.nvme.composite-2,\"temperature.acpitz.temp1,-zone\""
+        );
+        assert_eq!(row.toLine(CsvFormat::Tsv), "1700000000\t12.50\t20.00\t5.00\t40.00\t1024\t0\t38.0\t41.3\t30.0");
+        assert_eq!(row.columns().len(), row.values().len());
+    }
+}
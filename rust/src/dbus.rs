@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+use zbus::names::BusName;
+use zbus::object_server::SignalEmitter;
+use zbus::zvariant::Value;
+use crate::snapshot::SystemSnapshot;
+use crate::utils::{*};
+
+/// Well-known name requested by `exportDbus()`, also the name of the interface it publishes
+pub const DBUS_NAME: &str = "io.github.ryzeon_dev.Sysutil";
+
+/// Path of the object published by `exportDbus()`
+pub const DBUS_PATH: &str = "/io/github/ryzeon_dev/Sysutil";
+
+/// Message bus the snapshot service is published on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DbusBus {
+    Session,
+    System
+}
+
+#[derive(Debug, Default)]
+struct SnapshotService {
+    snapshot: String,
+    values: HashMap<&'static str, f64>
+}
+
+impl SnapshotService {
+    fn value(&self, name: &str) -> f64 {
+        return self.values.get(name).copied().unwrap_or(0_f64);
+    }
+}
+
+#[zbus::interface(name = "io.github.ryzeon_dev.Sysutil")]
+impl SnapshotService {
+    /// Returns the last snapshot, in the format of `exportJson()`
+    #[zbus(name = "Snapshot")]
+    fn snapshot(&self) -> String {
+        self.snapshot.clone()
+    }
+
+    #[zbus(property, name = "CpuUsage")]
+    fn cpuUsage(&self) -> f64 {
+        self.value("CpuUsage")
+    }
+
+    #[zbus(property, name = "RamUsage")]
+    fn ramUsage(&self) -> f64 {
+        self.value("RamUsage")
+    }
+
+    #[zbus(property, name = "NetworkDownload")]
+    fn networkDownload(&self) -> f64 {
+        self.value("NetworkDownload")
+    }
+
+    #[zbus(property, name = "NetworkUpload")]
+    fn networkUpload(&self) -> f64 {
+        self.value("NetworkUpload")
+    }
+
+    #[zbus(property, name = "Load")]
+    fn load(&self) -> f64 {
+        self.value("Load")
+    }
+
+    /// Emitted after every snapshot, call `Snapshot` to read it
+    #[zbus(signal, name = "Updated")]
+    async fn updated(emitter: &SignalEmitter<'_>) -> zbus::Result<()>;
+}
+
+// values published as properties: CPU and RAM usage in percentage, network rates in bytes per second, one minute load
+fn propertyValues(snapshot: &SystemSnapshot) -> [(&'static str, f64); 5] {
+    return [
+        ("CpuUsage", snapshot.cpu.as_ref().map(|cpu| cpu.averageUsage.total).unwrap_or(0_f32) as f64),
+        ("RamUsage", snapshot.ram.as_ref().map(|ram| ram.usage).unwrap_or(0_f32) as f64),
+        ("NetworkDownload", snapshot.networkRate.as_ref().map(|rate| rate.download).unwrap_or(0_f32) as f64),
+        ("NetworkUpload", snapshot.networkRate.as_ref().map(|rate| rate.upload).unwrap_or(0_f32) as f64),
+        ("Load", snapshot.load.as_ref().map(|load| load.oneMinute).unwrap_or(0_f32) as f64)
+    ];
+}
+
+/// Publishes system snapshots on D-Bus as `io.github.ryzeon_dev.Sysutil`, at `/io/github/ryzeon_dev/Sysutil`
+///
+/// A snapshot is captured every `interval` (on top of the ~1 second capture itself), the `Snapshot` method returns
+/// the last one as JSON, usage values are exposed as properties with `PropertiesChanged` signals and
+/// the `Updated` signal follows every capture.
+/// Blocks the calling thread, only returns when the bus connection fails.
+/// Publishing on the system bus needs a policy file allowing to own the name
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use sysutil::dbus::DbusBus;
+///
+/// sysutil::exportDbus(DbusBus::Session, Duration::from_secs(5)).unwrap();
+/// ```
+pub fn exportDbus(bus: DbusBus, interval: Duration) -> zbus::Result<()> {
+    linuxCheck();
+
+    let builder = match bus {
+        DbusBus::Session => zbus::blocking::connection::Builder::session()?,
+        DbusBus::System => zbus::blocking::connection::Builder::system()?
+    };
+
+    let connection = builder.name(DBUS_NAME)?.serve_at(DBUS_PATH, SnapshotService::default())?.build()?;
+    let interface = connection.object_server().interface::<_, SnapshotService>(DBUS_PATH)?;
+
+    loop {
+        let snapshot = SystemSnapshot::capture();
+        let mut changed = HashMap::<&str, Value>::new();
+
+        {
+            let mut service = interface.get_mut();
+            service.snapshot = snapshot.toJson().toString();
+
+            for (name, value) in propertyValues(&snapshot) {
+                if service.values.insert(name, value) != Some(value) {
+                    changed.insert(name, Value::from(value));
+                }
+            }
+        }
+
+        if !changed.is_empty() {
+            connection.emit_signal(
+                None::<BusName>, DBUS_PATH, "org.freedesktop.DBus.Properties", "PropertiesChanged",
+                &(DBUS_NAME, changed, Vec::<&str>::new())
+            )?;
+        }
+
+        connection.emit_signal(None::<BusName>, DBUS_PATH, DBUS_NAME, "Updated", &())?;
+        thread::sleep(interval);
+    }
+}
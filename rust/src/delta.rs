@@ -0,0 +1,181 @@
+use rsjson::{Json, Node, NodeContent};
+use crate::snapshot::SystemSnapshot;
+
+// compares two values member by member, floats by their bits so that unchanged readings never show up as changed
+fn sameContent(first: &NodeContent, second: &NodeContent) -> bool {
+    match (first, second) {
+        (NodeContent::String(first), NodeContent::String(second)) => first == second,
+        (NodeContent::Int(first), NodeContent::Int(second)) => first == second,
+        (NodeContent::Float(first), NodeContent::Float(second)) => first.to_bits() == second.to_bits(),
+        (NodeContent::Bool(first), NodeContent::Bool(second)) => first == second,
+        (NodeContent::Null, NodeContent::Null) => true,
+        (NodeContent::List(first), NodeContent::List(second)) => {
+            first.len() == second.len() && first.iter().zip(second).all(|(first, second)| sameContent(first, second))
+        },
+        (NodeContent::Json(first), NodeContent::Json(second)) => isEmpty(&mergePatch(first, second)) && isEmpty(&mergePatch(second, first)),
+        _ => false
+    }
+}
+
+fn isEmpty(json: &Json) -> bool {
+    return json.getAllNodes().is_empty();
+}
+
+fn findNode(json: &Json, label: &str) -> Option<NodeContent> {
+    return json.getAllNodes().into_iter().find(|node| node.getLabel() == label).map(|node| node.getContent());
+}
+
+/// Returns the JSON merge patch (RFC 7386) turning `before` into `after`, an empty object if nothing changed
+///
+/// Changed members are included with their new value, removed members as `null`, nested objects only with their
+/// changed members. Lists are replaced as a whole, as merge patches can not address list elements
+pub fn mergePatch(before: &Json, after: &Json) -> Json {
+    let mut patch = Json::new();
+
+    for node in after.getAllNodes() {
+        let label = node.getLabel();
+        let content = node.getContent();
+
+        match (findNode(before, &label), content) {
+            (Some(NodeContent::Json(previous)), NodeContent::Json(current)) => {
+                let nested = mergePatch(&previous, &current);
+
+                if !isEmpty(&nested) {
+                    patch.addNode(Node::new(label, NodeContent::Json(nested)));
+                }
+            },
+            (Some(previous), content) if sameContent(&previous, &content) => {},
+            (_, content) => patch.addNode(Node::new(label, content))
+        }
+    }
+
+    for node in before.getAllNodes() {
+        if findNode(after, &node.getLabel()).is_none() {
+            patch.addNode(Node::new(node.getLabel(), NodeContent::Null));
+        }
+    }
+
+    return patch;
+}
+
+/// Applies a JSON merge patch (RFC 7386) to `target`, the inverse of `mergePatch()`
+///
+/// `null` members of the patch remove the member from `target`, so a `null` value and a missing member are the same
+/// once patched, as the RFC specifies
+pub fn applyMergePatch(target: &Json, patch: &Json) -> Json {
+    let mut patched = Json::new();
+
+    for node in target.getAllNodes() {
+        let label = node.getLabel();
+
+        match findNode(patch, &label) {
+            None => patched.addNode(node),
+            Some(NodeContent::Null) => {},
+            Some(NodeContent::Json(nested)) => {
+                let current = match node.getContent() {
+                    NodeContent::Json(current) => current,
+                    _ => Json::new()
+                };
+
+                patched.addNode(Node::new(label, NodeContent::Json(applyMergePatch(&current, &nested))));
+            },
+            Some(content) => patched.addNode(Node::new(label, content))
+        }
+    }
+
+    for node in patch.getAllNodes() {
+        let label = node.getLabel();
+
+        if findNode(target, &label).is_some() {
+            continue;
+        }
+
+        match node.getContent() {
+            NodeContent::Null => {},
+            NodeContent::Json(nested) => patched.addNode(Node::new(label, NodeContent::Json(applyMergePatch(&Json::new(), &nested)))),
+            content => patched.addNode(Node::new(label, content))
+        }
+    }
+
+    return patched;
+}
+
+/// Turns a stream of snapshots into a full document followed by merge patches of the changed fields only
+///
+/// The first encoded snapshot is sent whole, every later one as the `mergePatch()` from the previous one, which keeps
+/// mostly static data such as hardware inventory out of the stream. Receivers start from an empty object and
+/// `applyMergePatch()` every message, since the full document is also a valid patch of an empty object
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use sysutil::delta::DeltaEncoder;
+/// use sysutil::monitor::Monitor;
+///
+/// let monitor = Monitor::new(Duration::from_secs(5));
+/// let mut encoder = DeltaEncoder::new();
+///
+/// monitor.onUpdate(move |snapshot| {
+///     println!("{}", encoder.encode(snapshot).toString());
+/// });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DeltaEncoder {
+    previous: Option<Json>
+}
+
+impl DeltaEncoder {
+    pub fn new() -> DeltaEncoder {
+        return DeltaEncoder {
+            previous: None
+        };
+    }
+
+    /// Returns the whole `toJson()` of `snapshot` on the first call and after `reset()`, the merge patch from the
+    /// previously encoded snapshot otherwise
+    pub fn encode(&mut self, snapshot: &SystemSnapshot) -> Json {
+        let current = snapshot.toJson();
+
+        let encoded = match &self.previous {
+            Some(previous) => mergePatch(previous, &current),
+            None => current.clone()
+        };
+
+        self.previous = Some(current);
+        return encoded;
+    }
+
+    /// Makes the next `encode()` send the full document again, e.g. when a new receiver connects
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Whether the next `encode()` sends the full document
+    pub fn isReset(&self) -> bool {
+        return self.previous.is_none();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roundTripsMergePatch() {
+        let before = Json::fromString(
+            r#"{"cpu": {"model": "Ryzen", "usage": 12.5, "cores": 8}, "ram": {"usage": 40.0}, "battery": {"capacity": 80}}"#
+        ).unwrap();
+        let after = Json::fromString(
+            r#"{"cpu": {"model": "Ryzen", "usage": 30.0, "cores": 8}, "ram": {"usage": 40.0}, "ipv4": ["10.0.0.2"]}"#
+        ).unwrap();
+
+        let patch = mergePatch(&before, &after);
+        assert_eq!(patch.getAllNodes().len(), 3);
+        assert!(matches!(findNode(&patch, "cpu"), Some(NodeContent::Json(cpu)) if cpu.getAllNodes().len() == 1));
+        assert!(matches!(findNode(&patch, "battery"), Some(NodeContent::Null)));
+        assert!(findNode(&patch, "ram").is_none());
+
+        let patched = applyMergePatch(&before, &patch);
+        assert!(isEmpty(&mergePatch(&patched, &after)) && isEmpty(&mergePatch(&after, &patched)));
+        assert!(isEmpty(&mergePatch(&after, &after)));
+    }
+}
@@ -1,9 +1,13 @@
-use std::{fs, path};
-use std::fs::read_dir;
+use std::{cmp, fmt, fs, path, thread};
+#[cfg(feature = "gpu-tuning")]
+use std::io;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
 use crate::utils::{*};
 
 /// Encloses gpu metrics parameters
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GpuMetrics {
     pub temperatureEdge: u16,
     pub temperatureHotspot: u16,
@@ -12,16 +16,18 @@ pub struct GpuMetrics {
     pub temperatureVrsoc: u16,
     pub temperatureVrmem: u16,
     pub averageSocketPower: u16,
-    pub averageGfxclkFrequency: u16,
-    pub averageSockclkFrequency: u16,
-    pub averageUclkFrequency: u16,
-    pub currentGfxclk: u16,
-    pub currentSockclk: u16,
-    pub currentUclk: u16,
-    pub currentVclk0: u16,
-    pub currentDclk0: u16,
-    pub currentVclk1: u16,
-    pub currentDclk1: u16,
+    /// Average activity of the multimedia engines (VCN, or UVD and VCE on older GPUs), in percentage
+    pub averageMultimediaActivity: u16,
+    pub averageGfxclkFrequency: Frequency,
+    pub averageSockclkFrequency: Frequency,
+    pub averageUclkFrequency: Frequency,
+    pub currentGfxclk: Frequency,
+    pub currentSockclk: Frequency,
+    pub currentUclk: Frequency,
+    pub currentVclk0: Frequency,
+    pub currentDclk0: Frequency,
+    pub currentVclk1: Frequency,
+    pub currentDclk1: Frequency,
     pub throttleStatus: u32,
     pub currentFanSpeed: u16,
     pub pcieLinkWidth: u16,
@@ -37,100 +43,424 @@ pub fn gpuUsage() -> Option<f32> {
     return Some(gpuUsage);
 }
 
-/// Returns metrics parameters from the amdgpu driver
-pub fn gpuMetrics() -> Option<GpuMetrics> {
+/// Contains the busy percentage of each GPU engine, summed over every DRM client
+///
+/// Engines not reported by the driver are `None`, values can exceed 100 when an engine has several rings
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpuEngineUsage {
+    pub graphics: Option<f32>,
+    pub compute: Option<f32>,
+    pub decode: Option<f32>,
+    pub encode: Option<f32>,
+    /// Every engine as named by the driver, e.g. `gfx`, `dec`, `enc_1`
+    pub engines: HashMap<String, f32>
+}
+
+// engine busy time in nanoseconds for each DRM client, keyed by client id so that duplicated fds are counted once
+fn engineTimes() -> HashMap<String, HashMap<String, u64>> {
+    let mut clients = HashMap::<String, HashMap<String, u64>>::new();
+
+    let Ok(processes) = fs::read_dir(sysPath("/proc")) else {
+        return clients;
+    };
+
+    for process in processes.flatten() {
+        let Ok(descriptors) = fs::read_dir(process.path().join("fdinfo")) else {
+            continue;
+        };
+
+        for descriptor in descriptors.flatten() {
+            let content = readFile(descriptor.path());
+            if !content.contains("drm-client-id") {
+                continue;
+            }
+
+            let mut device = String::new();
+            let mut clientId = String::new();
+            let mut engines = HashMap::<String, u64>::new();
+
+            for line in content.lines() {
+                let Some((key, value)) = line.split_once(':') else {
+                    continue;
+                };
+
+                // client ids are only unique per device
+                if key == "drm-pdev" {
+                    device = value.trim().to_string();
+
+                } else if key == "drm-client-id" {
+                    clientId = value.trim().to_string();
+
+                } else if let Some(engine) = key.strip_prefix("drm-engine-") {
+                    // drm-engine-capacity-<engine> holds the number of rings, not a time
+                    if engine.starts_with("capacity-") {
+                        continue;
+                    }
+
+                    if let Some(Ok(time)) = value.trim().strip_suffix("ns").map(|time| time.trim().parse::<u64>()) {
+                        engines.insert(engine.to_string(), time);
+                    }
+                }
+            }
+
+            clients.insert(format!("{}/{}", device, clientId), engines);
+        }
+    }
+
+    return clients;
+}
+
+/// Returns the busy percentage of each GPU engine (graphics, compute, video decode and encode), sampled over 500 ms
+///
+/// Data comes from DRM fdinfo, so only clients of processes readable by the current user are accounted;
+/// returns `None` if no DRM client is found
+pub fn gpuEngineUsage() -> Option<GpuEngineUsage> {
     linuxCheck();
 
-    let filePipe = fs::read(path::Path::new("/sys/class/drm/card0/device/gpu_metrics"));
+    let start = Instant::now();
+    let before = engineTimes();
+    thread::sleep(Duration::from_millis(500));
+    let after = engineTimes();
+    let elapsed = start.elapsed().as_nanos() as f32;
 
-    let mut bytes: Vec<u8> = Vec::<u8>::new();
-    let mut error = false;
+    if after.is_empty() {
+        return None;
+    }
 
-    match filePipe {
-        Err(_) => {
-            error = true;
-        },
-        Ok(bytesPipe) => {
-            bytes = bytesPipe;
+    let mut engines = HashMap::<String, f32>::new();
+    for (client, times) in &after {
+        for (engine, time) in times {
+            // clients which appeared during the sample only count from their first reading on
+            let previous = before.get(client).and_then(|times| times.get(engine)).copied().unwrap_or(*time);
+            *engines.entry(engine.clone()).or_insert(0_f32) += time.saturating_sub(previous) as f32;
         }
+    }
+
+    for busy in engines.values_mut() {
+        *busy = *busy * 100_f32 / elapsed;
+    }
+
+    // video engines may be split in several instances, e.g. `enc` and `enc_1`
+    let family = |name: &str| -> Option<f32> {
+        let mut total = None;
+
+        for (engine, busy) in &engines {
+            if engine == name || engine.starts_with(&format!("{}_", name)) {
+                total = Some(total.unwrap_or(0_f32) + busy);
+            }
+        }
+
+        total
     };
 
-    if error {
-        return None;
+    return Some(GpuEngineUsage {
+        graphics: family("gfx").or(family("render")),
+        compute: family("compute"),
+        decode: family("dec"),
+        encode: family("enc"),
+        engines: engines
+    });
+}
+
+/// GPU compute stack used by a process
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ComputeRuntime {
+    /// AMD ROCm / HIP, through `/dev/kfd`
+    Rocm,
+    /// NVIDIA CUDA, through `/dev/nvidia-uvm`
+    Cuda,
+    /// Compute work submitted through a DRM render node, e.g. OpenCL or Vulkan compute
+    Drm
+}
+
+impl ComputeRuntime {
+    pub fn toString(&self) -> String {
+        match self {
+            ComputeRuntime::Rocm => String::from("ROCm"),
+            ComputeRuntime::Cuda => String::from("CUDA"),
+            ComputeRuntime::Drm => String::from("DRM")
+        }
     }
+}
 
-    let format = bytes[2];
-    let content = bytes[3];
+impl fmt::Display for ComputeRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains a process using the GPU for compute
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComputeProcess {
+    pub pid: u32,
+    pub name: String,
+    pub runtime: ComputeRuntime,
+    /// VRAM allocated by the process, `None` when the driver does not report it (e.g. proprietary NVIDIA)
+    pub vram: Option<ByteSize>
+}
 
-    bytes = bytes[4..].to_vec();
+impl fmt::Display for ComputeProcess {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}) {}", self.name, self.pid, self.runtime)?;
 
-    if format != 1 {
-        return None;
+        match &self.vram {
+            Some(vram) => write!(f, ", {} VRAM", vram),
+            None => Ok(())
+        }
     }
+}
 
-    Some(
-        GpuMetrics {
-            temperatureEdge: match content {
-                0 => bytesToU16(bytes[8..10].to_vec()),
-                _ => bytesToU16(bytes[0..2].to_vec())
-            },
+// parses fdinfo sizes such as `drm-memory-vram: 1024 KiB`
+fn fdinfoBytes(value: &str) -> Option<u64> {
+    let mut parts = value.split_whitespace();
+    let amount = parts.next()?.parse::<u64>().ok()?;
 
-            temperatureHotspot: match content {
-                0 => bytesToU16(bytes[10..12].to_vec()),
-                _ => bytesToU16(bytes[2..4].to_vec())
-            },
+    let multiplier = match parts.next() {
+        Some("KiB") => 1024,
+        Some("MiB") => 1024 * 1024,
+        Some("GiB") => 1024 * 1024 * 1024,
+        _ => 1
+    };
 
-            temperatureMem: match content {
-                0 => bytesToU16(bytes[12..14].to_vec()),
-                _ => bytesToU16(bytes[4..6].to_vec())
-            },
+    return Some(amount * multiplier);
+}
 
-            temperatureVrgfx: match content {
-                0 => bytesToU16(bytes[14..16].to_vec()),
-                _ => bytesToU16(bytes[6..8].to_vec())
-            },
+/// Returns the processes using a GPU for compute (ROCm, CUDA, or compute engines of DRM drivers) with their VRAM usage
+///
+/// ROCm VRAM comes from the kfd process directory, which is only readable by root, DRM clients report
+/// it through fdinfo; only processes readable by the current user are listed
+pub fn computeProcesses() -> Vec<ComputeProcess> {
+    linuxCheck();
 
-            temperatureVrsoc: match content {
-                0 => bytesToU16(bytes[16..18].to_vec()),
-                _ => bytesToU16(bytes[8..10].to_vec())
-            },
+    let mut processes = Vec::<ComputeProcess>::new();
 
-            temperatureVrmem: match content {
-                0 => bytesToU16(bytes[18..20].to_vec()),
-                _ => bytesToU16(bytes[10..12].to_vec())
-            },
+    let Ok(entries) = fs::read_dir(sysPath("/proc")) else {
+        return processes;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+
+        let Ok(descriptors) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        let mut runtime = None;
+        let mut drmVram = None::<u64>;
+        let mut seenClients = Vec::<String>::new();
+
+        for descriptor in descriptors.flatten() {
+            let target = fs::read_link(descriptor.path()).map(|target| target.to_string_lossy().to_string()).unwrap_or_default();
+
+            if target == "/dev/kfd" {
+                runtime = Some(ComputeRuntime::Rocm);
+                continue;
+            }
+
+            if target == "/dev/nvidia-uvm" {
+                runtime = runtime.or(Some(ComputeRuntime::Cuda));
+                continue;
+            }
+
+            if !target.starts_with("/dev/dri/") {
+                continue;
+            }
+
+            let info = readFile(entry.path().join("fdinfo").join(descriptor.file_name()));
+            let field = |name: &str| info.lines().find_map(|line| line.strip_prefix(name)).map(|value| value.trim().to_string());
+
+            // duplicated descriptors share the same client
+            let client = format!("{}/{}", field("drm-pdev:").unwrap_or_default(), field("drm-client-id:").unwrap_or_default());
+            if seenClients.contains(&client) {
+                continue;
+            }
+            seenClients.push(client);
+
+            if let Some(vram) = field("drm-memory-vram:").or(field("drm-total-vram0:")).and_then(|value| fdinfoBytes(&value)) {
+                drmVram = Some(drmVram.unwrap_or(0) + vram);
+            }
+
+            let computeTime = field("drm-engine-compute:")
+                .and_then(|time| time.strip_suffix("ns").and_then(|time| time.trim().parse::<u64>().ok()))
+                .unwrap_or(0);
+
+            if computeTime > 0 {
+                runtime = runtime.or(Some(ComputeRuntime::Drm));
+            }
+        }
+
+        let Some(runtime) = runtime else {
+            continue;
+        };
+
+        let vram = match runtime {
+            // one vram_<gpu id> file per GPU used by the process
+            ComputeRuntime::Rocm => fs::read_dir(sysPath(format!("/sys/class/kfd/kfd/proc/{}", pid))).ok().map(|files| {
+                files.flatten()
+                    .filter(|file| file.file_name().to_string_lossy().starts_with("vram_"))
+                    .map(|file| readFile(file.path()).parse::<u64>().unwrap_or(0))
+                    .sum::<u64>()
+            }).or(drmVram),
+            _ => drmVram
+        };
+
+        processes.push(ComputeProcess {
+            pid: pid,
+            name: readFile(entry.path().join("comm")),
+            runtime: runtime,
+            vram: vram.map(ByteSize::fromBytes)
+        });
+    }
+
+    return processes;
+}
+
+/// Error returned when the gpu_metrics blob cannot be read or decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GpuMetricsError {
+    /// The gpu_metrics file does not exist or cannot be read
+    Unavailable,
+    /// The blob is shorter than the layout of its version requires
+    Truncated { expected: usize, actual: usize },
+    /// The format revision is not supported
+    UnsupportedVersion { format: u8, content: u8 }
+}
 
-            averageSocketPower: match content {
-                0 => bytesToU16(bytes[26..28].to_vec()),
-                _ => bytesToU16(bytes[18..20].to_vec())
+impl fmt::Display for GpuMetricsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GpuMetricsError::Unavailable => write!(f, "gpu_metrics is not available"),
+            GpuMetricsError::Truncated { expected, actual } => {
+                write!(f, "gpu_metrics is truncated: expected {} bytes, got {}", expected, actual)
             },
+            GpuMetricsError::UnsupportedVersion { format, content } => {
+                write!(f, "unsupported gpu_metrics version {}.{}", format, content)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GpuMetricsError {}
+
+// header (structure size, format and content revisions) followed by the fields read below
+const GPU_METRICS_HEADER_SIZE: usize = 4;
+const GPU_METRICS_MINIMUM_SIZE: usize = GPU_METRICS_HEADER_SIZE + 74;
+
+/// Decodes a gpu_metrics blob, as exposed by the amdgpu driver in sysfs
+///
+/// Versions 1.0 to 1.3 are supported, other versions return `GpuMetricsError::UnsupportedVersion`
+pub fn parseGpuMetrics(blob: &[u8]) -> Result<GpuMetrics, GpuMetricsError> {
+    if blob.len() < GPU_METRICS_HEADER_SIZE {
+        return Err(GpuMetricsError::Truncated { expected: GPU_METRICS_HEADER_SIZE, actual: blob.len() });
+    }
+
+    let format = blob[2];
+    let content = blob[3];
+
+    // content revisions 0 to 3 share the fields read here, while 1.4 and 1.5 (MI300) start from a different layout,
+    // without the edge and voltage regulator temperatures
+    if format != 1 || content > 3 {
+        traceEvent!(debug, format = format, content = content, "unsupported gpu_metrics format");
+        return Err(GpuMetricsError::UnsupportedVersion { format, content });
+    }
+
+    let structureSize = u16::from_le_bytes([blob[0], blob[1]]) as usize;
+    let expected = GPU_METRICS_MINIMUM_SIZE.max(structureSize);
+
+    if blob.len() < expected {
+        return Err(GpuMetricsError::Truncated { expected, actual: blob.len() });
+    }
+
+    let bytes = &blob[GPU_METRICS_HEADER_SIZE..];
+    let u16At = |offset: usize| -> Result<u16, GpuMetricsError> {
+        match bytes.get(offset..offset + 2) {
+            Some(slice) => Ok(bytesToU16(slice.to_vec())),
+            None => Err(GpuMetricsError::Truncated { expected: GPU_METRICS_HEADER_SIZE + offset + 2, actual: blob.len() })
+        }
+    };
+    let u32At = |offset: usize| -> Result<u32, GpuMetricsError> {
+        match bytes.get(offset..offset + 4) {
+            Some(slice) => Ok(bytesToU32(slice.to_vec())),
+            None => Err(GpuMetricsError::Truncated { expected: GPU_METRICS_HEADER_SIZE + offset + 4, actual: blob.len() })
+        }
+    };
+    let frequencyAt = |offset: usize| -> Result<Frequency, GpuMetricsError> {
+        Ok(Frequency::fromMhz(u16At(offset)? as usize))
+    };
+
+    // revision 0 has an additional 64 bit system clock counter before the temperatures
+    let shift = match content {
+        0 => 8,
+        _ => 0
+    };
+
+    Ok(
+        GpuMetrics {
+            temperatureEdge: u16At(shift)?,
+            temperatureHotspot: u16At(shift + 2)?,
+            temperatureMem: u16At(shift + 4)?,
+            temperatureVrgfx: u16At(shift + 6)?,
+            temperatureVrsoc: u16At(shift + 8)?,
+            temperatureVrmem: u16At(shift + 10)?,
+            averageSocketPower: u16At(shift + 18)?,
+            averageMultimediaActivity: u16At(shift + 16)?,
 
-            averageGfxclkFrequency: bytesToU16(bytes[36..38].to_vec()),
-            averageSockclkFrequency: bytesToU16(bytes[38..40].to_vec()),
-            averageUclkFrequency: bytesToU16(bytes[40..42].to_vec()),
+            averageGfxclkFrequency: frequencyAt(36)?,
+            averageSockclkFrequency: frequencyAt(38)?,
+            averageUclkFrequency: frequencyAt(40)?,
 
-            currentGfxclk: bytesToU16(bytes[50..52].to_vec()),
-            currentSockclk: bytesToU16(bytes[52..54].to_vec()),
-            currentUclk: bytesToU16(bytes[54..56].to_vec()),
-            currentVclk0: bytesToU16(bytes[56..58].to_vec()),
-            currentDclk0: bytesToU16(bytes[58..60].to_vec()),
-            currentVclk1: bytesToU16(bytes[60..62].to_vec()),
-            currentDclk1: bytesToU16(bytes[62..64].to_vec()),
+            currentGfxclk: frequencyAt(50)?,
+            currentSockclk: frequencyAt(52)?,
+            currentUclk: frequencyAt(54)?,
+            currentVclk0: frequencyAt(56)?,
+            currentDclk0: frequencyAt(58)?,
+            currentVclk1: frequencyAt(60)?,
+            currentDclk1: frequencyAt(62)?,
 
-            throttleStatus: bytesToU32(bytes[64..68].to_vec()),
-            currentFanSpeed: bytesToU16(bytes[68..70].to_vec()),
-            pcieLinkWidth: bytesToU16(bytes[70..72].to_vec()),
-            pcieLinkSpeed: bytesToU16(bytes[72..74].to_vec())
+            throttleStatus: u32At(64)?,
+            currentFanSpeed: u16At(68)?,
+            pcieLinkWidth: u16At(70)?,
+            pcieLinkSpeed: u16At(72)?
         }
     )
 }
 
+/// Returns metrics parameters from the amdgpu driver, reporting why they are not available
+pub fn tryGpuMetrics() -> Result<GpuMetrics, GpuMetricsError> {
+    linuxCheck();
+    return readGpuMetrics(path::Path::new("/sys/class/drm/card0/device"));
+}
+
+// reads the metrics of the card whose sysfs device directory is `device`
+fn readGpuMetrics(device: &path::Path) -> Result<GpuMetrics, GpuMetricsError> {
+    let blob = match fs::read(sysPath(device.join("gpu_metrics"))) {
+        Ok(blob) => blob,
+        Err(_error) => {
+            traceEvent!(debug, error = %_error, "failed to read gpu_metrics");
+            return Err(GpuMetricsError::Unavailable);
+        }
+    };
+
+    return parseGpuMetrics(&blob);
+}
+
+/// Returns metrics parameters from the amdgpu driver
+pub fn gpuMetrics() -> Option<GpuMetrics> {
+    return tryGpuMetrics().ok();
+}
+
 /// Contains all information about VRAM
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VRAM {
     pub size: Option<ByteSize>,
     pub usage: Option<f32>,
-    pub frequency: Option<usize>,
+    pub frequency: Option<Frequency>,
     pub busWidth: Option<usize>
 }
 
@@ -145,12 +475,31 @@ impl VRAM {
     }
 }
 
+impl fmt::Display for VRAM {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.size, self.usage) {
+            (Some(size), Some(usage)) => write!(f, "{}, {:.1}% used", size, usage)?,
+            (Some(size), None) => write!(f, "{}", size)?,
+            _ => write!(f, "unknown")?
+        }
+
+        if let Some(frequency) = self.frequency {
+            write!(f, " @ {}", frequency)?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Returns gpu's vram size as specified in `ByteSize` struct, returns `None` if it's not possible to retrieve data
 pub fn vramSize() -> Option<ByteSize> {
     linuxCheck();
+    return readVramSize(path::Path::new("/sys/class/drm/card0/device"));
+}
 
-    let fileContent = readFile("/sys/class/drm/card0/device/mem_info_vram_total");
-    match fileContent.parse::<usize>() {
+fn readVramSize(device: &path::Path) -> Option<ByteSize> {
+    let fileContent = readFile(device.join("mem_info_vram_total"));
+    match fileContent.parse::<u64>() {
         Err(_) => {
             return None
         },
@@ -163,26 +512,29 @@ pub fn vramSize() -> Option<ByteSize> {
 /// Returns gpu's vram usage in percentage, returns `None` if it's not possible to retrieve data
 pub fn vramUsage() -> Option<f32> {
     linuxCheck();
+    return readVramUsage(path::Path::new("/sys/class/drm/card0/device"));
+}
 
-    let vramTotal = readFile("/sys/class/drm/card0/device/mem_info_vram_total");
-    let vramUsed = readFile("/sys/class/drm/card0/device/mem_info_vram_used");
+fn readVramUsage(device: &path::Path) -> Option<f32> {
+    let vramTotal = readFile(device.join("mem_info_vram_total")).parse::<usize>().ok()?;
+    let vramUsed = readFile(device.join("mem_info_vram_used")).parse::<usize>().ok()?;
 
-    if vramTotal.is_empty() || vramUsed.is_empty() {
+    if vramTotal == 0 {
         return None;
     }
 
-    let uVramTotal: usize = vramTotal.parse::<usize>().unwrap();
-    let uVramUsed: usize = vramUsed.parse::<usize>().unwrap();
-
-    return Some(uVramUsed as f32 * 100_f32 / uVramTotal as f32);
+    return Some(vramUsed as f32 * 100_f32 / vramTotal as f32);
 }
 
-/// Returns VRAM frequency in MT/s
-pub fn vramFrequency() -> Option<usize> {
+/// Returns VRAM maximum memory clock as reported by the kfd topology
+pub fn vramFrequency() -> Option<Frequency> {
     let kfdTopologyNodes = "/sys/class/kfd/kfd/topology/nodes/";
-    for dir in read_dir(kfdTopologyNodes).unwrap() {
-        let path = dir.unwrap().path();
-        let directory = path.to_str().unwrap();
+    // the kfd topology only exists with the amdgpu driver loaded
+    for dir in tryReadDir(kfdTopologyNodes).unwrap_or_default() {
+        let path = dir.path();
+        let Some(directory) = path.to_str() else {
+            continue
+        };
 
         let content = readFile(format!("{}/properties", directory));
         let mut isGpu = false;
@@ -192,7 +544,7 @@ pub fn vramFrequency() -> Option<usize> {
                 let splitedLine = line.split(" ").collect::<Vec<&str>>();
                 match splitedLine.last() {
                     Some(cores) => {
-                        if cores.parse::<usize>().unwrap() != 0 {
+                        if cores.parse::<usize>().unwrap_or(0) != 0 {
                             isGpu = true;
                         }
                     },
@@ -219,13 +571,9 @@ pub fn vramFrequency() -> Option<usize> {
                 }
             }
 
-            let frequency = {
-                let binding = frequencyLine.split(" ").collect::<Vec<&str>>();
-                let last = binding.last().unwrap();
-                last.parse::<usize>().unwrap()
-            };
+            let frequency = tryParse::<usize, _>(kfdTopologyNodes, frequencyLine.split(" ").last()?).ok()?;
 
-            return Some(frequency);
+            return Some(Frequency::fromMhz(frequency));
         }
     }
 
@@ -235,9 +583,12 @@ pub fn vramFrequency() -> Option<usize> {
 /// Returns VRAM bus width in bits
 pub fn vramBusWidth() -> Option<usize> {
     let kfdTopologyNodes = "/sys/class/kfd/kfd/topology/nodes/";
-    for dir in read_dir(kfdTopologyNodes).unwrap() {
-        let path = dir.unwrap().path();
-        let directory = path.to_str().unwrap();
+    // the kfd topology only exists with the amdgpu driver loaded
+    for dir in tryReadDir(kfdTopologyNodes).unwrap_or_default() {
+        let path = dir.path();
+        let Some(directory) = path.to_str() else {
+            continue
+        };
 
         let content = readFile(format!("{}/properties", directory));
         let mut isGpu = false;
@@ -247,7 +598,7 @@ pub fn vramBusWidth() -> Option<usize> {
                 let splitedLine = line.split(" ").collect::<Vec<&str>>();
                 match splitedLine.last() {
                     Some(cores) => {
-                        if cores.parse::<usize>().unwrap() != 0 {
+                        if cores.parse::<usize>().unwrap_or(0) != 0 {
                             isGpu = true;
                         }
                     },
@@ -274,15 +625,714 @@ pub fn vramBusWidth() -> Option<usize> {
                 }
             }
 
-            let width = {
-                let binding = widthLine.split(" ").collect::<Vec<&str>>();
-                let last = binding.last().unwrap();
-                last.parse::<usize>().unwrap()
-            };
+            let width = tryParse::<usize, _>(kfdTopologyNodes, widthLine.split(" ").last()?).ok()?;
 
             return Some(width);
         }
     }
 
     return None;
-}
\ No newline at end of file
+}
+
+/// Contains a temperature channel of a GPU, labelled as by the driver (e.g. `edge`, `junction`, `mem`)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpuTemperature {
+    pub label: String,
+    pub temperature: Option<f32>,
+    pub critical: Option<f32>
+}
+
+impl fmt::Display for GpuTemperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.temperature {
+            Some(temperature) => write!(f, "{}: {:.1} °C", self.label, temperature),
+            None => write!(f, "{}: unknown", self.label)
+        }
+    }
+}
+
+/// Contains the hwmon sensors belonging to a single GPU
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpuSensors {
+    /// DRM card name, e.g. `card0`
+    pub card: String,
+    pub pciAddress: String,
+    pub driver: String,
+    pub temperatures: Vec<GpuTemperature>,
+    /// Power draw in watts
+    pub power: Option<f32>,
+    /// Power limit in watts
+    pub powerCap: Option<f32>,
+    pub fanRpm: Option<usize>
+}
+
+impl GpuSensors {
+    /// Returns the temperature channel with the specified label
+    pub fn temperature(&self, label: &str) -> Option<f32> {
+        return self.temperatures.iter().find(|channel| channel.label == label)?.temperature;
+    }
+}
+
+impl fmt::Display for GpuSensors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({} {})", self.card, self.driver, self.pciAddress)?;
+
+        for channel in &self.temperatures {
+            write!(f, ", {}", channel)?;
+        }
+
+        if let Some(power) = self.power {
+            write!(f, ", {:.1} W", power)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn readGpuHwmon(hwmon: &path::Path) -> (Vec<GpuTemperature>, Option<f32>, Option<f32>, Option<usize>) {
+    let mut temperatures = Vec::<GpuTemperature>::new();
+
+    let millidegrees = |name: String| readFile(hwmon.join(name)).parse::<f32>().ok().map(|value| value / 1000_f32);
+    let microwatts = |name: &str| readFile(hwmon.join(name)).parse::<f32>().ok().map(|value| value / 1_000_000_f32);
+
+    for index in 1.. {
+        let input = hwmon.join(format!("temp{}_input", index));
+        if !input.exists() {
+            break;
+        }
+
+        let label = readFile(hwmon.join(format!("temp{}_label", index)));
+        temperatures.push(GpuTemperature {
+            label: match label.is_empty() {
+                true => format!("temp{}", index),
+                false => label
+            },
+            temperature: millidegrees(format!("temp{}_input", index)),
+            critical: millidegrees(format!("temp{}_crit", index))
+        });
+    }
+
+    // older kernels only expose the average power, newer ones only the instantaneous one
+    let power = microwatts("power1_average").or(microwatts("power1_input"));
+    let powerCap = microwatts("power1_cap");
+    let fanRpm = readFile(hwmon.join("fan1_input")).parse::<usize>().ok();
+
+    return (temperatures, power, powerCap, fanRpm);
+}
+
+// name of the target of a sysfs link, e.g. the PCI address of a `device` link
+fn linkName(link: &path::Path) -> String {
+    return fs::read_link(link).ok()
+        .and_then(|target| target.file_name().map(|name| name.to_string_lossy().to_string()))
+        .unwrap_or_default();
+}
+
+// orders DRM cards by their index, so that `card10` comes after `card2`
+fn compareCards(first: &str, second: &str) -> cmp::Ordering {
+    let index = |card: &str| card.strip_prefix("card").and_then(|index| index.parse::<usize>().ok());
+    return index(first).cmp(&index(second)).then_with(|| first.cmp(second));
+}
+
+/// Returns the hwmon sensors of every GPU, each attributed to its DRM card
+pub fn gpuSensors() -> Vec<GpuSensors> {
+    linuxCheck();
+
+    let mut gpus = Vec::<GpuSensors>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/class/drm")) else {
+        return gpus;
+    };
+
+    for entry in entries.flatten() {
+        let card = entry.file_name().to_string_lossy().to_string();
+
+        // connectors are named `cardN-<connector>`
+        if !card.starts_with("card") || card.contains('-') {
+            continue;
+        }
+
+        let device = entry.path().join("device");
+        let Some(hwmon) = fs::read_dir(device.join("hwmon")).ok()
+            .and_then(|mut hwmons| hwmons.find_map(|hwmon| hwmon.ok())) else {
+            continue;
+        };
+
+        let (temperatures, power, powerCap, fanRpm) = readGpuHwmon(&hwmon.path());
+        gpus.push(GpuSensors {
+            pciAddress: linkName(&device),
+            driver: linkName(&device.join("driver")),
+            card: card,
+            temperatures: temperatures,
+            power: power,
+            powerCap: powerCap,
+            fanRpm: fanRpm
+        });
+    }
+
+    gpus.sort_by(|first, second| compareCards(&first.card, &second.card));
+    return gpus;
+}
+
+/// Contains usage, VRAM, metrics and connected displays of a single GPU, as enumerated by `gpus()`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Gpu {
+    /// DRM card name, e.g. `card1`
+    pub card: String,
+    pub pciAddress: String,
+    pub driver: String,
+    /// Busy percentage, `None` when the driver does not report it
+    pub usage: Option<f32>,
+    pub vram: VRAM,
+    /// amdgpu metrics, `None` for other drivers
+    pub metrics: Option<GpuMetrics>,
+    /// Connected DRM connectors, e.g. `DP-1`, `HDMI-A-1`
+    pub displays: Vec<String>
+}
+
+impl fmt::Display for Gpu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({} {}): VRAM {}", self.card, self.driver, self.pciAddress, self.vram)?;
+
+        if let Some(usage) = self.usage {
+            write!(f, ", {:.1}% busy", usage)?;
+        }
+
+        if !self.displays.is_empty() {
+            write!(f, ", displays {}", self.displays.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+// reads `<property> <value>` lines of a kfd topology `properties` file
+fn kfdProperty(properties: &path::Path, property: &str) -> Option<usize> {
+    return readFile(properties).lines()
+        .find_map(|line| line.strip_prefix(property)?.strip_prefix(' '))
+        .and_then(|value| value.trim().parse::<usize>().ok());
+}
+
+// kfd topology node of a card, matched through its render node, e.g. `drm_render_minor 128` for `renderD128`
+fn kfdNode(device: &path::Path) -> Option<path::PathBuf> {
+    let minor = tryReadDir(device.join("drm")).ok()?.iter()
+        .find_map(|entry| entry.file_name().to_string_lossy().strip_prefix("renderD")?.parse::<usize>().ok())?;
+
+    return tryReadDir("/sys/class/kfd/kfd/topology/nodes").ok()?.iter()
+        .map(|node| node.path())
+        .find(|node| kfdProperty(&node.join("properties"), "drm_render_minor") == Some(minor));
+}
+
+/// Returns every GPU with its DRM card, sorted by card name
+///
+/// Unlike the other functions of this module, which read `card0` only, every card is read; VRAM clock and bus width
+/// come from the kfd topology and are `None` for cards without a kfd node, e.g. non AMD GPUs
+/// ## Example
+/// ```rust,no_run
+/// for gpu in sysutil::gpu::gpus() {
+///     println!("{}", gpu);
+/// }
+/// ```
+pub fn gpus() -> Vec<Gpu> {
+    linuxCheck();
+
+    let entries = tryReadDir("/sys/class/drm").unwrap_or_default();
+    let names = entries.iter().map(|entry| entry.file_name().to_string_lossy().to_string()).collect::<Vec<String>>();
+
+    let mut gpus = Vec::<Gpu>::new();
+
+    for (entry, card) in entries.iter().zip(&names) {
+        // connectors are named `cardN-<connector>`
+        if !card.starts_with("card") || card.contains('-') {
+            continue;
+        }
+
+        let device = entry.path().join("device");
+        let memoryBank = kfdNode(&device).map(|node| node.join("mem_banks/0/properties"));
+
+        let displays = entries.iter().zip(&names)
+            .filter_map(|(connector, name)| {
+                let connectorName = name.strip_prefix(&format!("{}-", card))?;
+                (readFile(connector.path().join("status")) == "connected").then(|| connectorName.to_string())
+            })
+            .collect::<Vec<String>>();
+
+        gpus.push(Gpu {
+            card: card.clone(),
+            pciAddress: linkName(&device),
+            driver: linkName(&device.join("driver")),
+            usage: readFile(device.join("gpu_busy_percent")).parse::<f32>().ok(),
+            vram: VRAM {
+                size: readVramSize(&device),
+                usage: readVramUsage(&device),
+                frequency: memoryBank.as_ref().and_then(|bank| kfdProperty(bank, "mem_clk_max")).map(Frequency::fromMhz),
+                busWidth: memoryBank.as_ref().and_then(|bank| kfdProperty(bank, "width"))
+            },
+            metrics: readGpuMetrics(&device).ok(),
+            displays: displays
+        });
+    }
+
+    gpus.sort_by(|first, second| compareCards(&first.card, &second.card));
+    return gpus;
+}
+
+pub use crate::utils::PcieLink;
+
+/// Returns current and maximum PCIe link speed and width of the GPU
+///
+/// Unlike `pcieLinkSpeed` and `pcieLinkWidth` in `GpuMetrics`, which are raw driver values, speeds are in GT/s;
+/// many GPUs lower the link speed when idle, so a slower current link is not necessarily a fault
+pub fn gpuPcieLink() -> Option<PcieLink> {
+    linuxCheck();
+    return readPcieLink(&sysPath("/sys/class/drm/card0/device"));
+}
+
+/// Contains an amdgpu power profile, as listed by `pp_power_profile_mode`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerProfile {
+    pub index: usize,
+    /// Profile name as reported by the driver, e.g. `3D_FULL_SCREEN`, `VR`, `COMPUTE`
+    pub name: String,
+    pub active: bool
+}
+
+impl fmt::Display for PowerProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}{}", self.index, self.name, if self.active { " (active)" } else { "" })
+    }
+}
+
+fn parsePowerProfiles(content: &str) -> Vec<PowerProfile> {
+    let mut profiles = Vec::<PowerProfile>::new();
+
+    // profile lines are `<index> <NAME>[ ]*[*]:`, followed by the profile parameters on the same line or on the next ones
+    for line in content.lines() {
+        let Some((header, _)) = line.split_once(':') else {
+            continue;
+        };
+
+        let mut fields = header.split_whitespace();
+        let Some(Ok(index)) = fields.next().map(|index| index.parse::<usize>()) else {
+            continue;
+        };
+
+        let name = fields.collect::<Vec<&str>>().join("");
+        if name.is_empty() {
+            continue;
+        }
+
+        profiles.push(PowerProfile {
+            index: index,
+            active: name.contains('*'),
+            name: name.replace('*', "")
+        });
+    }
+
+    return profiles;
+}
+
+/// Returns the power profiles supported by the amdgpu driver, with the active one marked
+pub fn powerProfiles() -> Vec<PowerProfile> {
+    linuxCheck();
+    return parsePowerProfiles(&readFile("/sys/class/drm/card0/device/pp_power_profile_mode"));
+}
+
+/// Returns the forced DPM performance level of the GPU, e.g. `auto`, `manual`, `high`
+pub fn performanceLevel() -> Option<String> {
+    linuxCheck();
+
+    let level = readFile("/sys/class/drm/card0/device/power_dpm_force_performance_level").trim().to_string();
+    return if level.is_empty() { None } else { Some(level) };
+}
+
+/// Forces the DPM performance level of the GPU (e.g. `auto` to give control back to the driver), requires root privileges
+#[cfg(feature = "gpu-tuning")]
+pub fn setPerformanceLevel(level: &str) -> io::Result<()> {
+    linuxCheck();
+    return fs::write(sysPath("/sys/class/drm/card0/device/power_dpm_force_performance_level"), level);
+}
+
+/// Activates the power profile with the specified index, requires root privileges
+///
+/// The driver only honours profiles when the performance level is `manual`, so the level is switched to `manual` as
+/// well and the previous one is returned; pass it to `setPerformanceLevel()` to restore it
+#[cfg(feature = "gpu-tuning")]
+pub fn setPowerProfile(index: usize) -> io::Result<String> {
+    linuxCheck();
+
+    let path = sysPath("/sys/class/drm/card0/device/power_dpm_force_performance_level");
+    let previous = fs::read_to_string(&path)?.trim().to_string();
+
+    fs::write(&path, "manual")?;
+    fs::write(sysPath("/sys/class/drm/card0/device/pp_power_profile_mode"), index.to_string())?;
+
+    return Ok(previous);
+}
+
+/// Contains a point of an overdrive table, e.g. `1: 2615Mhz` or `0: 700MHz 709mV`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverdrivePoint {
+    /// `None` for single values, such as the voltage offset
+    pub index: Option<usize>,
+    pub frequency: Option<Frequency>,
+    pub voltageMv: Option<i32>
+}
+
+/// Contains the allowed range of an overdrive parameter, in MHz for clocks and in mV for voltages
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverdriveRange {
+    pub name: String,
+    pub minimum: i32,
+    pub maximum: i32,
+    pub unit: String
+}
+
+/// Contains the overdrive clock/voltage table of the amdgpu driver, as reported by `pp_od_clk_voltage`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverdriveTable {
+    /// Each section (e.g. `OD_SCLK`, `OD_MCLK`, `OD_VDDC_CURVE`, `OD_VDDGFX_OFFSET`) with its points
+    pub sections: Vec<(String, Vec<OverdrivePoint>)>,
+    pub ranges: Vec<OverdriveRange>
+}
+
+impl OverdriveTable {
+    /// Returns the points of the specified section, e.g. `OD_SCLK`
+    pub fn section(&self, name: &str) -> Option<&Vec<OverdrivePoint>> {
+        return self.sections.iter().find(|(section, _)| section == name).map(|(_, points)| points);
+    }
+
+    /// Returns the allowed range of the specified parameter, e.g. `SCLK`, `MCLK` or `VDDC`
+    pub fn range(&self, name: &str) -> Option<&OverdriveRange> {
+        return self.ranges.iter().find(|range| range.name == name);
+    }
+}
+
+// splits a value such as `2615Mhz` or `-50mV` into its number and lowercase unit
+fn splitUnit(value: &str) -> Option<(i32, String)> {
+    let position = value.find(|character: char| character.is_alphabetic())?;
+    let number = value[..position].parse::<i32>().ok()?;
+
+    return Some((number, value[position..].to_lowercase()));
+}
+
+fn parseOverdrivePoint(values: &str, index: Option<usize>) -> OverdrivePoint {
+    let mut point = OverdrivePoint {
+        index: index,
+        frequency: None,
+        voltageMv: None
+    };
+
+    for value in values.split_whitespace() {
+        match splitUnit(value) {
+            Some((number, unit)) if unit == "mhz" => point.frequency = Some(Frequency::fromMhz(number.max(0) as usize)),
+            Some((number, unit)) if unit == "mv" => point.voltageMv = Some(number),
+            _ => {}
+        }
+    }
+
+    return point;
+}
+
+fn parseOverdriveTable(content: &str) -> Option<OverdriveTable> {
+    let mut sections = Vec::<(String, Vec<OverdrivePoint>)>::new();
+    let mut ranges = Vec::<OverdriveRange>::new();
+    let mut current = String::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        // section headers are `OD_<NAME>:` alone on their line
+        if let Some(section) = line.strip_suffix(':') {
+            current = section.to_string();
+
+            if current != "OD_RANGE" {
+                sections.push((current.clone(), Vec::<OverdrivePoint>::new()));
+            }
+            continue;
+        }
+
+        if current == "OD_RANGE" {
+            let Some((name, values)) = line.split_once(':') else {
+                continue;
+            };
+
+            let values = values.split_whitespace().filter_map(splitUnit).collect::<Vec<(i32, String)>>();
+            if values.len() == 2 {
+                ranges.push(OverdriveRange {
+                    name: name.trim().to_string(),
+                    minimum: values[0].0,
+                    maximum: values[1].0,
+                    unit: match values[0].1.as_str() {
+                        "mhz" => String::from("MHz"),
+                        "mv" => String::from("mV"),
+                        unit => unit.to_string()
+                    }
+                });
+            }
+
+        } else if let Some((_, points)) = sections.last_mut() {
+            let point = match line.split_once(':') {
+                Some((index, values)) => parseOverdrivePoint(values, index.trim().parse::<usize>().ok()),
+                None => parseOverdrivePoint(line, None)
+            };
+
+            points.push(point);
+        }
+    }
+
+    if sections.is_empty() && ranges.is_empty() {
+        return None;
+    }
+
+    return Some(OverdriveTable {
+        sections: sections,
+        ranges: ranges
+    });
+}
+
+/// Returns the overdrive clock/voltage table and its limits, `None` if overdrive is not enabled
+///
+/// Overdrive must be enabled through the `amdgpu.ppfeaturemask` kernel parameter
+pub fn overdriveTable() -> Option<OverdriveTable> {
+    linuxCheck();
+    return parseOverdriveTable(&readFile("/sys/class/drm/card0/device/pp_od_clk_voltage"));
+}
+
+/// Clock domains that can be edited through `setOverdriveClock()`
+#[cfg(feature = "gpu-tuning")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OverdriveClock {
+    Core,
+    Memory
+}
+
+/// Writes a raw command to `pp_od_clk_voltage` (e.g. `s 1 2000`), requires root privileges
+///
+/// **Warning**: overclocking and undervolting can make the system unstable or damage the hardware,
+/// changes are staged until `commitOverdrive()` is called
+#[cfg(feature = "gpu-tuning")]
+pub fn writeOverdrive(command: &str) -> io::Result<()> {
+    linuxCheck();
+    return fs::write(sysPath("/sys/class/drm/card0/device/pp_od_clk_voltage"), command);
+}
+
+/// Stages a new frequency for the specified point of the core or memory clock table, see `writeOverdrive()`
+#[cfg(feature = "gpu-tuning")]
+pub fn setOverdriveClock(clock: OverdriveClock, index: usize, frequency: Frequency) -> io::Result<()> {
+    let domain = match clock {
+        OverdriveClock::Core => "s",
+        OverdriveClock::Memory => "m"
+    };
+
+    return writeOverdrive(&format!("{} {} {}", domain, index, frequency.mhz() as usize));
+}
+
+/// Stages a core voltage offset in mV (negative values undervolt), see `writeOverdrive()`
+#[cfg(feature = "gpu-tuning")]
+pub fn setOverdriveVoltageOffset(offsetMv: i32) -> io::Result<()> {
+    return writeOverdrive(&format!("vo {}", offsetMv));
+}
+
+/// Applies the staged overdrive changes
+#[cfg(feature = "gpu-tuning")]
+pub fn commitOverdrive() -> io::Result<()> {
+    return writeOverdrive("c");
+}
+
+/// Restores the default overdrive table
+#[cfg(feature = "gpu-tuning")]
+pub fn resetOverdrive() -> io::Result<()> {
+    return writeOverdrive("r");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // writes the header and the given 16 bit fields, offsets are counted from the end of the header
+    fn metricsBlob(format: u8, content: u8, size: usize, fields: &[(usize, u16)]) -> Vec<u8> {
+        let mut blob = vec![0_u8; size];
+        blob[..4].copy_from_slice(&[(size & 0xFF) as u8, (size >> 8) as u8, format, content]);
+
+        for (offset, value) in fields {
+            blob[GPU_METRICS_HEADER_SIZE + offset..GPU_METRICS_HEADER_SIZE + offset + 2].copy_from_slice(&value.to_le_bytes());
+        }
+
+        return blob;
+    }
+
+    // revision 0 starts with the 64 bit system clock counter
+    fn v1_0() -> Vec<u8> {
+        return metricsBlob(1, 0, 96, &[
+            (0, 52501), (2, 1883), (8, 52), (10, 61), (12, 58), (14, 49), (16, 47), (18, 50), (26, 87),
+            (36, 1650), (38, 960), (40, 875), (50, 1702), (52, 960), (54, 875), (68, 1320), (70, 16), (72, 4)
+        ]);
+    }
+
+    fn v1_1() -> Vec<u8> {
+        return metricsBlob(1, 1, 120, &[
+            (0, 45), (2, 50), (4, 62), (6, 41), (8, 40), (10, 44), (18, 35), (36, 800), (38, 600), (40, 1000),
+            (50, 820), (52, 600), (54, 1000), (56, 1200), (58, 1100), (64, 4), (68, 900), (70, 16), (72, 3)
+        ]);
+    }
+
+    fn v1_3() -> Vec<u8> {
+        return metricsBlob(1, 3, 128, &[
+            (0, 38), (2, 42), (4, 54), (6, 36), (8, 35), (10, 39), (18, 12), (36, 500), (38, 400), (40, 96),
+            (50, 512), (52, 400), (54, 96), (70, 16), (72, 4)
+        ]);
+    }
+
+    #[test]
+    fn parsesRevisionZero() {
+        let metrics = parseGpuMetrics(&v1_0()).unwrap();
+
+        assert_eq!(metrics.temperatureEdge, 52);
+        assert_eq!(metrics.temperatureHotspot, 61);
+        assert_eq!(metrics.temperatureVrmem, 50);
+        assert_eq!(metrics.averageSocketPower, 87);
+        assert_eq!(metrics.averageGfxclkFrequency, Frequency::fromMhz(1650));
+        assert_eq!(metrics.currentGfxclk, Frequency::fromMhz(1702));
+        assert_eq!(metrics.currentFanSpeed, 1320);
+        assert_eq!(metrics.pcieLinkWidth, 16);
+        assert_eq!(metrics.pcieLinkSpeed, 4);
+    }
+
+    #[test]
+    fn parsesLaterRevisions() {
+        let metrics = parseGpuMetrics(&v1_1()).unwrap();
+
+        assert_eq!(metrics.temperatureEdge, 45);
+        assert_eq!(metrics.temperatureMem, 62);
+        assert_eq!(metrics.averageSocketPower, 35);
+        assert_eq!(metrics.currentVclk0, Frequency::fromMhz(1200));
+        assert_eq!(metrics.currentDclk0, Frequency::fromMhz(1100));
+        assert_eq!(metrics.throttleStatus, 0x4);
+
+        let metrics = parseGpuMetrics(&v1_3()).unwrap();
+
+        assert_eq!(metrics.temperatureEdge, 38);
+        assert_eq!(metrics.averageUclkFrequency, Frequency::fromMhz(96));
+        assert_eq!(metrics.currentFanSpeed, 0);
+    }
+
+    #[test]
+    fn parsesMultimediaActivity() {
+        // average_mm_activity follows the gfx and umc activities, after the six temperatures
+        let withActivity = |blob: &[u8], offset: usize, activity: u16| {
+            let mut blob = blob.to_vec();
+            blob[offset..offset + 2].copy_from_slice(&activity.to_le_bytes());
+            blob
+        };
+
+        assert_eq!(parseGpuMetrics(&withActivity(&v1_0(), 28, 37)).unwrap().averageMultimediaActivity, 37);
+        assert_eq!(parseGpuMetrics(&withActivity(&v1_1(), 20, 82)).unwrap().averageMultimediaActivity, 82);
+    }
+
+    #[test]
+    fn parsesPowerProfiles() {
+        let content = "PROFILE_INDEX(NAME) CLOCK_TYPE(NAME) FPS MinActiveFreqType MinActiveFreq\n \
+            0 BOOTUP_DEFAULT :\n                    0(       GFXCLK)       0       5       1       0\n \
+            1 3D_FULL_SCREEN*:\n                    0(       GFXCLK)       1       5       1       0\n \
+            5 COMPUTE        :\n                    0(       GFXCLK)       0       5       1       0\n \
+            6 CUSTOM         :";
+        let profiles = parsePowerProfiles(content);
+
+        assert_eq!(profiles.len(), 4);
+        assert_eq!(profiles[1].name, "3D_FULL_SCREEN");
+        assert!(profiles[1].active);
+        assert_eq!(profiles[2].index, 5);
+        assert!(!profiles[2].active);
+
+        let legacy = parsePowerProfiles("NUM        MODE_NAME     SCLK_UP_HYST\n  0   BOOTUP_DEFAULT:        -\n  4   VIDEO *:        0");
+        assert_eq!(legacy.len(), 2);
+        assert_eq!(legacy[1].name, "VIDEO");
+        assert!(legacy[1].active);
+    }
+
+    #[test]
+    fn parsesOverdriveTables() {
+        let table = parseOverdriveTable(
+            "OD_SCLK:\n0: 500Mhz\n1: 2615Mhz\nOD_MCLK:\n0: 97Mhz\n1: 1000MHz\nOD_VDDGFX_OFFSET:\n-25mV\n\
+            OD_RANGE:\nSCLK:     500Mhz       3150Mhz\nMCLK:     674Mhz       1200Mhz\n"
+        ).unwrap();
+
+        assert_eq!(table.section("OD_SCLK").unwrap()[1].frequency, Some(Frequency::fromMhz(2615)));
+        assert_eq!(table.section("OD_MCLK").unwrap().len(), 2);
+        assert_eq!(table.section("OD_VDDGFX_OFFSET").unwrap()[0], OverdrivePoint { index: None, frequency: None, voltageMv: Some(-25) });
+        assert_eq!(table.range("SCLK").unwrap().maximum, 3150);
+        assert_eq!(table.range("MCLK").unwrap().unit, "MHz");
+
+        let legacy = parseOverdriveTable("OD_SCLK:\n0:        300MHz        750mV\nOD_RANGE:\nVDDC:         750mV        1150mV").unwrap();
+        assert_eq!(legacy.section("OD_SCLK").unwrap()[0].voltageMv, Some(750));
+        assert_eq!(legacy.range("VDDC").unwrap().minimum, 750);
+
+        assert!(parseOverdriveTable("").is_none());
+    }
+
+    #[test]
+    fn rejectsUnsupportedVersions() {
+        assert_eq!(
+            parseGpuMetrics(&metricsBlob(2, 1, 120, &[])).unwrap_err(),
+            GpuMetricsError::UnsupportedVersion { format: 2, content: 1 }
+        );
+
+        // revisions 1.4 and 1.5 do not share the layout of the earlier ones
+        for content in [4, 5] {
+            let mut newer = v1_3();
+            newer[3] = content;
+            assert_eq!(parseGpuMetrics(&newer).unwrap_err(), GpuMetricsError::UnsupportedVersion { format: 1, content: content });
+        }
+    }
+
+    #[test]
+    fn rejectsShortBlobs() {
+        assert_eq!(
+            parseGpuMetrics(&v1_1()[..64]).unwrap_err(),
+            GpuMetricsError::Truncated { expected: 120, actual: 64 }
+        );
+        assert_eq!(
+            parseGpuMetrics(&[]).unwrap_err(),
+            GpuMetricsError::Truncated { expected: 4, actual: 0 }
+        );
+        assert!(parseGpuMetrics(&v1_1()[..40]).is_err());
+    }
+
+    #[test]
+    fn sortsCardsByIndex() {
+        let mut cards = vec!["card10", "card2", "card1", "card0"];
+        cards.sort_by(|first, second| compareCards(first, second));
+
+        assert_eq!(cards, ["card0", "card1", "card2", "card10"]);
+    }
+
+    #[test]
+    fn enumeratesEveryCard() {
+        let root = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/root/multigpu");
+        let gpus = SysPaths::new(root).scope(gpus);
+
+        assert_eq!(gpus.iter().map(|gpu| gpu.card.as_str()).collect::<Vec<&str>>(), ["card0", "card1"]);
+
+        assert_eq!(gpus[0].usage, Some(12_f32));
+        assert_eq!(gpus[0].vram.usage, Some(25_f32));
+        assert_eq!(gpus[0].vram.frequency, Some(Frequency::fromMhz(1000)));
+        assert_eq!(gpus[0].vram.busWidth, Some(256));
+        assert_eq!(gpus[0].displays, ["DP-1"]);
+
+        assert_eq!(gpus[1].usage, None);
+        assert_eq!(gpus[1].vram.size, Some(ByteSize::fromBytes(4294967296)));
+        assert_eq!(gpus[1].vram.busWidth, None);
+        assert!(gpus[1].metrics.is_none() && gpus[1].displays.is_empty());
+    }
+}
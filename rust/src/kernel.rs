@@ -0,0 +1,446 @@
+use std::{fmt, fs, path};
+use std::collections::HashMap;
+use std::time::Duration;
+use crate::utils::{*};
+
+/// Contains a kernel log record about a hardware error
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MachineCheckRecord {
+    pub message: String,
+    pub sinceBoot: Option<Duration>
+}
+
+impl fmt::Display for MachineCheckRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(sinceBoot) = self.sinceBoot {
+            write!(f, "[{:.3}] ", sinceBoot.as_secs_f64())?;
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Contains the memory errors counted by EDAC for a single DIMM
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DimmErrors {
+    /// Label set by the firmware, e.g. `CPU_SrcID#0_MC#0_Chan#0_DIMM#0`
+    pub label: String,
+    pub correctable: u64,
+    pub uncorrectable: u64
+}
+
+/// Contains the memory errors counted by an EDAC memory controller
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemoryControllerErrors {
+    /// Controller name in `/sys/devices/system/edac/mc`, e.g. `mc0`
+    pub controller: String,
+    /// Driver name, e.g. `Skylake Socket#0 IMC#0`
+    pub name: String,
+    pub correctable: u64,
+    pub uncorrectable: u64,
+    pub dimms: Vec<DimmErrors>
+}
+
+/// Contains the hardware machine-check exceptions recorded since boot
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MachineCheckEvents {
+    /// Machine check exceptions raised, summed over every CPU
+    pub exceptions: u64,
+    /// Periodic machine check polls, summed over every CPU
+    pub polls: u64,
+    /// Error reporting banks of the processor
+    pub banks: usize,
+    pub memoryControllers: Vec<MemoryControllerErrors>,
+    /// Hardware error messages found in the kernel log
+    pub records: Vec<MachineCheckRecord>,
+    /// Whether the kernel log could be read, `records` is empty otherwise
+    pub kernelLogReadable: bool
+}
+
+impl MachineCheckEvents {
+    /// Returns `true` if any exception, memory error or hardware error message was found
+    pub fn hasErrors(&self) -> bool {
+        return self.exceptions > 0
+            || !self.records.is_empty()
+            || self.memoryControllers.iter().any(|controller| controller.correctable > 0 || controller.uncorrectable > 0);
+    }
+}
+
+impl fmt::Display for MachineCheckEvents {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (correctable, uncorrectable) = self.memoryControllers.iter()
+            .fold((0, 0), |(correctable, uncorrectable), controller| {
+                (correctable + controller.correctable, uncorrectable + controller.uncorrectable)
+            });
+
+        write!(
+            f, "{} exceptions, {} correctable and {} uncorrectable memory errors",
+            self.exceptions, correctable, uncorrectable
+        )
+    }
+}
+
+// sums the per-CPU counters of an `/proc/interrupts` row, e.g. `MCE:   0   0   Machine check exceptions`
+fn interruptCount(interrupts: &str, name: &str) -> u64 {
+    let Some(line) = interrupts.lines().find(|line| line.trim_start().starts_with(name)) else {
+        return 0;
+    };
+
+    return line.trim_start()[name.len()..].split_whitespace()
+        .map_while(|count| count.parse::<u64>().ok())
+        .sum();
+}
+
+fn edacCounter(directory: &path::Path, names: &[&str]) -> u64 {
+    return names.iter()
+        .find_map(|name| readFile(directory.join(name)).parse::<u64>().ok())
+        .unwrap_or(0);
+}
+
+fn memoryControllers() -> Vec<MemoryControllerErrors> {
+    let mut controllers = Vec::<MemoryControllerErrors>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/devices/system/edac/mc")) else {
+        return controllers;
+    };
+
+    for entry in entries.flatten() {
+        let controller = entry.file_name().to_string_lossy().to_string();
+        if !controller.starts_with("mc") {
+            continue;
+        }
+
+        let mut dimms = Vec::<DimmErrors>::new();
+
+        // `dimm*` on current kernels, `rank*` when the controller reports per rank
+        for dimm in fs::read_dir(entry.path()).into_iter().flatten().flatten() {
+            let name = dimm.file_name().to_string_lossy().to_string();
+            if !name.starts_with("dimm") && !name.starts_with("rank") {
+                continue;
+            }
+
+            let mut label = readFile(dimm.path().join("dimm_label"));
+            if label.is_empty() {
+                label = name;
+            }
+
+            dimms.push(DimmErrors {
+                label: label,
+                correctable: edacCounter(&dimm.path(), &["dimm_ce_count"]),
+                uncorrectable: edacCounter(&dimm.path(), &["dimm_ue_count"])
+            });
+        }
+
+        dimms.sort_by(|first, second| first.label.cmp(&second.label));
+
+        controllers.push(MemoryControllerErrors {
+            controller: controller,
+            name: readFile(entry.path().join("mc_name")),
+            correctable: edacCounter(&entry.path(), &["ce_count"]) + edacCounter(&entry.path(), &["ce_noinfo_count"]),
+            uncorrectable: edacCounter(&entry.path(), &["ue_count"]) + edacCounter(&entry.path(), &["ue_noinfo_count"]),
+            dimms: dimms
+        });
+    }
+
+    controllers.sort_by(|first, second| first.controller.cmp(&second.controller));
+    return controllers;
+}
+
+// messages logged by the x86 MCE handler, the APEI/GHES firmware error reporting and EDAC drivers
+const MACHINE_CHECK_MARKERS: [&str; 4] = ["mce: ", "[Hardware Error]", "Machine check", "EDAC MC"];
+
+fn parseMachineCheckRecord(record: &str) -> Option<MachineCheckRecord> {
+    let (header, message) = record.split_once(';')?;
+
+    if !MACHINE_CHECK_MARKERS.iter().any(|marker| message.contains(marker)) {
+        return None;
+    }
+
+    let microseconds = header.split(',').nth(2)?.parse::<u64>().ok();
+
+    return Some(MachineCheckRecord {
+        message: message.trim().to_string(),
+        sinceBoot: microseconds.map(Duration::from_micros)
+    });
+}
+
+/// Returns machine-check exceptions counters, EDAC memory error counters and hardware error messages of the kernel log
+///
+/// Reading the kernel log usually requires root privileges (or `kernel.dmesg_restrict = 0`),
+/// the counters are available to every user
+pub fn machineCheckEvents() -> MachineCheckEvents {
+    linuxCheck();
+
+    let interrupts = readFile("/proc/interrupts");
+
+    let banks = fs::read_dir(sysPath("/sys/devices/system/machinecheck/machinecheck0")).into_iter().flatten().flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("bank"))
+        .count();
+
+    let (records, kernelLogReadable) = match kmsgRecords() {
+        Ok(records) => (records.iter().filter_map(|record| parseMachineCheckRecord(record)).collect(), true),
+        Err(_error) => {
+            traceEvent!(debug, error = %_error, "failed to read /dev/kmsg");
+            (Vec::<MachineCheckRecord>::new(), false)
+        }
+    };
+
+    return MachineCheckEvents {
+        exceptions: interruptCount(&interrupts, "MCE:"),
+        polls: interruptCount(&interrupts, "MCP:"),
+        banks: banks,
+        memoryControllers: memoryControllers(),
+        records: records,
+        kernelLogReadable: kernelLogReadable
+    };
+}
+
+// splits on whitespace outside double quotes, quotes are removed as the kernel does
+fn parseCmdline(cmdline: &str) -> HashMap<String, Option<String>> {
+    let mut flags = HashMap::<String, Option<String>>::new();
+    let mut parameters = Vec::<String>::new();
+    let mut current = String::new();
+    let mut quoted = false;
+
+    for character in cmdline.trim().chars() {
+        match character {
+            '"' => quoted = !quoted,
+            character if character.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    parameters.push(std::mem::take(&mut current));
+                }
+            },
+            character => current.push(character)
+        }
+    }
+
+    if !current.is_empty() {
+        parameters.push(current);
+    }
+
+    // parameters after `--` are passed to init
+    for parameter in parameters.into_iter().take_while(|parameter| parameter != "--") {
+        match parameter.split_once('=') {
+            Some((name, value)) => flags.insert(name.to_string(), Some(value.to_string())),
+            None => flags.insert(parameter, None)
+        };
+    }
+
+    return flags;
+}
+
+/// Returns the kernel boot parameters, flags without a value (e.g. `quiet`) map to `None`
+///
+/// When a parameter is repeated the last occurrence is kept, like the kernel does for most of them
+pub fn cmdlineFlags() -> HashMap<String, Option<String>> {
+    linuxCheck();
+
+    return parseCmdline(&readFile("/proc/cmdline"));
+}
+
+fn flagValue(flags: &HashMap<String, Option<String>>, name: &str) -> Option<String> {
+    return flags.get(name).cloned().flatten();
+}
+
+// parameters turning off a single CPU vulnerability mitigation
+const MITIGATION_OFF_FLAGS: [&str; 4] = ["nospectre_v1", "nospectre_v2", "nopti", "nospec_store_bypass_disable"];
+const MITIGATION_OFF_VALUES: [&str; 9] = [
+    "pti", "spectre_v2", "spec_store_bypass_disable", "mds", "tsx_async_abort", "l1tf", "mmio_stale_data", "retbleed", "srbds"
+];
+
+/// Returns `true` if CPU vulnerability mitigations are disabled on the kernel command line,
+/// globally with `mitigations=off` or one by one (`nopti`, `spectre_v2=off`, ...)
+pub fn mitigationsDisabled() -> bool {
+    let flags = cmdlineFlags();
+
+    if flagValue(&flags, "mitigations").as_deref() == Some("off") {
+        return true;
+    }
+
+    return MITIGATION_OFF_FLAGS.iter().any(|flag| flags.contains_key(*flag))
+        || MITIGATION_OFF_VALUES.iter().any(|name| flagValue(&flags, name).as_deref() == Some("off"));
+}
+
+/// Returns `true` if an IOMMU is active: it must not be disabled on the kernel command line
+/// and the kernel must have registered at least one IOMMU device in `/sys/class/iommu`
+pub fn iommuEnabled() -> bool {
+    let flags = cmdlineFlags();
+
+    let disabled = ["intel_iommu", "amd_iommu", "iommu"].iter()
+        .any(|name| flagValue(&flags, name).map(|value| value.split(',').any(|option| option == "off")).unwrap_or(false));
+
+    if disabled {
+        return false;
+    }
+
+    return fs::read_dir(sysPath("/sys/class/iommu")).map(|mut entries| entries.next().is_some()).unwrap_or(false);
+}
+
+/// Kernel preemption model
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PreemptionModel {
+    /// No forced preemption, throughput oriented
+    None,
+    /// Preemption at explicit points only
+    Voluntary,
+    /// Preemptible kernel, low latency desktop
+    Full,
+    /// Full preemption for real-time tasks, deferred for the others
+    Lazy,
+    /// PREEMPT_RT kernel
+    Realtime,
+    Unknown
+}
+
+impl PreemptionModel {
+    pub fn fromString(model: &str) -> PreemptionModel {
+        match model.trim().to_lowercase().as_str() {
+            "none" => PreemptionModel::None,
+            "voluntary" => PreemptionModel::Voluntary,
+            "full" => PreemptionModel::Full,
+            "lazy" => PreemptionModel::Lazy,
+            "rt" | "realtime" => PreemptionModel::Realtime,
+            _ => PreemptionModel::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            PreemptionModel::None => "none",
+            PreemptionModel::Voluntary => "voluntary",
+            PreemptionModel::Full => "full",
+            PreemptionModel::Lazy => "lazy",
+            PreemptionModel::Realtime => "rt",
+            PreemptionModel::Unknown => "unknown"
+        })
+    }
+}
+
+impl fmt::Display for PreemptionModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains the scheduler settings relevant to latency tuning
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchedulerInfo {
+    pub preemption: PreemptionModel,
+    /// Whether the model can be changed at boot or runtime (`preempt=` parameter, PREEMPT_DYNAMIC)
+    pub dynamicPreemption: bool,
+    /// `None` if the kernel is built without autogroup support
+    pub autogroup: Option<bool>,
+    /// Timer interrupt frequency in Hz (CONFIG_HZ), `None` if the kernel configuration is not readable
+    pub timerFrequency: Option<u32>
+}
+
+impl fmt::Display for SchedulerInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "preemption {}", self.preemption)?;
+
+        if let Some(frequency) = self.timerFrequency {
+            write!(f, ", {} Hz", frequency)?;
+        }
+
+        if let Some(autogroup) = self.autogroup {
+            write!(f, ", autogroup {}", if autogroup { "on" } else { "off" })?;
+        }
+
+        Ok(())
+    }
+}
+
+// the debugfs file lists every model, with the active one in parentheses: `none voluntary (full) lazy`
+fn activePreemption(models: &str) -> Option<PreemptionModel> {
+    let active = models.split_whitespace().find(|model| model.starts_with('('))?;
+    return Some(PreemptionModel::fromString(active.trim_matches(|character| character == '(' || character == ')')));
+}
+
+/// Returns the `CONFIG_*` options of the running kernel, read from `/boot/config-<release>`
+///
+/// The compressed `/proc/config.gz` is not read, options set to `n` are missing from the map
+pub fn kernelConfig() -> HashMap<String, String> {
+    linuxCheck();
+
+    let release = readFile("/proc/sys/kernel/osrelease");
+
+    return readFile(format!("/boot/config-{}", release)).lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.trim_matches('"').to_string()))
+        .collect();
+}
+
+/// Returns the preemption model, autogroup setting and timer frequency of the running kernel
+///
+/// The active model is read from `/sys/kernel/debug/sched/preempt` when debugfs is readable (root),
+/// then from the kernel configuration, then guessed from the kernel version string
+pub fn schedulerInfo() -> SchedulerInfo {
+    linuxCheck();
+
+    let config = kernelConfig();
+    let enabled = |option: &str| config.get(option).map(|value| value == "y").unwrap_or(false);
+
+    // e.g. `#1 SMP PREEMPT_DYNAMIC Thu Jan 1 00:00:00 UTC 2024`
+    let version = readFile("/proc/sys/kernel/version");
+    let versionFlags: Vec<&str> = version.split_whitespace().collect();
+
+    let realtime = readFile("/sys/kernel/realtime") == "1" || enabled("CONFIG_PREEMPT_RT") || versionFlags.contains(&"PREEMPT_RT");
+    let dynamicPreemption = enabled("CONFIG_PREEMPT_DYNAMIC") || versionFlags.contains(&"PREEMPT_DYNAMIC");
+
+    let preemption = if realtime {
+        PreemptionModel::Realtime
+    } else if let Some(model) = activePreemption(&readFile("/sys/kernel/debug/sched/preempt")) {
+        model
+    } else if let Some(model) = flagValue(&cmdlineFlags(), "preempt").filter(|_| dynamicPreemption) {
+        PreemptionModel::fromString(&model)
+    } else if enabled("CONFIG_PREEMPT_LAZY") && !dynamicPreemption {
+        PreemptionModel::Lazy
+    } else if enabled("CONFIG_PREEMPT") {
+        PreemptionModel::Full
+    } else if enabled("CONFIG_PREEMPT_VOLUNTARY") {
+        PreemptionModel::Voluntary
+    } else if enabled("CONFIG_PREEMPT_NONE") {
+        PreemptionModel::None
+    } else if versionFlags.contains(&"PREEMPT") {
+        PreemptionModel::Full
+    } else {
+        PreemptionModel::Unknown
+    };
+
+    let autogroup = match readFile("/proc/sys/kernel/sched_autogroup_enabled").as_str() {
+        "1" => Some(true),
+        "0" => Some(false),
+        _ => None
+    };
+
+    return SchedulerInfo {
+        preemption: preemption,
+        dynamicPreemption: dynamicPreemption,
+        autogroup: autogroup,
+        timerFrequency: config.get("CONFIG_HZ").and_then(|frequency| frequency.parse::<u32>().ok())
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsesCmdline() {
+        let flags = parseCmdline(
+            "BOOT_IMAGE=/vmlinuz root=UUID=1234 ro quiet acpi_osi=\"Windows 2020\" console=tty0 console=ttyS0 -- single"
+        );
+
+        assert_eq!(flags.get("root"), Some(&Some(String::from("UUID=1234"))));
+        assert_eq!(flags.get("quiet"), Some(&None));
+        assert_eq!(flags.get("acpi_osi"), Some(&Some(String::from("Windows 2020"))));
+        assert_eq!(flags.get("console"), Some(&Some(String::from("ttyS0"))));
+        assert!(!flags.contains_key("single"));
+    }
+}
@@ -23,25 +23,223 @@
 #![allow(dead_code)]
 #![allow(non_camel_case_types)]
 
+#[cfg(feature = "gpu")]
 pub mod gpu;
+#[cfg(feature = "cpu")]
 pub mod cpu;
 pub mod ram;
+#[cfg(feature = "network")]
 pub mod network;
+#[cfg(feature = "storage")]
 pub mod storage;
 pub mod motherboard;
+pub mod system;
+pub mod kernel;
+pub mod cgroups;
+pub mod process;
+#[cfg(feature = "systemd")]
+pub mod services;
+#[cfg(feature = "sensors")]
 pub mod sensors;
+#[cfg(feature = "bus")]
 pub mod bus;
+#[cfg(feature = "export")]
+pub mod snapshot;
+#[cfg(feature = "export")]
+pub mod statusbar;
+#[cfg(feature = "export")]
+pub mod archive;
+#[cfg(feature = "export")]
+pub mod delta;
+#[cfg(feature = "export")]
+pub mod units;
+#[cfg(all(feature = "cpu", feature = "network"))]
+pub mod monitor;
+#[cfg(all(feature = "cpu", feature = "network", feature = "sensors"))]
+pub mod csv;
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+#[cfg(feature = "dbus")]
+pub mod dbus;
+#[cfg(feature = "query-server")]
+pub mod query;
+#[cfg(all(feature = "gpu", feature = "sensors"))]
+pub mod power;
 mod utils;
-pub use utils::{ByteSize, ByteUnit};
+pub use utils::{ByteSize, ByteUnit, ErrorCode, Frequency, ParseByteSizeError, PcieLink, SysError, SysPaths, Unit};
+#[cfg(feature = "export")]
+pub use snapshot::{SectionError, SnapshotBuilder, SysInfo, SystemSnapshot};
+#[cfg(feature = "dbus")]
+pub use dbus::exportDbus;
 
+#[cfg(feature = "export")]
 use rsjson::{Json, Node, NodeContent};
+#[cfg(feature = "export")]
+use std::collections::HashMap;
 
-/// Returns a `rsjson::Json` object containing all the data which `sysutil` can extract
-pub fn exportJson() -> rsjson::Json {
-    let mut json = rsjson::Json::new();
+#[cfg(feature = "export")]
+fn processorUsageNode(usage: &cpu::ProcessorUsage) -> Json {
+    let mut usageNodeContent = Json::new();
+
+    usageNodeContent.addNode(Node::new(
+        "total".to_string(),
+        NodeContent::Float(usage.total)
+    ));
+
+    usageNodeContent.addNode(Node::new(
+        "user".to_string(),
+        NodeContent::Float(usage.user)
+    ));
+
+    usageNodeContent.addNode(Node::new(
+        "nice".to_string(),
+        NodeContent::Float(usage.nice)
+    ));
+
+    usageNodeContent.addNode(Node::new(
+        "system".to_string(),
+        NodeContent::Float(usage.system)
+    ));
+
+    usageNodeContent.addNode(Node::new(
+        "idle".to_string(),
+        NodeContent::Float(usage.idle)
+    ));
+
+    usageNodeContent.addNode(Node::new(
+        "iowait".to_string(),
+        NodeContent::Float(usage.iowait)
+    ));
+
+    usageNodeContent.addNode(Node::new(
+        "interrupt".to_string(),
+        NodeContent::Float(usage.interrupt)
+    ));
+
+    usageNodeContent.addNode(Node::new(
+        "soft-interrupt".to_string(),
+        NodeContent::Float(usage.soft_interrupt)
+    ));
+
+    return usageNodeContent;
+}
+
+#[cfg(feature = "export")]
+fn sensorsNode(
+    temperatures: Vec<sensors::CategorizedTemperature>,
+    thermalZones: Vec<sensors::ThermalZone>,
+    coolingDevices: Vec<sensors::CoolingDevice>,
+    backlights: Vec<sensors::Backlight>,
+    battery: Option<sensors::Battery>
+) -> Json {
+    let mut sensorsNodeContent = Json::new();
+
+    // channels are grouped by hwmon device, keeping the order of categorizedTemperatures()
+    let mut chipsNodeContent = Vec::<NodeContent>::new();
+    let mut currentDevice = String::new();
+    let mut currentChip = String::new();
+    let mut channelsNodeContent = Vec::<NodeContent>::new();
+
+    let mut pushChip = |device: &String, chip: &String, channels: Vec<NodeContent>| {
+        if channels.is_empty() {
+            return;
+        }
+
+        let mut chipNodeContent = Json::new();
+        chipNodeContent.addNode(Node::new("device", NodeContent::String(device.clone())));
+        chipNodeContent.addNode(Node::new("name", NodeContent::String(chip.clone())));
+        chipNodeContent.addNode(Node::new("temperatures", NodeContent::List(channels)));
+        chipsNodeContent.push(NodeContent::Json(chipNodeContent));
+    };
+
+    for temperature in temperatures {
+        if temperature.device != currentDevice {
+            pushChip(&currentDevice, &currentChip, std::mem::take(&mut channelsNodeContent));
+            currentDevice = temperature.device.clone();
+            currentChip = temperature.chip.clone();
+        }
+
+        let mut channelNodeContent = Json::new();
+        channelNodeContent.addNode(Node::new("label", NodeContent::String(temperature.label)));
+        channelNodeContent.addNode(Node::new("category", NodeContent::String(temperature.category.toString())));
+        channelNodeContent.addNode(Node::new("temperature", NodeContent::Float(temperature.temperature)));
+        channelsNodeContent.push(NodeContent::Json(channelNodeContent));
+    }
+
+    pushChip(&currentDevice, &currentChip, channelsNodeContent);
+
+    sensorsNodeContent.addNode(Node::new("chips", NodeContent::List(chipsNodeContent)));
+
+    let mut zonesNodeContent = Vec::<NodeContent>::new();
+
+    for zone in thermalZones {
+        let mut zoneNodeContent = Json::new();
+        zoneNodeContent.addNode(Node::new("name", NodeContent::String(zone.name)));
+        zoneNodeContent.addNode(Node::new("type", NodeContent::String(zone.zoneType)));
+        zoneNodeContent.addNode(Node::new(
+            "temperature",
+            match zone.temperature {
+                Some(temperature) => NodeContent::Float(temperature),
+                None => NodeContent::Null
+            }
+        ));
+        zoneNodeContent.addNode(Node::new(
+            "cooling-devices",
+            NodeContent::List(zone.coolingDevices.into_iter().map(NodeContent::String).collect())
+        ));
+        zonesNodeContent.push(NodeContent::Json(zoneNodeContent));
+    }
+
+    sensorsNodeContent.addNode(Node::new("thermal-zones", NodeContent::List(zonesNodeContent)));
+
+    let mut coolingNodeContent = Vec::<NodeContent>::new();
+
+    for device in coolingDevices {
+        let mut deviceNodeContent = Json::new();
+        deviceNodeContent.addNode(Node::new("name", NodeContent::String(device.name.clone())));
+        deviceNodeContent.addNode(Node::new("type", NodeContent::String(device.deviceType.clone())));
+        deviceNodeContent.addNode(Node::new("current-state", NodeContent::Int(device.currentState as usize)));
+        deviceNodeContent.addNode(Node::new("max-state", NodeContent::Int(device.maxState as usize)));
+        deviceNodeContent.addNode(Node::new("usage", NodeContent::Float(device.usage())));
+        coolingNodeContent.push(NodeContent::Json(deviceNodeContent));
+    }
+
+    sensorsNodeContent.addNode(Node::new("cooling-devices", NodeContent::List(coolingNodeContent)));
+
+    let mut backlightsNodeContent = Vec::<NodeContent>::new();
 
+    for backlight in backlights {
+        let mut backlightNodeContent = Json::new();
+        backlightNodeContent.addNode(Node::new("name", NodeContent::String(backlight.name.clone())));
+        backlightNodeContent.addNode(Node::new("type", NodeContent::String(backlight.backlightType.toString())));
+        backlightNodeContent.addNode(Node::new("brightness", NodeContent::Int(backlight.brightness as usize)));
+        backlightNodeContent.addNode(Node::new("max-brightness", NodeContent::Int(backlight.maxBrightness as usize)));
+        backlightNodeContent.addNode(Node::new("percentage", NodeContent::Float(backlight.percentage())));
+        backlightsNodeContent.push(NodeContent::Json(backlightNodeContent));
+    }
+
+    sensorsNodeContent.addNode(Node::new("backlights", NodeContent::List(backlightsNodeContent)));
+
+    // combined battery of multi-battery laptops, the top level `battery` node keeps reporting the first one
+    sensorsNodeContent.addNode(Node::new(
+        "battery",
+        match battery {
+            Some(battery) => {
+                let mut batteryNodeContent = Json::new();
+                batteryNodeContent.addNode(Node::new("capacity", NodeContent::Int(battery.capacity as usize)));
+                batteryNodeContent.addNode(Node::new("status", NodeContent::String(battery.status.toString())));
+                NodeContent::Json(batteryNodeContent)
+            },
+            None => NodeContent::Null
+        }
+    ));
+
+    return sensorsNodeContent;
+}
+
+#[cfg(feature = "export")]
+fn cpuNode(cpu: cpu::CPU, clockSource: Option<cpu::ClockSource>) -> Json {
     let mut cpuNodeContent = rsjson::Json::new();
-    let cpu = cpu::CPU::new();
 
     cpuNodeContent.addNode(rsjson::Node::new(
         "model-name".to_string(),
@@ -69,7 +267,7 @@ pub fn exportJson() -> rsjson::Json {
             let mut list = Vec::<NodeContent>::new();
 
             for governor in cpu.info.governors {
-                list.push(NodeContent::String(governor));
+                list.push(NodeContent::String(governor.toString()));
             }
 
             list
@@ -92,56 +290,27 @@ pub fn exportJson() -> rsjson::Json {
         }
     ));
 
+    for (label, count) in [("performance-cores", cpu.info.performanceCores), ("efficiency-cores", cpu.info.efficiencyCores)] {
+        cpuNodeContent.addNode(rsjson::Node::new(
+            label.to_string(),
+            match count {
+                Some(count) => NodeContent::Int(count),
+                None => NodeContent::Null
+            }
+        ));
+    }
+
     cpuNodeContent.addNode(rsjson::Node::new(
         "architecture".to_string(),
-        NodeContent::String(cpu.info.architecture)
+        NodeContent::String(cpu.info.architecture.toString())
     ));
 
     cpuNodeContent.addNode(rsjson::Node::new(
         "byte-order".to_string(),
-        NodeContent::String(cpu.info.byteOrder)
-    ));
-
-    let mut cpuAverageUsageNodeContent = Json::new();
-    cpuAverageUsageNodeContent.addNode(Node::new(
-        "total".to_string(),
-        NodeContent::Float(cpu.averageUsage.total)
-    ));
-
-    cpuAverageUsageNodeContent.addNode(Node::new(
-        "user".to_string(),
-        NodeContent::Float(cpu.averageUsage.user)
-    ));
-
-    cpuAverageUsageNodeContent.addNode(Node::new(
-        "nice".to_string(),
-        NodeContent::Float(cpu.averageUsage.nice)
-    ));
-
-    cpuAverageUsageNodeContent.addNode(Node::new(
-        "system".to_string(),
-        NodeContent::Float(cpu.averageUsage.system)
-    ));
-
-    cpuAverageUsageNodeContent.addNode(Node::new(
-        "idle".to_string(),
-        NodeContent::Float(cpu.averageUsage.idle)
-    ));
-
-    cpuAverageUsageNodeContent.addNode(Node::new(
-        "iowait".to_string(),
-        NodeContent::Float(cpu.averageUsage.iowait)
+        NodeContent::String(cpu.info.byteOrder.toString())
     ));
 
-    cpuAverageUsageNodeContent.addNode(Node::new(
-        "interrupt".to_string(),
-        NodeContent::Float(cpu.averageUsage.interrupt)
-    ));
-
-    cpuAverageUsageNodeContent.addNode(Node::new(
-        "soft-interrupt".to_string(),
-        NodeContent::Float(cpu.averageUsage.soft_interrupt)
-    ));
+    let cpuAverageUsageNodeContent = processorUsageNode(&cpu.averageUsage);
 
     let mut cpuSchedulerPolicyContent = Json::new();
 
@@ -160,12 +329,12 @@ pub fn exportJson() -> rsjson::Json {
 
         policyNodeContent.addNode(Node::new(
             "minimum-scaling-mhz".to_string(),
-            NodeContent::Float(policy.minimumScalingMHz)
+            NodeContent::Float(policy.minimumScaling.mhz())
         ));
 
         policyNodeContent.addNode(Node::new(
             "maximum-scaling-mhz".to_string(),
-            NodeContent::Float(policy.maximumScalingMHz)
+            NodeContent::Float(policy.maximumScaling.mhz())
         ));
 
         cpuSchedulerPolicyContent.addNode(Node::new(
@@ -189,39 +358,68 @@ pub fn exportJson() -> rsjson::Json {
         NodeContent::Int(cpu.averageFrequency.khz)
     ));
 
-    let mut cpuClockSourceNodeContent = Json::new();
-    let clockSource = cpu::clockSource();
+    let mut perProcessorUsageNodeContent = Json::new();
+    for (processorID, usage) in cpu.perProcessorUsage.iter().enumerate() {
+        perProcessorUsageNodeContent.addNode(Node::new(
+            processorID.to_string(),
+            NodeContent::Json(processorUsageNode(usage))
+        ));
+    }
 
-    cpuClockSourceNodeContent.addNode(Node::new(
-        "current".to_string(),
-        NodeContent::String(clockSource.current)
+    cpuNodeContent.addNode(Node::new(
+        "per-processor-usage".to_string(),
+        NodeContent::Json(perProcessorUsageNodeContent)
     ));
 
-    cpuClockSourceNodeContent.addNode(Node::new(
-        "available".to_string(),
-        NodeContent::List({
-            let mut list = Vec::<NodeContent>::new();
-
-            for source in clockSource.available {
-                list.push(NodeContent::String(source));
-            }
+    let mut perProcessorFrequencyNodeContent = Json::new();
+    for processor in cpu.perProcessorFrequency {
+        perProcessorFrequencyNodeContent.addNode(Node::new(
+            processor.processorID,
+            NodeContent::Int(processor.frequency.khz)
+        ));
+    }
 
-            list
-        })
+    cpuNodeContent.addNode(Node::new(
+        "per-processor-frequency".to_string(),
+        NodeContent::Json(perProcessorFrequencyNodeContent)
     ));
 
     cpuNodeContent.addNode(Node::new(
         "clock-source".to_string(),
-        NodeContent::Json(cpuClockSourceNodeContent)
-    ));
+        match clockSource {
+            Some(clockSource) => {
+                let mut cpuClockSourceNodeContent = Json::new();
+
+                cpuClockSourceNodeContent.addNode(Node::new(
+                    "current".to_string(),
+                    NodeContent::String(clockSource.current)
+                ));
 
-    json.addNode(rsjson::Node::new(
-        "cpu".to_string(),
-        NodeContent::Json(cpuNodeContent)
+                cpuClockSourceNodeContent.addNode(Node::new(
+                    "available".to_string(),
+                    NodeContent::List({
+                        let mut list = Vec::<NodeContent>::new();
+
+                        for source in clockSource.available {
+                            list.push(NodeContent::String(source));
+                        }
+
+                        list
+                    })
+                ));
+
+                NodeContent::Json(cpuClockSourceNodeContent)
+            },
+            None => NodeContent::Null
+        }
     ));
 
+    return cpuNodeContent;
+}
+
+#[cfg(feature = "export")]
+fn ramNode(ram: ram::RAM) -> Json {
     let mut ramNodeContent = Json::new();
-    let ram = ram::RAM::new();
 
     ramNodeContent.addNode(Node::new(
         "usage".to_string(),
@@ -237,7 +435,7 @@ pub fn exportJson() -> rsjson::Json {
         "frequency",
         match ram.frequency {
             Some(frequency) => {
-                NodeContent::Int(frequency)
+                NodeContent::Int(frequency.mhz() as usize)
             },
             None => NodeContent::Null
         }
@@ -253,13 +451,12 @@ pub fn exportJson() -> rsjson::Json {
         }
     ));
 
-    json.addNode(Node::new(
-        "ram".to_string(),
-        NodeContent::Json(ramNodeContent)
-    ));
+    return ramNodeContent;
+}
 
+#[cfg(feature = "export")]
+fn motherboardNode(motherboard: motherboard::Motherboard) -> Json {
     let mut motherBoardNodeContent = Json::new();
-    let motherboard = motherboard::motherboardInfo();
 
     motherBoardNodeContent.addNode(Node::new(
         "name".to_string(),
@@ -302,13 +499,12 @@ pub fn exportJson() -> rsjson::Json {
         NodeContent::Json(biosNodeContent)
     ));
 
-    json.addNode(Node::new(
-        "motherboard".to_string(),
-        NodeContent::Json(motherBoardNodeContent)
-    ));
+    return motherBoardNodeContent;
+}
 
+#[cfg(feature = "export")]
+fn nvmeDevicesNode(nvmeDevices: Vec<storage::NvmeDevice>) -> Vec<NodeContent> {
     let mut nvmeDevicesNodeContent = Vec::<NodeContent>::new();
-    let nvmeDevices = storage::nvmeDevices();
 
     for device in nvmeDevices {
         let mut deviceNodeContent = Json::new();
@@ -340,7 +536,7 @@ pub fn exportJson() -> rsjson::Json {
 
         deviceNodeContent.addNode(Node::new(
             "size".to_string(),
-            NodeContent::Int(device.size.b())
+            NodeContent::Int(device.size.bytes() as usize)
         ));
 
         let mut partitionsNodeList = Vec::<NodeContent>::new();
@@ -364,7 +560,7 @@ pub fn exportJson() -> rsjson::Json {
 
             partitionNodeContent.addNode(Node::new(
                 "size".to_string(),
-                NodeContent::Int(partition.size.b())
+                NodeContent::Int(partition.size.bytes() as usize)
             ));
 
             partitionNodeContent.addNode(Node::new(
@@ -383,13 +579,12 @@ pub fn exportJson() -> rsjson::Json {
         nvmeDevicesNodeContent.push(NodeContent::Json(deviceNodeContent));
     }
 
-    json.addNode(Node::new(
-        "nvme-devices".to_string(),
-        NodeContent::List(nvmeDevicesNodeContent)
-    ));
+    return nvmeDevicesNodeContent;
+}
 
+#[cfg(feature = "export")]
+fn storageDevicesNode(storageDevices: Vec<storage::StorageDevice>) -> Vec<NodeContent> {
     let mut storageDevicesNodeContent = Vec::<NodeContent>::new();
-    let storageDevices = storage::storageDevices();
 
     for device in storageDevices {
         let mut deviceNodeContent = Json::new();
@@ -407,7 +602,28 @@ pub fn exportJson() -> rsjson::Json {
 
         deviceNodeContent.addNode(Node::new(
             "size".to_string(),
-            NodeContent::Int(device.size.b())
+            NodeContent::Int(device.size.bytes() as usize)
+        ));
+
+        deviceNodeContent.addNode(Node::new(
+            "transport".to_string(),
+            NodeContent::String(device.transport.toString())
+        ));
+
+        deviceNodeContent.addNode(Node::new(
+            "target".to_string(),
+            match device.target {
+                Some(target) => NodeContent::String(target),
+                None => NodeContent::Null
+            }
+        ));
+
+        deviceNodeContent.addNode(Node::new(
+            "host".to_string(),
+            match device.host {
+                Some(host) => NodeContent::String(host),
+                None => NodeContent::Null
+            }
         ));
 
         let mut partitionsNodeList = Vec::<NodeContent>::new();
@@ -431,7 +647,7 @@ pub fn exportJson() -> rsjson::Json {
 
             partitionNodeContent.addNode(Node::new(
                 "size".to_string(),
-                NodeContent::Int(partition.size.b())
+                NodeContent::Int(partition.size.bytes() as usize)
             ));
 
             partitionNodeContent.addNode(Node::new(
@@ -450,68 +666,65 @@ pub fn exportJson() -> rsjson::Json {
         storageDevicesNodeContent.push(NodeContent::Json(deviceNodeContent));
     }
 
-    json.addNode(Node::new(
-        "storage-devices".to_string(),
-        NodeContent::List(storageDevicesNodeContent)
+    return storageDevicesNodeContent;
+}
+
+#[cfg(feature = "export")]
+fn batteryNode(battery: sensors::Battery) -> Json {
+    let mut batteryNodeContent = Json::new();
+
+    batteryNodeContent.addNode(Node::new(
+        String::from("capacity"),
+        NodeContent::Int(battery.capacity as usize)
     ));
 
-    match sensors::batteryInfo() {
-        None => {
-            json.addNode(Node::new(String::from("battery"), NodeContent::Null));
-        },
-        Some(battery) => {
-            let mut batteryNodeContent = Json::new();
+    batteryNodeContent.addNode(Node::new(
+        String::from("status"),
+        NodeContent::String(battery.status.toString())
+    ));
 
-            batteryNodeContent.addNode(Node::new(
-                String::from("capacity"),
-                NodeContent::Int(battery.capacity as usize)
-            ));
+    return batteryNodeContent;
+}
 
-            batteryNodeContent.addNode(Node::new(
-                String::from("status"),
-                match battery.status {
-                    sensors::BatteryStatus::Charging => NodeContent::String(String::from("Charging")),
-                    sensors::BatteryStatus::Discharging => NodeContent::String(String::from("Discharging")),
-                    sensors::BatteryStatus::Full => NodeContent::String(String::from("Full")),
-                }
-            ));
+#[cfg(feature = "export")]
+fn backlightNode(backlight: sensors::Backlight) -> Json {
+    let mut brightnessNodeContent = Json::new();
 
-            json.addNode(Node::new(
-                String::from("battery"),
-                NodeContent::Json(batteryNodeContent)
-            ));
-        }
-    };
+    brightnessNodeContent.addNode(Node::new(
+        String::from("name"),
+        NodeContent::String(backlight.name)
+    ));
 
-    match sensors::getBacklight() {
-        None => {
-            json.addNode(Node::new(String::from("backlight"), NodeContent::Null));
-        },
-        Some(backlight) => {
-            let mut brightnessNodeContent = Json::new();
+    brightnessNodeContent.addNode(Node::new(
+        String::from("type"),
+        NodeContent::String(backlight.backlightType.toString())
+    ));
 
-            brightnessNodeContent.addNode(Node::new(
-                String::from("brightness"),
-                NodeContent::Int(backlight.brightness as usize)
-            ));
+    brightnessNodeContent.addNode(Node::new(
+        String::from("brightness"),
+        NodeContent::Int(backlight.brightness as usize)
+    ));
 
-            brightnessNodeContent.addNode(Node::new(
-                String::from("max-brightness"),
-                NodeContent::Int(backlight.maxBrightness as usize)
-            ));
+    brightnessNodeContent.addNode(Node::new(
+        String::from("max-brightness"),
+        NodeContent::Int(backlight.maxBrightness as usize)
+    ));
 
-            json.addNode(Node::new(
-                String::from("backlight"),
-                NodeContent::Json(brightnessNodeContent)
-            ));
-        }
-    }
+    return brightnessNodeContent;
+}
 
+#[cfg(feature = "export")]
+fn networkNode(
+    networkRate: network::NetworkRate,
+    routes: Vec<network::NetworkRoute>,
+    interfaces: Vec<network::NetworkInterface>,
+    ipv4Addresses: Vec<network::IPv4>,
+    ipv6Addresses: Vec<network::IPv6>,
+    statistics: HashMap<String, network::InterfaceStatistics>
+) -> Json {
     let mut networkNodeContent = rsjson::Json::new();
     let mut networkRateNodeContent = rsjson::Json::new();
 
-    let networkRate = network::networkRate();
-
     networkRateNodeContent.addNode(Node::new(
         "download",
         NodeContent::Float(networkRate.download)
@@ -528,7 +741,6 @@ pub fn exportJson() -> rsjson::Json {
     ));
 
     let mut networkRoutesNodeConent = Vec::<NodeContent>::new();
-    let routes = network::networkRoutes();
 
     for route in routes {
         let mut routeNodeContent = rsjson::Json::new();
@@ -568,6 +780,26 @@ pub fn exportJson() -> rsjson::Json {
             NodeContent::String(route.routeStatus.toString())
         ));
 
+        routeNodeContent.addNode(Node::new(
+            "tx-queue",
+            NodeContent::Int(route.txQueue as usize)
+        ));
+
+        routeNodeContent.addNode(Node::new(
+            "rx-queue",
+            NodeContent::Int(route.rxQueue as usize)
+        ));
+
+        routeNodeContent.addNode(Node::new(
+            "timer",
+            NodeContent::String(route.timer.toString())
+        ));
+
+        routeNodeContent.addNode(Node::new(
+            "retransmits",
+            NodeContent::Int(route.retransmits as usize)
+        ));
+
         networkRoutesNodeConent.push(NodeContent::Json(routeNodeContent));
     }
 
@@ -576,12 +808,91 @@ pub fn exportJson() -> rsjson::Json {
         NodeContent::List(networkRoutesNodeConent)
     ));
 
-    json.addNode(Node::new(
-        "network",
-        NodeContent::Json(networkNodeContent)
+    let mut interfacesNodeContent = rsjson::Json::new();
+
+    for iface in interfaces {
+        let mut ifaceNodeContent = rsjson::Json::new();
+
+        ifaceNodeContent.addNode(Node::new(
+            "mac",
+            NodeContent::String(iface.macAddress)
+        ));
+
+        ifaceNodeContent.addNode(Node::new(
+            "interface-type",
+            NodeContent::String(match iface.interfaceType {
+                network::InterfaceType::Physical => String::from("physical"),
+                network::InterfaceType::Virtual => String::from("virtual")
+            })
+        ));
+
+        ifaceNodeContent.addNode(Node::new(
+            "ipv4",
+            NodeContent::List({
+                let mut binding = Vec::<NodeContent>::new();
+
+                for address in &ipv4Addresses {
+                    if address.interface == iface.name {
+                        binding.push(NodeContent::String(address.to_string()));
+                    }
+                }
+
+                binding
+            })
+        ));
+
+        ifaceNodeContent.addNode(Node::new(
+            "ipv6",
+            NodeContent::List({
+                let mut binding = Vec::<NodeContent>::new();
+
+                for address in &ipv6Addresses {
+                    if address.interface == iface.name {
+                        binding.push(NodeContent::String(address.to_string()));
+                    }
+                }
+
+                binding
+            })
+        ));
+
+        ifaceNodeContent.addNode(Node::new(
+            "statistics",
+            match statistics.get(&iface.name) {
+                None => NodeContent::Null,
+                Some(statistics) => {
+                    let mut statisticsNodeContent = rsjson::Json::new();
+
+                    statisticsNodeContent.addNode(Node::new("rx-bytes", NodeContent::Int(statistics.rxBytes)));
+                    statisticsNodeContent.addNode(Node::new("tx-bytes", NodeContent::Int(statistics.txBytes)));
+                    statisticsNodeContent.addNode(Node::new("rx-packets", NodeContent::Int(statistics.rxPackets)));
+                    statisticsNodeContent.addNode(Node::new("tx-packets", NodeContent::Int(statistics.txPackets)));
+                    statisticsNodeContent.addNode(Node::new("rx-errors", NodeContent::Int(statistics.rxErrors)));
+                    statisticsNodeContent.addNode(Node::new("tx-errors", NodeContent::Int(statistics.txErrors)));
+                    statisticsNodeContent.addNode(Node::new("rx-dropped", NodeContent::Int(statistics.rxDropped)));
+                    statisticsNodeContent.addNode(Node::new("tx-dropped", NodeContent::Int(statistics.txDropped)));
+
+                    NodeContent::Json(statisticsNodeContent)
+                }
+            }
+        ));
+
+        interfacesNodeContent.addNode(Node::new(
+            iface.name,
+            NodeContent::Json(ifaceNodeContent)
+        ));
+    }
+
+    networkNodeContent.addNode(Node::new(
+        "interfaces",
+        NodeContent::Json(interfacesNodeContent)
     ));
 
-    let temperatureSensors = sensors::temperatureSensors();
+    return networkNodeContent;
+}
+
+#[cfg(feature = "export")]
+fn temperatureSensorsNode(temperatureSensors: Vec<sensors::TemperatureSensor>) -> Vec<NodeContent> {
     let mut temperatureSensorsNodeContent = Vec::<NodeContent>::new();
 
     for sensor in temperatureSensors {
@@ -603,13 +914,12 @@ pub fn exportJson() -> rsjson::Json {
         temperatureSensorsNodeContent.push(NodeContent::Json(temperatureSensorNodeContent));
     }
 
-    json.addNode(Node::new(
-        "temperature-sensors",
-        NodeContent::List(temperatureSensorsNodeContent)
-    ));
+    return temperatureSensorsNodeContent;
+}
 
+#[cfg(feature = "export")]
+fn vramNode(vram: gpu::VRAM) -> Json {
     let mut vramNodeContent = Json::new();
-    let vram = gpu::VRAM::new();
 
     vramNodeContent.addNode(Node::new(
         "size-gib",
@@ -641,7 +951,7 @@ pub fn exportJson() -> rsjson::Json {
         "frequency",
         match vram.frequency {
             Some(frequency) => {
-                NodeContent::Int(frequency)
+                NodeContent::Int(frequency.mhz() as usize)
             },
 
             None => {
@@ -663,110 +973,133 @@ pub fn exportJson() -> rsjson::Json {
         }
     ));
 
-    json.addNode(Node::new(
-        "vram",
-        NodeContent::Json(vramNodeContent)
+    return vramNodeContent;
+}
+
+#[cfg(feature = "export")]
+fn gpuMetricsNode(metrics: gpu::GpuMetrics) -> Json {
+    let mut metricsNodeContent = Json::new();
+
+    metricsNodeContent.addNode(Node::new(
+        "temperature-edge",
+        NodeContent::Int(metrics.temperatureEdge as usize)
     ));
 
-    match gpu::gpuMetrics() {
-        None => {
-            json.addNode(Node::new(
-                "gpu-metrics",
-                NodeContent::Null
-            ));
-        },
-        Some(metrics) => {
-            let mut metricsNodeContent = Json::new();
+    metricsNodeContent.addNode(Node::new(
+        "temperature-hotspot",
+        NodeContent::Int(metrics.temperatureHotspot as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "temperature-edge",
-                NodeContent::Int(metrics.temperatureEdge as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "temperature-mem",
+        NodeContent::Int(metrics.temperatureMem as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "temperature-hotspot",
-                NodeContent::Int(metrics.temperatureHotspot as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "temperature-vrgfx",
+        NodeContent::Int(metrics.temperatureVrgfx as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "temperature-mem",
-                NodeContent::Int(metrics.temperatureMem as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "temperature-vrsoc",
+        NodeContent::Int(metrics.temperatureVrsoc as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "temperature-vrgfx",
-                NodeContent::Int(metrics.temperatureVrgfx as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "temperature-vrmem",
+        NodeContent::Int(metrics.temperatureVrmem as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "temperature-vrsoc",
-                NodeContent::Int(metrics.temperatureVrsoc as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "average-socket-power",
+        NodeContent::Int(metrics.averageSocketPower as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "temperature-vrmem",
-                NodeContent::Int(metrics.temperatureVrmem as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "average-multimedia-activity",
+        NodeContent::Int(metrics.averageMultimediaActivity as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "average-socket-power",
-                NodeContent::Int(metrics.averageSocketPower as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "average-gfxclk-frequency",
+        NodeContent::Int(metrics.averageGfxclkFrequency.mhz() as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "average-gfxclk-frequency",
-                NodeContent::Int(metrics.averageGfxclkFrequency as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "average-sockclk-frequency",
+        NodeContent::Int(metrics.averageSockclkFrequency.mhz() as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "average-sockclk-frequency",
-                NodeContent::Int(metrics.averageSockclkFrequency as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "average-uclk-frequency",
+        NodeContent::Int(metrics.averageUclkFrequency.mhz() as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "average-uclk-frequency",
-                NodeContent::Int(metrics.averageUclkFrequency as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "current-gfxclk",
+        NodeContent::Int(metrics.currentGfxclk.mhz() as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "current-gfxclk",
-                NodeContent::Int(metrics.currentGfxclk as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "current-sockclk",
+        NodeContent::Int(metrics.currentSockclk.mhz() as usize)
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "current-sockclk",
-                NodeContent::Int(metrics.currentSockclk as usize)
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "throttle-status",
+        NodeContent::Int(metrics.throttleStatus as usize),
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "throttle-status",
-                NodeContent::Int(metrics.throttleStatus as usize),
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "current-fan-speed",
+        NodeContent::Int(metrics.currentFanSpeed as usize),
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "current-fan-speed",
-                NodeContent::Int(metrics.currentFanSpeed as usize),
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "pcie-link-width",
+        NodeContent::Int(metrics.pcieLinkWidth as usize),
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "pcie-link-width",
-                NodeContent::Int(metrics.pcieLinkWidth as usize),
-            ));
+    metricsNodeContent.addNode(Node::new(
+        "pcie-link-speed",
+        NodeContent::Int(metrics.pcieLinkSpeed as usize),
+    ));
 
-            metricsNodeContent.addNode(Node::new(
-                "pcie-link-speed",
-                NodeContent::Int(metrics.pcieLinkSpeed as usize),
-            ));
+    return metricsNodeContent;
+}
 
-            json.addNode(Node::new(
-                "gpu-metrics",
-                NodeContent::Json(metricsNodeContent)
-            ));
-        }
+#[cfg(feature = "export")]
+fn gpusNode(gpus: Vec<gpu::Gpu>) -> Vec<NodeContent> {
+    let mut gpusNodeContent = Vec::<NodeContent>::new();
+
+    for gpu in gpus {
+        let mut gpuNodeContent = Json::new();
+
+        gpuNodeContent.addNode(Node::new("card", NodeContent::String(gpu.card)));
+        gpuNodeContent.addNode(Node::new("pci-address", NodeContent::String(gpu.pciAddress)));
+        gpuNodeContent.addNode(Node::new("driver", NodeContent::String(gpu.driver)));
+        gpuNodeContent.addNode(Node::new(
+            "usage",
+            match gpu.usage {
+                Some(usage) => NodeContent::Float(usage),
+                None => NodeContent::Null
+            }
+        ));
+        gpuNodeContent.addNode(Node::new("vram", NodeContent::Json(vramNode(gpu.vram))));
+        gpuNodeContent.addNode(Node::new("metrics", optionalNode(gpu.metrics.map(gpuMetricsNode))));
+        gpuNodeContent.addNode(Node::new(
+            "displays",
+            NodeContent::List(gpu.displays.into_iter().map(NodeContent::String).collect())
+        ));
+
+        gpusNodeContent.push(NodeContent::Json(gpuNodeContent));
     }
 
-    let load = cpu::getLoad();
+    return gpusNodeContent;
+}
 
+#[cfg(feature = "export")]
+fn loadNode(load: cpu::Load) -> Json {
     let mut loadNodeContent = Json::new();
     loadNodeContent.addNode(Node::new(
         "one-minute", NodeContent::Float(load.oneMinute)
@@ -780,12 +1113,13 @@ pub fn exportJson() -> rsjson::Json {
         "fifteen-minutes", NodeContent::Float(load.fifteenMinutes)
     ));
 
-    json.addNode(Node::new(
-        "load", NodeContent::Json(loadNodeContent)
-    ));
+    return loadNodeContent;
+}
 
+#[cfg(feature = "export")]
+fn ipv4Node(addresses: Vec<network::IPv4>) -> Vec<NodeContent> {
     let mut ipv4NodeContent = Vec::<NodeContent>::new();
-    for ipv4 in network::getIPv4() {
+    for ipv4 in addresses {
         let mut ipNode = rsjson::Json::new();
 
         ipNode.addNode(Node::new(
@@ -801,13 +1135,13 @@ pub fn exportJson() -> rsjson::Json {
         ipv4NodeContent.push(NodeContent::Json(ipNode));
     }
 
-    json.addNode(Node::new(
-        "ipv4",
-        NodeContent::List(ipv4NodeContent)
-    ));
+    return ipv4NodeContent;
+}
 
+#[cfg(feature = "export")]
+fn busInputNode(inputs: Vec<bus::BusInput>) -> Vec<NodeContent> {
     let mut busInputNodeContent = Vec::<NodeContent>::new();
-    for input in bus::busInput() {
+    for input in inputs {
         let mut inputNode = rsjson::Json::new();
 
         inputNode.addNode(Node::new(
@@ -894,12 +1228,11 @@ pub fn exportJson() -> rsjson::Json {
         busInputNodeContent.push(NodeContent::Json(inputNode));
     }
 
-    json.addNode(Node::new(
-        "bus-input",
-        NodeContent::List(busInputNodeContent)
-    ));
+    return busInputNodeContent;
+}
 
-    let netIfaces = network::networkInterfaces();
+#[cfg(feature = "export")]
+fn networkInterfacesNode(netIfaces: Vec<network::NetworkInterface>) -> Json {
     let mut ifacesNodeContent = rsjson::Json::new();
 
     for iface in netIfaces {
@@ -924,15 +1257,220 @@ pub fn exportJson() -> rsjson::Json {
         ));
     }
 
-    json.addNode(Node::new(
-        "network-interfaces",
-        NodeContent::Json(ifacesNodeContent)
+    return ifacesNodeContent;
+}
+
+#[cfg(feature = "export")]
+fn optionalNode(node: Option<Json>) -> NodeContent {
+    match node {
+        Some(node) => NodeContent::Json(node),
+        None => NodeContent::Null
+    }
+}
+
+#[cfg(feature = "export")]
+fn optionalList(list: Option<Vec<NodeContent>>) -> NodeContent {
+    match list {
+        Some(list) => NodeContent::List(list),
+        None => NodeContent::Null
+    }
+}
+
+/// Builds the document returned by `exportJson()` from a snapshot, sections which were not collected are `null`,
+/// the ones which failed an `{"error": {...}}` object
+#[cfg(feature = "export")]
+pub(crate) fn snapshotJson(snapshot: SystemSnapshot) -> Json {
+    let errors = snapshot.errors.clone();
+    let mut json = Json::new();
+
+    json.addNode(Node::new("cpu", optionalNode(snapshot.cpu.map(|cpu| cpuNode(cpu, snapshot.clockSource)))));
+    json.addNode(Node::new("ram", optionalNode(snapshot.ram.map(ramNode))));
+    json.addNode(Node::new("motherboard", optionalNode(snapshot.motherboard.map(motherboardNode))));
+    json.addNode(Node::new("nvme-devices", optionalList(snapshot.nvmeDevices.map(nvmeDevicesNode))));
+    json.addNode(Node::new("storage-devices", optionalList(snapshot.storageDevices.map(storageDevicesNode))));
+    json.addNode(Node::new("battery", optionalNode(snapshot.battery.map(batteryNode))));
+    json.addNode(Node::new("backlight", optionalNode(snapshot.backlight.map(backlightNode))));
+
+    json.addNode(Node::new("network", optionalNode(snapshot.networkRate.map(|rate| networkNode(
+        rate,
+        snapshot.networkRoutes.unwrap_or_default(),
+        snapshot.networkInterfaces.clone().unwrap_or_default(),
+        snapshot.ipv4.clone().unwrap_or_default(),
+        snapshot.ipv6.unwrap_or_default(),
+        snapshot.interfaceStatistics.unwrap_or_default()
+    )))));
+
+    json.addNode(Node::new("temperature-sensors", optionalList(snapshot.temperatureSensors.map(temperatureSensorsNode))));
+
+    json.addNode(Node::new("sensors", optionalNode(snapshot.categorizedTemperatures.map(|temperatures| sensorsNode(
+        temperatures,
+        snapshot.thermalZones.unwrap_or_default(),
+        snapshot.coolingDevices.unwrap_or_default(),
+        snapshot.backlights.unwrap_or_default(),
+        snapshot.aggregateBattery
+    )))));
+
+    json.addNode(Node::new("gpus", optionalList(snapshot.gpus.map(gpusNode))));
+
+    // single GPU nodes of earlier versions, describing `card0` only
+    #[cfg(feature = "legacy-gpu-export")]
+    json.addNode(Node::new("vram", optionalNode(snapshot.vram.map(vramNode))));
+    #[cfg(feature = "legacy-gpu-export")]
+    json.addNode(Node::new("gpu-metrics", optionalNode(snapshot.gpuMetrics.map(gpuMetricsNode))));
+    json.addNode(Node::new("load", optionalNode(snapshot.load.map(loadNode))));
+    json.addNode(Node::new("ipv4", optionalList(snapshot.ipv4.map(ipv4Node))));
+    json.addNode(Node::new("bus-input", optionalList(snapshot.busInput.map(busInputNode))));
+    json.addNode(Node::new("network-interfaces", optionalNode(snapshot.networkInterfaces.map(networkInterfacesNode))));
+
+    // sections which could not be collected carry the reason instead of `null`
+    let mut exported = Json::new();
+
+    for node in json.getAllNodes() {
+        match (node.getContent(), errors.get(&node.getLabel())) {
+            (NodeContent::Null, Some(error)) => exported.addNode(Node::new(node.getLabel(), NodeContent::Json(error.toJson()))),
+            _ => exported.addNode(node)
+        }
+    }
+
+    return exported;
+}
+
+/// Returns a `rsjson::Json` object containing all the data which `sysutil` can extract
+///
+/// Same as `SystemSnapshot::capture().toJson()`, use `SysInfo::builder()` and `SnapshotBuilder::export()` to only collect some of the sections
+#[cfg(feature = "export")]
+pub fn exportJson() -> rsjson::Json {
+    return SystemSnapshot::capture().toJson();
+}
+
+/// Returns the value at the dotted `path` of `exportJson()`, `None` if the path does not exist
+///
+/// Only the section containing the value is collected, which keeps status bar scripts cheap
+/// ## Example
+/// ```rust,no_run
+/// println!("{:?}", sysutil::exportValue("cpu.usage.total"));
+/// println!("{:?}", sysutil::exportValue("network-interfaces.eth0.mac"));
+/// ```
+#[cfg(feature = "export")]
+pub fn exportValue(path: &str) -> Option<rsjson::NodeContent> {
+    let section = path.split('.').next().unwrap_or_default();
+    return snapshot::sectionsFor(section)?.collect().value(path);
+}
+
+fn usageBar(percentage: f32, width: usize) -> String {
+    let filled = ((percentage.clamp(0_f32, 100_f32) / 100_f32) * width as f32).round() as usize;
+    format!("[{}{}]", "#".repeat(filled), ".".repeat(width - filled))
+}
+
+fn fitBytes(size: &ByteSize) -> String {
+    let (value, unit) = size.fitBase1024();
+    format!("{:.1} {}", value, unit.toString())
+}
+
+/// Returns a multi-line, human-readable summary of the system, similar to the output of neofetch or inxi
+/// ```rust,no_run
+/// println!("{}", sysutil::report());
+/// ```
+pub fn report() -> String {
+    let mut lines = Vec::<String>::new();
+
+    #[cfg(feature = "cpu")]
+    reportCpu(&mut lines);
+
+    let ramUsage = ram::ramUsage();
+    lines.push(format!("{:<10} {} {:.1}% of {}", "RAM", usageBar(ramUsage, 20), ramUsage, fitBytes(&ram::ramSize())));
+
+    #[cfg(feature = "gpu")]
+    reportGpu(&mut lines);
+
+    #[cfg(feature = "storage")]
+    reportStorage(&mut lines);
+
+    #[cfg(feature = "network")]
+    reportNetwork(&mut lines);
+
+    #[cfg(feature = "sensors")]
+    reportBattery(&mut lines);
+
+    lines.join("\n")
+}
+
+#[cfg(feature = "cpu")]
+fn reportCpu(lines: &mut Vec<String>) {
+    let cpu = cpu::CPU::new();
+    lines.push(format!("{:<10} {}", "CPU", cpu.info.modelName));
+    lines.push(format!(
+        "{:<10} {} cores, {} threads @ {:.2} GHz (max {:.2} GHz)",
+        "", cpu.info.cores, cpu.info.threads, cpu.averageFrequency.ghz(), cpu.info.maxFrequencyMHz / 1000_f32
     ));
+    lines.push(format!("{:<10} {} {:.1}%", "Usage", usageBar(cpu.averageUsage.total, 20), cpu.averageUsage.total));
+
+    let load = cpu::getLoad();
+    lines.push(format!("{:<10} {:.2} {:.2} {:.2}", "Load", load.oneMinute, load.fiveMinutes, load.fifteenMinutes));
+}
+
+#[cfg(feature = "gpu")]
+fn reportGpu(lines: &mut Vec<String>) {
+    if let Some(usage) = gpu::gpuUsage() {
+        lines.push(format!("{:<10} {} {:.1}%", "GPU", usageBar(usage, 20), usage));
+    }
+
+    if let (Some(size), Some(usage)) = (gpu::vramSize(), gpu::vramUsage()) {
+        lines.push(format!("{:<10} {} {:.1}% of {}", "VRAM", usageBar(usage, 20), usage, fitBytes(&size)));
+    }
+}
 
-    return json
+#[cfg(feature = "storage")]
+fn reportStorage(lines: &mut Vec<String>) {
+    let mut mountPoints = Vec::<(String, String)>::new();
+    for device in storage::nvmeDevices() {
+        for partition in device.partitions {
+            mountPoints.push((partition.device, partition.mountPoint));
+        }
+    }
+
+    for device in storage::storageDevices() {
+        for partition in device.partitions {
+            mountPoints.push((partition.device, partition.mountPoint));
+        }
+    }
+
+    let mut label = "Disks";
+    for (device, mountPoint) in mountPoints {
+        if mountPoint.is_empty() {
+            continue
+        }
+
+        if let Some(usage) = storage::filesystemUsage(&mountPoint) {
+            lines.push(format!(
+                "{:<10} {} {:.1}% of {:.1} GiB {} on {}",
+                label, usageBar(usage.usage(), 20), usage.usage(), usage.size.gib(), device, mountPoint
+            ));
+            label = "";
+        }
+    }
+}
+
+#[cfg(feature = "network")]
+fn reportNetwork(lines: &mut Vec<String>) {
+    let mut label = "IPv4";
+    for address in network::getIPv4() {
+        lines.push(format!("{:<10} {} ({})", label, address, address.interface));
+        label = "";
+    }
+}
+
+#[cfg(feature = "sensors")]
+fn reportBattery(lines: &mut Vec<String>) {
+    if let Some(battery) = sensors::batteryInfo() {
+        lines.push(format!(
+            "{:<10} {} {}% ({:?})",
+            "Battery", usageBar(battery.capacity as f32, 20), battery.capacity, battery.status
+        ));
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "cpu"))]
 mod tests {
     use super::*;
 
@@ -0,0 +1,196 @@
+#![allow(non_snake_case)]
+
+use std::{env, process, thread};
+use std::time::Duration;
+use rsjson::NodeContent;
+use sysutil::{cpu, ram, gpu, network, storage, sensors, motherboard, bus, statusbar};
+use sysutil::csv::CsvFormat;
+use sysutil::statusbar::Thresholds;
+
+const USAGE: &str = "Usage: sysutil <command> [options]
+
+Commands:
+    report                  print a summary of the system
+    cpu                     print CPU information and usage
+    ram                     print RAM information
+    gpu                     print GPU metrics and VRAM information
+    net [--watch SECONDS]   print network interfaces, addresses and rate
+    storage                 print storage devices and partitions
+    sensors                 print temperature sensors, battery and backlight
+    motherboard             print motherboard and BIOS information
+    bus                     print bus input devices
+    export --json           print every available information as JSON
+    export --csv|--tsv [--append FILE]
+                            print a row of numeric metrics with its header, or
+                            append it to FILE
+    get PATH                print a single value of the JSON export, e.g. cpu.usage.total
+    bar BLOCK [--i3status]  print a waybar (or i3status-rust) block, BLOCK being
+                            cpu, memory, network, battery or temperature";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(1);
+}
+
+fn printNetwork() {
+    for interface in network::networkInterfaces() {
+        println!("{}", interface);
+    }
+
+    for address in network::getIPv4() {
+        println!("{} ({})", address, address.interface);
+    }
+
+    printRate();
+}
+
+fn printRate() {
+    println!("{}", network::networkRate());
+}
+
+// strings are printed without quotes, so values can be used as they are in status bars
+fn printValue(value: &NodeContent) {
+    match value {
+        NodeContent::String(string) => println!("{}", string),
+        NodeContent::Int(int) => println!("{}", int),
+        NodeContent::Float(float) => println!("{}", float),
+        NodeContent::Bool(bool) => println!("{}", bool),
+        NodeContent::Json(json) => println!("{}", json.toString()),
+        NodeContent::List(list) => list.iter().for_each(printValue),
+        NodeContent::Null => println!("null")
+    }
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    let command = match args.first() {
+        Some(command) => command.as_str(),
+        None => usage()
+    };
+
+    match command {
+        "report" => println!("{}", sysutil::report()),
+        "cpu" => {
+            println!("{}", cpu::CPU::new());
+            println!("load: {}", cpu::getLoad());
+            println!("clock source: {}", cpu::clockSource().current);
+        },
+        "ram" => println!("{}", ram::RAM::new()),
+        "gpu" => {
+            match gpu::gpuUsage() {
+                Some(usage) => println!("usage: {:.1}%", usage),
+                None => println!("usage: unknown")
+            }
+
+            println!("VRAM: {}", gpu::VRAM::new());
+            println!("{:#?}", gpu::gpuMetrics());
+        },
+        "net" => {
+            match args.get(1).map(|arg| arg.as_str()) {
+                None => printNetwork(),
+                Some("--watch") => {
+                    // negative, NaN and overflowing intervals are rejected rather than panicking in the loop
+                    let interval = match args.get(2).and_then(|arg| arg.parse::<f32>().ok()) {
+                        Some(seconds) => Duration::try_from_secs_f32(seconds).unwrap_or_else(|_| usage()),
+                        None => usage()
+                    };
+
+                    loop {
+                        printRate();
+                        thread::sleep(interval);
+                    }
+                },
+                Some(_) => usage()
+            }
+        },
+        "storage" => {
+            for device in storage::nvmeDevices() {
+                println!("{}", device);
+
+                for partition in device.partitions {
+                    println!("    {}", partition);
+                }
+            }
+
+            for device in storage::storageDevices() {
+                println!("{}", device);
+
+                for partition in device.partitions {
+                    println!("    {}", partition);
+                }
+            }
+        },
+        "sensors" => {
+            for sensor in sensors::temperatureSensors() {
+                println!("{}", sensor);
+            }
+
+            if let Some(battery) = sensors::batteryInfo() {
+                println!("battery: {}", battery);
+            }
+
+            for backlight in sensors::backlights() {
+                println!("backlight {} ({}): {}", backlight.name, backlight.backlightType, backlight);
+            }
+        },
+        "motherboard" => println!("{}", motherboard::motherboardInfo()),
+        "bus" => println!("{:#?}", bus::busInput()),
+        "export" => {
+            match args.get(1).map(|arg| arg.as_str()) {
+                Some("--json") | None => println!("{}", sysutil::exportJson().toString()),
+                Some(format @ ("--csv" | "--tsv")) => {
+                    let format = CsvFormat::fromString(format.trim_start_matches("--"));
+
+                    match (args.get(2).map(|arg| arg.as_str()), args.get(3)) {
+                        (None, None) => print!("{}", sysutil::csv::exportCsv(format)),
+                        (Some("--append"), Some(path)) => {
+                            if let Err(error) = sysutil::csv::appendCsv(path, format) {
+                                eprintln!("failed to append to `{}`: {}", path, error);
+                                process::exit(1);
+                            }
+                        },
+                        _ => usage()
+                    }
+                },
+                Some(_) => usage()
+            }
+        },
+        "get" => {
+            let Some(path) = args.get(1) else {
+                usage()
+            };
+
+            match sysutil::exportValue(path) {
+                Some(value) => printValue(&value),
+                None => {
+                    eprintln!("no value at `{}`", path);
+                    process::exit(1);
+                }
+            }
+        },
+        "bar" => {
+            let block = match args.get(1).map(|arg| arg.as_str()) {
+                Some("cpu") => Some(statusbar::cpuBlock(Thresholds::new(70_f32, 90_f32))),
+                Some("memory") => Some(statusbar::memoryBlock(Thresholds::new(80_f32, 95_f32))),
+                Some("network") => Some(statusbar::networkBlock()),
+                Some("battery") => statusbar::batteryBlock(Thresholds::new(20_f32, 10_f32)),
+                Some("temperature") => statusbar::temperatureBlock(Thresholds::new(80_f32, 95_f32)),
+                _ => usage()
+            };
+
+            // an empty line hides the module, e.g. on systems without battery
+            let Some(block) = block else {
+                println!();
+                return;
+            };
+
+            match args.get(2).map(|arg| arg.as_str()) {
+                Some("--i3status") => println!("{}", block.toI3status()),
+                None => println!("{}", block.toWaybar()),
+                Some(_) => usage()
+            }
+        },
+        "-h" | "--help" | "help" => println!("{}", USAGE),
+        _ => usage()
+    }
+}
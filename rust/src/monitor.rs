@@ -0,0 +1,320 @@
+use std::sync::{Arc, RwLock, Weak};
+#[cfg(feature = "export")]
+use std::sync::{mpsc, Mutex};
+#[cfg(feature = "export")]
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::cpu::{cpuUsage, CpuUsage};
+use crate::network::{networkRate, NetworkRate};
+use crate::ram::ramUsage;
+#[cfg(feature = "export")]
+use crate::snapshot::{SnapshotBuilder, SystemSnapshot};
+
+/// Contains the values read by the last sampling of a `SharedMonitor`
+#[derive(Debug, Clone)]
+pub struct MonitorSample {
+    pub cpuUsage: CpuUsage,
+    pub ramUsage: f32,
+    pub networkRate: NetworkRate,
+    pub sampledAt: Instant
+}
+
+#[derive(Debug)]
+struct MonitorState {
+    latest: RwLock<Option<MonitorSample>>,
+}
+
+/// Thread-safe handle to CPU, RAM and network values sampled by a single background thread
+///
+/// Cloning the handle is cheap and every clone reads the same cached values, so multiple
+/// consumers do not each pay for the sampling sleeps of `cpuUsage()` and `networkRate()`.
+/// The background thread stops once every handle has been dropped
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use sysutil::monitor::SharedMonitor;
+///
+/// let monitor = SharedMonitor::new(Duration::from_secs(1));
+/// let widget = monitor.clone();
+///
+/// std::thread::spawn(move || {
+///     println!("{:?}", widget.cpuUsage());
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedMonitor {
+    state: Arc<MonitorState>
+}
+
+impl SharedMonitor {
+    /// Starts the background sampler, taking a new sample every `interval` (on top of the sampling time itself)
+    pub fn new(interval: Duration) -> SharedMonitor {
+        let state = Arc::new(MonitorState {
+            latest: RwLock::new(None)
+        });
+
+        let weak = Arc::downgrade(&state);
+        thread::spawn(move || sampler(weak, interval));
+
+        return SharedMonitor {
+            state: state
+        };
+    }
+
+    /// Returns the last sample, `None` if the first sampling has not completed yet
+    pub fn latest(&self) -> Option<MonitorSample> {
+        return self.state.latest.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    }
+
+    /// Blocks until the first sample is available, then returns it
+    pub fn wait(&self) -> MonitorSample {
+        loop {
+            if let Some(sample) = self.latest() {
+                return sample;
+            }
+
+            thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    /// Returns the last sampled CPU usage
+    pub fn cpuUsage(&self) -> Option<CpuUsage> {
+        return self.latest().map(|sample| sample.cpuUsage);
+    }
+
+    /// Returns the last sampled RAM usage, in percentage
+    pub fn ramUsage(&self) -> Option<f32> {
+        return self.latest().map(|sample| sample.ramUsage);
+    }
+
+    /// Returns the last sampled network rate
+    pub fn networkRate(&self) -> Option<NetworkRate> {
+        return self.latest().map(|sample| sample.networkRate);
+    }
+}
+
+fn sampler(state: Weak<MonitorState>, interval: Duration) {
+    loop {
+        let sample = MonitorSample {
+            cpuUsage: cpuUsage(),
+            ramUsage: ramUsage(),
+            networkRate: networkRate(),
+            sampledAt: Instant::now()
+        };
+
+        // every handle has been dropped, nobody will read the values anymore
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+
+        *state.latest.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(sample);
+        drop(state);
+
+        thread::sleep(interval);
+    }
+}
+
+#[cfg(feature = "export")]
+enum Subscriber {
+    Channel(mpsc::Sender<SystemSnapshot>),
+    Callback(Box<dyn FnMut(&SystemSnapshot) + Send>)
+}
+
+#[cfg(feature = "export")]
+struct MonitorShared {
+    latest: RwLock<Option<SystemSnapshot>>,
+    subscribers: Mutex<Vec<Subscriber>>,
+    stopped: AtomicBool
+}
+
+/// Receives the snapshots taken by a `Monitor`
+///
+/// Snapshots are queued until read, the subscription ends when the monitor is dropped
+#[cfg(feature = "export")]
+pub struct MonitorSubscription {
+    receiver: mpsc::Receiver<SystemSnapshot>
+}
+
+#[cfg(feature = "export")]
+impl MonitorSubscription {
+    /// Blocks until the next snapshot, returns `None` once the monitor has been dropped
+    pub fn recv(&self) -> Option<SystemSnapshot> {
+        return self.receiver.recv().ok();
+    }
+
+    /// Non-blocking variant of `recv()`, returns `None` when no snapshot is pending
+    pub fn tryRecv(&self) -> Option<SystemSnapshot> {
+        return self.receiver.try_recv().ok();
+    }
+
+    /// Returns every pending snapshot without blocking
+    pub fn pending(&self) -> Vec<SystemSnapshot> {
+        return self.receiver.try_iter().collect();
+    }
+}
+
+#[cfg(feature = "export")]
+impl Iterator for MonitorSubscription {
+    type Item = SystemSnapshot;
+
+    fn next(&mut self) -> Option<SystemSnapshot> {
+        return self.recv();
+    }
+}
+
+/// Captures a `SystemSnapshot` every `period` on a background thread and delivers it to subscribers
+///
+/// Subscribers either receive snapshots through a `MonitorSubscription` channel or have a callback invoked
+/// on the sampling thread. Only the sections selected with a `SnapshotBuilder` are collected,
+/// so per-subsystem monitors do not pay for the others. The thread stops when the monitor is dropped
+/// ## Example
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use sysutil::SysInfo;
+/// use sysutil::monitor::Monitor;
+///
+/// let monitor = Monitor::withSections(Duration::from_secs(2), SysInfo::builder().cpu().ram());
+/// monitor.onUpdate(|snapshot| println!("{:?}", snapshot.ram));
+///
+/// for snapshot in monitor.subscribe() {
+///     println!("{:?}", snapshot.cpu.map(|cpu| cpu.averageUsage.total));
+/// }
+/// ```
+#[cfg(feature = "export")]
+pub struct Monitor {
+    shared: Arc<MonitorShared>,
+    sampler: thread::Thread
+}
+
+#[cfg(feature = "export")]
+impl Monitor {
+    /// Starts monitoring every section of the snapshot, capturing one every `period`
+    pub fn new(period: Duration) -> Monitor {
+        return Monitor::withSections(period, SnapshotBuilder::default().all());
+    }
+
+    /// Starts monitoring the sections selected by `sections`, capturing a snapshot every `period`
+    ///
+    /// Captures taking longer than `period` (CPU usage and network rate sample for 250 ms and 500 ms) are taken back to back
+    pub fn withSections(period: Duration, sections: SnapshotBuilder) -> Monitor {
+        let shared = Arc::new(MonitorShared {
+            latest: RwLock::new(None),
+            subscribers: Mutex::new(Vec::<Subscriber>::new()),
+            stopped: AtomicBool::new(false)
+        });
+
+        let weak = Arc::downgrade(&shared);
+        let sampler = thread::spawn(move || snapshotSampler(weak, sections, period)).thread().clone();
+
+        return Monitor {
+            shared: shared,
+            sampler: sampler
+        };
+    }
+
+    /// Returns a channel receiving every snapshot captured from now on
+    pub fn subscribe(&self) -> MonitorSubscription {
+        let (sender, receiver) = mpsc::channel::<SystemSnapshot>();
+        self.shared.subscribers.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(Subscriber::Channel(sender));
+
+        return MonitorSubscription {
+            receiver: receiver
+        };
+    }
+
+    /// Invokes `callback` on the sampling thread with every snapshot captured from now on
+    ///
+    /// Slow callbacks delay the following captures
+    pub fn onUpdate<F: FnMut(&SystemSnapshot) + Send + 'static>(&self, callback: F) {
+        self.shared.subscribers.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(Subscriber::Callback(Box::new(callback)));
+    }
+
+    /// Returns the last snapshot, `None` if the first capture has not completed yet
+    pub fn latest(&self) -> Option<SystemSnapshot> {
+        return self.shared.latest.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    }
+}
+
+#[cfg(feature = "export")]
+impl Drop for Monitor {
+    fn drop(&mut self) {
+        self.shared.stopped.store(true, Ordering::Relaxed);
+        self.sampler.unpark();
+    }
+}
+
+#[cfg(feature = "export")]
+fn snapshotSampler(shared: Weak<MonitorShared>, sections: SnapshotBuilder, period: Duration) {
+    loop {
+        let startedAt = Instant::now();
+        let snapshot = sections.collect();
+
+        // the monitor has been dropped, nobody will read the snapshots anymore
+        let Some(state) = shared.upgrade() else {
+            return;
+        };
+
+        if state.stopped.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // subscriptions whose receiver has been dropped are removed
+        state.subscribers.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).retain_mut(|subscriber| {
+            match subscriber {
+                Subscriber::Channel(sender) => sender.send(snapshot.clone()).is_ok(),
+                Subscriber::Callback(callback) => {
+                    callback(&snapshot);
+                    true
+                }
+            }
+        });
+
+        *state.latest.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(snapshot);
+        drop(state);
+
+        // parked rather than slept, so dropping the monitor wakes the thread up
+        let deadline = startedAt + period;
+        loop {
+            let now = Instant::now();
+            if now >= deadline {
+                break;
+            }
+
+            thread::park_timeout(deadline - now);
+
+            if shared.upgrade().map(|state| state.stopped.load(Ordering::Relaxed)).unwrap_or(true) {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::cpu::ProcessorUsage;
+
+    fn usage(total: f32) -> ProcessorUsage {
+        ProcessorUsage {
+            total: total,
+            user: total,
+            nice: 0_f32,
+            system: 0_f32,
+            idle: 100_f32 - total,
+            iowait: 0_f32,
+            interrupt: 0_f32,
+            soft_interrupt: 0_f32
+        }
+    }
+
+    // 25% CPU over two processors at 40% and 10%, 42.5% RAM and 1 KiB/s download, shared by the exporters' tests
+    pub(crate) fn sample() -> MonitorSample {
+        MonitorSample {
+            cpuUsage: CpuUsage { average: usage(25_f32), processors: vec![usage(40_f32), usage(10_f32)] },
+            ramUsage: 42.5,
+            networkRate: NetworkRate { download: 1024_f32, upload: 0_f32 },
+            sampledAt: Instant::now()
+        }
+    }
+}
@@ -1,7 +1,10 @@
+use std::{fmt, fs};
+use std::sync::Mutex;
 use crate::utils::{*};
 
 /// Contains information relative to the motherboard and the installed bios
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Motherboard {
     pub name: String,
     pub vendor: String,
@@ -11,6 +14,7 @@ pub struct Motherboard {
 
 /// Contains information relative to the installed bios
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bios {
     pub vendor: String,
     pub release: String,
@@ -18,6 +22,18 @@ pub struct Bios {
     pub date: String
 }
 
+impl fmt::Display for Bios {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} ({})", self.vendor, self.version, self.date)
+    }
+}
+
+impl fmt::Display for Motherboard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}, BIOS {}", self.vendor, self.name, self.version, self.bios)
+    }
+}
+
 /// Returns information about the currently installed BIOS
 pub fn biosInfo() -> Bios {
     linuxCheck();
@@ -36,8 +52,34 @@ pub fn biosInfo() -> Bios {
     }
 }
 
+static MOTHERBOARD_INFO: Mutex<Option<Motherboard>> = Mutex::new(None);
+
 /// Returns information about the motherboard
+///
+/// DMI data does not change at runtime, so it is read once and cached,
+/// use `refreshMotherboardInfo()` to read it again
 pub fn motherboardInfo() -> Motherboard {
+    let mut cache = MOTHERBOARD_INFO.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    match cache.as_ref() {
+        Some(motherboard) => motherboard.clone(),
+        None => {
+            let motherboard = readMotherboardInfo();
+            *cache = Some(motherboard.clone());
+            motherboard
+        }
+    }
+}
+
+/// Reads again motherboard and BIOS information, updating the cached value returned by `motherboardInfo()`
+pub fn refreshMotherboardInfo() -> Motherboard {
+    let motherboard = readMotherboardInfo();
+    *MOTHERBOARD_INFO.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(motherboard.clone());
+
+    return motherboard;
+}
+
+fn readMotherboardInfo() -> Motherboard {
     linuxCheck();
 
     let name = String::from(readFile("/sys/devices/virtual/dmi/id/board_name").trim());
@@ -53,3 +95,387 @@ pub fn motherboardInfo() -> Motherboard {
         bios: bios
     }
 }
+
+/// Where a firmware version was read from
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FirmwareSource {
+    /// DMI tables exposed by the kernel
+    Dmi,
+    /// fwupd daemon, over D-Bus
+    Fwupd
+}
+
+impl FirmwareSource {
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            FirmwareSource::Dmi => "DMI",
+            FirmwareSource::Fwupd => "fwupd"
+        })
+    }
+}
+
+impl fmt::Display for FirmwareSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains the firmware version of a system component
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FirmwareVersion {
+    /// Component name, e.g. `BIOS`, `Embedded Controller`, or the device name reported by fwupd
+    pub component: String,
+    pub vendor: String,
+    pub version: String,
+    /// Release date, only known for the BIOS
+    pub date: Option<String>,
+    pub source: FirmwareSource
+}
+
+impl fmt::Display for FirmwareVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.component, self.version)?;
+
+        if let Some(date) = &self.date {
+            write!(f, " ({})", date)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "fwupd")]
+fn fwupdVersions() -> Vec<FirmwareVersion> {
+    use std::collections::HashMap;
+    use zbus::zvariant::OwnedValue;
+
+    let devices = zbus::blocking::Connection::system().and_then(|connection| {
+        let fwupd = zbus::blocking::Proxy::new(&connection, "org.freedesktop.fwupd", "/", "org.freedesktop.fwupd")?;
+        fwupd.call::<_, _, Vec<HashMap<String, OwnedValue>>>("GetDevices", &())
+    });
+
+    let devices = match devices {
+        Ok(devices) => devices,
+        Err(_error) => {
+            traceEvent!(debug, error = %_error, "fwupd is not reachable");
+            return Vec::<FirmwareVersion>::new();
+        }
+    };
+
+    let mut versions = Vec::<FirmwareVersion>::new();
+
+    for device in devices {
+        let string = |name: &str| device.get(name)
+            .and_then(|value| value.try_clone().ok())
+            .and_then(|value| String::try_from(value).ok())
+            .unwrap_or_default();
+
+        let version = string("Version");
+        if version.is_empty() {
+            continue;
+        }
+
+        versions.push(FirmwareVersion {
+            component: string("Name"),
+            vendor: string("Vendor"),
+            version: version,
+            date: None,
+            source: FirmwareSource::Fwupd
+        });
+    }
+
+    return versions;
+}
+
+/// Returns the firmware versions of the BIOS, the embedded controller and,
+/// with the `fwupd` feature, every device fwupd can see
+pub fn firmwareVersions() -> Vec<FirmwareVersion> {
+    linuxCheck();
+
+    let mut versions = Vec::<FirmwareVersion>::new();
+    let bios = motherboardInfo().bios;
+
+    versions.push(FirmwareVersion {
+        component: String::from("BIOS"),
+        vendor: bios.vendor.clone(),
+        version: bios.version,
+        date: Some(bios.date).filter(|date| !date.is_empty()),
+        source: FirmwareSource::Dmi
+    });
+
+    let ecRelease = String::from(readFile("/sys/devices/virtual/dmi/id/ec_firmware_release").trim());
+
+    if !ecRelease.is_empty() {
+        versions.push(FirmwareVersion {
+            component: String::from("Embedded Controller"),
+            vendor: bios.vendor,
+            version: ecRelease,
+            date: None,
+            source: FirmwareSource::Dmi
+        });
+    }
+
+    #[cfg(feature = "fwupd")]
+    versions.extend(fwupdVersions());
+
+    return versions;
+}
+
+/// Usage status of a physical expansion slot, as reported by SMBIOS
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SlotUsage {
+    Available,
+    InUse,
+    Unavailable,
+    Unknown
+}
+
+impl SlotUsage {
+    fn fromCode(code: u8) -> SlotUsage {
+        match code {
+            3 => SlotUsage::Available,
+            4 => SlotUsage::InUse,
+            5 => SlotUsage::Unavailable,
+            _ => SlotUsage::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            SlotUsage::Available => "Available",
+            SlotUsage::InUse => "In use",
+            SlotUsage::Unavailable => "Unavailable",
+            SlotUsage::Unknown => "Unknown"
+        })
+    }
+}
+
+impl fmt::Display for SlotUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains a physical expansion slot of the motherboard (SMBIOS type 9 record)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Slot {
+    /// Silkscreen label, e.g. `PCIEX16_1`
+    pub designation: String,
+    /// Slot form factor, e.g. `PCI Express 4 x16`, `M.2 Socket 3`
+    pub slotType: String,
+    /// Data bus width in lanes, when the slot is PCI Express
+    pub width: Option<u8>,
+    pub usage: SlotUsage,
+    /// PCI address of the slot (`segment:bus:device.function`), `None` when the firmware does not report it
+    pub busAddress: Option<String>
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} ({})", self.designation, self.slotType, self.usage)?;
+
+        if let Some(address) = &self.busAddress {
+            write!(f, " at {}", address)?;
+        }
+
+        Ok(())
+    }
+}
+
+// slot types of SMBIOS 3.x section 7.10.1
+fn slotTypeName(code: u8) -> String {
+    let pcieWidths = ["", " x1", " x2", " x4", " x8", " x16"];
+
+    let name = match code {
+        0x02 => "Unknown",
+        0x03 => "ISA",
+        0x04 => "MCA",
+        0x05 => "EISA",
+        0x06 => "PCI",
+        0x07 => "PC Card (PCMCIA)",
+        0x08 => "VL-VESA",
+        0x09 => "Proprietary",
+        0x0A => "Processor Card",
+        0x0B => "Proprietary Memory Card",
+        0x0C => "I/O Riser Card",
+        0x0D => "NuBus",
+        0x0E => "PCI 66MHz Capable",
+        0x0F => "AGP",
+        0x10 => "AGP 2X",
+        0x11 => "AGP 4X",
+        0x12 => "PCI-X",
+        0x13 => "AGP 8X",
+        0x14 => "M.2 Socket 1-DP",
+        0x15 => "M.2 Socket 1-SD",
+        0x16 => "M.2 Socket 2",
+        0x17 => "M.2 Socket 3",
+        0x18 => "MXM Type I",
+        0x19 => "MXM Type II",
+        0x1A => "MXM Type III",
+        0x1B => "MXM Type III-HE",
+        0x1C => "MXM Type IV",
+        0x1D => "MXM 3.0 Type A",
+        0x1E => "MXM 3.0 Type B",
+        0x1F => "PCI Express 2 SFF-8639 (U.2)",
+        0x20 => "PCI Express 3 SFF-8639 (U.2)",
+        0x21 => "PCI Express Mini 52-pin with bottom-side keep-outs",
+        0x22 => "PCI Express Mini 52-pin without bottom-side keep-outs",
+        0x23 => "PCI Express Mini 76-pin",
+        0x24 => "PCI Express 4 SFF-8639 (U.2)",
+        0x25 => "PCI Express 5 SFF-8639 (U.2)",
+        0x26 => "OCP NIC 3.0 Small Form Factor",
+        0x27 => "OCP NIC 3.0 Large Form Factor",
+        0x28 => "OCP NIC Prior to 3.0",
+        0x30 => "CXL Flexbus 1.0",
+        0xA0 => "PC-98/C20",
+        0xA1 => "PC-98/C24",
+        0xA2 => "PC-98/E",
+        0xA3 => "PC-98/Local Bus",
+        0xA4 => "PC-98/Card",
+        0xA5..=0xAA => return format!("PCI Express{}", pcieWidths[(code - 0xA5) as usize]),
+        0xAB..=0xB0 => return format!("PCI Express 2{}", pcieWidths[(code - 0xAB) as usize]),
+        0xB1..=0xB6 => return format!("PCI Express 3{}", pcieWidths[(code - 0xB1) as usize]),
+        0xB7..=0xBC => return format!("PCI Express 4{}", pcieWidths[(code - 0xB7) as usize]),
+        0xBD..=0xC2 => return format!("PCI Express 5{}", pcieWidths[(code - 0xBD) as usize]),
+        // unlike the previous generations, PCI Express 6 has a single code for every width
+        0xC3 => "PCI Express 6",
+        0xC4 => "EDSFF E1",
+        0xC5 => "EDSFF E3",
+        _ => "Other"
+    };
+
+    return String::from(name);
+}
+
+/// Parses a raw SMBIOS type 9 structure, formatted area followed by its string set
+fn parseSlot(raw: &[u8]) -> Option<Slot> {
+    if raw.len() < 0x0C || raw[0] != 9 {
+        return None;
+    }
+
+    let length = raw[1] as usize;
+    if raw.len() < length {
+        return None;
+    }
+
+    let strings: Vec<String> = raw[length..].split(|byte| *byte == 0)
+        .take_while(|string| !string.is_empty())
+        .map(|string| String::from_utf8_lossy(string).trim().to_string())
+        .collect();
+
+    let designation = match raw[4] {
+        0 => String::new(),
+        index => strings.get(index as usize - 1).cloned().unwrap_or_default()
+    };
+
+    let width = match raw[6] {
+        0x08 => Some(1),
+        0x09 => Some(2),
+        0x0A => Some(4),
+        0x0B => Some(8),
+        0x0C => Some(12),
+        0x0D => Some(16),
+        0x0E => Some(32),
+        _ => None
+    };
+
+    // segment, bus and device/function were added in SMBIOS 2.6, 0xFF marks them as not provided
+    let busAddress = if length >= 0x11 {
+        let segment = u16::from_le_bytes([raw[0x0D], raw[0x0E]]);
+        let (bus, deviceFunction) = (raw[0x0F], raw[0x10]);
+
+        if segment == 0xFFFF || bus == 0xFF || deviceFunction == 0xFF {
+            None
+        } else {
+            Some(format!("{:04x}:{:02x}:{:02x}.{}", segment, bus, deviceFunction >> 3, deviceFunction & 0x07))
+        }
+    } else {
+        None
+    };
+
+    return Some(Slot {
+        designation: designation,
+        slotType: slotTypeName(raw[5]),
+        width: width,
+        usage: SlotUsage::fromCode(raw[7]),
+        busAddress: busAddress
+    });
+}
+
+/// Returns the physical expansion slots listed by the firmware, with their usage status
+///
+/// SMBIOS entries are only readable by root, an empty list is returned otherwise
+pub fn slots() -> Vec<Slot> {
+    linuxCheck();
+
+    let mut entries: Vec<(u32, fs::DirEntry)> = fs::read_dir(sysPath("/sys/firmware/dmi/entries")).into_iter().flatten().flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let instance = name.strip_prefix("9-")?.parse::<u32>().ok()?;
+            Some((instance, entry))
+        })
+        .collect();
+
+    entries.sort_by_key(|(instance, _)| *instance);
+
+    return entries.iter()
+        .filter_map(|(_, entry)| fs::read(entry.path().join("raw")).ok())
+        .filter_map(|raw| parseSlot(&raw))
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsesSmbiosSlot() {
+        let mut raw = vec![
+            9, 0x11, 0x20, 0x00,
+            1, 0xBC, 0x0D, 4, 4,
+            0x01, 0x00, 0x04, 0x01,
+            0x00, 0x00, 0x01, 0x08
+        ];
+        raw.extend_from_slice(b"PCIEX16_1\0\0");
+
+        let slot = parseSlot(&raw).unwrap();
+
+        assert_eq!(slot.designation, "PCIEX16_1");
+        assert_eq!(slot.slotType, "PCI Express 4 x16");
+        assert_eq!(slot.width, Some(16));
+        assert_eq!(slot.usage, SlotUsage::InUse);
+        assert_eq!(slot.busAddress.as_deref(), Some("0000:01:01.0"));
+    }
+
+    #[test]
+    fn unpopulatedSlotHasNoAddress() {
+        let mut raw = vec![
+            9, 0x11, 0x21, 0x00,
+            1, 0x17, 0x0A, 3, 1,
+            0x02, 0x00, 0x04, 0x01,
+            0xFF, 0xFF, 0xFF, 0xFF
+        ];
+        raw.extend_from_slice(b"M2_1\0\0");
+
+        let slot = parseSlot(&raw).unwrap();
+
+        assert_eq!(slot.slotType, "M.2 Socket 3");
+        assert_eq!(slot.usage, SlotUsage::Available);
+        assert_eq!(slot.busAddress, None);
+    }
+
+    #[test]
+    fn namesSlotTypes() {
+        assert_eq!(slotTypeName(0x0E), "PCI 66MHz Capable");
+        assert_eq!(slotTypeName(0x12), "PCI-X");
+        assert_eq!(slotTypeName(0xA5), "PCI Express");
+        assert_eq!(slotTypeName(0xB6), "PCI Express 3 x16");
+        assert_eq!(slotTypeName(0xC3), "PCI Express 6");
+        assert_eq!(slotTypeName(0xC4), "EDSFF E1");
+        assert_eq!(slotTypeName(0x01), "Other");
+    }
+}
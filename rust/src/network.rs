@@ -1,15 +1,46 @@
-use std::{fmt, fs, thread};
-use std::time::Duration;
+use std::{fmt, fs, mem, thread};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime};
+#[cfg(any(feature = "ping", feature = "nl80211"))]
+use std::io;
+#[cfg(feature = "ping")]
+use std::net::{IpAddr, ToSocketAddrs};
 use crate::utils::{*};
-/// Contains total download and upload newtwork rate (in bytes)
+/// Contains total download and upload newtwork rate (in bytes per second)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetworkRate {
     pub download: f32,
     pub upload: f32,
 }
 
+impl NetworkRate {
+    /// Returns the download rate with its unit
+    pub fn downloadWithUnit(&self) -> (f32, Unit) {
+        return (self.download, Unit::BytesPerSecond);
+    }
+
+    /// Returns the upload rate with its unit
+    pub fn uploadWithUnit(&self) -> (f32, Unit) {
+        return (self.upload, Unit::BytesPerSecond);
+    }
+}
+
+impl fmt::Display for NetworkRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "down {}/s, up {}/s",
+            ByteSize::fromBytes(self.download as u64), ByteSize::fromBytes(self.upload as u64)
+        )
+    }
+}
+
 /// Different route types
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RouteType {
     TCP,
     TCP6,
@@ -18,6 +49,7 @@ pub enum RouteType {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RouteStatus {
     ESTABLISHED,
     SYN_SENT,
@@ -73,6 +105,24 @@ impl RouteStatus {
         }
     }
 
+    /// Inverse of `toString()`, unknown values are mapped to `RouteStatus::NEW_SYN_RECEIVED`
+    pub fn fromString(status: &str) -> RouteStatus {
+        match status {
+            "established" => RouteStatus::ESTABLISHED,
+            "syn sent" => RouteStatus::SYN_SENT,
+            "syn received" => RouteStatus::SYN_RECEIVED,
+            "fin wait 1" => RouteStatus::FIN_WAIT1,
+            "fin wait 2" => RouteStatus::FIN_WAIT2,
+            "time wait" => RouteStatus::TIME_WAIT,
+            "closed" => RouteStatus::CLOSED,
+            "close wait" => RouteStatus::CLOSE_WAIT,
+            "last acknowledgment" => RouteStatus::LAST_ACKNOWLEDGMENT,
+            "listening" => RouteStatus::LISTENING,
+            "closing" => RouteStatus::CLOSING,
+            _ => RouteStatus::NEW_SYN_RECEIVED
+        }
+    }
+
     pub fn toString(&self) -> String {
         match self {
             RouteStatus::ESTABLISHED => String::from("established"),
@@ -91,19 +141,88 @@ impl RouteStatus {
     }
 }
 
+/// Kernel timer pending on a socket, as reported in the `tr` column of `/proc/net/tcp`
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SocketTimer {
+    None,
+    Retransmit,
+    KeepAlive,
+    TimeWait,
+    ZeroWindowProbe
+}
+
+impl SocketTimer {
+    fn fromCode(code: &str) -> SocketTimer {
+        match code {
+            "01" => SocketTimer::Retransmit,
+            "02" => SocketTimer::KeepAlive,
+            "03" => SocketTimer::TimeWait,
+            "04" => SocketTimer::ZeroWindowProbe,
+            _ => SocketTimer::None
+        }
+    }
+
+    /// Inverse of `toString()`, unknown values are mapped to `SocketTimer::None`
+    pub fn fromString(timer: &str) -> SocketTimer {
+        match timer {
+            "retransmit" => SocketTimer::Retransmit,
+            "keepalive" => SocketTimer::KeepAlive,
+            "time wait" => SocketTimer::TimeWait,
+            "zero window probe" => SocketTimer::ZeroWindowProbe,
+            _ => SocketTimer::None
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            SocketTimer::None => String::from("none"),
+            SocketTimer::Retransmit => String::from("retransmit"),
+            SocketTimer::KeepAlive => String::from("keepalive"),
+            SocketTimer::TimeWait => String::from("time wait"),
+            SocketTimer::ZeroWindowProbe => String::from("zero window probe")
+        }
+    }
+}
+
+impl fmt::Display for SocketTimer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
 /// Represents a network route and its type, containing local address+port, remote address+port and connection status
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetworkRoute {
     pub routeType: RouteType,
     pub localAddress: String,
     pub localPort: u16,
     pub remoteAddress: String,
     pub remotePort: u16,
-    pub routeStatus: RouteStatus
+    pub routeStatus: RouteStatus,
+    /// Bytes in the send queue, not yet acknowledged by the peer for TCP
+    pub txQueue: u64,
+    /// Bytes in the receive queue, not yet read by the application
+    pub rxQueue: u64,
+    pub timer: SocketTimer,
+    /// Unrecovered retransmission timeouts, a growing value on an established connection indicates a stuck peer
+    pub retransmits: u64
+}
+
+impl fmt::Display for NetworkRoute {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{:?} {}:{} -> {}:{} ({})",
+            self.routeType, self.localAddress, self.localPort,
+            self.remoteAddress, self.remotePort, self.routeStatus.toString()
+        )
+    }
 }
 
 /// Holds information related to an IP address
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IPv4 {
     pub address: String,
     pub interface: String,
@@ -124,54 +243,99 @@ impl IPv4 {
     }
 }
 
+/// Holds information related to an IPv6 address
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IPv6 {
+    pub address: String,
+    pub interface: String,
+    pub prefixLength: u8,
+    pub scope: String
+}
+
+impl fmt::Display for IPv6 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefixLength)
+    }
+}
+
+/// Contains traffic counters of a network interface, as reported by sysfs
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InterfaceStatistics {
+    pub rxBytes: usize,
+    pub txBytes: usize,
+    pub rxPackets: usize,
+    pub txPackets: usize,
+    pub rxErrors: usize,
+    pub txErrors: usize,
+    pub rxDropped: usize,
+    pub txDropped: usize
+}
+
+/// Contains the counters of a network interface, as reported by `/proc/net/dev`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InterfaceCounters {
+    pub interface: String,
+    pub rxBytes: u64,
+    pub rxPackets: u64,
+    pub rxErrors: u64,
+    pub rxDropped: u64,
+    pub rxFifo: u64,
+    pub rxFrame: u64,
+    pub rxCompressed: u64,
+    pub rxMulticast: u64,
+    pub txBytes: u64,
+    pub txPackets: u64,
+    pub txErrors: u64,
+    pub txDropped: u64,
+    pub txFifo: u64,
+    pub txCollisions: u64,
+    pub txCarrier: u64,
+    pub txCompressed: u64
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum InterfaceType {
     Physical, Virtual
 }
 
+impl fmt::Display for InterfaceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InterfaceType::Physical => write!(f, "physical"),
+            InterfaceType::Virtual => write!(f, "virtual")
+        }
+    }
+}
+
 /// Contains information about network interfaces
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetworkInterface {
     pub name: String,
     pub macAddress: String,
     pub interfaceType: InterfaceType
 }
 
-fn makeNetmask(ip: &String, broadcast: &String) -> String {
-    let splittedIp = {
-        let binding = ip.split(".").collect::<Vec<&str>>();
-        let mut bytesIp = Vec::<u8>::new();
-
-        for chunk in binding {
-            bytesIp.push(chunk.parse::<u8>().unwrap());
-        }
-
-        bytesIp
-    };
-
-    let splittedBrd = {
-        let binding = broadcast.split(".").collect::<Vec<&str>>();
-        let mut bytesIp = Vec::<u8>::new();
-
-        for chunk in binding {
-            bytesIp.push(chunk.parse::<u8>().unwrap());
-        }
-
-        bytesIp
-    };
+impl fmt::Display for NetworkInterface {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}, {})", self.name, self.interfaceType, self.macAddress)
+    }
+}
 
-    let mut mask = Vec::<u8>::new();
-    for i in 0..4 {
-        let ipByte = splittedIp.get(i).unwrap().to_owned();
+fn makeNetmask(ip: &String, broadcast: &String) -> Option<String> {
+    let splittedIp = ip.parse::<Ipv4Addr>().ok()?.octets();
+    let splittedBrd = broadcast.parse::<Ipv4Addr>().ok()?.octets();
 
-        let brdByte = splittedBrd.get(i).unwrap().to_owned();
-        mask.push(!ipByte | (ipByte & !brdByte));
+    let mut mask = [0u8; 4];
+    for (byte, (ipByte, brdByte)) in mask.iter_mut().zip(splittedIp.iter().zip(splittedBrd.iter())) {
+        *byte = !ipByte | (ipByte & !brdByte);
     }
 
-    format!(
-        "{}.{}.{}.{}", mask.get(0).unwrap(), mask.get(1).unwrap(),
-        mask.get(2).unwrap(), mask.get(3).unwrap()
-    )
+    return Some(Ipv4Addr::from(mask).to_string());
 }
 
 fn bitsToByte(bits: &mut Vec<u8>) -> u8{
@@ -207,27 +371,17 @@ fn netmaskFromCidr(cidr: u8) -> String {
 
 }
 
-fn makeu8Vec(ip: &String) -> Vec<u8> {
-    let splitted = ip.split(".");
-    let mut uIP = Vec::<u8>::new();
-
-    for octet in splitted {
-        uIP.push(octet.parse::<u8>().unwrap())
-    }
+fn ipToBaseNetwork(ip: &str, mask: &str) -> Option<String> {
+    let ip = ip.parse::<Ipv4Addr>().ok()?;
+    let mask = mask.parse::<Ipv4Addr>().ok()?;
 
-    return uIP
+    return Some(Ipv4Addr::from(u32::from(ip) & u32::from(mask)).to_string());
 }
 
-fn ipToBaseNetwork(ip: &String, mask: &String) -> String {
-    let ipVec = makeu8Vec(ip);
-    let maskVec = makeu8Vec(mask);
-
-    let mut baseNetwork: Vec<String> = Vec::<String>::new();
-    for i in 0..4 {
-        baseNetwork.push((ipVec[i] & maskVec[i]).to_string());
-    }
-
-    return baseNetwork.join(".");
+// returns the prefix length of a `/<prefix length> link UNICAST` line of `/proc/net/fib_trie`
+fn fibPrefixLength(line: &str) -> Result<u8, SysError> {
+    let prefix = line.trim().trim_start_matches('/').split_whitespace().next().unwrap_or_default();
+    return tryParse::<u8, _>("/proc/net/fib_trie", prefix);
 }
 
 /// Returns the various ip addresses associated to the various network interfaces in the device
@@ -241,17 +395,22 @@ pub fn getIPv4() -> Vec<IPv4> {
     let mut index: usize = 0;
     let lines = fibTrie.split("\n").collect::<Vec<&str>>();
 
-    while index < lines.len() {
-        let line = lines.get(index).unwrap().to_string();
-
+    while let Some(line) = lines.get(index) {
         if !line.contains("link UNICAST") {
             index += 1;
             continue
         }
 
-        let cidr = line.trim().get(1..3).unwrap().to_string();
         index += 1;
-        let binding = lines.get(index).unwrap().to_string().replace("|--", "");
+        let Ok(prefixLength) = fibPrefixLength(line) else {
+            continue
+        };
+
+        let cidr = prefixLength.to_string();
+        let Some(binding) = lines.get(index) else {
+            break
+        };
+        let binding = binding.replace("|--", "");
 
         if binding.contains("+") {
             index += 1;
@@ -263,15 +422,17 @@ pub fn getIPv4() -> Vec<IPv4> {
 
         let address = binding.trim().to_string();
 
-        while index < lines.len() && !lines.get(index).unwrap().contains("host LOCAL") {
+        while index < lines.len() && !lines[index].contains("host LOCAL") {
             index += 1;
         }
 
         index += 1;
-        let binding = lines.get(index).unwrap().replace("|--", "");
+        let Some(binding) = lines.get(index) else {
+            break
+        };
 
-        let broadcast = binding.trim().to_string();
-        let netmask = netmaskFromCidr(cidr.parse::<u8>().unwrap());
+        let broadcast = binding.replace("|--", "").trim().to_string();
+        let netmask = netmaskFromCidr(prefixLength);
 
         addresses.push((address, broadcast, netmask, cidr));
         index += 1;
@@ -285,8 +446,14 @@ pub fn getIPv4() -> Vec<IPv4> {
         }
 
         let splittedLine = line.split("\t").collect::<Vec<&str>>();
-        let device = splittedLine.first().unwrap().trim().to_string();
-        let network = bytesToAddress(splittedLine.get(1).unwrap().trim().to_string(), ".");
+        let (Some(device), Some(network)) = (splittedLine.first(), splittedLine.get(1)) else {
+            continue
+        };
+
+        let device = device.trim().to_string();
+        let Some(network) = bytesToAddress(network.trim(), ".") else {
+            continue
+        };
 
         let mut ip = String::new();
         let mut brd = String::new();
@@ -297,8 +464,7 @@ pub fn getIPv4() -> Vec<IPv4> {
         for (address, broadcast, netmask, cidrMask) in &addresses {
 
 
-            let baseAddress = ipToBaseNetwork(address, netmask);
-            if &network == &baseAddress {
+            if ipToBaseNetwork(address, netmask).as_ref() == Some(&network) {
                 if usedIps.contains(address) {
                     break
                 }
@@ -328,33 +494,86 @@ pub fn getIPv4() -> Vec<IPv4> {
     return ipv4Addresses;
 }
 
-fn bytesToAddress(address: String, separator: &str) -> String {
+/// Returns the IPv6 addresses associated to the various network interfaces in the device
+pub fn getIPv6() -> Vec<IPv6> {
+    linuxCheck();
+
+    let mut addresses = Vec::<IPv6>::new();
+    let fileContent = readFile("/proc/net/if_inet6");
+
+    for line in fileContent.split("\n") {
+        let splittedLine = line.split_whitespace().collect::<Vec<&str>>();
+
+        if splittedLine.len() < 6 {
+            continue
+        }
+
+        let address = match u128::from_str_radix(splittedLine[0], 16) {
+            Ok(address) => Ipv6Addr::from(address).to_string(),
+            Err(_) => continue
+        };
+
+        let scope = match splittedLine[3] {
+            "00" => String::from("global"),
+            "10" => String::from("host"),
+            "20" => String::from("link"),
+            "40" => String::from("site"),
+            other => String::from(other)
+        };
+
+        addresses.push(IPv6 {
+            address: address,
+            interface: splittedLine[5].to_string(),
+            prefixLength: u8::from_str_radix(splittedLine[2], 16).unwrap_or(0),
+            scope: scope
+        });
+    }
+
+    return addresses;
+}
+
+/// Returns traffic counters for the specified interface, returns `None` if the interface does not exist
+pub fn interfaceStatistics(interface: &str) -> Option<InterfaceStatistics> {
+    linuxCheck();
+
+    let basePath = format!("/sys/class/net/{}/statistics", interface);
+    if !sysPath(&basePath).exists() {
+        return None;
+    }
+
+    let counter = |name: &str| readFile(format!("{}/{}", basePath, name)).parse::<usize>().unwrap_or(0);
+
+    Some(InterfaceStatistics {
+        rxBytes: counter("rx_bytes"),
+        txBytes: counter("tx_bytes"),
+        rxPackets: counter("rx_packets"),
+        txPackets: counter("tx_packets"),
+        rxErrors: counter("rx_errors"),
+        txErrors: counter("tx_errors"),
+        rxDropped: counter("rx_dropped"),
+        txDropped: counter("tx_dropped")
+    })
+}
+
+// `None` when the address is not made of hexadecimal byte pairs
+fn bytesToAddress(address: &str, separator: &str) -> Option<String> {
     let mut chunks = Vec::<String>::new();
 
     let mut index: usize = 0;
     while index < address.len() {
-        chunks.push(
-            i64::from_str_radix(
-                &address[index..index+2],
-                16
-            ).unwrap().to_string()
-        );
+        chunks.push(u8::from_str_radix(address.get(index..index + 2)?, 16).ok()?.to_string());
         index += 2;
     }
 
     chunks.reverse();
-    return chunks.join(separator);
+    return Some(chunks.join(separator));
 }
 
-fn bytesToPort(port: String) -> u16 {
-    let (LSB, MSB) = port.split_at(2);
-    (
-        (
-            i64::from_str_radix(MSB, 16).unwrap() as u16
-        ) << 8
-    ) + (
-        i64::from_str_radix(LSB, 16).unwrap() as u16
-    )
+fn bytesToPort(port: &str) -> Option<u16> {
+    let LSB = u8::from_str_radix(port.get(..2)?, 16).ok()?;
+    let MSB = u8::from_str_radix(port.get(2..)?, 16).ok()?;
+
+    return Some(((MSB as u16) << 8) + LSB as u16);
 }
 
 fn getRoutes(file: String, separator: &str, routeType: RouteType) -> Vec<NetworkRoute> {
@@ -366,16 +585,22 @@ fn getRoutes(file: String, separator: &str, routeType: RouteType) -> Vec<Network
         }
 
         let splittedLine: Vec<&str> = line.trim().split(" ").collect();
-        let local: Vec<&str> = splittedLine[1].split(":").collect();
+        let endpoint = |index: usize| {
+            let (address, port) = splittedLine.get(index)?.split_once(":")?;
+            Some((bytesToAddress(address, separator)?, bytesToPort(port)?))
+        };
 
-        let localAddress = bytesToAddress(local[0].to_string(), separator);
-        let localPort = bytesToPort(local[1].to_string());
+        let (Some((localAddress, localPort)), Some((remoteAddress, remotePort)), Some(statusCode)) =
+            (endpoint(1), endpoint(2), splittedLine.get(3).map(|code| code.trim())) else {
+            continue;
+        };
 
-        let remote: Vec<&str> = splittedLine[2].split(":").collect();
-        let remoteAddress = bytesToAddress(remote[0].to_string(), separator);
-        let remotePort = bytesToPort(remote[1].to_string());
+        // the following columns are padded with a variable number of spaces
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        let hex = |value: Option<&str>| value.and_then(|value| u64::from_str_radix(value, 16).ok()).unwrap_or(0);
 
-        let statusCode = splittedLine[3].trim();
+        let queues = columns.get(4).and_then(|queues| queues.split_once(":"));
+        let timerCode = columns.get(5).and_then(|timer| timer.split(":").next()).unwrap_or("00");
 
         let status = {
             if routeType == RouteType::TCP || routeType == RouteType::TCP6 {
@@ -393,7 +618,11 @@ fn getRoutes(file: String, separator: &str, routeType: RouteType) -> Vec<Network
                 localPort: localPort,
                 remoteAddress: remoteAddress,
                 remotePort: remotePort,
-                routeStatus: status
+                routeStatus: status,
+                txQueue: hex(queues.map(|queues| queues.0)),
+                rxQueue: hex(queues.map(|queues| queues.1)),
+                timer: SocketTimer::fromCode(timerCode),
+                retransmits: hex(columns.get(6).copied())
             }
         );
     }
@@ -409,20 +638,16 @@ pub fn networkInterfaces() -> Vec<NetworkInterface> {
     let virtualInterfaces = {
         let mut ifaces = Vec::<String>::new();
         
-        for iface in fs::read_dir("/sys/devices/virtual/net").unwrap() {
-            ifaces.push(iface.unwrap().file_name().to_str().unwrap().to_string());
+        for iface in tryReadDir("/sys/devices/virtual/net").unwrap_or_default() {
+            ifaces.push(iface.file_name().to_string_lossy().to_string());
         }
         
         ifaces
     };
 
-    for directory in fs::read_dir(baseDirectory).unwrap() {
-        let dir = directory.unwrap();
-
-        let name = dir.file_name().to_str().unwrap().to_string();
-        let path = dir.path().to_str().unwrap().to_string();
-
-        let mac = fs::read_to_string(format!("{}/address", path)).unwrap().trim().to_string();
+    for dir in tryReadDir(baseDirectory).unwrap_or_default() {
+        let name = dir.file_name().to_string_lossy().to_string();
+        let mac = readFile(dir.path().join("address"));
 
         let mut interfaceType = InterfaceType::Physical;
         
@@ -449,44 +674,151 @@ pub fn networkInterfaces() -> Vec<NetworkInterface> {
     return interfaces;
 }
 
-fn getRate() -> (usize, usize) {
-    let stats = readFile("/proc/net/dev");
-
-    let mut downloadRate = 0_usize;
-    let mut uploadRate = 0_usize;
+/// Parses the content of `/proc/net/dev`
+///
+/// Each interface line is `name: <8 receive columns> <8 transmit columns>`, the counters
+/// may be glued to the colon when they are large, so the line is split on the colon first
+fn parseNetDev(content: &str) -> Vec<InterfaceCounters> {
+    let mut interfaces = Vec::<InterfaceCounters>::new();
 
-    for line in stats.split('\n') {
-        if line.contains(':') {
-            let splitted = {
-                let tmp = line.split(" ");
-
-                let mut data = Vec::<usize>::new();
-                for chunk in tmp {
+    for line in content.split('\n') {
+        // the two header lines use `|` as separator and contain no colon
+        let Some((name, counters)) = line.split_once(':') else {
+            continue;
+        };
 
-                    if !chunk.is_empty() && !chunk.contains(' ') && !chunk.contains(":") {
-                        data.push(chunk.parse().unwrap_or(0));
-                    }
-                }
-                data
-            };
+        let mut columns = Vec::<u64>::new();
+        for chunk in counters.split_whitespace() {
+            columns.push(chunk.parse().unwrap_or_else(|_error| {
+                traceEvent!(warn, value = chunk, error = %_error, "failed to parse /proc/net/dev counter");
+                0
+            }));
+        }
 
-            downloadRate += splitted[0];
-            uploadRate += splitted[8];
+        if columns.len() < 16 {
+            traceEvent!(debug, line = line, "skipping malformed /proc/net/dev line");
+            continue;
         }
+
+        interfaces.push(InterfaceCounters {
+            interface: name.trim().to_string(),
+            rxBytes: columns[0],
+            rxPackets: columns[1],
+            rxErrors: columns[2],
+            rxDropped: columns[3],
+            rxFifo: columns[4],
+            rxFrame: columns[5],
+            rxCompressed: columns[6],
+            rxMulticast: columns[7],
+            txBytes: columns[8],
+            txPackets: columns[9],
+            txErrors: columns[10],
+            txDropped: columns[11],
+            txFifo: columns[12],
+            txCollisions: columns[13],
+            txCarrier: columns[14],
+            txCompressed: columns[15]
+        });
     }
-    return (downloadRate, uploadRate);
+
+    return interfaces;
+}
+
+/// Returns the counters of every network interface, as reported by `/proc/net/dev`
+pub fn interfaceCounters() -> Vec<InterfaceCounters> {
+    linuxCheck();
+    return parseNetDev(&readFile("/proc/net/dev"));
 }
 
 /// Returns current network rate (downlaod and upload), expressed in bytes
 pub fn networkRate() -> NetworkRate {
+    return networkRateWithInterval(Duration::from_millis(500));
+}
+
+/// Same as `networkRate()`, but samples over `interval` instead of 500 ms, the rate is still in bytes per second
+pub fn networkRateWithInterval(interval: Duration) -> NetworkRate {
+    linuxCheck();
+    traceSpan!("networkRate");
+
+    let before = interfaceCounters();
+    thread::sleep(interval);
+    let after = interfaceCounters();
+
+    return rateBetween(&before, after, interval);
+}
+
+/// Same as `networkRate()`, but waits between the two reads with `tokio::time::sleep` instead of blocking the thread
+#[cfg(feature = "async")]
+pub async fn networkRateAsync() -> NetworkRate {
     linuxCheck();
 
-    let (downBefore, upBefore) = getRate();
-    thread::sleep(Duration::from_millis(500));
-    let (downAfter, upAfter) = getRate();
+    let before = parseNetDev(&tokio::fs::read_to_string(sysPath("/proc/net/dev")).await.unwrap_or_default());
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let after = parseNetDev(&tokio::fs::read_to_string(sysPath("/proc/net/dev")).await.unwrap_or_default());
+
+    return rateBetween(&before, after, Duration::from_millis(500));
+}
+
+/// Contains the interface counters read at some point, used as starting point by `networkRateSince()`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NetworkBaseline {
+    counters: Vec<InterfaceCounters>,
+    /// Wall clock time, so that a baseline stored by another process still gives the elapsed time
+    capturedAt: SystemTime
+}
+
+impl NetworkBaseline {
+    /// Reads the current interface counters
+    pub fn capture() -> NetworkBaseline {
+        return NetworkBaseline {
+            counters: interfaceCounters(),
+            capturedAt: SystemTime::now()
+        };
+    }
+}
+
+/// Returns network rate since `baseline` was captured, without sleeping, then moves `baseline` to the current counters
+///
+/// The rate is in bytes per second over the time elapsed since the baseline, zero if no time elapsed
+pub fn networkRateSince(baseline: &mut NetworkBaseline) -> NetworkRate {
+    let current = NetworkBaseline::capture();
+    let elapsed = current.capturedAt.duration_since(baseline.capturedAt).unwrap_or_default();
+    let rate = rateBetween(&baseline.counters, current.counters.clone(), elapsed);
+
+    *baseline = current;
+    return rate;
+}
+
+// computes the rate from two reads of the counters taken `elapsed` apart
+fn rateBetween(before: &[InterfaceCounters], after: Vec<InterfaceCounters>, elapsed: Duration) -> NetworkRate {
+    let mut downloaded = 0_u64;
+    let mut uploaded = 0_u64;
+
+    for counters in after {
+        let Some(previous) = before.iter().find(|previous| previous.interface == counters.interface) else {
+            continue;
+        };
+
+        // counters going backwards mean the interface was reset or wrapped, its sample is meaningless
+        if counters.rxBytes < previous.rxBytes || counters.txBytes < previous.txBytes {
+            continue;
+        }
+
+        downloaded = downloaded.saturating_add(counters.rxBytes - previous.rxBytes);
+        uploaded = uploaded.saturating_add(counters.txBytes - previous.txBytes);
+    }
+
+    let seconds = elapsed.as_secs_f32();
+    if seconds == 0_f32 {
+        return NetworkRate {
+            download: 0_f32,
+            upload: 0_f32
+        };
+    }
 
-    let downloadRate: f32 = ((downAfter - downBefore) as f32) / 0.5_f32;
-    let uploadRate: f32 = ((upAfter - upBefore) as f32) / 0.5_f32;
+    let downloadRate: f32 = (downloaded as f32) / seconds;
+    let uploadRate: f32 = (uploaded as f32) / seconds;
 
     return NetworkRate {
         download: downloadRate,
@@ -494,26 +826,1504 @@ pub fn networkRate() -> NetworkRate {
     };
 }
 
-/// Returns a list of each internal network route
-pub fn networkRoutes() -> Vec<NetworkRoute> {
+/// Contains the transfer of a network interface accumulated by an `InterfaceTracker`
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InterfaceTransfer {
+    pub interface: String,
+    /// Bytes received since the tracker was created
+    pub rxBytes: u64,
+    /// Bytes sent since the tracker was created
+    pub txBytes: u64,
+    /// Download rate between the last two updates, in bytes per second
+    pub rxRate: f32,
+    /// Upload rate between the last two updates, in bytes per second
+    pub txRate: f32,
+    pub peakRxRate: f32,
+    pub peakTxRate: f32
+}
+
+impl fmt::Display for InterfaceTransfer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{}: {} down, {} up (peak {}/s down, {}/s up)", self.interface,
+            ByteSize::fromBytes(self.rxBytes), ByteSize::fromBytes(self.txBytes),
+            ByteSize::fromBytes(self.peakRxRate as u64), ByteSize::fromBytes(self.peakTxRate as u64)
+        )
+    }
+}
+
+/// Accumulates per-interface transfer and peak rates across calls to `update()`
+///
+/// Kernel counters restart when an interface is reset or recreated, the tracker keeps counting
+/// from the new values so the totals cover the whole tracking session
+#[derive(Debug, Clone)]
+pub struct InterfaceTracker {
+    previous: Vec<InterfaceCounters>,
+    transfers: Vec<InterfaceTransfer>,
+    startedAt: Instant,
+    updatedAt: Instant
+}
+
+impl InterfaceTracker {
+    /// Creates a tracker, using the current counters as starting point
+    pub fn new() -> InterfaceTracker {
+        linuxCheck();
+
+        let now = Instant::now();
+
+        return InterfaceTracker {
+            previous: interfaceCounters(),
+            transfers: Vec::<InterfaceTransfer>::new(),
+            startedAt: now,
+            updatedAt: now
+        };
+    }
+
+    /// Reads the counters again and accumulates the transfer since the last update
+    pub fn update(&mut self) {
+        self.record(interfaceCounters(), Instant::now());
+    }
+
+    fn record(&mut self, counters: Vec<InterfaceCounters>, now: Instant) {
+        let elapsed = now.duration_since(self.updatedAt).as_secs_f32();
+
+        for current in &counters {
+            // a counter lower than before means it restarted from zero
+            let (received, sent) = match self.previous.iter().find(|previous| previous.interface == current.interface) {
+                Some(previous) => (
+                    if current.rxBytes >= previous.rxBytes { current.rxBytes - previous.rxBytes } else { current.rxBytes },
+                    if current.txBytes >= previous.txBytes { current.txBytes - previous.txBytes } else { current.txBytes }
+                ),
+                None => (0, 0)
+            };
+
+            let index = match self.transfers.iter().position(|transfer| transfer.interface == current.interface) {
+                Some(index) => index,
+                None => {
+                    self.transfers.push(InterfaceTransfer {
+                        interface: current.interface.clone(),
+                        rxBytes: 0,
+                        txBytes: 0,
+                        rxRate: 0_f32,
+                        txRate: 0_f32,
+                        peakRxRate: 0_f32,
+                        peakTxRate: 0_f32
+                    });
+
+                    self.transfers.len() - 1
+                }
+            };
+
+            let transfer = &mut self.transfers[index];
+            transfer.rxBytes = transfer.rxBytes.saturating_add(received);
+            transfer.txBytes = transfer.txBytes.saturating_add(sent);
+
+            if elapsed > 0_f32 {
+                transfer.rxRate = received as f32 / elapsed;
+                transfer.txRate = sent as f32 / elapsed;
+                transfer.peakRxRate = transfer.peakRxRate.max(transfer.rxRate);
+                transfer.peakTxRate = transfer.peakTxRate.max(transfer.txRate);
+            }
+        }
+
+        self.previous = counters;
+        self.updatedAt = now;
+    }
+
+    /// Returns the accumulated transfer of every interface seen since the tracker was created
+    pub fn transfers(&self) -> Vec<InterfaceTransfer> {
+        return self.transfers.clone();
+    }
+
+    /// Returns the accumulated transfer of `interface`, `None` if it has not been seen yet
+    pub fn interface(&self, interface: &str) -> Option<InterfaceTransfer> {
+        return self.transfers.iter().find(|transfer| transfer.interface == interface).cloned();
+    }
+
+    /// Returns the total bytes received and sent by every interface since the tracker was created
+    pub fn total(&self) -> (u64, u64) {
+        return self.transfers.iter().fold((0, 0), |(received, sent), transfer| {
+            (received + transfer.rxBytes, sent + transfer.txBytes)
+        });
+    }
+
+    /// Returns the time elapsed since the tracker was created
+    pub fn elapsed(&self) -> Duration {
+        return self.startedAt.elapsed();
+    }
+}
+
+impl Default for InterfaceTracker {
+    fn default() -> InterfaceTracker {
+        return InterfaceTracker::new();
+    }
+}
+
+/// Contains network stack sysctls relevant to routing and TCP tuning, values are `None` when not readable
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NetworkStackConfig {
+    pub ipv4Forwarding: Option<bool>,
+    pub ipv6Forwarding: Option<bool>,
+    /// Reverse path filtering: 0 disabled, 1 strict, 2 loose
+    pub rpFilter: Option<u8>,
+    pub tcpCongestionControl: String,
+    pub availableCongestionControl: Vec<String>,
+    pub defaultQdisc: String,
+    pub somaxconn: Option<u32>,
+    pub tcpSyncookies: Option<bool>,
+    pub tcpTimestamps: Option<bool>,
+    pub tcpSack: Option<bool>,
+    /// ECN: 0 disabled, 1 requested on outgoing connections, 2 accepted when requested
+    pub tcpEcn: Option<u8>,
+    pub tcpFinTimeout: Option<u32>,
+    pub localPortRange: Option<(u16, u16)>,
+    pub rmemMax: Option<ByteSize>,
+    pub wmemMax: Option<ByteSize>
+}
+
+impl fmt::Display for NetworkStackConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "forwarding {}, congestion control {}, qdisc {}",
+            if self.ipv4Forwarding.unwrap_or(false) || self.ipv6Forwarding.unwrap_or(false) { "on" } else { "off" },
+            self.tcpCongestionControl, self.defaultQdisc
+        )
+    }
+}
+
+/// Returns the network stack configuration read from `/proc/sys/net`
+pub fn stackConfig() -> NetworkStackConfig {
     linuxCheck();
-    let mut routes: Vec<NetworkRoute> = Vec::<NetworkRoute>::new();
 
-    routes.append(
-        &mut getRoutes(readFile("/proc/net/tcp"), ".", RouteType::TCP)
-    );
+    let sysctl = |name: &str| readFile(format!("/proc/sys/net/{}", name));
+    let flag = |name: &str| sysctl(name).parse::<u8>().ok().map(|value| value != 0);
 
-    routes.append(
-        &mut getRoutes(readFile("/proc/net/udp"), ".", RouteType::UDP)
-    );
+    // the kernel applies the maximum of `all` and the interface value, `default` is inherited by new interfaces
+    let rpFilter = [sysctl("ipv4/conf/all/rp_filter"), sysctl("ipv4/conf/default/rp_filter")].iter()
+        .filter_map(|value| value.parse::<u8>().ok())
+        .max();
 
-    routes.append(
-        &mut getRoutes(readFile("/proc/net/tcp6"), ":", RouteType::TCP6)
-    );
+    let localPortRange = {
+        let range = sysctl("ipv4/ip_local_port_range");
+        let bounds: Vec<u16> = range.split_whitespace().filter_map(|value| value.parse().ok()).collect();
 
-    routes.append(
-        &mut getRoutes(readFile("/proc/net/udp6"), ":", RouteType::UDP6)
-    );
+        if bounds.len() == 2 { Some((bounds[0], bounds[1])) } else { None }
+    };
 
-    return routes;
-}
\ No newline at end of file
+    return NetworkStackConfig {
+        ipv4Forwarding: flag("ipv4/ip_forward"),
+        ipv6Forwarding: flag("ipv6/conf/all/forwarding"),
+        rpFilter: rpFilter,
+        tcpCongestionControl: sysctl("ipv4/tcp_congestion_control"),
+        availableCongestionControl: sysctl("ipv4/tcp_available_congestion_control").split_whitespace().map(String::from).collect(),
+        defaultQdisc: sysctl("core/default_qdisc"),
+        somaxconn: sysctl("core/somaxconn").parse().ok(),
+        tcpSyncookies: flag("ipv4/tcp_syncookies"),
+        tcpTimestamps: flag("ipv4/tcp_timestamps"),
+        tcpSack: flag("ipv4/tcp_sack"),
+        tcpEcn: sysctl("ipv4/tcp_ecn").parse().ok(),
+        tcpFinTimeout: sysctl("ipv4/tcp_fin_timeout").parse().ok(),
+        localPortRange: localPortRange,
+        rmemMax: sysctl("core/rmem_max").parse::<u64>().ok().map(ByteSize::fromBytes),
+        wmemMax: sysctl("core/wmem_max").parse::<u64>().ok().map(ByteSize::fromBytes)
+    };
+}
+
+/// Contains netfilter connection tracking table usage
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConntrackUsage {
+    pub count: usize,
+    pub max: usize,
+    pub buckets: Option<usize>,
+    /// Tracked entries per layer 4 protocol (e.g. `tcp`, `udp`), empty if `/proc/net/nf_conntrack` is not readable
+    pub protocols: HashMap<String, usize>
+}
+
+impl ConntrackUsage {
+    /// Returns the table fill level in percentage, new connections are dropped when it reaches 100%
+    pub fn usage(&self) -> f32 {
+        if self.max == 0 {
+            return 0_f32;
+        }
+
+        self.count as f32 * 100_f32 / self.max as f32
+    }
+}
+
+impl fmt::Display for ConntrackUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{} entries ({:.1}%)", self.count, self.max, self.usage())
+    }
+}
+
+/// Returns connection tracking table usage, `None` if the `nf_conntrack` module is not loaded
+pub fn conntrackUsage() -> Option<ConntrackUsage> {
+    linuxCheck();
+
+    let count = fs::read_to_string(sysPath("/proc/sys/net/netfilter/nf_conntrack_count")).ok()?.trim().parse::<usize>().ok()?;
+    let max = readFile("/proc/sys/net/netfilter/nf_conntrack_max").parse::<usize>().unwrap_or(0);
+
+    // lines start with `ipv4 2 tcp 6 <timeout> ...`, the file is only readable by root
+    let mut protocols = HashMap::<String, usize>::new();
+    for line in readFile("/proc/net/nf_conntrack").lines() {
+        if let Some(protocol) = line.split_whitespace().nth(2) {
+            *protocols.entry(protocol.to_string()).or_insert(0) += 1;
+        }
+    }
+
+    return Some(ConntrackUsage {
+        count: count,
+        max: max,
+        buckets: readFile("/proc/sys/net/netfilter/nf_conntrack_buckets").parse::<usize>().ok(),
+        protocols: protocols
+    });
+}
+
+/// Contains a multicast group joined on an interface
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MulticastGroup {
+    pub interface: String,
+    pub address: String,
+    /// Number of sockets (or kernel users) subscribed to the group
+    pub users: usize,
+    pub ipv6: bool
+}
+
+impl fmt::Display for MulticastGroup {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} on {} ({} users)", self.address, self.interface, self.users)
+    }
+}
+
+// `/proc/net/igmp` lists each interface followed by indented group lines, with the address in host byte order
+fn parseIgmp(content: &str) -> Vec<MulticastGroup> {
+    let mut groups = Vec::<MulticastGroup>::new();
+    let mut interface = String::new();
+
+    for line in content.lines().skip(1) {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+
+        if !line.starts_with('\t') {
+            interface = columns.get(1).unwrap_or(&"").to_string();
+            continue
+        }
+
+        if columns.len() < 2 || columns[0].len() != 8 {
+            continue
+        }
+
+        let Some(address) = bytesToAddress(columns[0], ".") else {
+            continue
+        };
+
+        groups.push(MulticastGroup {
+            interface: interface.clone(),
+            address: address,
+            users: columns[1].parse().unwrap_or(0),
+            ipv6: false
+        });
+    }
+
+    return groups;
+}
+
+// `/proc/net/igmp6` lines are `index interface address users flags timer`
+fn parseIgmp6(content: &str) -> Vec<MulticastGroup> {
+    let mut groups = Vec::<MulticastGroup>::new();
+
+    for line in content.lines() {
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        if columns.len() < 4 {
+            continue
+        }
+
+        let Ok(address) = u128::from_str_radix(columns[2], 16) else {
+            continue
+        };
+
+        groups.push(MulticastGroup {
+            interface: columns[1].to_string(),
+            address: Ipv6Addr::from(address).to_string(),
+            users: columns[3].parse().unwrap_or(0),
+            ipv6: true
+        });
+    }
+
+    return groups;
+}
+
+/// Returns the IPv4 and IPv6 multicast groups joined on every interface
+pub fn multicastGroups() -> Vec<MulticastGroup> {
+    linuxCheck();
+
+    let mut groups = parseIgmp(&readFile("/proc/net/igmp"));
+    groups.append(&mut parseIgmp6(&readFile("/proc/net/igmp6")));
+
+    return groups;
+}
+
+/// Radio access technology of a modem
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AccessTechnology {
+    Gsm,
+    Umts,
+    Cdma,
+    Lte,
+    Nr5g,
+    Unknown
+}
+
+impl AccessTechnology {
+    /// Converts a ModemManager `MMModemAccessTechnology` bitmask, keeping the most recent technology
+    pub fn fromBitmask(bitmask: u32) -> AccessTechnology {
+        if bitmask & (1 << 15) != 0 {
+            AccessTechnology::Nr5g
+        } else if bitmask & (1 << 14 | 1 << 16 | 1 << 17) != 0 {
+            AccessTechnology::Lte
+        } else if bitmask & (0b11111 << 5) != 0 {
+            AccessTechnology::Umts
+        } else if bitmask & (0b1111 << 10) != 0 {
+            AccessTechnology::Cdma
+        } else if bitmask & (0b1111 << 1) != 0 {
+            AccessTechnology::Gsm
+        } else {
+            AccessTechnology::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            AccessTechnology::Gsm => String::from("2G"),
+            AccessTechnology::Umts => String::from("3G"),
+            AccessTechnology::Cdma => String::from("CDMA"),
+            AccessTechnology::Lte => String::from("LTE"),
+            AccessTechnology::Nr5g => String::from("5G"),
+            AccessTechnology::Unknown => String::from("unknown")
+        }
+    }
+}
+
+impl fmt::Display for AccessTechnology {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// State of the SIM card of a modem
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SimState {
+    Missing,
+    /// A PIN or PUK is required before the modem can be used
+    Locked,
+    Ready
+}
+
+impl SimState {
+    pub fn toString(&self) -> String {
+        match self {
+            SimState::Missing => String::from("missing"),
+            SimState::Locked => String::from("locked"),
+            SimState::Ready => String::from("ready")
+        }
+    }
+}
+
+impl fmt::Display for SimState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains modem details as reported by ModemManager
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModemInfo {
+    pub manufacturer: String,
+    pub model: String,
+    pub operator: Option<String>,
+    /// Signal quality, in percentage
+    pub signalQuality: u8,
+    pub technology: AccessTechnology,
+    pub simState: SimState,
+    /// Kernel ports of the modem, e.g. `wwan0`, `cdc-wdm0`
+    pub ports: Vec<String>
+}
+
+impl fmt::Display for ModemInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{} {} on {} ({}, {}% signal, SIM {})", self.manufacturer, self.model,
+            self.operator.clone().unwrap_or(String::from("no operator")), self.technology, self.signalQuality, self.simState
+        )
+    }
+}
+
+/// Contains a WWAN (mobile broadband) network interface
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WwanInterface {
+    pub interface: String,
+    pub driver: Option<String>,
+    /// Control ports exposed by the wwan subsystem, e.g. `wwan0at0`, `wwan0mbim0`
+    pub controlPorts: Vec<String>,
+    /// Modem details, only available with the `modem-manager` feature and ModemManager running
+    pub modem: Option<ModemInfo>
+}
+
+impl fmt::Display for WwanInterface {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.modem {
+            Some(modem) => write!(f, "{}: {}", self.interface, modem),
+            None => write!(f, "{}", self.interface)
+        }
+    }
+}
+
+/// Returns the modems managed by ModemManager, empty if the service is not reachable on the system bus
+#[cfg(feature = "modem-manager")]
+pub fn modems() -> Vec<ModemInfo> {
+    use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+    type Properties = HashMap<String, HashMap<String, OwnedValue>>;
+
+    linuxCheck();
+
+    let mut modems = Vec::<ModemInfo>::new();
+
+    let objects = zbus::blocking::Connection::system().and_then(|connection| {
+        let manager = zbus::blocking::Proxy::new(
+            &connection, "org.freedesktop.ModemManager1", "/org/freedesktop/ModemManager1", "org.freedesktop.DBus.ObjectManager"
+        )?;
+
+        manager.call::<_, _, HashMap<OwnedObjectPath, Properties>>("GetManagedObjects", &())
+    });
+
+    let objects = match objects {
+        Ok(objects) => objects,
+        Err(_error) => {
+            traceEvent!(debug, error = %_error, "ModemManager is not reachable");
+            return modems;
+        }
+    };
+
+    for interfaces in objects.values() {
+        let Some(modem) = interfaces.get("org.freedesktop.ModemManager1.Modem") else {
+            continue
+        };
+
+        let property = |interface: &HashMap<String, OwnedValue>, name: &str| -> Option<OwnedValue> {
+            interface.get(name).and_then(|value| value.try_clone().ok())
+        };
+
+        let string = |name: &str| property(modem, name).and_then(|value| String::try_from(value).ok()).unwrap_or_default();
+
+        let signalQuality = property(modem, "SignalQuality")
+            .and_then(|value| <(u32, bool)>::try_from(value).ok())
+            .map(|(quality, _)| quality.min(100) as u8)
+            .unwrap_or(0);
+
+        let technology = property(modem, "AccessTechnologies")
+            .and_then(|value| u32::try_from(value).ok())
+            .map(AccessTechnology::fromBitmask)
+            .unwrap_or(AccessTechnology::Unknown);
+
+        // ModemManager reports "/" as SIM path when no card is inserted, MM_MODEM_LOCK_NONE is 1
+        let simPresent = property(modem, "Sim")
+            .and_then(|value| OwnedObjectPath::try_from(value).ok())
+            .map(|path| path.as_str() != "/")
+            .unwrap_or(false);
+
+        let unlockRequired = property(modem, "UnlockRequired").and_then(|value| u32::try_from(value).ok()).unwrap_or(0);
+
+        let simState = if !simPresent {
+            SimState::Missing
+        } else if unlockRequired > 1 {
+            SimState::Locked
+        } else {
+            SimState::Ready
+        };
+
+        let operator = interfaces.get("org.freedesktop.ModemManager1.Modem.Modem3gpp")
+            .and_then(|modem3gpp| property(modem3gpp, "OperatorName"))
+            .and_then(|value| String::try_from(value).ok())
+            .filter(|operator| !operator.is_empty());
+
+        let ports = property(modem, "Ports")
+            .and_then(|value| Vec::<(String, u32)>::try_from(value).ok())
+            .map(|ports| ports.into_iter().map(|(port, _)| port).collect())
+            .unwrap_or_default();
+
+        modems.push(ModemInfo {
+            manufacturer: string("Manufacturer"),
+            model: string("Model"),
+            operator: operator,
+            signalQuality: signalQuality,
+            technology: technology,
+            simState: simState,
+            ports: ports
+        });
+    }
+
+    return modems;
+}
+
+/// Returns WWAN network interfaces, with modem details when built with the `modem-manager` feature
+pub fn wwanInterfaces() -> Vec<WwanInterface> {
+    linuxCheck();
+
+    let mut interfaces = Vec::<WwanInterface>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/class/net")) else {
+        return interfaces;
+    };
+
+    // control ports of the wwan subsystem are named after their device, e.g. wwan0at0 for wwan0
+    let controlPorts: Vec<String> = fs::read_dir(sysPath("/sys/class/wwan")).into_iter().flatten().flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+
+    #[cfg(feature = "modem-manager")]
+    let modems = modems();
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let isWwan = readFile(entry.path().join("uevent")).lines().any(|line| line == "DEVTYPE=wwan");
+
+        if !isWwan && !name.starts_with("wwan") {
+            continue
+        }
+
+        let driver = fs::read_link(entry.path().join("device/driver")).ok()
+            .and_then(|link| link.file_name().map(|driver| driver.to_string_lossy().to_string()));
+
+        #[cfg(feature = "modem-manager")]
+        let modem = modems.iter().find(|modem| modem.ports.contains(&name)).cloned();
+        #[cfg(not(feature = "modem-manager"))]
+        let modem = None;
+
+        interfaces.push(WwanInterface {
+            controlPorts: controlPorts.iter().filter(|port| port.starts_with(&name) && **port != name).cloned().collect(),
+            interface: name,
+            driver: driver,
+            modem: modem
+        });
+    }
+
+    return interfaces;
+}
+
+/// Represents a change of the network configuration notified by the kernel
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NetworkEvent {
+    InterfaceAdded { interface: String },
+    InterfaceRemoved { interface: String },
+    /// The interface is administratively up and has carrier
+    InterfaceUp { interface: String },
+    InterfaceDown { interface: String },
+    AddressAdded { interface: String, address: String, prefixLength: u8 },
+    AddressRemoved { interface: String, address: String, prefixLength: u8 },
+    /// A default route of the main table was added or removed, `gateway` and `interface` are `None` on removal
+    DefaultRouteChanged { gateway: Option<String>, interface: Option<String> }
+}
+
+/// Receives network configuration changes, read from rtnetlink by a background thread
+///
+/// The thread exits at the first notification received after the watcher has been dropped
+pub struct NetworkWatcher {
+    receiver: mpsc::Receiver<NetworkEvent>
+}
+
+impl NetworkWatcher {
+    /// Blocks until the next event, returns `None` if the netlink socket failed
+    pub fn recv(&self) -> Option<NetworkEvent> {
+        return self.receiver.recv().ok();
+    }
+
+    /// Non-blocking variant of `recv()`, returns `None` when no event is pending
+    pub fn tryRecv(&self) -> Option<NetworkEvent> {
+        return self.receiver.try_recv().ok();
+    }
+
+    /// Returns every pending event without blocking
+    pub fn pending(&self) -> Vec<NetworkEvent> {
+        return self.receiver.try_iter().collect();
+    }
+}
+
+impl Iterator for NetworkWatcher {
+    type Item = NetworkEvent;
+
+    fn next(&mut self) -> Option<NetworkEvent> {
+        return self.recv();
+    }
+}
+
+// from linux/rtnetlink.h, linux/if_link.h, linux/if_addr.h and linux/netlink.h
+const RTM_NEWLINK: u16 = 16;
+const RTM_DELLINK: u16 = 17;
+const RTM_NEWADDR: u16 = 20;
+const RTM_DELADDR: u16 = 21;
+const RTM_NEWROUTE: u16 = 24;
+const RTM_DELROUTE: u16 = 25;
+const RTMGRP_LINK: u32 = 0x1;
+const RTMGRP_IPV4_IFADDR: u32 = 0x10;
+const RTMGRP_IPV4_ROUTE: u32 = 0x40;
+const RTMGRP_IPV6_IFADDR: u32 = 0x100;
+const RTMGRP_IPV6_ROUTE: u32 = 0x400;
+const IFLA_IFNAME: u16 = 3;
+const IFA_ADDRESS: u16 = 1;
+const IFA_LOCAL: u16 = 2;
+const RTA_OIF: u16 = 4;
+const RTA_GATEWAY: u16 = 5;
+const RTA_TABLE: u16 = 15;
+const RT_TABLE_MAIN: u32 = 254;
+const RTN_UNICAST: u8 = 1;
+const NLMSG_HEADER_LENGTH: usize = 16;
+
+// interface index to name and up state, names are kept so removals can still be reported by name
+type LinkStates = HashMap<u32, (String, bool)>;
+
+fn netlinkAlign(length: usize) -> usize {
+    return (length + 3) & !3;
+}
+
+// splits the `rtattr` list following a message header into (type, payload) pairs
+fn netlinkAttributes(mut buffer: &[u8]) -> Vec<(u16, &[u8])> {
+    let mut attributes = Vec::<(u16, &[u8])>::new();
+
+    while buffer.len() >= 4 {
+        let length = u16::from_ne_bytes([buffer[0], buffer[1]]) as usize;
+        if length < 4 || length > buffer.len() {
+            break
+        }
+
+        // the high bits of the type are flags (nested, byte order)
+        attributes.push((u16::from_ne_bytes([buffer[2], buffer[3]]) & 0x3FFF, &buffer[4..length]));
+        buffer = &buffer[netlinkAlign(length).min(buffer.len())..];
+    }
+
+    return attributes;
+}
+
+fn netlinkAddress(family: u8, payload: &[u8]) -> Option<String> {
+    match (family as i32, payload.len()) {
+        (libc::AF_INET, 4) => Some(Ipv4Addr::new(payload[0], payload[1], payload[2], payload[3]).to_string()),
+        (libc::AF_INET6, 16) => Some(Ipv6Addr::from(<[u8; 16]>::try_from(payload).ok()?).to_string()),
+        _ => None
+    }
+}
+
+fn interfaceName(index: u32, links: &LinkStates) -> String {
+    if let Some((name, _)) = links.get(&index) {
+        return name.clone();
+    }
+
+    let mut name = [0 as libc::c_char; libc::IF_NAMESIZE];
+    if unsafe { libc::if_indextoname(index, name.as_mut_ptr()) }.is_null() {
+        return index.to_string();
+    }
+
+    return unsafe { std::ffi::CStr::from_ptr(name.as_ptr()) }.to_string_lossy().to_string();
+}
+
+// decodes a single netlink message, updating the known link states
+fn decodeNetlinkMessage(messageType: u16, body: &[u8], links: &mut LinkStates) -> Vec<NetworkEvent> {
+    let mut events = Vec::<NetworkEvent>::new();
+
+    match messageType {
+        RTM_NEWLINK | RTM_DELLINK if body.len() >= 16 => {
+            let index = u32::from_ne_bytes(body[4..8].try_into().unwrap());
+            let flags = u32::from_ne_bytes(body[8..12].try_into().unwrap());
+
+            let name = netlinkAttributes(&body[16..]).into_iter()
+                .find(|(kind, _)| *kind == IFLA_IFNAME)
+                .map(|(_, payload)| String::from_utf8_lossy(payload).trim_end_matches('\0').to_string())
+                .unwrap_or_else(|| interfaceName(index, links));
+
+            if messageType == RTM_DELLINK {
+                links.remove(&index);
+                events.push(NetworkEvent::InterfaceRemoved { interface: name });
+                return events;
+            }
+
+            let up = flags & libc::IFF_UP as u32 != 0 && flags & libc::IFF_RUNNING as u32 != 0;
+
+            match links.insert(index, (name.clone(), up)) {
+                None => {
+                    events.push(NetworkEvent::InterfaceAdded { interface: name.clone() });
+
+                    if up {
+                        events.push(NetworkEvent::InterfaceUp { interface: name });
+                    }
+                },
+
+                Some((_, wasUp)) if wasUp != up => {
+                    events.push(if up {
+                        NetworkEvent::InterfaceUp { interface: name }
+                    } else {
+                        NetworkEvent::InterfaceDown { interface: name }
+                    });
+                },
+
+                _ => {}
+            }
+        },
+
+        RTM_NEWADDR | RTM_DELADDR if body.len() >= 8 => {
+            let family = body[0];
+            let prefixLength = body[1];
+            let index = u32::from_ne_bytes(body[4..8].try_into().unwrap());
+
+            // IFA_LOCAL is the interface address on point-to-point links, where IFA_ADDRESS is the peer
+            let attributes = netlinkAttributes(&body[8..]);
+            let address = attributes.iter().find(|(kind, _)| *kind == IFA_LOCAL)
+                .or(attributes.iter().find(|(kind, _)| *kind == IFA_ADDRESS))
+                .and_then(|(_, payload)| netlinkAddress(family, payload));
+
+            if let Some(address) = address {
+                let interface = interfaceName(index, links);
+
+                events.push(if messageType == RTM_NEWADDR {
+                    NetworkEvent::AddressAdded { interface, address, prefixLength }
+                } else {
+                    NetworkEvent::AddressRemoved { interface, address, prefixLength }
+                });
+            }
+        },
+
+        RTM_NEWROUTE | RTM_DELROUTE if body.len() >= 12 => {
+            let family = body[0];
+            let destinationLength = body[1];
+            let routeType = body[7];
+
+            let attributes = netlinkAttributes(&body[12..]);
+            let table = attributes.iter().find(|(kind, _)| *kind == RTA_TABLE)
+                .and_then(|(_, payload)| Some(u32::from_ne_bytes((*payload).try_into().ok()?)))
+                .unwrap_or(body[4] as u32);
+
+            if destinationLength != 0 || table != RT_TABLE_MAIN || routeType != RTN_UNICAST {
+                return events;
+            }
+
+            if messageType == RTM_DELROUTE {
+                events.push(NetworkEvent::DefaultRouteChanged { gateway: None, interface: None });
+                return events;
+            }
+
+            let gateway = attributes.iter().find(|(kind, _)| *kind == RTA_GATEWAY)
+                .and_then(|(_, payload)| netlinkAddress(family, payload));
+
+            let interface = attributes.iter().find(|(kind, _)| *kind == RTA_OIF)
+                .and_then(|(_, payload)| Some(u32::from_ne_bytes((*payload).try_into().ok()?)))
+                .map(|index| interfaceName(index, links));
+
+            events.push(NetworkEvent::DefaultRouteChanged { gateway, interface });
+        },
+
+        _ => {}
+    }
+
+    return events;
+}
+
+// splits a datagram read from the netlink socket into messages
+fn decodeNetlink(mut buffer: &[u8], links: &mut LinkStates) -> Vec<NetworkEvent> {
+    let mut events = Vec::<NetworkEvent>::new();
+
+    while buffer.len() >= NLMSG_HEADER_LENGTH {
+        let length = u32::from_ne_bytes(buffer[0..4].try_into().unwrap()) as usize;
+        if length < NLMSG_HEADER_LENGTH || length > buffer.len() {
+            break
+        }
+
+        let messageType = u16::from_ne_bytes([buffer[4], buffer[5]]);
+        events.append(&mut decodeNetlinkMessage(messageType, &buffer[NLMSG_HEADER_LENGTH..length], links));
+
+        buffer = &buffer[netlinkAlign(length).min(buffer.len())..];
+    }
+
+    return events;
+}
+
+/// Subscribes to rtnetlink link, address and route notifications and streams them through a `NetworkWatcher`
+///
+/// Interfaces existing when the watcher is created are not reported as added.
+/// Returns `None` if the netlink socket cannot be opened
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::network::{watch, NetworkEvent};
+///
+/// for event in watch().unwrap() {
+///     if let NetworkEvent::DefaultRouteChanged { gateway, .. } = event {
+///         println!("default gateway is now {:?}", gateway);
+///     }
+/// }
+/// ```
+pub fn watch() -> Option<NetworkWatcher> {
+    linuxCheck();
+
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_RAW | libc::SOCK_CLOEXEC, libc::NETLINK_ROUTE) };
+    if fd < 0 {
+        traceEvent!(debug, "failed to open rtnetlink socket");
+        return None;
+    }
+
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    let mut address: libc::sockaddr_nl = unsafe { mem::zeroed() };
+    address.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+    address.nl_groups = RTMGRP_LINK | RTMGRP_IPV4_IFADDR | RTMGRP_IPV6_IFADDR | RTMGRP_IPV4_ROUTE | RTMGRP_IPV6_ROUTE;
+
+    let bound = unsafe {
+        libc::bind(
+            socket.as_raw_fd(), &address as *const libc::sockaddr_nl as *const libc::sockaddr,
+            mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t
+        )
+    };
+
+    if bound != 0 {
+        traceEvent!(debug, "failed to bind rtnetlink socket");
+        return None;
+    }
+
+    // current links, so that only changes are reported
+    let mut links = LinkStates::new();
+    for entry in fs::read_dir(sysPath("/sys/class/net")).into_iter().flatten().flatten() {
+        let Ok(index) = readFile(entry.path().join("ifindex")).parse::<u32>() else {
+            continue
+        };
+
+        let flags = u32::from_str_radix(readFile(entry.path().join("flags")).trim_start_matches("0x"), 16).unwrap_or(0);
+        let up = flags & libc::IFF_UP as u32 != 0 && readFile(entry.path().join("operstate")) != "down";
+
+        links.insert(index, (entry.file_name().to_string_lossy().to_string(), up));
+    }
+
+    let (sender, receiver) = mpsc::channel::<NetworkEvent>();
+    thread::spawn(move || {
+        let mut buffer = vec![0_u8; 64 * 1024];
+
+        loop {
+            let length = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+
+            if length < 0 {
+                // ENOBUFS means notifications were dropped, the socket is still usable
+                if std::io::Error::last_os_error().raw_os_error() == Some(libc::ENOBUFS) {
+                    continue
+                }
+
+                return;
+            }
+
+            for event in decodeNetlink(&buffer[..length as usize], &mut links) {
+                if sender.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    return Some(NetworkWatcher {
+        receiver: receiver
+    });
+}
+
+/// Returns a list of each internal network route
+pub fn networkRoutes() -> Vec<NetworkRoute> {
+    linuxCheck();
+    let mut routes: Vec<NetworkRoute> = Vec::<NetworkRoute>::new();
+
+    routes.append(
+        &mut getRoutes(readFile("/proc/net/tcp"), ".", RouteType::TCP)
+    );
+
+    routes.append(
+        &mut getRoutes(readFile("/proc/net/udp"), ".", RouteType::UDP)
+    );
+
+    routes.append(
+        &mut getRoutes(readFile("/proc/net/tcp6"), ":", RouteType::TCP6)
+    );
+
+    routes.append(
+        &mut getRoutes(readFile("/proc/net/udp6"), ":", RouteType::UDP6)
+    );
+
+    return routes;
+}
+
+/// Contains round trip statistics of an ICMP echo probe
+#[cfg(feature = "ping")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PingStatistics {
+    pub host: String,
+    pub address: IpAddr,
+    pub transmitted: usize,
+    pub received: usize,
+    /// Round trip times, `None` if no reply was received
+    pub minimum: Option<Duration>,
+    pub average: Option<Duration>,
+    pub maximum: Option<Duration>
+}
+
+#[cfg(feature = "ping")]
+impl PingStatistics {
+    /// Returns lost packets in percentage
+    pub fn loss(&self) -> f32 {
+        if self.transmitted == 0 {
+            return 0_f32;
+        }
+
+        (self.transmitted - self.received) as f32 * 100_f32 / self.transmitted as f32
+    }
+}
+
+#[cfg(feature = "ping")]
+impl fmt::Display for PingStatistics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}/{} received, {:.0}% loss", self.host, self.address, self.received, self.transmitted, self.loss())?;
+
+        match (self.minimum, self.average, self.maximum) {
+            (Some(minimum), Some(average), Some(maximum)) => write!(
+                f, ", rtt {:.3}/{:.3}/{:.3} ms",
+                minimum.as_secs_f64() * 1000_f64, average.as_secs_f64() * 1000_f64, maximum.as_secs_f64() * 1000_f64
+            ),
+            _ => Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "ping")]
+fn icmpChecksum(packet: &[u8]) -> u16 {
+    let mut sum = 0_u32;
+
+    for chunk in packet.chunks(2) {
+        let word = if chunk.len() == 2 { u16::from_be_bytes([chunk[0], chunk[1]]) } else { (chunk[0] as u16) << 8 };
+        sum += word as u32;
+    }
+
+    while sum >> 16 != 0 {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+
+    return !(sum as u16);
+}
+
+// opens an unprivileged ICMP datagram socket (allowed by net.ipv4.ping_group_range), falling back to a raw socket
+#[cfg(feature = "ping")]
+fn icmpSocket(ipv6: bool) -> io::Result<(OwnedFd, bool)> {
+    let (domain, protocol) = if ipv6 {
+        (libc::AF_INET6, libc::IPPROTO_ICMPV6)
+    } else {
+        (libc::AF_INET, libc::IPPROTO_ICMP)
+    };
+
+    for (kind, raw) in [(libc::SOCK_DGRAM, false), (libc::SOCK_RAW, true)] {
+        let fd = unsafe { libc::socket(domain, kind | libc::SOCK_CLOEXEC, protocol) };
+
+        if fd >= 0 {
+            return Ok((unsafe { OwnedFd::from_raw_fd(fd) }, raw));
+        }
+    }
+
+    return Err(io::Error::last_os_error());
+}
+
+#[cfg(feature = "ping")]
+fn socketAddress(address: IpAddr) -> (libc::sockaddr_storage, libc::socklen_t) {
+    let mut storage: libc::sockaddr_storage = unsafe { mem::zeroed() };
+
+    match address {
+        IpAddr::V4(address) => {
+            let ipv4 = unsafe { &mut *(&mut storage as *mut libc::sockaddr_storage as *mut libc::sockaddr_in) };
+            ipv4.sin_family = libc::AF_INET as libc::sa_family_t;
+            ipv4.sin_addr.s_addr = u32::from_ne_bytes(address.octets());
+
+            (storage, mem::size_of::<libc::sockaddr_in>() as libc::socklen_t)
+        },
+
+        IpAddr::V6(address) => {
+            let ipv6 = unsafe { &mut *(&mut storage as *mut libc::sockaddr_storage as *mut libc::sockaddr_in6) };
+            ipv6.sin6_family = libc::AF_INET6 as libc::sa_family_t;
+            ipv6.sin6_addr.s6_addr = address.octets();
+
+            (storage, mem::size_of::<libc::sockaddr_in6>() as libc::socklen_t)
+        }
+    }
+}
+
+#[cfg(feature = "ping")]
+fn setReceiveTimeout(socket: &OwnedFd, timeout: Duration) -> io::Result<()> {
+    let value = libc::timeval {
+        tv_sec: timeout.as_secs() as libc::time_t,
+        tv_usec: timeout.subsec_micros().max(1) as libc::suseconds_t
+    };
+
+    let result = unsafe {
+        libc::setsockopt(
+            socket.as_raw_fd(), libc::SOL_SOCKET, libc::SO_RCVTIMEO,
+            &value as *const libc::timeval as *const libc::c_void, mem::size_of::<libc::timeval>() as libc::socklen_t
+        )
+    };
+
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    return Ok(());
+}
+
+/// Sends `count` ICMP echo requests to `host`, one after the other, waiting up to `timeout` for each reply
+///
+/// Uses an unprivileged ICMP datagram socket when `net.ipv4.ping_group_range` allows it, a raw socket otherwise
+/// (which requires root or `CAP_NET_RAW`); returns an error if `host` can't be resolved, no socket can be opened or
+/// `count` does not fit the 16 bits ICMP sequence number
+#[cfg(feature = "ping")]
+pub fn ping(host: &str, count: usize, timeout: Duration) -> io::Result<PingStatistics> {
+    linuxCheck();
+
+    let sequences = u16::try_from(count)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "count exceeds the ICMP sequence number range"))?;
+
+    let address = (host, 0).to_socket_addrs()?
+        .map(|address| address.ip())
+        .min_by_key(|address| address.is_ipv6())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "host has no address"))?;
+
+    let ipv6 = address.is_ipv6();
+    let (socket, raw) = icmpSocket(ipv6)?;
+    let (destination, destinationLength) = socketAddress(address);
+
+    let (requestType, replyType) = if ipv6 { (128_u8, 129_u8) } else { (8_u8, 0_u8) };
+    let identifier = std::process::id() as u16;
+
+    let mut roundTrips = Vec::<Duration>::new();
+
+    for sequence in 0..sequences {
+        let mut packet = vec![requestType, 0, 0, 0];
+        packet.extend_from_slice(&identifier.to_be_bytes());
+        packet.extend_from_slice(&sequence.to_be_bytes());
+        packet.extend_from_slice(b"sysutil ping....");
+
+        // the kernel fills the checksum of ICMPv6 and datagram sockets, raw IPv4 needs it computed
+        let checksum = icmpChecksum(&packet);
+        packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+
+        let sentAt = Instant::now();
+        let sent = unsafe {
+            libc::sendto(
+                socket.as_raw_fd(), packet.as_ptr() as *const libc::c_void, packet.len(), 0,
+                &destination as *const libc::sockaddr_storage as *const libc::sockaddr, destinationLength
+            )
+        };
+
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let deadline = sentAt + timeout;
+        let mut buffer = [0_u8; 1500];
+
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            setReceiveTimeout(&socket, remaining)?;
+
+            let length = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+            if length < 0 {
+                break
+            }
+
+            // raw IPv4 sockets also return the IP header
+            let offset = if raw && !ipv6 { ((buffer[0] & 0x0F) as usize) * 4 } else { 0 };
+            let Some(reply) = buffer.get(offset..length as usize) else {
+                continue
+            };
+
+            if reply.len() < 8 || reply[0] != replyType || u16::from_be_bytes([reply[6], reply[7]]) != sequence {
+                continue
+            }
+
+            // datagram sockets rewrite the identifier and only deliver their own replies
+            if raw && u16::from_be_bytes([reply[4], reply[5]]) != identifier {
+                continue
+            }
+
+            roundTrips.push(sentAt.elapsed());
+            break
+        }
+    }
+
+    let average = if roundTrips.is_empty() {
+        None
+    } else {
+        Some(roundTrips.iter().sum::<Duration>() / roundTrips.len() as u32)
+    };
+
+    return Ok(PingStatistics {
+        host: host.to_string(),
+        address: address,
+        transmitted: count,
+        received: roundTrips.len(),
+        minimum: roundTrips.iter().min().copied(),
+        average: average,
+        maximum: roundTrips.iter().max().copied()
+    });
+}
+
+/// Contains the security schemes advertised by a Wi-Fi access point
+#[cfg(feature = "nl80211")]
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WifiSecurity {
+    pub wep: bool,
+    pub wpa: bool,
+    pub wpa2: bool,
+    pub wpa3: bool,
+    /// 802.1X authentication (WPA-Enterprise)
+    pub enterprise: bool,
+    /// Opportunistic wireless encryption (Enhanced Open)
+    pub owe: bool
+}
+
+#[cfg(feature = "nl80211")]
+impl WifiSecurity {
+    /// Returns whether the network accepts clients without credentials
+    pub fn isOpen(&self) -> bool {
+        return !(self.wep || self.wpa || self.wpa2 || self.wpa3 || self.enterprise);
+    }
+}
+
+#[cfg(feature = "nl80211")]
+impl fmt::Display for WifiSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut schemes = Vec::<&str>::new();
+
+        for (enabled, name) in [
+            (self.wep, "WEP"), (self.wpa, "WPA"), (self.wpa2, "WPA2"),
+            (self.wpa3, "WPA3"), (self.enterprise, "802.1X"), (self.owe, "OWE")
+        ] {
+            if enabled {
+                schemes.push(name);
+            }
+        }
+
+        if schemes.is_empty() {
+            schemes.push("open");
+        }
+
+        write!(f, "{}", schemes.join(" "))
+    }
+}
+
+/// Contains an access point found by a Wi-Fi scan
+#[cfg(feature = "nl80211")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WifiNetwork {
+    /// Network name, empty for hidden networks
+    pub ssid: String,
+    pub bssid: String,
+    /// Frequency, in MHz
+    pub frequency: u32,
+    pub channel: u32,
+    /// Signal strength, in dBm
+    pub signal: f32,
+    pub security: WifiSecurity,
+    /// Whether the interface is associated with this access point
+    pub associated: bool
+}
+
+#[cfg(feature = "nl80211")]
+impl fmt::Display for WifiNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}) channel {}, {:.0} dBm, {}", self.ssid, self.bssid, self.channel, self.signal, self.security)
+    }
+}
+
+// from linux/genetlink.h and linux/nl80211.h
+#[cfg(feature = "nl80211")]
+const GENL_ID_CTRL: u16 = 0x10;
+#[cfg(feature = "nl80211")]
+const CTRL_CMD_GETFAMILY: u8 = 3;
+#[cfg(feature = "nl80211")]
+const CTRL_ATTR_FAMILY_ID: u16 = 1;
+#[cfg(feature = "nl80211")]
+const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+#[cfg(feature = "nl80211")]
+const NL80211_CMD_GET_SCAN: u8 = 32;
+#[cfg(feature = "nl80211")]
+const NL80211_ATTR_IFINDEX: u16 = 3;
+#[cfg(feature = "nl80211")]
+const NL80211_ATTR_BSS: u16 = 47;
+#[cfg(feature = "nl80211")]
+const NL80211_BSS_BSSID: u16 = 1;
+#[cfg(feature = "nl80211")]
+const NL80211_BSS_FREQUENCY: u16 = 2;
+#[cfg(feature = "nl80211")]
+const NL80211_BSS_CAPABILITY: u16 = 5;
+#[cfg(feature = "nl80211")]
+const NL80211_BSS_INFORMATION_ELEMENTS: u16 = 6;
+#[cfg(feature = "nl80211")]
+const NL80211_BSS_SIGNAL_MBM: u16 = 7;
+#[cfg(feature = "nl80211")]
+const NL80211_BSS_SIGNAL_UNSPEC: u16 = 8;
+#[cfg(feature = "nl80211")]
+const NL80211_BSS_STATUS: u16 = 9;
+#[cfg(feature = "nl80211")]
+const NLMSG_ERROR: u16 = 2;
+#[cfg(feature = "nl80211")]
+const NLMSG_DONE: u16 = 3;
+
+// sends a generic netlink request and collects the attributes of every reply, until the end of a dump
+#[cfg(feature = "nl80211")]
+fn genericNetlinkRequest(socket: &OwnedFd, family: u16, command: u8, dump: bool, attributes: &[(u16, Vec<u8>)]) -> io::Result<Vec<Vec<u8>>> {
+    let mut payload = vec![command, 1, 0, 0];
+
+    for (kind, value) in attributes {
+        payload.extend_from_slice(&((value.len() + 4) as u16).to_ne_bytes());
+        payload.extend_from_slice(&kind.to_ne_bytes());
+        payload.extend_from_slice(value);
+        payload.resize(netlinkAlign(payload.len()), 0);
+    }
+
+    let flags = if dump { libc::NLM_F_REQUEST | libc::NLM_F_DUMP } else { libc::NLM_F_REQUEST };
+
+    let mut message = Vec::<u8>::new();
+    message.extend_from_slice(&((payload.len() + NLMSG_HEADER_LENGTH) as u32).to_ne_bytes());
+    message.extend_from_slice(&family.to_ne_bytes());
+    message.extend_from_slice(&(flags as u16).to_ne_bytes());
+    message.extend_from_slice(&[0_u8; 8]);
+    message.extend_from_slice(&payload);
+
+    if unsafe { libc::send(socket.as_raw_fd(), message.as_ptr() as *const libc::c_void, message.len(), 0) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let mut replies = Vec::<Vec<u8>>::new();
+    let mut buffer = vec![0_u8; 64 * 1024];
+
+    loop {
+        let length = unsafe { libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0) };
+        if length < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut received = &buffer[..length as usize];
+
+        while received.len() >= NLMSG_HEADER_LENGTH {
+            let messageLength = (u32::from_ne_bytes(received[0..4].try_into().unwrap()) as usize).min(received.len());
+            let messageType = u16::from_ne_bytes([received[4], received[5]]);
+
+            match messageType {
+                NLMSG_DONE => return Ok(replies),
+
+                NLMSG_ERROR => {
+                    // the error code is negated, 0 acknowledges the request
+                    let code = received.get(16..20).map(|code| i32::from_ne_bytes(code.try_into().unwrap())).unwrap_or(0);
+
+                    if code != 0 {
+                        return Err(io::Error::from_raw_os_error(-code));
+                    }
+
+                    return Ok(replies);
+                },
+
+                _ => {
+                    // skip the generic netlink header
+                    replies.push(received.get(NLMSG_HEADER_LENGTH + 4..messageLength).unwrap_or_default().to_vec());
+
+                    if !dump {
+                        return Ok(replies);
+                    }
+                }
+            }
+
+            received = &received[netlinkAlign(messageLength).min(received.len())..];
+        }
+    }
+}
+
+#[cfg(feature = "nl80211")]
+fn wifiChannel(frequency: u32) -> u32 {
+    match frequency {
+        2484 => 14,
+        2412..=2472 => (frequency - 2407) / 5,
+        5955..=7115 => (frequency - 5950) / 5,
+        5000..=5900 => (frequency - 5000) / 5,
+        _ => 0
+    }
+}
+
+// reads SSID and security from the information elements broadcast in beacons
+#[cfg(feature = "nl80211")]
+fn parseInformationElements(mut elements: &[u8], privacy: bool) -> (String, WifiSecurity) {
+    let mut ssid = String::new();
+    let mut security = WifiSecurity::default();
+
+    while elements.len() >= 2 {
+        let id = elements[0];
+        let length = elements[1] as usize;
+
+        let Some(data) = elements.get(2..2 + length) else {
+            break
+        };
+
+        match id {
+            0 => ssid = String::from_utf8_lossy(data).to_string(),
+
+            // RSN: version, group cipher, pairwise ciphers, then the authentication key management suites
+            48 if data.len() >= 8 => {
+                let pairwiseCount = u16::from_le_bytes([data[6], data[7]]) as usize;
+                let akmStart = 8 + pairwiseCount * 4;
+
+                let akmCount = data.get(akmStart..akmStart + 2).map(|count| u16::from_le_bytes([count[0], count[1]]) as usize).unwrap_or(0);
+                let suites = data.get(akmStart + 2..).unwrap_or_default();
+
+                for suite in suites.chunks_exact(4).take(akmCount) {
+                    if suite[..3] != [0x00, 0x0F, 0xAC] {
+                        continue
+                    }
+
+                    match suite[3] {
+                        1 | 5 | 11 | 12 | 13 => security.enterprise = true,
+                        2 | 6 => security.wpa2 = true,
+                        8 | 9 | 24 | 25 => security.wpa3 = true,
+                        18 => security.owe = true,
+                        _ => {}
+                    }
+                }
+            },
+
+            // Microsoft vendor element, type 1 is WPA
+            221 if data.starts_with(&[0x00, 0x50, 0xF2, 0x01]) => security.wpa = true,
+
+            _ => {}
+        }
+
+        elements = &elements[2 + length..];
+    }
+
+    if privacy && !(security.wpa || security.wpa2 || security.wpa3 || security.enterprise || security.owe) {
+        security.wep = true;
+    }
+
+    return (ssid, security);
+}
+
+/// Returns the access points seen by the wireless `interface` during its last scan
+///
+/// Scans are run periodically by wpa_supplicant, iwd or NetworkManager, triggering a new one requires
+/// `CAP_NET_ADMIN` and is left to them; returns an error if the interface does not exist or is not wireless
+#[cfg(feature = "nl80211")]
+pub fn wifiScan(interface: &str) -> io::Result<Vec<WifiNetwork>> {
+    linuxCheck();
+
+    let name = std::ffi::CString::new(interface).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    let index = unsafe { libc::if_nametoindex(name.as_ptr()) };
+    if index == 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_RAW | libc::SOCK_CLOEXEC, libc::NETLINK_GENERIC) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    let socket = unsafe { OwnedFd::from_raw_fd(fd) };
+
+    // the controller answers ENOENT when cfg80211 is not loaded
+    let familyReply = genericNetlinkRequest(
+        &socket, GENL_ID_CTRL, CTRL_CMD_GETFAMILY, false, &[(CTRL_ATTR_FAMILY_NAME, b"nl80211\0".to_vec())]
+    ).map_err(|error| match error.raw_os_error() {
+        Some(libc::ENOENT) => io::Error::new(io::ErrorKind::Unsupported, "nl80211 is not available"),
+        _ => error
+    })?;
+
+    let family = familyReply.first()
+        .and_then(|reply| netlinkAttributes(reply).into_iter().find(|(kind, _)| *kind == CTRL_ATTR_FAMILY_ID))
+        .and_then(|(_, value)| Some(u16::from_ne_bytes(value.get(..2)?.try_into().ok()?)))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "nl80211 is not available"))?;
+
+    let replies = genericNetlinkRequest(
+        &socket, family, NL80211_CMD_GET_SCAN, true, &[(NL80211_ATTR_IFINDEX, index.to_ne_bytes().to_vec())]
+    )?;
+
+    let mut networks = Vec::<WifiNetwork>::new();
+
+    for reply in replies {
+        let Some((_, bss)) = netlinkAttributes(&reply).into_iter().find(|(kind, _)| *kind == NL80211_ATTR_BSS) else {
+            continue
+        };
+
+        let attributes = netlinkAttributes(bss);
+        let attribute = |kind: u16| attributes.iter().find(|(attributeKind, _)| *attributeKind == kind).map(|(_, value)| *value);
+        let u32Attribute = |kind: u16| attribute(kind).and_then(|value| Some(u32::from_ne_bytes(value.get(..4)?.try_into().ok()?)));
+
+        let Some(bssid) = attribute(NL80211_BSS_BSSID) else {
+            continue
+        };
+
+        let privacy = attribute(NL80211_BSS_CAPABILITY)
+            .and_then(|value| Some(u16::from_ne_bytes(value.get(..2)?.try_into().ok()?)))
+            .map(|capability| capability & 0x10 != 0)
+            .unwrap_or(false);
+
+        let (ssid, security) = parseInformationElements(attribute(NL80211_BSS_INFORMATION_ELEMENTS).unwrap_or_default(), privacy);
+
+        // mBm are hundredths of dBm, drivers without calibrated signal only report a 0-100 quality
+        let signal = match (u32Attribute(NL80211_BSS_SIGNAL_MBM), attribute(NL80211_BSS_SIGNAL_UNSPEC)) {
+            (Some(signal), _) => signal as i32 as f32 / 100_f32,
+            (None, Some(quality)) => quality.first().map(|quality| *quality as f32 / 2_f32 - 100_f32).unwrap_or(-100_f32),
+            _ => -100_f32
+        };
+
+        let frequency = u32Attribute(NL80211_BSS_FREQUENCY).unwrap_or(0);
+
+        networks.push(WifiNetwork {
+            ssid: ssid,
+            bssid: bssid.iter().map(|byte| format!("{:02x}", byte)).collect::<Vec<String>>().join(":"),
+            frequency: frequency,
+            channel: wifiChannel(frequency),
+            signal: signal,
+            security: security,
+            associated: u32Attribute(NL80211_BSS_STATUS).is_some()
+        });
+    }
+
+    networks.sort_by(|first, second| second.signal.total_cmp(&first.signal));
+    return Ok(networks);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsesFibTrie() {
+        // captured from a virtual machine with a single /24 network
+        let root = SysPaths::new(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/root/vm"));
+        let addresses = root.scope(getIPv4);
+
+        assert_eq!(addresses.len(), 1);
+        assert_eq!(addresses[0].address, "192.0.2.2");
+        assert_eq!(addresses[0].interface, "eth0");
+        assert_eq!(addresses[0].broadcast, "192.0.2.255");
+        assert_eq!(addresses[0].netmask, "255.255.255.0");
+        assert_eq!(addresses[0].cidr, "24");
+
+        assert_eq!(fibPrefixLength("              /8 link UNICAST").unwrap(), 8);
+        assert_eq!(fibPrefixLength("/24 link UNICAST").unwrap(), 24);
+        assert!(fibPrefixLength("/ link UNICAST").is_err());
+
+        assert_eq!(bytesToAddress("0100007F", "."), Some(String::from("127.0.0.1")));
+        assert_eq!(bytesToAddress("0100007", "."), None);
+        assert_eq!(bytesToPort("zz"), None);
+    }
+}
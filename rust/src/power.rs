@@ -0,0 +1,141 @@
+use std::{fmt, fs, path, thread};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use crate::gpu::gpuSensors;
+use crate::utils::{*};
+
+/// Contains the power drawn by the main consumers of the system, in watts
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerDraw {
+    /// CPU package power from RAPL, summed over every package
+    pub cpuPackage: Option<f32>,
+    /// RAPL sub-domains summed over every package, e.g. `core`, `uncore`, `dram`
+    pub cpuDomains: HashMap<String, f32>,
+    /// Power of each GPU, keyed by DRM card name
+    pub gpus: HashMap<String, f32>,
+    /// Battery discharge rate, `None` when running on external power
+    pub batteryDischarge: Option<f32>
+}
+
+impl PowerDraw {
+    /// Returns the sum of CPU package and GPU power
+    pub fn componentsTotal(&self) -> f32 {
+        return self.cpuPackage.unwrap_or(0_f32) + self.gpus.values().fold(0_f32, |total, power| total + power);
+    }
+
+    /// Returns the whole system draw when known (battery discharge), otherwise the sum of the measured components
+    pub fn total(&self) -> f32 {
+        return self.batteryDischarge.unwrap_or(self.componentsTotal());
+    }
+}
+
+impl fmt::Display for PowerDraw {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "CPU {:.1} W, GPU {:.1} W", self.cpuPackage.unwrap_or(0_f32), self.gpus.values().fold(0_f32, |total, power| total + power))?;
+
+        match self.batteryDischarge {
+            Some(discharge) => write!(f, ", battery {:.1} W", discharge),
+            None => Ok(())
+        }
+    }
+}
+
+// RAPL zones are `intel-rapl:<package>` with sub-zones `intel-rapl:<package>:<domain>`, AMD CPUs use the same naming
+fn raplEnergy() -> HashMap<String, (String, u64, u64)> {
+    let mut zones = HashMap::<String, (String, u64, u64)>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/class/powercap")) else {
+        return zones;
+    };
+
+    for entry in entries.flatten() {
+        let zone = entry.file_name().to_string_lossy().to_string();
+        if !zone.starts_with("intel-rapl:") {
+            continue;
+        }
+
+        // energy_uj is only readable by root since kernel 5.10
+        let Ok(energy) = readFile(entry.path().join("energy_uj")).parse::<u64>() else {
+            continue;
+        };
+
+        let range = readFile(entry.path().join("max_energy_range_uj")).parse::<u64>().unwrap_or(u64::MAX);
+        zones.insert(zone, (readFile(entry.path().join("name")), energy, range));
+    }
+
+    return zones;
+}
+
+fn batteryDischarge() -> Option<f32> {
+    let mut discharge = None;
+
+    for entry in fs::read_dir(sysPath("/sys/class/power_supply")).ok()?.flatten() {
+        let supply: path::PathBuf = entry.path();
+
+        if readFile(supply.join("type")) != "Battery" || readFile(supply.join("status")) != "Discharging" {
+            continue;
+        }
+
+        let value = |name: &str| readFile(supply.join(name)).parse::<f64>().ok();
+
+        // some batteries only report current and voltage
+        let microwatts = value("power_now").or_else(|| Some(value("current_now")? * value("voltage_now")? / 1_000_000_f64));
+
+        if let Some(microwatts) = microwatts {
+            discharge = Some(discharge.unwrap_or(0_f32) + (microwatts.abs() / 1_000_000_f64) as f32);
+        }
+    }
+
+    return discharge;
+}
+
+/// Returns the power drawn by the CPU (RAPL), the GPUs (hwmon) and the battery discharge rate, sampled over 500 ms
+///
+/// RAPL energy counters require root privileges on recent kernels, `cpuPackage` is `None` otherwise
+pub fn systemPowerDraw() -> PowerDraw {
+    linuxCheck();
+    traceSpan!("systemPowerDraw");
+
+    let start = Instant::now();
+    let before = raplEnergy();
+    thread::sleep(Duration::from_millis(500));
+    let after = raplEnergy();
+    let elapsed = start.elapsed().as_secs_f32();
+
+    let mut cpuPackage = None;
+    let mut cpuDomains = HashMap::<String, f32>::new();
+
+    for (zone, (name, energy, range)) in &after {
+        let Some((_, previous, _)) = before.get(zone) else {
+            continue;
+        };
+
+        // the counter wraps at max_energy_range_uj
+        let consumed = if energy >= previous { energy - previous } else { range - previous + energy };
+        let watts = consumed as f32 / 1_000_000_f32 / elapsed;
+
+        if zone.matches(':').count() == 1 {
+            // other top level zones, such as `psys` for the whole platform, already include the packages
+            if name.starts_with("package") {
+                cpuPackage = Some(cpuPackage.unwrap_or(0_f32) + watts);
+            }
+        } else {
+            *cpuDomains.entry(name.clone()).or_insert(0_f32) += watts;
+        }
+    }
+
+    let mut gpus = HashMap::<String, f32>::new();
+    for gpu in gpuSensors() {
+        if let Some(power) = gpu.power {
+            gpus.insert(gpu.card, power);
+        }
+    }
+
+    return PowerDraw {
+        cpuPackage: cpuPackage,
+        cpuDomains: cpuDomains,
+        gpus: gpus,
+        batteryDischarge: batteryDischarge()
+    };
+}
@@ -0,0 +1,983 @@
+use std::{fmt, fs, io, path, thread};
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::utils::{*};
+
+/// Contains a running process
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Process {
+    pub pid: u32,
+    /// Command name, as in `/proc/<pid>/comm`
+    pub name: String,
+    /// `/proc/<pid>` directory opened by `Process::open()`, pinning the queries to this very process
+    #[cfg_attr(feature = "serde", serde(skip))]
+    directory: Option<Arc<OwnedFd>>
+}
+
+impl Process {
+    /// Returns the process with the given PID, `None` if it does not exist
+    pub fn fromPid(pid: u32) -> Option<Process> {
+        linuxCheck();
+
+        let name = fs::read_to_string(sysPath(format!("/proc/{}/comm", pid))).ok()?;
+
+        return Some(Process {
+            pid: pid,
+            name: name.trim().to_string(),
+            directory: None
+        });
+    }
+
+    /// Opens a pidfd-backed handle to the process
+    ///
+    /// Queries made through `ProcessHandle::process()` read the `/proc` directory opened here,
+    /// so they fail once the process exits instead of silently reading a process which reused its PID
+    pub fn open(pid: u32) -> io::Result<ProcessHandle> {
+        linuxCheck();
+
+        let pidfd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if pidfd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let pidfd = unsafe { OwnedFd::from_raw_fd(pidfd as RawFd) };
+
+        let directoryPath = sysPath(format!("/proc/{}", pid));
+        let directoryPath = CString::new(directoryPath.as_os_str().as_encoded_bytes()).map_err(io::Error::other)?;
+        let directory = unsafe { libc::open(directoryPath.as_ptr(), libc::O_RDONLY | libc::O_DIRECTORY | libc::O_CLOEXEC) };
+        if directory < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let directory = unsafe { OwnedFd::from_raw_fd(directory) };
+
+        let handle = ProcessHandle {
+            pidfd: pidfd,
+            process: Process {
+                pid: pid,
+                name: String::new(),
+                directory: Some(Arc::new(directory))
+            }
+        };
+
+        // the pidfd was opened first: if the process is still alive, the directory belongs to it and not to a PID reuse
+        if !handle.isAlive() {
+            return Err(io::Error::from_raw_os_error(libc::ESRCH));
+        }
+
+        let mut handle = handle;
+        handle.process.name = readFile(handle.process.path().join("comm"));
+
+        return Ok(handle);
+    }
+
+    // the descriptor link belongs to the running system, below another root the directory is read by path instead
+    fn path(&self) -> path::PathBuf {
+        match &self.directory {
+            Some(directory) if SysPaths::current().root() == path::Path::new("/") => {
+                path::PathBuf::from(format!("/proc/self/fd/{}", directory.as_raw_fd()))
+            },
+            _ => sysPath("/proc").join(self.pid.to_string())
+        }
+    }
+
+    fn status(&self, field: &str) -> Option<String> {
+        return readFile(self.path().join("status")).lines()
+            .find_map(|line| line.strip_prefix(field).and_then(|rest| rest.strip_prefix(':')))
+            .map(|value| value.trim().to_string());
+    }
+
+    /// Returns the capabilities, seccomp mode and LSM label of the process, `None` if it exited
+    ///
+    /// The LSM label of processes owned by other users may not be readable
+    pub fn security(&self) -> Option<ProcessSecurity> {
+        let capabilities = u64::from_str_radix(&self.status("CapEff")?, 16).ok()?;
+
+        let seccomp = match self.status("Seccomp").as_deref() {
+            Some("1") => SeccompMode::Strict,
+            Some("2") => SeccompMode::Filter,
+            _ => SeccompMode::Disabled
+        };
+
+        // `attr/apparmor/current` exists since Linux 5.8, `attr/current` holds the label of the major LSM
+        let (lsm, label) = match fs::read_to_string(self.path().join("attr/apparmor/current")) {
+            Ok(label) => (Some(String::from("apparmor")), Some(label)),
+            Err(_) => {
+                let lsm = readFile("/sys/kernel/security/lsm").split(',')
+                    .find(|lsm| ["selinux", "apparmor", "smack"].contains(lsm))
+                    .map(|lsm| lsm.to_string());
+
+                // without a labelling LSM the file is missing or holds a placeholder
+                let label = lsm.as_ref().and_then(|_| fs::read_to_string(self.path().join("attr/current")).ok());
+
+                (lsm, label)
+            }
+        };
+
+        return Some(ProcessSecurity {
+            effectiveCapabilities: decodeCapabilities(capabilities),
+            seccomp: seccomp,
+            noNewPrivileges: self.status("NoNewPrivs").as_deref() == Some("1"),
+            lsm: lsm,
+            label: label.map(|label| label.trim_end_matches(['\0', '\n']).to_string()).filter(|label| !label.is_empty())
+        });
+    }
+
+    /// Returns the threads of the process with their state and consumed CPU time
+    pub fn threads(&self) -> Vec<Thread> {
+        let ticks = clockTicks();
+
+        let mut threads: Vec<Thread> = fs::read_dir(self.path().join("task")).into_iter().flatten().flatten()
+            .filter_map(|entry| {
+                let tid = entry.file_name().to_string_lossy().parse::<u32>().ok()?;
+                let stat = readFile(entry.path().join("stat"));
+                let (name, fields) = parseStat(&stat)?;
+                let field = |index: usize| fields.get(index).and_then(|value| value.parse::<u64>().ok());
+
+                Some(Thread {
+                    tid: tid,
+                    name: name,
+                    state: ProcessState::fromString(fields.first()?),
+                    cpuTime: Duration::from_secs_f64((field(11)? + field(12)?) as f64 / ticks),
+                    processor: field(36).map(|processor| processor as usize)
+                })
+            })
+            .collect();
+
+        threads.sort_by_key(|thread| thread.tid);
+        return threads;
+    }
+
+    /// Returns the CPU usage of each thread sampled over the specified interval, the busiest first
+    ///
+    /// Usage is in percentage of a single CPU, threads spawned during the interval are not reported
+    pub fn threadUsage(&self, interval: Duration) -> Vec<ThreadUsage> {
+        let start = Instant::now();
+        let before: HashMap<u32, Duration> = self.threads().into_iter().map(|thread| (thread.tid, thread.cpuTime)).collect();
+        thread::sleep(interval);
+        let after = self.threads();
+
+        let seconds = start.elapsed().as_secs_f32();
+
+        let mut usages: Vec<ThreadUsage> = after.into_iter()
+            .filter_map(|thread| {
+                let previous = before.get(&thread.tid)?;
+                let usage = if seconds > 0_f32 {
+                    thread.cpuTime.saturating_sub(*previous).as_secs_f32() * 100_f32 / seconds
+                } else {
+                    0_f32
+                };
+
+                Some(ThreadUsage { thread: thread, usage: usage })
+            })
+            .collect();
+
+        usages.sort_by(|first, second| second.usage.total_cmp(&first.usage));
+        return usages;
+    }
+
+    /// Returns swap usage and page fault counters of the process, `None` if it exited
+    pub fn memoryStats(&self) -> Option<ProcessMemoryStats> {
+        let stat = readFile(self.path().join("stat"));
+        let (_, fields) = parseStat(&stat)?;
+        let field = |index: usize| fields.get(index).and_then(|value| value.parse::<u64>().ok());
+
+        // `VmSwap:   1024 kB`, missing for kernel threads
+        let swapKib = self.status("VmSwap")
+            .and_then(|swap| swap.trim_end_matches("kB").trim().parse::<u64>().ok())
+            .unwrap_or(0);
+
+        return Some(ProcessMemoryStats {
+            swap: ByteSize::fromBytes(swapKib * 1024),
+            minorFaults: field(7)?,
+            majorFaults: field(9)?
+        });
+    }
+
+    /// Returns the page fault rates of the process sampled over the specified interval, `None` if it exited
+    pub fn faultRate(&self, interval: Duration) -> Option<FaultRate> {
+        let start = Instant::now();
+        let before = self.memoryStats()?;
+        thread::sleep(interval);
+        let after = self.memoryStats()?;
+
+        return Some(FaultRate::between(self.clone(), &before, after, start.elapsed()));
+    }
+
+    /// Returns the soft and hard limits of a resource for the process
+    pub fn limit(&self, resource: Resource) -> io::Result<ResourceLimit> {
+        let mut current = libc::rlimit64 { rlim_cur: 0, rlim_max: 0 };
+
+        if unsafe { libc::prlimit64(self.pid as libc::pid_t, resource.code() as _, std::ptr::null(), &mut current) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        return Ok(ResourceLimit {
+            soft: ResourceLimit::fromRaw(current.rlim_cur),
+            hard: ResourceLimit::fromRaw(current.rlim_max)
+        });
+    }
+
+    /// Sets the soft and hard limits of a resource for the process, `None` meaning unlimited
+    ///
+    /// Changing the limits of another process requires `CAP_SYS_RESOURCE` or the same user,
+    /// raising a hard limit always requires `CAP_SYS_RESOURCE`
+    pub fn setLimit(&self, resource: Resource, soft: Option<u64>, hard: Option<u64>) -> io::Result<()> {
+        let limit = libc::rlimit64 {
+            rlim_cur: soft.unwrap_or(libc::RLIM64_INFINITY),
+            rlim_max: hard.unwrap_or(libc::RLIM64_INFINITY)
+        };
+
+        if unsafe { libc::prlimit64(self.pid as libc::pid_t, resource.code() as _, &limit, std::ptr::null_mut()) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        return Ok(());
+    }
+
+    /// Returns the namespaces the process belongs to, empty if they are not readable (processes of other users need root)
+    pub fn namespaces(&self) -> Vec<Namespace> {
+        return NAMESPACE_TYPES.iter()
+            .filter_map(|namespaceType| Some(Namespace {
+                namespaceType: *namespaceType,
+                inode: self.namespace(*namespaceType)?
+            }))
+            .collect();
+    }
+
+    /// Returns the inode of the namespace of the given type, `None` if it is not readable
+    pub fn namespace(&self, namespaceType: NamespaceType) -> Option<u64> {
+        // the link target is `<type>:[<inode>]`, e.g. `net:[4026531840]`
+        let target = fs::read_link(self.path().join("ns").join(namespaceType.toString())).ok()?;
+
+        return target.to_string_lossy().split_once('[')
+            .and_then(|(_, inode)| inode.trim_end_matches(']').parse::<u64>().ok());
+    }
+}
+
+impl fmt::Display for Process {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.pid)
+    }
+}
+
+/// Resource whose usage can be limited per process, see `getrlimit(2)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Resource {
+    /// CPU time in seconds
+    CpuTime,
+    /// Largest file the process can create, in bytes
+    FileSize,
+    DataSegment,
+    Stack,
+    CoreFile,
+    ResidentSet,
+    /// Processes (threads) of the real user ID
+    Processes,
+    OpenFiles,
+    /// Memory which can be locked in RAM, in bytes
+    LockedMemory,
+    AddressSpace,
+    FileLocks,
+    PendingSignals,
+    MessageQueue,
+    Nice,
+    RealtimePriority,
+    /// CPU time of realtime tasks without blocking, in microseconds
+    RealtimeTimeout
+}
+
+impl Resource {
+    // the resource type differs between C libraries, the constants are small integers on every one
+    fn code(&self) -> i32 {
+        return match self {
+            Resource::CpuTime => libc::RLIMIT_CPU as i32,
+            Resource::FileSize => libc::RLIMIT_FSIZE as i32,
+            Resource::DataSegment => libc::RLIMIT_DATA as i32,
+            Resource::Stack => libc::RLIMIT_STACK as i32,
+            Resource::CoreFile => libc::RLIMIT_CORE as i32,
+            Resource::ResidentSet => libc::RLIMIT_RSS as i32,
+            Resource::Processes => libc::RLIMIT_NPROC as i32,
+            Resource::OpenFiles => libc::RLIMIT_NOFILE as i32,
+            Resource::LockedMemory => libc::RLIMIT_MEMLOCK as i32,
+            Resource::AddressSpace => libc::RLIMIT_AS as i32,
+            Resource::FileLocks => libc::RLIMIT_LOCKS as i32,
+            Resource::PendingSignals => libc::RLIMIT_SIGPENDING as i32,
+            Resource::MessageQueue => libc::RLIMIT_MSGQUEUE as i32,
+            Resource::Nice => libc::RLIMIT_NICE as i32,
+            Resource::RealtimePriority => libc::RLIMIT_RTPRIO as i32,
+            Resource::RealtimeTimeout => libc::RLIMIT_RTTIME as i32
+        };
+    }
+
+    /// Parses the name used by `ulimit` and `prlimit`, e.g. `nofile`, `memlock`
+    pub fn fromString(resource: &str) -> Option<Resource> {
+        match resource.to_lowercase().as_str() {
+            "cpu" => Some(Resource::CpuTime),
+            "fsize" => Some(Resource::FileSize),
+            "data" => Some(Resource::DataSegment),
+            "stack" => Some(Resource::Stack),
+            "core" => Some(Resource::CoreFile),
+            "rss" => Some(Resource::ResidentSet),
+            "nproc" => Some(Resource::Processes),
+            "nofile" => Some(Resource::OpenFiles),
+            "memlock" => Some(Resource::LockedMemory),
+            "as" => Some(Resource::AddressSpace),
+            "locks" => Some(Resource::FileLocks),
+            "sigpending" => Some(Resource::PendingSignals),
+            "msgqueue" => Some(Resource::MessageQueue),
+            "nice" => Some(Resource::Nice),
+            "rtprio" => Some(Resource::RealtimePriority),
+            "rttime" => Some(Resource::RealtimeTimeout),
+            _ => None
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            Resource::CpuTime => "cpu",
+            Resource::FileSize => "fsize",
+            Resource::DataSegment => "data",
+            Resource::Stack => "stack",
+            Resource::CoreFile => "core",
+            Resource::ResidentSet => "rss",
+            Resource::Processes => "nproc",
+            Resource::OpenFiles => "nofile",
+            Resource::LockedMemory => "memlock",
+            Resource::AddressSpace => "as",
+            Resource::FileLocks => "locks",
+            Resource::PendingSignals => "sigpending",
+            Resource::MessageQueue => "msgqueue",
+            Resource::Nice => "nice",
+            Resource::RealtimePriority => "rtprio",
+            Resource::RealtimeTimeout => "rttime"
+        })
+    }
+}
+
+impl fmt::Display for Resource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains the soft and hard limit of a resource, `None` meaning unlimited
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResourceLimit {
+    pub soft: Option<u64>,
+    pub hard: Option<u64>
+}
+
+impl ResourceLimit {
+    fn fromRaw(value: u64) -> Option<u64> {
+        if value == libc::RLIM64_INFINITY {
+            return None;
+        }
+
+        return Some(value);
+    }
+}
+
+impl fmt::Display for ResourceLimit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format = |value: Option<u64>| value.map(|value| value.to_string()).unwrap_or(String::from("unlimited"));
+        write!(f, "{} / {}", format(self.soft), format(self.hard))
+    }
+}
+
+/// Contains a pidfd-backed handle to a process, see `Process::open()`
+#[derive(Debug)]
+pub struct ProcessHandle {
+    pidfd: OwnedFd,
+    process: Process
+}
+
+impl ProcessHandle {
+    /// Returns the process, whose queries are bound to the opened process
+    pub fn process(&self) -> &Process {
+        return &self.process;
+    }
+
+    pub fn pid(&self) -> u32 {
+        return self.process.pid;
+    }
+
+    /// Returns `true` if the process has not exited yet
+    ///
+    /// Polls the pidfd, which becomes readable on exit, so it also works for processes owned by other users,
+    /// which signals (even signal 0) can not reach
+    pub fn isAlive(&self) -> bool {
+        return matches!(self.waitExit(Some(Duration::ZERO)), Ok(false));
+    }
+
+    /// Sends a signal (e.g. `libc::SIGTERM`) to the process, it can not reach another process reusing the PID
+    pub fn sendSignal(&self, signal: i32) -> io::Result<()> {
+        let result = unsafe {
+            libc::syscall(libc::SYS_pidfd_send_signal, self.pidfd.as_raw_fd(), signal, std::ptr::null::<libc::siginfo_t>(), 0)
+        };
+
+        if result < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        return Ok(());
+    }
+
+    /// Blocks until the process exits or the timeout expires, returns `true` if the process exited
+    ///
+    /// `None` waits without a timeout
+    pub fn waitExit(&self, timeout: Option<Duration>) -> io::Result<bool> {
+        let mut descriptor = libc::pollfd {
+            fd: self.pidfd.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0
+        };
+
+        let timeout = timeout.map(|timeout| timeout.as_millis().min(i32::MAX as u128) as i32).unwrap_or(-1);
+
+        loop {
+            match unsafe { libc::poll(&mut descriptor, 1, timeout) } {
+                -1 => {
+                    let error = io::Error::last_os_error();
+                    if error.kind() != io::ErrorKind::Interrupted {
+                        return Err(error);
+                    }
+                },
+                0 => return Ok(false),
+                _ => return Ok(true)
+            }
+        }
+    }
+
+    /// Same as `waitExit(None)`, but waits for the pidfd to become readable on the tokio reactor instead of blocking
+    /// the thread, combine it with `tokio::time::timeout` for a timeout
+    #[cfg(feature = "async")]
+    pub async fn waitExitAsync(&self) -> io::Result<()> {
+        // the pidfd stays owned by the handle, the reactor only borrows its number
+        let descriptor = tokio::io::unix::AsyncFd::with_interest(self.pidfd.as_raw_fd(), tokio::io::Interest::READABLE)?;
+        let _ready = descriptor.readable().await?;
+
+        return Ok(());
+    }
+}
+
+impl AsRawFd for ProcessHandle {
+    fn as_raw_fd(&self) -> RawFd {
+        return self.pidfd.as_raw_fd();
+    }
+}
+
+impl fmt::Display for ProcessHandle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.process)
+    }
+}
+
+/// Returns every running process
+pub fn processes() -> Vec<Process> {
+    linuxCheck();
+
+    let mut processes: Vec<Process> = fs::read_dir(sysPath("/proc")).into_iter().flatten().flatten()
+        .filter_map(|entry| entry.file_name().to_string_lossy().parse::<u32>().ok())
+        .filter_map(Process::fromPid)
+        .collect();
+
+    processes.sort_by_key(|process| process.pid);
+    return processes;
+}
+
+/// Scheduling state of a process or thread
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ProcessState {
+    Running,
+    Sleeping,
+    /// Uninterruptible sleep, usually waiting for I/O
+    DiskSleep,
+    Stopped,
+    Zombie,
+    Idle,
+    Unknown
+}
+
+impl ProcessState {
+    /// Parses the state letter of `/proc/<pid>/stat`
+    pub fn fromString(state: &str) -> ProcessState {
+        match state {
+            "R" => ProcessState::Running,
+            "S" => ProcessState::Sleeping,
+            "D" => ProcessState::DiskSleep,
+            "T" | "t" => ProcessState::Stopped,
+            "Z" | "X" => ProcessState::Zombie,
+            "I" => ProcessState::Idle,
+            _ => ProcessState::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            ProcessState::Running => "running",
+            ProcessState::Sleeping => "sleeping",
+            ProcessState::DiskSleep => "disk sleep",
+            ProcessState::Stopped => "stopped",
+            ProcessState::Zombie => "zombie",
+            ProcessState::Idle => "idle",
+            ProcessState::Unknown => "unknown"
+        })
+    }
+}
+
+impl fmt::Display for ProcessState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains a thread of a process
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Thread {
+    pub tid: u32,
+    pub name: String,
+    pub state: ProcessState,
+    /// User and system CPU time consumed since the thread started
+    pub cpuTime: Duration,
+    /// CPU the thread last ran on
+    pub processor: Option<usize>
+}
+
+impl fmt::Display for Thread {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}, {:.2}s CPU", self.name, self.tid, self.state, self.cpuTime.as_secs_f64())
+    }
+}
+
+/// Contains the CPU usage of a thread over a sampling interval
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThreadUsage {
+    pub thread: Thread,
+    /// Percentage of a single CPU
+    pub usage: f32
+}
+
+impl fmt::Display for ThreadUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {:.1}%", self.thread.name, self.thread.tid, self.usage)
+    }
+}
+
+/// Contains swap usage and page fault counters of a process
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessMemoryStats {
+    pub swap: ByteSize,
+    /// Faults served without I/O since the process started
+    pub minorFaults: u64,
+    /// Faults which required reading from disk or swap since the process started
+    pub majorFaults: u64
+}
+
+impl fmt::Display for ProcessMemoryStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} swapped, {} minor / {} major faults", self.swap, self.minorFaults, self.majorFaults)
+    }
+}
+
+/// Contains the page fault rates of a process over a sampling interval
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FaultRate {
+    pub process: Process,
+    pub swap: ByteSize,
+    pub minorPerSecond: f32,
+    /// Sustained major faults with swap in use point to a thrashing process
+    pub majorPerSecond: f32
+}
+
+impl FaultRate {
+    fn between(process: Process, before: &ProcessMemoryStats, after: ProcessMemoryStats, elapsed: Duration) -> FaultRate {
+        let seconds = elapsed.as_secs_f32();
+        let rate = |before: u64, after: u64| if seconds > 0_f32 { after.saturating_sub(before) as f32 / seconds } else { 0_f32 };
+
+        return FaultRate {
+            process: process,
+            minorPerSecond: rate(before.minorFaults, after.minorFaults),
+            majorPerSecond: rate(before.majorFaults, after.majorFaults),
+            swap: after.swap
+        };
+    }
+}
+
+impl fmt::Display for FaultRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{}: {:.1} major/s, {:.1} minor/s, {} swapped",
+            self.process, self.majorPerSecond, self.minorPerSecond, self.swap
+        )
+    }
+}
+
+/// Returns the page fault rates of every process sampled over the specified interval,
+/// sorted by major faults per second, the processes most likely causing swap thrashing first
+pub fn faultRates(interval: Duration) -> Vec<FaultRate> {
+    linuxCheck();
+
+    let start = Instant::now();
+    let before: Vec<(Process, ProcessMemoryStats)> = processes().into_iter()
+        .filter_map(|process| {
+            let stats = process.memoryStats()?;
+            Some((process, stats))
+        })
+        .collect();
+
+    thread::sleep(interval);
+    let elapsed = start.elapsed();
+
+    let mut rates: Vec<FaultRate> = before.into_iter()
+        .filter_map(|(process, stats)| {
+            let after = process.memoryStats()?;
+            Some(FaultRate::between(process, &stats, after, elapsed))
+        })
+        .collect();
+
+    rates.sort_by(|first, second| {
+        second.majorPerSecond.total_cmp(&first.majorPerSecond).then(second.swap.bytes().cmp(&first.swap.bytes()))
+    });
+
+    return rates;
+}
+
+/// Contains the resources used by every process of an application
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ApplicationUsage {
+    /// File name of the executable, or the systemd unit when the executable is not readable
+    pub name: String,
+    /// Path of the executable, `None` when the processes were grouped by systemd unit or command name
+    pub executable: Option<String>,
+    pub processes: Vec<Process>,
+    /// User and system CPU time consumed by the processes since they started
+    pub cpuTime: Duration,
+    pub rss: ByteSize,
+    /// Proportional set size, pages shared between processes are split between them instead of counted in each,
+    /// `None` unless `smaps_rollup` is readable for every one of the processes
+    pub pss: Option<ByteSize>,
+    /// Bytes read from storage, only counting the processes whose `io` file is readable
+    pub readBytes: u64,
+    pub writtenBytes: u64
+}
+
+impl fmt::Display for ApplicationUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{}: {} across {} processes, {:.1}s CPU",
+            self.name, self.pss.unwrap_or(self.rss), self.processes.len(), self.cpuTime.as_secs_f64()
+        )
+    }
+}
+
+// returns the systemd unit a cgroup path belongs to, e.g. `/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-4242.scope`
+fn systemdUnit(cgroup: &str) -> Option<String> {
+    return cgroup.rsplit('/')
+        .find(|segment| segment.ends_with(".service") || segment.ends_with(".scope"))
+        .map(|segment| segment.to_string());
+}
+
+/// Returns the running processes grouped by application, the most memory hungry first
+///
+/// Processes are grouped by executable path; when the executable is not readable (processes of other users need root)
+/// they are grouped by their systemd unit, or by command name outside of systemd
+pub fn byApplication() -> Vec<ApplicationUsage> {
+    linuxCheck();
+
+    let ticks = clockTicks();
+    let mut applications = HashMap::<String, ApplicationUsage>::new();
+
+    for process in processes() {
+        let stat = readFile(process.path().join("stat"));
+        let Some((_, fields)) = parseStat(&stat) else {
+            continue;
+        };
+        let field = |index: usize| fields.get(index).and_then(|value| value.parse::<u64>().ok()).unwrap_or(0);
+
+        // kernel threads have no memory map and no executable
+        let Some(rss) = process.status("VmRSS") else {
+            continue;
+        };
+        let kibibytes = |value: &str| value.trim_end_matches("kB").trim().parse::<u64>().ok();
+
+        let executable = fs::read_link(process.path().join("exe")).ok()
+            .map(|executable| executable.to_string_lossy().trim_end_matches(" (deleted)").to_string());
+
+        let (key, name) = match &executable {
+            Some(executable) => (
+                executable.clone(),
+                path::Path::new(executable).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or(executable.clone())
+            ),
+            None => match crate::cgroups::processCgroup(process.pid).as_deref().and_then(systemdUnit) {
+                Some(unit) => (format!("unit:{}", unit), unit),
+                None => (format!("comm:{}", process.name), process.name.clone())
+            }
+        };
+
+        let pss = readFile(process.path().join("smaps_rollup")).lines()
+            .find_map(|line| line.strip_prefix("Pss:"))
+            .and_then(kibibytes);
+
+        let io = readFile(process.path().join("io"));
+        let ioField = |name: &str| io.lines()
+            .find_map(|line| line.strip_prefix(name).and_then(|rest| rest.strip_prefix(':')))
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .unwrap_or(0);
+
+        let application = applications.entry(key).or_insert(ApplicationUsage {
+            name: name,
+            executable: executable,
+            processes: Vec::<Process>::new(),
+            cpuTime: Duration::ZERO,
+            rss: ByteSize::default(),
+            pss: None,
+            readBytes: 0,
+            writtenBytes: 0
+        });
+
+        application.cpuTime += Duration::from_secs_f64((field(11) + field(12)) as f64 / ticks);
+        application.rss += ByteSize::fromBytes(kibibytes(&rss).unwrap_or(0) * 1024);
+        // a partial PSS sum would rank the group below its RSS, so it is only kept when every process reports it
+        let pss = pss.map(|pss| ByteSize::fromBytes(pss * 1024));
+        application.pss = if application.processes.is_empty() {
+            pss
+        } else {
+            application.pss.zip(pss).map(|(total, pss)| total + pss)
+        };
+        application.readBytes += ioField("read_bytes");
+        application.writtenBytes += ioField("write_bytes");
+        application.processes.push(process);
+    }
+
+    let mut applications: Vec<ApplicationUsage> = applications.into_values().collect();
+    applications.sort_by(|first, second| {
+        let memory = |application: &ApplicationUsage| application.pss.unwrap_or(application.rss).bytes();
+        memory(second).cmp(&memory(first)).then(first.name.cmp(&second.name))
+    });
+
+    return applications;
+}
+
+fn clockTicks() -> f64 {
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    return if ticks > 0 { ticks as f64 } else { 100_f64 };
+}
+
+// splits `<pid> (<comm>) <state> <ppid> ...` into the command name and the fields following it,
+// the name may contain spaces and parentheses so the last `)` ends it
+fn parseStat(content: &str) -> Option<(String, Vec<&str>)> {
+    let start = content.find('(')?;
+    let end = content.rfind(')')?;
+
+    return Some((content[start + 1..end].to_string(), content[end + 1..].split_whitespace().collect()));
+}
+
+/// Kind of Linux namespace
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NamespaceType {
+    Cgroup,
+    Ipc,
+    Mount,
+    Network,
+    Pid,
+    Time,
+    User,
+    Uts
+}
+
+const NAMESPACE_TYPES: [NamespaceType; 8] = [
+    NamespaceType::Cgroup, NamespaceType::Ipc, NamespaceType::Mount, NamespaceType::Network,
+    NamespaceType::Pid, NamespaceType::Time, NamespaceType::User, NamespaceType::Uts
+];
+
+impl NamespaceType {
+    /// Parses the name used in `/proc/<pid>/ns`, e.g. `net`, `mnt`
+    pub fn fromString(namespaceType: &str) -> Option<NamespaceType> {
+        match namespaceType {
+            "cgroup" => Some(NamespaceType::Cgroup),
+            "ipc" => Some(NamespaceType::Ipc),
+            "mnt" => Some(NamespaceType::Mount),
+            "net" => Some(NamespaceType::Network),
+            "pid" => Some(NamespaceType::Pid),
+            "time" => Some(NamespaceType::Time),
+            "user" => Some(NamespaceType::User),
+            "uts" => Some(NamespaceType::Uts),
+            _ => None
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            NamespaceType::Cgroup => "cgroup",
+            NamespaceType::Ipc => "ipc",
+            NamespaceType::Mount => "mnt",
+            NamespaceType::Network => "net",
+            NamespaceType::Pid => "pid",
+            NamespaceType::Time => "time",
+            NamespaceType::User => "user",
+            NamespaceType::Uts => "uts"
+        })
+    }
+}
+
+impl fmt::Display for NamespaceType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains a namespace, identified by the inode of its `/proc/<pid>/ns` entry
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Namespace {
+    pub namespaceType: NamespaceType,
+    pub inode: u64
+}
+
+impl fmt::Display for Namespace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:[{}]", self.namespaceType, self.inode)
+    }
+}
+
+/// Returns the processes grouped by their namespace of the given type, keyed by namespace inode
+///
+/// Processes whose namespaces are not readable are left out, run as root to see every process
+pub fn namespaceGroups(namespaceType: NamespaceType) -> HashMap<u64, Vec<Process>> {
+    let mut groups = HashMap::<u64, Vec<Process>>::new();
+
+    for process in processes() {
+        if let Some(inode) = process.namespace(namespaceType) {
+            groups.entry(inode).or_default().push(process);
+        }
+    }
+
+    return groups;
+}
+
+/// Seccomp filtering applied to a process
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SeccompMode {
+    Disabled,
+    /// Only `read`, `write`, `_exit` and `sigreturn` are allowed
+    Strict,
+    /// System calls are filtered by a BPF program
+    Filter
+}
+
+impl SeccompMode {
+    pub fn toString(&self) -> String {
+        String::from(match self {
+            SeccompMode::Disabled => "disabled",
+            SeccompMode::Strict => "strict",
+            SeccompMode::Filter => "filter"
+        })
+    }
+}
+
+impl fmt::Display for SeccompMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains the security context of a process
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessSecurity {
+    /// Names of the effective capabilities, e.g. `CAP_NET_ADMIN`
+    pub effectiveCapabilities: Vec<String>,
+    pub seccomp: SeccompMode,
+    pub noNewPrivileges: bool,
+    /// Linux security module providing `label`, e.g. `selinux`, `apparmor`
+    pub lsm: Option<String>,
+    /// SELinux context or AppArmor profile, e.g. `system_u:system_r:sshd_t:s0`, `unconfined`
+    pub label: Option<String>
+}
+
+impl ProcessSecurity {
+    /// Returns `true` if the process holds every capability, like root processes usually do
+    pub fn fullyPrivileged(&self) -> bool {
+        return self.effectiveCapabilities.len() >= CAPABILITIES.len();
+    }
+}
+
+impl fmt::Display for ProcessSecurity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} capabilities, seccomp {}", self.effectiveCapabilities.len(), self.seccomp)?;
+
+        if let Some(label) = &self.label {
+            write!(f, ", {}", label)?;
+        }
+
+        Ok(())
+    }
+}
+
+// capability names in bit order, see `linux/capability.h`
+const CAPABILITIES: [&str; 41] = [
+    "CHOWN", "DAC_OVERRIDE", "DAC_READ_SEARCH", "FOWNER", "FSETID", "KILL", "SETGID", "SETUID", "SETPCAP",
+    "LINUX_IMMUTABLE", "NET_BIND_SERVICE", "NET_BROADCAST", "NET_ADMIN", "NET_RAW", "IPC_LOCK", "IPC_OWNER",
+    "SYS_MODULE", "SYS_RAWIO", "SYS_CHROOT", "SYS_PTRACE", "SYS_PACCT", "SYS_ADMIN", "SYS_BOOT", "SYS_NICE",
+    "SYS_RESOURCE", "SYS_TIME", "SYS_TTY_CONFIG", "MKNOD", "LEASE", "AUDIT_WRITE", "AUDIT_CONTROL", "SETFCAP",
+    "MAC_OVERRIDE", "MAC_ADMIN", "SYSLOG", "WAKE_ALARM", "BLOCK_SUSPEND", "AUDIT_READ", "PERFMON", "BPF",
+    "CHECKPOINT_RESTORE"
+];
+
+/// Decodes a capability bitmask (e.g. `CapEff` of `/proc/<pid>/status`) into capability names,
+/// bits unknown to this table are named after their number
+pub fn decodeCapabilities(mask: u64) -> Vec<String> {
+    return (0..64)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(|bit| match CAPABILITIES.get(bit as usize) {
+            Some(name) => format!("CAP_{}", name),
+            None => format!("CAP_{}", bit)
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsesStat() {
+        let (name, fields) = parseStat("1234 (Web Content (x)) S 1 1234 1234 0 -1 4194560 900 0 12 0 350 120").unwrap();
+
+        assert_eq!(name, "Web Content (x)");
+        assert_eq!(fields[0], "S");
+        assert_eq!(fields[7], "900");
+        assert_eq!(fields[11], "350");
+    }
+
+    #[test]
+    fn findsSystemdUnit() {
+        assert_eq!(
+            systemdUnit("/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-4242.scope").as_deref(),
+            Some("app-firefox-4242.scope")
+        );
+        assert_eq!(systemdUnit("/system.slice/sshd.service").as_deref(), Some("sshd.service"));
+        assert_eq!(systemdUnit("/"), None);
+    }
+
+    #[test]
+    fn decodesCapabilities() {
+        assert_eq!(decodeCapabilities(0x3000), vec!["CAP_NET_ADMIN", "CAP_NET_RAW"]);
+        assert_eq!(decodeCapabilities(0x000001ffffffffff).len(), 41);
+        assert_eq!(decodeCapabilities(1 << 45), vec!["CAP_45"]);
+        assert!(decodeCapabilities(0).is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+use std::{fmt::Write as _, io, thread};
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use crate::cpu::ProcessorUsage;
+use crate::monitor::{MonitorSample, SharedMonitor};
+use crate::utils::{*};
+
+/// Interval between two samplings of the monitor backing `servePrometheus()`
+const SAMPLING_INTERVAL: Duration = Duration::from_secs(1);
+
+// largest request accepted, scrapers send a few hundred bytes of headers
+const MAX_REQUEST_SIZE: usize = 8192;
+
+fn writeMetric(output: &mut String, name: &str, help: &str, values: &[(String, f32)]) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} gauge", name);
+
+    for (labels, value) in values {
+        let _ = writeln!(output, "{}{} {}", name, labels, value);
+    }
+}
+
+fn modeValues(usage: &ProcessorUsage) -> Vec<(String, f32)> {
+    return [
+        ("user", usage.user),
+        ("nice", usage.nice),
+        ("system", usage.system),
+        ("idle", usage.idle),
+        ("iowait", usage.iowait),
+        ("irq", usage.interrupt),
+        ("softirq", usage.soft_interrupt)
+    ].iter().map(|(mode, value)| (format!("{{mode=\"{}\"}}", mode), *value)).collect();
+}
+
+/// Renders a monitor sample in the Prometheus text exposition format
+pub fn renderPrometheus(sample: &MonitorSample) -> String {
+    let mut output = String::new();
+
+    writeMetric(
+        &mut output, "sysutil_cpu_usage_percent", "CPU usage averaged over every processor, by mode",
+        &modeValues(&sample.cpuUsage.average)
+    );
+
+    writeMetric(
+        &mut output, "sysutil_processor_usage_percent", "Total usage of each processor",
+        &sample.cpuUsage.processors.iter().enumerate()
+            .map(|(processor, usage)| (format!("{{processor=\"{}\"}}", processor), usage.total))
+            .collect::<Vec<(String, f32)>>()
+    );
+
+    writeMetric(&mut output, "sysutil_ram_usage_percent", "RAM in use", &[(String::new(), sample.ramUsage)]);
+    writeMetric(
+        &mut output, "sysutil_network_receive_bytes_per_second", "Bytes received by every interface",
+        &[(String::new(), sample.networkRate.download)]
+    );
+    writeMetric(
+        &mut output, "sysutil_network_transmit_bytes_per_second", "Bytes sent by every interface",
+        &[(String::new(), sample.networkRate.upload)]
+    );
+
+    return output;
+}
+
+fn respond(mut stream: TcpStream, monitor: &SharedMonitor) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+
+    let mut request = Vec::<u8>::new();
+    let mut buffer = [0_u8; 1024];
+
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") && request.len() < MAX_REQUEST_SIZE {
+        let read = stream.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    // `GET /metrics HTTP/1.1`, the query string is ignored
+    let request = String::from_utf8_lossy(&request);
+    let mut requestLine = request.lines().next().unwrap_or_default().split_whitespace();
+    let method = requestLine.next().unwrap_or_default();
+    let path = requestLine.next().unwrap_or_default().split('?').next().unwrap_or_default();
+
+    let (status, body) = if method != "GET" && method != "HEAD" {
+        ("405 Method Not Allowed", String::new())
+    } else if path != "/metrics" {
+        ("404 Not Found", String::new())
+    } else {
+        ("200 OK", renderPrometheus(&monitor.wait()))
+    };
+
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status, body.len()
+    );
+
+    if method != "HEAD" {
+        response.push_str(&body);
+    }
+
+    return stream.write_all(response.as_bytes());
+}
+
+/// Serves `/metrics` in the Prometheus text format on `bindAddress`, e.g. `"0.0.0.0:9100"`
+///
+/// Values are read from a `SharedMonitor` sampling every second, so scrapes never wait for the sampling sleeps.
+/// Blocks the calling thread serving requests, only returns if the address can not be bound
+/// ## Example
+/// ```rust,no_run
+/// std::thread::spawn(|| sysutil::prometheus::servePrometheus("0.0.0.0:9100"));
+/// ```
+pub fn servePrometheus<A: ToSocketAddrs>(bindAddress: A) -> io::Result<()> {
+    linuxCheck();
+
+    let listener = TcpListener::bind(bindAddress)?;
+    let monitor = SharedMonitor::new(SAMPLING_INTERVAL);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_error) => {
+                traceEvent!(debug, error = %_error, "failed to accept a metrics connection");
+                continue;
+            }
+        };
+
+        let monitor = monitor.clone();
+
+        thread::spawn(move || {
+            if let Err(_error) = respond(stream, &monitor) {
+                traceEvent!(debug, error = %_error, "failed to answer a metrics request");
+            }
+        });
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::tests::sample;
+
+    #[test]
+    fn rendersSample() {
+        let sample = sample();
+
+        let output = renderPrometheus(&sample);
+
+        assert!(output.contains("# TYPE sysutil_cpu_usage_percent gauge\n"));
+        assert!(output.contains("sysutil_cpu_usage_percent{mode=\"idle\"} 75\n"));
+        assert!(output.contains("sysutil_processor_usage_percent{processor=\"1\"} 10\n"));
+        assert!(output.contains("sysutil_ram_usage_percent 42.5\n"));
+        assert!(output.contains("sysutil_network_receive_bytes_per_second 1024\n"));
+    }
+}
@@ -0,0 +1,153 @@
+use std::{fs, io, thread};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::fs::FileTypeExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::time::Duration;
+use rsjson::{Json, Node, NodeContent};
+use crate::monitor::{MonitorSample, SharedMonitor};
+use crate::utils::{*};
+
+/// Interval between two samplings of the monitor backing `serveQueries()`
+const SAMPLING_INTERVAL: Duration = Duration::from_secs(1);
+
+// connections without any query for this long are closed
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+// longest query line accepted
+const MAX_QUERY_SIZE: u64 = 4096;
+
+/// Keys accepted in `{"get": "<key>"}` queries
+pub const QUERY_KEYS: [&str; 7] = [
+    "cpu.usage", "cpu.iowait", "cpu.processors", "ram.usage", "network.download", "network.upload", "sample.age"
+];
+
+/// Returns the value of `key` in `sample`, `None` if the key is not one of `QUERY_KEYS`
+///
+/// Usages are in percentage, network rates in bytes per second and the sample age in seconds
+pub fn queryValue(sample: &MonitorSample, key: &str) -> Option<NodeContent> {
+    return match key {
+        "cpu.usage" => Some(NodeContent::Float(sample.cpuUsage.average.total)),
+        "cpu.iowait" => Some(NodeContent::Float(sample.cpuUsage.average.iowait)),
+        "cpu.processors" => Some(NodeContent::List(
+            sample.cpuUsage.processors.iter().map(|usage| NodeContent::Float(usage.total)).collect()
+        )),
+        "ram.usage" => Some(NodeContent::Float(sample.ramUsage)),
+        "network.download" => Some(NodeContent::Float(sample.networkRate.download)),
+        "network.upload" => Some(NodeContent::Float(sample.networkRate.upload)),
+        "sample.age" => Some(NodeContent::Float(sample.sampledAt.elapsed().as_secs_f32())),
+        _ => None
+    };
+}
+
+fn reply(label: &str, content: NodeContent) -> String {
+    let mut json = Json::new();
+    json.addNode(Node::new(label, content));
+
+    return json.toString();
+}
+
+// answers a single query line with `{"value": ...}` or `{"error": "..."}`
+fn answer(query: &str, monitor: &SharedMonitor) -> String {
+    let Ok(json) = Json::fromString(query.trim()) else {
+        return reply("error", NodeContent::String(String::from("malformed query, expected {\"get\": \"<key>\"}")));
+    };
+
+    let key = json.getAllNodes().into_iter()
+        .find(|node| node.getLabel() == "get")
+        .map(|node| node.getContent());
+
+    let Some(NodeContent::String(key)) = key else {
+        return reply("error", NodeContent::String(String::from("missing \"get\" key")));
+    };
+
+    return match queryValue(&monitor.wait(), &key) {
+        Some(value) => reply("value", value),
+        None => reply("error", NodeContent::String(format!("unknown key `{}`, expected one of {}", key, QUERY_KEYS.join(", "))))
+    };
+}
+
+fn handle(stream: UnixStream, monitor: &SharedMonitor) -> io::Result<()> {
+    stream.set_read_timeout(Some(IDLE_TIMEOUT))?;
+
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    loop {
+        let mut query = String::new();
+        if reader.by_ref().take(MAX_QUERY_SIZE).read_line(&mut query)? == 0 {
+            return Ok(());
+        }
+
+        if query.trim().is_empty() {
+            continue;
+        }
+
+        writer.write_all(format!("{}\n", answer(&query, monitor)).as_bytes())?;
+    }
+}
+
+/// Answers JSON queries on the Unix socket at `socketPath`, one query per line, e.g. `{"get": "cpu.usage"}`
+///
+/// Each query gets a single line answer, `{"value": 12.5}` or `{"error": "..."}`, read from a `SharedMonitor`
+/// sampling every second, so queries never wait for the sampling sleeps. Connections stay open for further queries.
+/// A stale socket left at `socketPath` is replaced, any other existing file makes the bind fail.
+/// Blocks the calling thread serving connections, only returns if the socket can not be bound
+/// ## Example
+/// ```rust,no_run
+/// std::thread::spawn(|| sysutil::query::serveQueries("/tmp/sysutil.sock"));
+/// // echo '{"get": "cpu.usage"}' | socat - UNIX-CONNECT:/tmp/sysutil.sock
+/// ```
+pub fn serveQueries<P: AsRef<Path>>(socketPath: P) -> io::Result<()> {
+    linuxCheck();
+
+    let socketPath = socketPath.as_ref();
+    if let Ok(metadata) = fs::symlink_metadata(socketPath) {
+        if metadata.file_type().is_socket() {
+            fs::remove_file(socketPath)?;
+        }
+    }
+
+    let listener = UnixListener::bind(socketPath)?;
+    let monitor = SharedMonitor::new(SAMPLING_INTERVAL);
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(_error) => {
+                traceEvent!(debug, error = %_error, "failed to accept a query connection");
+                continue;
+            }
+        };
+
+        let monitor = monitor.clone();
+
+        thread::spawn(move || {
+            if let Err(_error) = handle(stream, &monitor) {
+                traceEvent!(debug, error = %_error, "failed to answer a query");
+            }
+        });
+    }
+
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitor::tests::sample;
+
+    #[test]
+    fn readsQueryValues() {
+        let sample = sample();
+
+        assert!(matches!(queryValue(&sample, "cpu.usage"), Some(NodeContent::Float(usage)) if usage == 25_f32));
+        assert!(matches!(queryValue(&sample, "cpu.processors"), Some(NodeContent::List(list)) if list.len() == 2));
+        assert!(matches!(queryValue(&sample, "network.download"), Some(NodeContent::Float(rate)) if rate == 1024_f32));
+        assert!(queryValue(&sample, "gpu.usage").is_none());
+
+        for key in QUERY_KEYS {
+            assert!(queryValue(&sample, key).is_some());
+        }
+    }
+}
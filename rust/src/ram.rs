@@ -1,12 +1,16 @@
-use std::fs::read_dir;
+use std::{fmt, fs, io, path, thread};
+use std::collections::HashMap;
+use std::os::unix::fs::MetadataExt;
+use std::time::{Duration, Instant};
 use crate::utils::{*};
 
 /// Contains all information about RAM
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RAM {
     pub size: ByteSize,
     pub usage: f32,
-    pub frequency: Option<usize>,
+    pub frequency: Option<Frequency>,
     pub busWidth: Option<usize>
 }
 
@@ -21,81 +25,71 @@ impl RAM {
     }
 }
 
-/// Returns current RAM usage in percentage
-pub fn ramUsage() -> f32 {
-    linuxCheck();
-
-    let content = readFile("/proc/meminfo");
+impl fmt::Display for RAM {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}, {:.1}% used", self.size, self.usage)?;
 
-    let mut memTotal = "";
-    let mut memAvailable = "";
-
-    for element in content.split('\n') {
-        if element.contains("MemTotal") {
-            memTotal = element;
-        } else if element.contains("MemAvailable") {
-            memAvailable = element;
+        if let Some(frequency) = self.frequency {
+            write!(f, " @ {}", frequency)?;
         }
+
+        Ok(())
     }
+}
 
-    let uMemTotal = {
-        let mut total = 0_usize;
-        for element in memTotal.split(" ") {
-            if element != "MemTotal:" && !element.is_empty() {
-                total = element.parse::<usize>().unwrap();
-                break;
-            }
-        }
-        total
+// reads a `<field>:   <value> kB` line of `/proc/meminfo`
+fn meminfoField(content: &str, field: &str) -> Result<usize, SysError> {
+    let Some(line) = content.lines().find(|line| line.split(':').next() == Some(field)) else {
+        return Err(SysError::parse("/proc/meminfo", &format!("no {} field", field)));
     };
 
-    let uMemAvailable = {
-        let mut available = 0_usize;
-        for element in memAvailable.split(" ") {
-            if element != "MemAvailable:" && !element.is_empty() {
-                available = element.parse::<usize>().unwrap();
-                break;
-            }
-        }
-        available
-    };
+    return tryParse("/proc/meminfo", line[field.len() + 1..].trim().trim_end_matches("kB"));
+}
 
-    return 100_f32 - uMemAvailable as f32 * 100_f32 / uMemTotal as f32;
+/// Returns current RAM usage in percentage, zero if it is not readable
+pub fn ramUsage() -> f32 {
+    return tryRamUsage().unwrap_or(0_f32);
 }
 
-/// Returns RAM size using the `ByteSize` data structure
-pub fn ramSize() -> ByteSize {
+/// Same as `ramUsage()`, but returns an error when `/proc/meminfo` is not readable
+pub fn tryRamUsage() -> Result<f32, SysError> {
     linuxCheck();
 
-    let content = readFile("/proc/meminfo");
-    let mut memTotal = "";
+    let content = tryReadFile("/proc/meminfo")?;
 
-    for element in content.split('\n') {
-        if element.contains("MemTotal") {
-            memTotal = element;
-        }
+    let uMemTotal = meminfoField(&content, "MemTotal")?;
+    let uMemAvailable = meminfoField(&content, "MemAvailable")?;
+
+    if uMemTotal == 0 {
+        return Err(SysError::parse("/proc/meminfo", "MemTotal: 0 kB"));
     }
 
-    let uMemTotal = {
-        let mut total = 0_usize;
-        for element in memTotal.split(" ") {
-            if element != "MemTotal:" && !element.is_empty() {
-                total = element.parse::<usize>().unwrap();
-                break;
-            }
-        }
-        total
-    };
+    return Ok(100_f32 - uMemAvailable as f32 * 100_f32 / uMemTotal as f32);
+}
 
-    ByteSize::fromBytes(uMemTotal * 1000)
+/// Returns RAM size using the `ByteSize` data structure, zero if it is not readable
+pub fn ramSize() -> ByteSize {
+    return tryRamSize().unwrap_or_default();
 }
 
-/// Returns RAM frequency in MT/s
-pub fn ramFrequency() -> Option<usize> {
+/// Same as `ramSize()`, but returns an error when `/proc/meminfo` is not readable
+pub fn tryRamSize() -> Result<ByteSize, SysError> {
+    linuxCheck();
+
+    let uMemTotal = meminfoField(&tryReadFile("/proc/meminfo")?, "MemTotal")?;
+
+    Ok(ByteSize::fromBytes(uMemTotal as u64 * 1000))
+}
+
+/// Returns RAM maximum memory clock as reported by the kfd topology
+pub fn ramFrequency() -> Option<Frequency> {
     let kfdTopologyNodes = "/sys/class/kfd/kfd/topology/nodes/";
-    for dir in read_dir(kfdTopologyNodes).unwrap() {
-        let path = dir.unwrap().path();
-        let directory = path.to_str().unwrap();
+    // the kfd topology only exists with the amdgpu driver loaded
+    for dir in tryReadDir(kfdTopologyNodes).unwrap_or_default() {
+        let path = dir.path();
+        let Some(directory) = path.to_str() else {
+            continue
+        };
 
         let content = readFile(format!("{}/properties", directory));
         let mut isCpu = false;
@@ -105,7 +99,7 @@ pub fn ramFrequency() -> Option<usize> {
                 let splitedLine = line.split(" ").collect::<Vec<&str>>();
                 match splitedLine.last() {
                     Some(cores) => {
-                        if cores.parse::<usize>().unwrap() != 0 {
+                        if cores.parse::<usize>().unwrap_or(0) != 0 {
                             isCpu = true;
                         }
                     },
@@ -132,13 +126,9 @@ pub fn ramFrequency() -> Option<usize> {
                 }
             }
 
-            let frequency = {
-                let binding = frequencyLine.split(" ").collect::<Vec<&str>>();
-                let last = binding.last().unwrap();
-                last.parse::<usize>().unwrap()
-            };
+            let frequency = tryParse::<usize, _>(kfdTopologyNodes, frequencyLine.split(" ").last()?).ok()?;
 
-            return Some(frequency);
+            return Some(Frequency::fromMhz(frequency));
         }
     }
 
@@ -148,9 +138,12 @@ pub fn ramFrequency() -> Option<usize> {
 /// Returns RAM bus width in bits
 pub fn ramBusWidth() -> Option<usize> {
     let kfdTopologyNodes = "/sys/class/kfd/kfd/topology/nodes/";
-    for dir in read_dir(kfdTopologyNodes).unwrap() {
-        let path = dir.unwrap().path();
-        let directory = path.to_str().unwrap();
+    // the kfd topology only exists with the amdgpu driver loaded
+    for dir in tryReadDir(kfdTopologyNodes).unwrap_or_default() {
+        let path = dir.path();
+        let Some(directory) = path.to_str() else {
+            continue
+        };
 
         let content = readFile(format!("{}/properties", directory));
         let mut isCpu = false;
@@ -160,7 +153,7 @@ pub fn ramBusWidth() -> Option<usize> {
                 let splitedLine = line.split(" ").collect::<Vec<&str>>();
                 match splitedLine.last() {
                     Some(cores) => {
-                        if cores.parse::<usize>().unwrap() != 0 {
+                        if cores.parse::<usize>().unwrap_or(0) != 0 {
                             isCpu = true;
                         }
                     },
@@ -187,15 +180,428 @@ pub fn ramBusWidth() -> Option<usize> {
                 }
             }
 
-            let width = {
-                let binding = widthLine.split(" ").collect::<Vec<&str>>();
-                let last = binding.last().unwrap();
-                last.parse::<usize>().unwrap()
-            };
+            let width = tryParse::<usize, _>(kfdTopologyNodes, widthLine.split(" ").last()?).ok()?;
 
             return Some(width);
         }
     }
 
     return None;
-}
\ No newline at end of file
+}
+
+/// Contains swap traffic, in pages per second
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwapActivity {
+    pub pagesInPerSecond: f32,
+    pub pagesOutPerSecond: f32
+}
+
+impl SwapActivity {
+    /// Returns the swap-in rate in bytes per second
+    pub fn bytesInPerSecond(&self) -> f32 {
+        return self.pagesInPerSecond * pageSize() as f32;
+    }
+
+    /// Returns the swap-out rate in bytes per second
+    pub fn bytesOutPerSecond(&self) -> f32 {
+        return self.pagesOutPerSecond * pageSize() as f32;
+    }
+}
+
+impl fmt::Display for SwapActivity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "in {:.1} pages/s, out {:.1} pages/s", self.pagesInPerSecond, self.pagesOutPerSecond)
+    }
+}
+
+fn pageSize() -> usize {
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+
+    if size <= 0 {
+        return 4096;
+    }
+
+    return size as usize;
+}
+
+fn vmstatCounters(names: [&str; 2]) -> [u64; 2] {
+    let mut counters = [0_u64; 2];
+
+    for line in readFile("/proc/vmstat").lines() {
+        let Some((name, value)) = line.split_once(' ') else {
+            continue;
+        };
+
+        for (index, counterName) in names.iter().enumerate() {
+            if name == *counterName {
+                counters[index] = value.trim().parse::<u64>().unwrap_or(0);
+            }
+        }
+    }
+
+    return counters;
+}
+
+/// Returns swap-in and swap-out traffic, sampled over the specified interval
+///
+/// Sustained traffic in both directions is a much better thrashing indicator than swap usage
+pub fn swapActivity(interval: Duration) -> SwapActivity {
+    linuxCheck();
+
+    let start = Instant::now();
+    let [inBefore, outBefore] = vmstatCounters(["pswpin", "pswpout"]);
+    thread::sleep(interval);
+    let [inAfter, outAfter] = vmstatCounters(["pswpin", "pswpout"]);
+
+    let seconds = start.elapsed().as_secs_f32();
+    if seconds == 0_f32 {
+        return SwapActivity {
+            pagesInPerSecond: 0_f32,
+            pagesOutPerSecond: 0_f32
+        };
+    }
+
+    return SwapActivity {
+        pagesInPerSecond: inAfter.saturating_sub(inBefore) as f32 / seconds,
+        pagesOutPerSecond: outAfter.saturating_sub(outBefore) as f32 / seconds
+    };
+}
+
+/// Kernel memory overcommit policy (`vm.overcommit_memory`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OvercommitMode {
+    Heuristic,
+    Always,
+    Never,
+    Other(String)
+}
+
+impl OvercommitMode {
+    pub fn fromString(mode: &str) -> OvercommitMode {
+        match mode {
+            "0" => OvercommitMode::Heuristic,
+            "1" => OvercommitMode::Always,
+            "2" => OvercommitMode::Never,
+            other => OvercommitMode::Other(other.to_string())
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            OvercommitMode::Heuristic => String::from("heuristic"),
+            OvercommitMode::Always => String::from("always"),
+            OvercommitMode::Never => String::from("never"),
+            OvercommitMode::Other(mode) => mode.clone()
+        }
+    }
+}
+
+impl fmt::Display for OvercommitMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains the memory overcommit policy and how much memory is currently committed
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CommitInfo {
+    pub overcommitMode: OvercommitMode,
+    /// Percentage of RAM counted in the commit limit
+    pub overcommitRatio: Option<usize>,
+    /// Memory that can be allocated, only enforced when the mode is `Never`
+    pub commitLimit: ByteSize,
+    /// Memory currently allocated by processes, even if not yet used (`Committed_AS`)
+    pub committed: ByteSize
+}
+
+impl CommitInfo {
+    /// Returns committed memory in percentage of the commit limit, can exceed 100 unless the mode is `Never`
+    pub fn usage(&self) -> f32 {
+        if self.commitLimit.bytes() == 0 {
+            return 0_f32;
+        }
+
+        return self.committed.bytes() as f32 * 100_f32 / self.commitLimit.bytes() as f32;
+    }
+}
+
+impl fmt::Display for CommitInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{} committed of {} ({:.1}%), overcommit {}",
+            self.committed, self.commitLimit, self.usage(), self.overcommitMode
+        )
+    }
+}
+
+fn meminfoValue(content: &str, name: &str) -> Option<ByteSize> {
+    for line in content.lines() {
+        let Some((label, value)) = line.split_once(':') else {
+            continue;
+        };
+
+        if label == name {
+            let kib = value.trim().trim_end_matches("kB").trim().parse::<u64>().ok()?;
+            return Some(ByteSize::fromBytes(kib * 1024));
+        }
+    }
+
+    return None;
+}
+
+/// Returns the overcommit policy together with the commit limit and the committed memory
+pub fn commitInfo() -> CommitInfo {
+    linuxCheck();
+
+    let meminfo = readFile("/proc/meminfo");
+
+    return CommitInfo {
+        overcommitMode: OvercommitMode::fromString(&readFile("/proc/sys/vm/overcommit_memory")),
+        overcommitRatio: readFile("/proc/sys/vm/overcommit_ratio").parse::<usize>().ok(),
+        commitLimit: meminfoValue(&meminfo, "CommitLimit").unwrap_or_default(),
+        committed: meminfoValue(&meminfo, "Committed_AS").unwrap_or_default()
+    };
+}
+
+/// Contains an out-of-memory kill
+///
+/// Events read from the kernel log carry the victim process, those read from cgroup `memory.events`
+/// only carry the cgroup and the number of kills
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OomEvent {
+    pub process: Option<String>,
+    pub pid: Option<u32>,
+    pub cgroup: Option<String>,
+    /// Time of the kill, as elapsed time since boot
+    pub sinceBoot: Option<Duration>,
+    pub kills: u64
+}
+
+impl fmt::Display for OomEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(sinceBoot) = self.sinceBoot {
+            write!(f, "[{:.3}] ", sinceBoot.as_secs_f64())?;
+        }
+
+        match (&self.process, self.pid, &self.cgroup) {
+            (Some(process), Some(pid), _) => write!(f, "killed {} ({})", process, pid),
+            (_, _, Some(cgroup)) => write!(f, "{} kills in {}", self.kills, cgroup),
+            _ => write!(f, "{} kills", self.kills)
+        }
+    }
+}
+
+// parses `<level>,<sequence>,<microseconds>,<flags>;Out of memory: Killed process <pid> (<name>) ...`
+fn parseKmsgOom(record: &str) -> Option<OomEvent> {
+    let (header, message) = record.split_once(';')?;
+    let killed = message.split_once("Killed process ")?.1;
+
+    let (pid, rest) = killed.split_once(' ')?;
+    let process = rest.strip_prefix('(')?.split_once(')')?.0;
+    let microseconds = header.split(',').nth(2)?.parse::<u64>().ok();
+
+    return Some(OomEvent {
+        process: Some(process.to_string()),
+        pid: pid.parse::<u32>().ok(),
+        cgroup: None,
+        sinceBoot: microseconds.map(Duration::from_micros),
+        kills: 1
+    });
+}
+
+fn kmsgOomEvents() -> io::Result<Vec<OomEvent>> {
+    return Ok(kmsgRecords()?.iter().filter_map(|record| parseKmsgOom(record)).collect());
+}
+
+fn cgroupOomEvents(directory: &path::Path, events: &mut Vec<OomEvent>) {
+    // memory.events also counts the kills of descendant cgroups, the local variant (Linux 5.2+) does not
+    let mut content = readFile(directory.join("memory.events.local"));
+    if content.is_empty() {
+        content = readFile(directory.join("memory.events"));
+    }
+
+    for line in content.lines() {
+        if let Some(Ok(kills)) = line.strip_prefix("oom_kill ").map(|kills| kills.trim().parse::<u64>()) {
+            if kills > 0 {
+                events.push(OomEvent {
+                    process: None,
+                    pid: None,
+                    cgroup: Some(directory.strip_prefix(sysPath("/sys/fs/cgroup")).unwrap_or(directory).display().to_string()),
+                    sinceBoot: None,
+                    kills: kills
+                });
+            }
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(directory) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        if entry.file_type().map(|kind| kind.is_dir()).unwrap_or(false) {
+            cgroupOomEvents(&entry.path(), events);
+        }
+    }
+}
+
+/// Returns the OOM kills found in the kernel log
+///
+/// Reading the kernel log usually requires root privileges (or `kernel.dmesg_restrict = 0`), when it is not
+/// readable the kill counters of each cgroup v2 (`memory.events`) are returned instead
+pub fn oomEvents() -> Vec<OomEvent> {
+    linuxCheck();
+
+    match kmsgOomEvents() {
+        Ok(events) => events,
+        Err(_error) => {
+            traceEvent!(debug, error = %_error, "failed to read /dev/kmsg, falling back to cgroup memory.events");
+
+            let mut events = Vec::<OomEvent>::new();
+            cgroupOomEvents(&sysPath("/sys/fs/cgroup"), &mut events);
+            events
+        }
+    }
+}
+
+/// Contains the dma-buf memory referenced by a process
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessDmaBuf {
+    pub pid: u32,
+    pub name: String,
+    pub buffers: usize,
+    pub size: ByteSize
+}
+
+/// Contains dma-buf (shared graphics and media buffers) usage, each buffer is counted once even if shared
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DmaBufUsage {
+    pub buffers: usize,
+    pub total: ByteSize,
+    /// Memory of each exporter, e.g. `amdgpu`, `i915`, `system`
+    pub exporters: HashMap<String, ByteSize>,
+    /// Processes holding at least one buffer, empty when the sysfs statistics were used
+    pub processes: Vec<ProcessDmaBuf>
+}
+
+impl fmt::Display for DmaBufUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} in {} buffers", self.total, self.buffers)
+    }
+}
+
+// reads `/sys/kernel/dmabuf/buffers`, only available when CONFIG_DMABUF_SYSFS_STATS is enabled
+fn sysfsDmaBufs() -> Option<HashMap<u64, (String, u64)>> {
+    let mut buffers = HashMap::<u64, (String, u64)>::new();
+
+    for entry in fs::read_dir(sysPath("/sys/kernel/dmabuf/buffers")).ok()?.flatten() {
+        let Ok(inode) = entry.file_name().to_string_lossy().parse::<u64>() else {
+            continue;
+        };
+
+        let size = readFile(entry.path().join("size")).parse::<u64>().unwrap_or(0);
+        buffers.insert(inode, (readFile(entry.path().join("exporter_name")), size));
+    }
+
+    return Some(buffers);
+}
+
+// walks the file descriptors of every readable process, dma-bufs are the ones linking to `/dmabuf:`
+fn processDmaBufs(buffers: &mut HashMap<u64, (String, u64)>) -> Vec<ProcessDmaBuf> {
+    let mut processes = Vec::<ProcessDmaBuf>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/proc")) else {
+        return processes;
+    };
+
+    for entry in entries.flatten() {
+        let Ok(pid) = entry.file_name().to_string_lossy().parse::<u32>() else {
+            continue;
+        };
+
+        let Ok(descriptors) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        let mut seen = Vec::<u64>::new();
+        let mut size = 0_u64;
+
+        for descriptor in descriptors.flatten() {
+            let isDmaBuf = fs::read_link(descriptor.path())
+                .map(|target| target.to_string_lossy().starts_with("/dmabuf:"))
+                .unwrap_or(false);
+
+            if !isDmaBuf {
+                continue;
+            }
+
+            let Ok(metadata) = fs::metadata(descriptor.path()) else {
+                continue;
+            };
+
+            if seen.contains(&metadata.ino()) {
+                continue;
+            }
+            seen.push(metadata.ino());
+
+            let info = readFile(entry.path().join("fdinfo").join(descriptor.file_name()));
+            let field = |name: &str| -> String {
+                info.lines().find_map(|line| line.strip_prefix(name)).unwrap_or("").trim().to_string()
+            };
+
+            let bufferSize = field("size:").parse::<u64>().unwrap_or(metadata.size());
+            buffers.entry(metadata.ino()).or_insert((field("exp_name:"), bufferSize));
+            size += bufferSize;
+        }
+
+        if !seen.is_empty() {
+            processes.push(ProcessDmaBuf {
+                pid: pid,
+                name: readFile(entry.path().join("comm")),
+                buffers: seen.len(),
+                size: ByteSize::fromBytes(size)
+            });
+        }
+    }
+
+    processes.sort_by(|first, second| second.size.cmp(&first.size));
+    return processes;
+}
+
+/// Returns dma-buf usage, which is accounted neither in process RSS nor in VRAM usage
+///
+/// Buffers are read from the sysfs statistics when available, otherwise from the file descriptors
+/// of the processes, in which case only the processes readable by the current user are accounted
+pub fn dmaBufUsage() -> DmaBufUsage {
+    linuxCheck();
+
+    let (buffers, processes) = match sysfsDmaBufs() {
+        Some(buffers) => (buffers, Vec::<ProcessDmaBuf>::new()),
+        None => {
+            let mut buffers = HashMap::<u64, (String, u64)>::new();
+            let processes = processDmaBufs(&mut buffers);
+            (buffers, processes)
+        }
+    };
+
+    let mut exporters = HashMap::<String, ByteSize>::new();
+    let mut total = 0_u64;
+
+    for (exporter, size) in buffers.values() {
+        *exporters.entry(exporter.clone()).or_default() += ByteSize::fromBytes(*size);
+        total += size;
+    }
+
+    return DmaBufUsage {
+        buffers: buffers.len(),
+        total: ByteSize::fromBytes(total),
+        exporters: exporters,
+        processes: processes
+    };
+}
@@ -1,17 +1,56 @@
-use std::{fs, path, thread};
+use std::{fmt, fs, path, thread};
+#[cfg(feature = "ddc")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "ddc")]
+use std::os::fd::AsRawFd;
+#[cfg(feature = "ddc")]
+use std::time::Duration;
 use crate::utils::{*};
 use crate::utils::linuxCheck;
 
 /// Represents the current status of battery
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BatteryStatus {
     Charging,
     Discharging,
     Full,
+    NotCharging,
+    Unknown,
+}
+
+impl BatteryStatus {
+    /// Converts the content of the `status` sysfs attribute, unrecognized values map to `Unknown`
+    pub fn fromString(status: &str) -> BatteryStatus {
+        match status {
+            "Charging" => BatteryStatus::Charging,
+            "Discharging" => BatteryStatus::Discharging,
+            "Full" => BatteryStatus::Full,
+            "Not charging" => BatteryStatus::NotCharging,
+            _ => BatteryStatus::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            BatteryStatus::Charging => String::from("Charging"),
+            BatteryStatus::Discharging => String::from("Discharging"),
+            BatteryStatus::Full => String::from("Full"),
+            BatteryStatus::NotCharging => String::from("Not charging"),
+            BatteryStatus::Unknown => String::from("Unknown")
+        }
+    }
+}
+
+impl fmt::Display for BatteryStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
 }
 
 /// Contains capacity and current status of battery
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Battery {
     pub capacity: u8,
     pub status: BatteryStatus,
@@ -23,51 +62,348 @@ impl Battery {
     }
 }
 
+impl fmt::Display for Battery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}% ({})", self.capacity, self.status)
+    }
+}
+
 /// Contains temperature sensor's name and recorded temperature
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TemperatureSensor {
     pub label: String,
     pub temperature: Option<f32>,
 }
 
+impl TemperatureSensor {
+    /// Returns the temperature with its unit, `None` if it could not be read
+    pub fn withUnit(&self) -> Option<(f32, Unit)> {
+        return self.temperature.map(|temperature| (temperature, Unit::Celsius));
+    }
+}
+
+impl fmt::Display for TemperatureSensor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.temperature {
+            Some(temperature) => write!(f, "{}: {:.1} °C", self.label, temperature),
+            None => write!(f, "{}: unknown", self.label)
+        }
+    }
+}
+
+/// Represents how a backlight device controls brightness, as reported by its `type` sysfs attribute
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BacklightType {
+    /// Firmware interface, e.g. ACPI video
+    Firmware,
+    /// Platform specific driver, e.g. a vendor laptop module
+    Platform,
+    /// Direct register access by the GPU driver
+    Raw,
+    Unknown
+}
+
+impl BacklightType {
+    pub fn fromString(backlightType: &str) -> BacklightType {
+        match backlightType {
+            "firmware" => BacklightType::Firmware,
+            "platform" => BacklightType::Platform,
+            "raw" => BacklightType::Raw,
+            _ => BacklightType::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            BacklightType::Firmware => String::from("firmware"),
+            BacklightType::Platform => String::from("platform"),
+            BacklightType::Raw => String::from("raw"),
+            BacklightType::Unknown => String::from("unknown")
+        }
+    }
+}
+
+impl fmt::Display for BacklightType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
 /// Holds information about backlight
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Backlight {
+    /// Device name in `/sys/class/backlight`, e.g. `intel_backlight`, `acpi_video0`, `ddcci5`
+    pub name: String,
+    pub backlightType: BacklightType,
     pub brightness: u32,
     pub maxBrightness: u32
 }
 
-/// Returns every temperature sensor in the system, using the `TemperatureSensor` struct
+impl Backlight {
+    /// Returns the brightness in percentage of the maximum
+    pub fn percentage(&self) -> f32 {
+        if self.maxBrightness == 0 {
+            return 0_f32;
+        }
+
+        self.brightness as f32 * 100_f32 / self.maxBrightness as f32
+    }
+
+    /// Converts a percentage into the brightness value to write for this device
+    pub fn brightnessFromPercentage(&self, percentage: f32) -> u32 {
+        return (percentage.clamp(0_f32, 100_f32) * self.maxBrightness as f32 / 100_f32).round() as u32;
+    }
+}
+
+impl fmt::Display for Backlight {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.brightness, self.maxBrightness)
+    }
+}
+
+/// Returns every temperature sensor in the system, using the `TemperatureSensor` struct, empty if there is none
 pub fn temperatureSensors() -> Vec<TemperatureSensor> {
-    linuxCheck();
+    return tryTemperatureSensors().unwrap_or_default();
+}
 
-    let hwmonPath = path::Path::new("/sys/class/hwmon");
-    let dirs = fs::read_dir(hwmonPath).unwrap();
+/// Same as `temperatureSensors()`, but returns an error when `/sys/class/hwmon` is not readable,
+/// as on machines without any hwmon driver (e.g. containers and some VMs)
+pub fn tryTemperatureSensors() -> Result<Vec<TemperatureSensor>, SysError> {
+    linuxCheck();
 
     let mut sensors = Vec::<TemperatureSensor>::new();
 
-    for dir in dirs {
-        let dirPath = dir.unwrap().path();
+    for dir in tryReadDir("/sys/class/hwmon")? {
+        let dirPath = dir.path();
 
-        let labelFile = dirPath.join("name");
-        let label = readFile(labelFile.to_str().unwrap());
+        let label = readFile(dirPath.join("name"));
 
         let temperatureFile = dirPath.join("temp1_input");
-        let temperature = readFile(temperatureFile.to_str().unwrap());
+        let temperature = readFile(&temperatureFile);
 
         sensors.push(TemperatureSensor {
             label: label,
-            temperature: match temperature.parse::<f32>() {
-                Err(_) => None,
+            temperature: match tryParse::<f32, _>(&temperatureFile, &temperature) {
+                Err(_error) => {
+                    traceEvent!(debug, path = %temperatureFile.display(), error = %_error, "failed to parse temperature");
+                    None
+                },
                 Ok(value) => Some(value / 1000_f32),
             },
         });
     }
-    return sensors;
+    return Ok(sensors);
+}
+
+/// Semantic category of a temperature channel, independent of the hwmon driver reporting it
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SensorCategory {
+    CpuPackage,
+    CpuCore(usize),
+    /// AMD core complex die
+    CpuCcd(usize),
+    GpuEdge,
+    /// Hottest point of the GPU die
+    GpuHotspot,
+    GpuMemory,
+    NvmeComposite,
+    NvmeSensor(usize),
+    Motherboard,
+    /// Channel not covered by the mapping, holds `<chip>/<label>`
+    Other(String)
+}
+
+impl SensorCategory {
+    pub fn fromString(category: &str) -> SensorCategory {
+        let number = |prefix: &str| category.strip_prefix(prefix).and_then(|index| index.parse::<usize>().ok());
+
+        if let Some(core) = number("CPU core ") {
+            return SensorCategory::CpuCore(core);
+        } else if let Some(ccd) = number("CPU CCD ") {
+            return SensorCategory::CpuCcd(ccd);
+        } else if let Some(sensor) = number("NVMe sensor ") {
+            return SensorCategory::NvmeSensor(sensor);
+        }
+
+        match category {
+            "CPU package" => SensorCategory::CpuPackage,
+            "GPU edge" => SensorCategory::GpuEdge,
+            "GPU hotspot" => SensorCategory::GpuHotspot,
+            "GPU memory" => SensorCategory::GpuMemory,
+            "NVMe composite" => SensorCategory::NvmeComposite,
+            "Motherboard" => SensorCategory::Motherboard,
+            _ => SensorCategory::Other(category.to_string())
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            SensorCategory::CpuPackage => String::from("CPU package"),
+            SensorCategory::CpuCore(core) => format!("CPU core {}", core),
+            SensorCategory::CpuCcd(ccd) => format!("CPU CCD {}", ccd),
+            SensorCategory::GpuEdge => String::from("GPU edge"),
+            SensorCategory::GpuHotspot => String::from("GPU hotspot"),
+            SensorCategory::GpuMemory => String::from("GPU memory"),
+            SensorCategory::NvmeComposite => String::from("NVMe composite"),
+            SensorCategory::NvmeSensor(sensor) => format!("NVMe sensor {}", sensor),
+            SensorCategory::Motherboard => String::from("Motherboard"),
+            SensorCategory::Other(name) => name.clone()
+        }
+    }
+}
+
+impl fmt::Display for SensorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Maps a hwmon chip name and channel label (e.g. `k10temp` and `Tctl`) to a semantic category
+pub fn categorizeSensor(chip: &str, label: &str) -> SensorCategory {
+    let number = |prefix: &str| label.strip_prefix(prefix).and_then(|index| index.trim().parse::<usize>().ok());
+
+    match chip {
+        "coretemp" => {
+            if label.starts_with("Package id") {
+                return SensorCategory::CpuPackage;
+            }
+
+            if let Some(core) = number("Core ") {
+                return SensorCategory::CpuCore(core);
+            }
+        },
+
+        "k10temp" | "zenpower" => {
+            // Tctl includes a fan control offset on some models, Tdie is the real die temperature; chips reporting
+            // both have their Tctl moved out of the package by `preferDieTemperature()`
+            if label == "Tctl" || label == "Tdie" {
+                return SensorCategory::CpuPackage;
+            }
+
+            if let Some(ccd) = number("Tccd") {
+                return SensorCategory::CpuCcd(ccd);
+            }
+        },
+
+        "cpu_thermal" | "soc_thermal" => return SensorCategory::CpuPackage,
+
+        "amdgpu" | "radeon" | "nouveau" | "i915" | "xe" => {
+            match label {
+                "edge" | "temp1" | "" => return SensorCategory::GpuEdge,
+                "junction" => return SensorCategory::GpuHotspot,
+                "mem" | "vram" => return SensorCategory::GpuMemory,
+                _ => {}
+            }
+        },
+
+        "nvme" => {
+            if label == "Composite" {
+                return SensorCategory::NvmeComposite;
+            }
+
+            if let Some(sensor) = number("Sensor ") {
+                return SensorCategory::NvmeSensor(sensor);
+            }
+        },
+
+        _ => {
+            // Super I/O chips and firmware zones monitor the board itself
+            let boardChips = ["nct", "it87", "it86", "w83", "f71", "asus", "acpitz", "gigabyte_wmi", "dell_smm"];
+
+            if boardChips.iter().any(|prefix| chip.starts_with(prefix)) {
+                return SensorCategory::Motherboard;
+            }
+        }
+    }
+
+    return SensorCategory::Other(format!("{}/{}", chip, label));
+}
+
+/// Contains a temperature channel with its semantic category
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CategorizedTemperature {
+    pub category: SensorCategory,
+    /// hwmon device in `/sys/class/hwmon`, e.g. `hwmon2`, tells apart chips sharing a name
+    pub device: String,
+    /// hwmon chip name, e.g. `k10temp`
+    pub chip: String,
+    /// Channel label as reported by the driver, `temp<N>` when it has none
+    pub label: String,
+    pub temperature: f32
+}
+
+impl CategorizedTemperature {
+    /// Returns the temperature with its unit
+    pub fn withUnit(&self) -> (f32, Unit) {
+        return (self.temperature, Unit::Celsius);
+    }
+}
+
+impl fmt::Display for CategorizedTemperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {:.1} °C", self.category, self.temperature)
+    }
+}
+
+// keeps a single package temperature on AMD chips reporting both Tctl and Tdie, the one without the offset
+fn preferDieTemperature(channels: &mut [CategorizedTemperature]) {
+    if !channels.iter().any(|channel| channel.label == "Tdie") {
+        return;
+    }
+
+    for channel in channels.iter_mut().filter(|channel| channel.label == "Tctl") {
+        channel.category = SensorCategory::Other(format!("{}/{}", channel.chip, channel.label));
+    }
+}
+
+/// Returns every temperature channel of every hwmon chip, mapped to semantic categories through `categorizeSensor()`
+pub fn categorizedTemperatures() -> Vec<CategorizedTemperature> {
+    linuxCheck();
+
+    let mut temperatures = Vec::<CategorizedTemperature>::new();
+
+    let mut chips: Vec<fs::DirEntry> = fs::read_dir(sysPath("/sys/class/hwmon")).into_iter().flatten().flatten().collect();
+    chips.sort_by_key(|chip| chip.file_name().to_string_lossy().trim_start_matches("hwmon").parse::<u32>().unwrap_or(0));
+
+    for chip in chips {
+        let chipPath = chip.path();
+        let device = chip.file_name().to_string_lossy().to_string();
+        let chipName = readFile(chipPath.join("name"));
+        let mut channels = Vec::<CategorizedTemperature>::new();
+
+        for index in 1..=64 {
+            let Ok(value) = readFile(chipPath.join(format!("temp{}_input", index))).parse::<f32>() else {
+                continue;
+            };
+
+            let mut label = readFile(chipPath.join(format!("temp{}_label", index)));
+            if label.is_empty() {
+                label = format!("temp{}", index);
+            }
+
+            channels.push(CategorizedTemperature {
+                category: categorizeSensor(&chipName, &label),
+                device: device.clone(),
+                chip: chipName.clone(),
+                label: label,
+                temperature: value / 1000_f32
+            });
+        }
+
+        preferDieTemperature(&mut channels);
+        temperatures.append(&mut channels);
+    }
+
+    return temperatures;
 }
 
 fn battery_path() -> Option<path::PathBuf> {
-    fs::read_dir("/sys/class/power_supply")
+    fs::read_dir(sysPath("/sys/class/power_supply"))
         .ok()?
         .map(|entry| {
             let path = entry.ok()?.path();
@@ -99,35 +435,553 @@ pub fn batteryInfo() -> Option<Battery> {
         return None;
     }
 
-    let status = match status.as_str() {
-        "Charging" => BatteryStatus::Charging,
-        "Discharging" => BatteryStatus::Discharging,
-        "Full" => BatteryStatus::Full,
-        _ => return None,
+    let parsedStatus = BatteryStatus::fromString(&status);
+    if let BatteryStatus::Unknown = parsedStatus {
+        traceEvent!(debug, status = status, "unknown battery status");
+    }
+
+    Some(Battery::new(capacity.parse::<u8>().unwrap_or(0), parsedStatus))
+}
+
+/// Returns a single logical battery combining every system battery, as shown by desktop environments on dual-battery laptops
+///
+/// Capacity is weighted by each battery's full energy, charge being converted to energy with the design voltage;
+/// when a battery reports neither, the capacity is the plain average of the batteries' percentages instead.
+/// Peripheral batteries such as mice are ignored;
+/// the status is discharging if any battery discharges, charging if any charges and full only if all are full
+pub fn aggregateBattery() -> Option<Battery> {
+    linuxCheck();
+
+    // energy now and full in µWh, and percentage of every battery
+    let mut energies = Vec::<Option<(f64, f64)>>::new();
+    let mut capacities = Vec::<f64>::new();
+    let mut statuses = Vec::<BatteryStatus>::new();
+
+    for entry in fs::read_dir(sysPath("/sys/class/power_supply")).ok()?.flatten() {
+        let path = entry.path();
+
+        // batteries of devices (mice, headsets) report the `Device` scope
+        if readFile(path.join("type")) != "Battery" || readFile(path.join("scope")) == "Device" {
+            continue;
+        }
+
+        let value = |name: &str| readFile(path.join(name)).parse::<f64>().ok();
+
+        let energy = match (value("energy_now"), value("energy_full")) {
+            (Some(now), Some(full)) => Some((now, full)),
+            // charge is in µAh and the voltage in µV
+            _ => match (value("charge_now"), value("charge_full"), value("voltage_min_design")) {
+                (Some(now), Some(full), Some(voltage)) => Some((now * voltage / 1_000_000_f64, full * voltage / 1_000_000_f64)),
+                _ => None
+            }
+        };
+
+        let capacity = value("capacity").or_else(|| energy.filter(|(_, full)| *full > 0_f64).map(|(now, full)| now * 100_f64 / full));
+
+        let Some(capacity) = capacity else {
+            continue
+        };
+
+        energies.push(energy);
+        capacities.push(capacity);
+        statuses.push(BatteryStatus::fromString(&readFile(path.join("status"))));
+    }
+
+    if statuses.is_empty() {
+        return None;
+    }
+
+    let any = |status: fn(&BatteryStatus) -> bool| statuses.iter().any(status);
+
+    let status = if any(|status| matches!(status, BatteryStatus::Discharging)) {
+        BatteryStatus::Discharging
+    } else if any(|status| matches!(status, BatteryStatus::Charging)) {
+        BatteryStatus::Charging
+    } else if statuses.iter().all(|status| matches!(status, BatteryStatus::Full)) {
+        BatteryStatus::Full
+    } else if any(|status| matches!(status, BatteryStatus::NotCharging)) {
+        BatteryStatus::NotCharging
+    } else {
+        BatteryStatus::Unknown
+    };
+
+    let full = energies.iter().flatten().map(|(_, full)| full).sum::<f64>();
+
+    let capacity = if energies.iter().all(|energy| energy.is_some()) && full > 0_f64 {
+        energies.iter().flatten().map(|(now, _)| now).sum::<f64>() * 100_f64 / full
+    } else {
+        capacities.iter().sum::<f64>() / capacities.len() as f64
+    };
+
+    return Some(Battery::new(capacity.round().clamp(0_f64, 100_f64) as u8, status));
+}
+
+/// Returns every backlight device with its type, including external monitors exposed through DDC/CI drivers
+pub fn backlights() -> Vec<Backlight> {
+    linuxCheck();
+
+    let mut backlights = Vec::<Backlight>::new();
+
+    let Ok(dirs) = fs::read_dir(sysPath("/sys/class/backlight")) else {
+        return backlights;
     };
 
-    Some(Battery::new(capacity.parse::<u8>().unwrap_or(0), status))
+    for entry in dirs.flatten() {
+        let path = entry.path();
+
+        let (Ok(brightness), Ok(maxBrightness)) = (
+            readFile(path.join("brightness")).parse::<u32>(),
+            readFile(path.join("max_brightness")).parse::<u32>()
+        ) else {
+            continue;
+        };
+
+        backlights.push(Backlight {
+            name: entry.file_name().to_string_lossy().to_string(),
+            backlightType: BacklightType::fromString(&readFile(path.join("type"))),
+            brightness,
+            maxBrightness
+        });
+    }
+
+    backlights.sort_by(|first, second| first.name.cmp(&second.name));
+    return backlights;
 }
 
 /// Returns the current backlight brightness and the maximum possible value or `None` if it's not possible to retrieve data
+///
+/// When several devices exist, the one desktop environments control is returned: firmware first, then platform, then raw
 pub fn getBacklight() -> Option<Backlight> {
-    let mut dirs = fs::read_dir("/sys/class/backlight").ok()?;
-    let path = dirs.find(|entry| {
-        let entry = entry.as_ref().unwrap().path();
-        if entry.join("brightness").exists() && entry.join("max_brightness").exists() {
-            return true;
+    return backlights().into_iter().min_by_key(|backlight| match backlight.backlightType {
+        BacklightType::Firmware => 0,
+        BacklightType::Platform => 1,
+        BacklightType::Raw => 2,
+        BacklightType::Unknown => 3
+    });
+}
+
+/// Contains the state of a thermal cooling device (fan, CPU frequency limit, ...)
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoolingDevice {
+    /// Device name in `/sys/class/thermal`, e.g. `cooling_device0`
+    pub name: String,
+    /// Kind of device, e.g. `Fan`, `Processor`, `intel_powerclamp`
+    pub deviceType: String,
+    pub currentState: u64,
+    pub maxState: u64
+}
+
+impl CoolingDevice {
+    /// Returns the current cooling level in percentage of the maximum
+    pub fn usage(&self) -> f32 {
+        if self.maxState == 0 {
+            return 0_f32;
         }
 
-        false
-    })?.ok()?;
+        self.currentState as f32 * 100_f32 / self.maxState as f32
+    }
+}
 
-    let brightness = fs::read_to_string(path.path().join("brightness")).ok()?.trim().parse::<u32>().ok()?;
-    let maxBrightness = fs::read_to_string(path.path().join("max_brightness")).ok()?.trim().parse::<u32>().ok()?;
+impl fmt::Display for CoolingDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}/{}", self.name, self.deviceType, self.currentState, self.maxState)
+    }
+}
 
-    Some(
-        Backlight {
-            brightness,
-            maxBrightness,
+/// Contains a thermal zone and the cooling devices bound to it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThermalZone {
+    /// Zone name in `/sys/class/thermal`, e.g. `thermal_zone0`
+    pub name: String,
+    /// Sensor backing the zone, e.g. `x86_pkg_temp`, `acpitz`
+    pub zoneType: String,
+    pub temperature: Option<f32>,
+    /// Names of the cooling devices the zone drives, matching `CoolingDevice.name`
+    pub coolingDevices: Vec<String>
+}
+
+impl ThermalZone {
+    /// Returns the temperature with its unit, `None` if it could not be read
+    pub fn withUnit(&self) -> Option<(f32, Unit)> {
+        return self.temperature.map(|temperature| (temperature, Unit::Celsius));
+    }
+}
+
+impl fmt::Display for ThermalZone {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.temperature {
+            Some(temperature) => write!(f, "{} ({}): {:.1} °C", self.name, self.zoneType, temperature),
+            None => write!(f, "{} ({})", self.name, self.zoneType)
+        }
+    }
+}
+
+fn thermalEntries(prefix: &str) -> Vec<fs::DirEntry> {
+    let mut entries: Vec<fs::DirEntry> = fs::read_dir(sysPath("/sys/class/thermal")).into_iter().flatten().flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(prefix))
+        .collect();
+
+    // sort numerically, so that cooling_device10 comes after cooling_device9
+    entries.sort_by_key(|entry| entry.file_name().to_string_lossy().trim_start_matches(prefix).parse::<u32>().unwrap_or(0));
+    return entries;
+}
+
+/// Returns every thermal cooling device with its current and maximum state
+pub fn coolingDevices() -> Vec<CoolingDevice> {
+    linuxCheck();
+
+    let mut devices = Vec::<CoolingDevice>::new();
+
+    for entry in thermalEntries("cooling_device") {
+        let path = entry.path();
+
+        devices.push(CoolingDevice {
+            name: entry.file_name().to_string_lossy().to_string(),
+            deviceType: readFile(path.join("type")),
+            currentState: readFile(path.join("cur_state")).parse().unwrap_or(0),
+            maxState: readFile(path.join("max_state")).parse().unwrap_or(0)
+        });
+    }
+
+    return devices;
+}
+
+/// Returns every thermal zone with its temperature and the cooling devices bound to its trip points
+pub fn thermalZones() -> Vec<ThermalZone> {
+    linuxCheck();
+
+    let mut zones = Vec::<ThermalZone>::new();
+
+    for entry in thermalEntries("thermal_zone") {
+        let path = entry.path();
+
+        // bindings are exposed as `cdev<N>` links to the cooling device
+        let mut coolingDevices = Vec::<String>::new();
+        for binding in fs::read_dir(&path).into_iter().flatten().flatten() {
+            let bindingName = binding.file_name().to_string_lossy().to_string();
+
+            if !bindingName.starts_with("cdev") || bindingName.contains('_') {
+                continue;
+            }
+
+            if let Some(device) = fs::read_link(binding.path()).ok().and_then(|link| link.file_name().map(|name| name.to_string_lossy().to_string())) {
+                if !coolingDevices.contains(&device) {
+                    coolingDevices.push(device);
+                }
+            }
+        }
+
+        coolingDevices.sort();
+
+        zones.push(ThermalZone {
+            name: entry.file_name().to_string_lossy().to_string(),
+            zoneType: readFile(path.join("type")),
+            temperature: readFile(path.join("temp")).parse::<f32>().ok().map(|temperature| temperature / 1000_f32),
+            coolingDevices: coolingDevices
+        });
+    }
+
+    return zones;
+}
+
+/// Holds information about a hardware watchdog device
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Watchdog {
+    pub device: String,
+    pub identity: String,
+    /// Timeout in seconds
+    pub timeout: Option<u32>,
+    /// Seconds left before the system is reset, only exposed by some drivers
+    pub timeLeft: Option<u32>,
+    pub pretimeout: Option<u32>,
+    /// Whether the watchdog cannot be stopped once started
+    pub nowayout: Option<bool>,
+    pub active: Option<bool>
+}
+
+impl fmt::Display for Watchdog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.device, self.identity)?;
+
+        match self.active {
+            Some(true) => write!(f, ", active")?,
+            Some(false) => write!(f, ", inactive")?,
+            None => {}
+        }
+
+        if let Some(timeout) = self.timeout {
+            write!(f, ", timeout {} s", timeout)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns every watchdog device found in sysfs
+pub fn watchdogs() -> Vec<Watchdog> {
+    linuxCheck();
+
+    let mut watchdogs = Vec::<Watchdog>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/sys/class/watchdog")) else {
+        return watchdogs;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+
+        watchdogs.push(Watchdog {
+            device: format!("/dev/{}", entry.file_name().to_string_lossy()),
+            identity: readFile(path.join("identity")),
+            timeout: readFile(path.join("timeout")).parse::<u32>().ok(),
+            timeLeft: readFile(path.join("timeleft")).parse::<u32>().ok(),
+            pretimeout: readFile(path.join("pretimeout")).parse::<u32>().ok(),
+            nowayout: match readFile(path.join("nowayout")).as_str() {
+                "0" => Some(false),
+                "1" => Some(true),
+                _ => None
+            },
+            active: match readFile(path.join("state")).as_str() {
+                "active" => Some(true),
+                "inactive" => Some(false),
+                _ => None
+            }
+        });
+    }
+
+    watchdogs.sort_by(|first, second| first.device.cmp(&second.device));
+    return watchdogs;
+}
+
+/// Contains the current and maximum value of a VCP (monitor control) feature
+#[cfg(feature = "ddc")]
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VcpValue {
+    pub current: u16,
+    pub maximum: u16
+}
+
+#[cfg(feature = "ddc")]
+impl VcpValue {
+    /// Returns the current value in percentage of the maximum
+    pub fn percentage(&self) -> f32 {
+        if self.maximum == 0 {
+            return 0_f32;
+        }
+
+        self.current as f32 * 100_f32 / self.maximum as f32
+    }
+}
+
+#[cfg(feature = "ddc")]
+impl fmt::Display for VcpValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.current, self.maximum)
+    }
+}
+
+/// Represents an external monitor reachable through DDC/CI
+#[cfg(feature = "ddc")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DdcDisplay {
+    /// DRM connector, e.g. `card0-DP-1`
+    pub connector: String,
+    /// I2C device of the connector DDC channel, e.g. `/dev/i2c-5`
+    pub device: String,
+    pub brightness: Option<VcpValue>,
+    pub contrast: Option<VcpValue>
+}
+
+#[cfg(feature = "ddc")]
+impl fmt::Display for DdcDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.connector, self.device)?;
+
+        match self.brightness {
+            Some(brightness) => write!(f, ", brightness {:.0}%", brightness.percentage()),
+            None => Ok(())
+        }
+    }
+}
+
+// from linux/i2c-dev.h and the VESA DDC/CI and MCCS standards
+#[cfg(feature = "ddc")]
+const I2C_SLAVE: libc::c_ulong = 0x0703;
+#[cfg(feature = "ddc")]
+const DDC_CI_ADDRESS: libc::c_ulong = 0x37;
+#[cfg(feature = "ddc")]
+const VCP_BRIGHTNESS: u8 = 0x10;
+#[cfg(feature = "ddc")]
+const VCP_CONTRAST: u8 = 0x12;
+
+#[cfg(feature = "ddc")]
+fn ddcOpen(device: &str) -> io::Result<fs::File> {
+    let file = fs::OpenOptions::new().read(true).write(true).open(device)?;
+
+    if unsafe { libc::ioctl(file.as_raw_fd(), I2C_SLAVE as _, DDC_CI_ADDRESS) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    return Ok(file);
+}
+
+// sends a DDC/CI message: source address, length with the high bit set, payload and XOR checksum seeded with the destination address
+#[cfg(feature = "ddc")]
+fn ddcWrite(file: &mut fs::File, payload: &[u8]) -> io::Result<()> {
+    let mut message = vec![0x51, 0x80 | payload.len() as u8];
+    message.extend_from_slice(payload);
+
+    let checksum = message.iter().fold(0x6E_u8, |checksum, byte| checksum ^ byte);
+    message.push(checksum);
+
+    return file.write_all(&message);
+}
+
+#[cfg(feature = "ddc")]
+impl DdcDisplay {
+    /// Reads a VCP feature of the monitor, e.g. `0x10` for brightness
+    pub fn readVcp(&self, code: u8) -> io::Result<VcpValue> {
+        let mut file = ddcOpen(&self.device)?;
+        ddcWrite(&mut file, &[0x01, code])?;
+
+        // monitors need up to 40 ms to prepare the reply
+        thread::sleep(Duration::from_millis(40));
+
+        let mut reply = [0_u8; 11];
+        file.read_exact(&mut reply)?;
+
+        // the checksum of replies is seeded with the virtual host address 0x50
+        let checksum = reply[..10].iter().fold(0x50_u8, |checksum, byte| checksum ^ byte);
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid DDC/CI reply");
+
+        if checksum != reply[10] || reply[2] != 0x02 || reply[4] != code {
+            return Err(invalid());
+        }
+
+        if reply[3] != 0x00 {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "VCP feature not supported by the monitor"));
+        }
+
+        return Ok(VcpValue {
+            maximum: u16::from_be_bytes([reply[6], reply[7]]),
+            current: u16::from_be_bytes([reply[8], reply[9]])
+        });
+    }
+
+    /// Writes a VCP feature of the monitor
+    pub fn setVcp(&self, code: u8, value: u16) -> io::Result<()> {
+        let mut file = ddcOpen(&self.device)?;
+        let [high, low] = value.to_be_bytes();
+
+        ddcWrite(&mut file, &[0x03, code, high, low])?;
+
+        // monitors ignore messages received in the next 50 ms
+        thread::sleep(Duration::from_millis(50));
+        return Ok(());
+    }
+
+    /// Sets the brightness, in the monitor own scale (see `brightness.maximum`)
+    pub fn setBrightness(&self, value: u16) -> io::Result<()> {
+        return self.setVcp(VCP_BRIGHTNESS, value);
+    }
+
+    /// Sets the contrast, in the monitor own scale (see `contrast.maximum`)
+    pub fn setContrast(&self, value: u16) -> io::Result<()> {
+        return self.setVcp(VCP_CONTRAST, value);
+    }
+}
+
+/// Returns the connected external monitors answering DDC/CI, with their brightness and contrast
+///
+/// Access to `/dev/i2c-*` usually requires root or membership of the `i2c` group and the `i2c-dev` module loaded;
+/// internal panels (eDP, LVDS) are skipped, their backlight is available through `backlights()`
+#[cfg(feature = "ddc")]
+pub fn ddcDisplays() -> Vec<DdcDisplay> {
+    linuxCheck();
+
+    let mut displays = Vec::<DdcDisplay>::new();
+
+    let Ok(connectors) = fs::read_dir(sysPath("/sys/class/drm")) else {
+        return displays;
+    };
+
+    for connector in connectors.flatten() {
+        let name = connector.file_name().to_string_lossy().to_string();
+
+        if !name.contains('-') || name.contains("eDP") || name.contains("LVDS") {
+            continue;
+        }
+
+        if readFile(connector.path().join("status")) != "connected" {
+            continue;
+        }
+
+        // the ddc link points to the i2c adapter of the connector
+        let Some(bus) = fs::read_link(connector.path().join("ddc")).ok()
+            .and_then(|link| link.file_name().map(|bus| bus.to_string_lossy().to_string())) else {
+            continue;
+        };
+
+        let mut display = DdcDisplay {
+            connector: name,
+            device: format!("/dev/{}", bus),
+            brightness: None,
+            contrast: None
+        };
+
+        display.brightness = display.readVcp(VCP_BRIGHTNESS).ok();
+
+        // monitors without DDC/CI do not answer at all
+        if display.brightness.is_none() {
+            continue;
+        }
+
+        display.contrast = display.readVcp(VCP_CONTRAST).ok();
+        displays.push(display);
+    }
+
+    displays.sort_by(|first, second| first.connector.cmp(&second.connector));
+    return displays;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel(chip: &str, label: &str) -> CategorizedTemperature {
+        CategorizedTemperature {
+            category: categorizeSensor(chip, label),
+            device: String::from("hwmon1"),
+            chip: chip.to_string(),
+            label: label.to_string(),
+            temperature: 45_f32
         }
-    )
-}
\ No newline at end of file
+    }
+
+    #[test]
+    fn prefersTdieOverTctl() {
+        assert_eq!(categorizeSensor("k10temp", "Tctl"), SensorCategory::CpuPackage);
+        assert_eq!(categorizeSensor("k10temp", "Tccd2"), SensorCategory::CpuCcd(2));
+        assert_eq!(categorizeSensor("coretemp", "Core 3"), SensorCategory::CpuCore(3));
+
+        let mut channels = vec![channel("k10temp", "Tctl"), channel("k10temp", "Tdie"), channel("k10temp", "Tccd1")];
+        preferDieTemperature(&mut channels);
+
+        let categories: Vec<SensorCategory> = channels.into_iter().map(|channel| channel.category).collect();
+        assert_eq!(categories, vec![
+            SensorCategory::Other(String::from("k10temp/Tctl")),
+            SensorCategory::CpuPackage,
+            SensorCategory::CpuCcd(1)
+        ]);
+
+        // Tctl stays the package temperature on models without Tdie
+        let mut channels = vec![channel("k10temp", "Tctl"), channel("k10temp", "Tccd1")];
+        preferDieTemperature(&mut channels);
+        assert_eq!(channels[0].category, SensorCategory::CpuPackage);
+    }
+}
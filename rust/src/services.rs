@@ -0,0 +1,322 @@
+use std::{fmt, io};
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use crate::utils::{*};
+
+/// Contains the error level journal entries logged by a systemd unit
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServiceErrors {
+    pub unit: String,
+    /// Entries at `err` priority or more severe
+    pub errors: usize,
+    /// Entries at `crit`, `alert` or `emerg` priority, included in `errors`
+    pub critical: usize,
+    pub lastMessage: Option<String>
+}
+
+impl fmt::Display for ServiceErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {} errors ({} critical)", self.unit, self.errors, self.critical)?;
+
+        if let Some(message) = &self.lastMessage {
+            write!(f, ", last: {}", message)?;
+        }
+
+        Ok(())
+    }
+}
+
+// reads the 4 hexadecimal digits of a `\uXXXX` escape
+fn jsonCodeUnit(characters: &mut std::str::Chars) -> Option<u32> {
+    let code: String = characters.by_ref().take(4).collect();
+
+    if code.len() != 4 {
+        return None;
+    }
+
+    return u32::from_str_radix(&code, 16).ok();
+}
+
+// reads a string field of a `journalctl -o json` entry, binary fields (byte arrays) are skipped
+//
+// characters outside the basic multilingual plane are escaped as UTF-16 surrogate pairs, unpaired surrogates
+// become U+FFFD
+fn jsonStringField(entry: &str, name: &str) -> Option<String> {
+    let start = entry.find(&format!("\"{}\":\"", name))? + name.len() + 4;
+    let mut value = String::new();
+    let mut characters = entry[start..].chars();
+
+    while let Some(character) = characters.next() {
+        match character {
+            '"' => return Some(value),
+            '\\' => match characters.next()? {
+                '"' => value.push('"'),
+                '\\' => value.push('\\'),
+                '/' => value.push('/'),
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'u' => {
+                    let code = jsonCodeUnit(&mut characters)?;
+
+                    let decoded = match code {
+                        0xD800..=0xDBFF => {
+                            // the low surrogate has to follow right away, otherwise the next escape is left to the loop
+                            let mut lookahead = characters.clone();
+
+                            match (lookahead.next(), lookahead.next(), jsonCodeUnit(&mut lookahead)) {
+                                (Some('\\'), Some('u'), Some(low @ 0xDC00..=0xDFFF)) => {
+                                    characters = lookahead;
+                                    char::from_u32(0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00))
+                                },
+                                _ => None
+                            }
+                        },
+                        code => char::from_u32(code)
+                    };
+
+                    value.push(decoded.unwrap_or(char::REPLACEMENT_CHARACTER));
+                },
+                escaped => value.push(escaped)
+            },
+            character => value.push(character)
+        }
+    }
+
+    return None;
+}
+
+fn journalErrors(arguments: &[String]) -> io::Result<HashMap<String, ServiceErrors>> {
+    let output = Command::new("journalctl")
+        .args(["--priority", "0..3", "--output", "json", "--quiet", "--no-pager"])
+        .args(arguments)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()));
+    }
+
+    let mut services = HashMap::<String, ServiceErrors>::new();
+
+    // one JSON object per line, oldest first
+    for entry in String::from_utf8_lossy(&output.stdout).lines() {
+        let Some(unit) = jsonStringField(entry, "_SYSTEMD_UNIT") else {
+            continue;
+        };
+
+        let priority = jsonStringField(entry, "PRIORITY").and_then(|priority| priority.parse::<u8>().ok()).unwrap_or(3);
+
+        let service = services.entry(unit.clone()).or_insert(ServiceErrors {
+            unit: unit,
+            errors: 0,
+            critical: 0,
+            lastMessage: None
+        });
+
+        service.errors += 1;
+        if priority <= 2 {
+            service.critical += 1;
+        }
+
+        if let Some(message) = jsonStringField(entry, "MESSAGE") {
+            service.lastMessage = Some(message);
+        }
+    }
+
+    return Ok(services);
+}
+
+fn sinceArgument(since: SystemTime) -> String {
+    return format!("--since=@{}", since.duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or(0));
+}
+
+/// Returns the journal entries at `err` priority or more severe logged by `unit` since `since`
+///
+/// The journal is read through `journalctl`, users outside the `systemd-journal` group only see their own units
+pub fn recentErrors(unit: &str, since: SystemTime) -> io::Result<ServiceErrors> {
+    linuxCheck();
+
+    let mut services = journalErrors(&[format!("--unit={}", unit), sinceArgument(since)])?;
+
+    // `--unit` also matches messages about the unit logged by systemd itself, only count the unit's own
+    return Ok(services.remove(unit).or_else(|| services.remove(&format!("{}.service", unit))).unwrap_or(ServiceErrors {
+        unit: unit.to_string(),
+        errors: 0,
+        critical: 0,
+        lastMessage: None
+    }));
+}
+
+/// Returns every unit which logged error level journal entries since `since`, the noisiest first
+pub fn failingServices(since: SystemTime) -> io::Result<Vec<ServiceErrors>> {
+    linuxCheck();
+
+    let mut services: Vec<ServiceErrors> = journalErrors(&[sinceArgument(since)])?.into_values().collect();
+    services.sort_by(|first, second| second.errors.cmp(&first.errors).then(first.unit.cmp(&second.unit)));
+
+    return Ok(services);
+}
+
+/// Contains a login session tracked by systemd-logind
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Session {
+    pub id: String,
+    pub user: String,
+    pub uid: u32,
+    /// Seat the session is attached to, e.g. `seat0`, `None` for remote and background sessions
+    pub seat: Option<String>,
+    pub tty: Option<String>,
+    /// e.g. `x11`, `wayland`, `tty`
+    pub sessionType: String,
+    /// e.g. `user`, `greeter`, `manager`
+    pub class: String,
+    /// e.g. `active`, `online`, `closing`
+    pub state: String,
+    pub remote: bool,
+    /// Whether the desktop or the terminal reported the session as idle
+    pub idle: bool,
+    /// When the idle hint was last changed, `None` if it never was
+    pub idleSince: Option<SystemTime>
+}
+
+impl Session {
+    /// Returns how long the session has been idle, `None` if it is not idle
+    pub fn idleTime(&self) -> Option<Duration> {
+        if !self.idle {
+            return None;
+        }
+
+        return self.idleSince.and_then(|since| SystemTime::now().duration_since(since).ok());
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} ({}, {})", self.id, self.user, self.sessionType, self.state)?;
+
+        if let Some(seat) = &self.seat {
+            write!(f, " on {}", seat)?;
+        }
+
+        if let Some(idle) = self.idleTime() {
+            write!(f, ", idle for {}s", idle.as_secs())?;
+        }
+
+        Ok(())
+    }
+}
+
+// parses `loginctl show-session` output, `Key=value` lines with a blank line between sessions
+fn parseSessions(content: &str) -> Vec<Session> {
+    let mut sessions = Vec::<Session>::new();
+
+    for block in content.split("\n\n") {
+        let properties: HashMap<&str, &str> = block.lines().filter_map(|line| line.split_once('=')).collect();
+
+        let Some(id) = properties.get("Id") else {
+            continue;
+        };
+
+        let optional = |name: &str| properties.get(name).filter(|value| !value.is_empty()).map(|value| value.to_string());
+        let string = |name: &str| optional(name).unwrap_or_default();
+
+        let idleSince = properties.get("IdleSinceHint")
+            .and_then(|microseconds| microseconds.parse::<u64>().ok())
+            .filter(|microseconds| *microseconds > 0)
+            .map(|microseconds| UNIX_EPOCH + Duration::from_micros(microseconds));
+
+        sessions.push(Session {
+            id: id.to_string(),
+            user: string("Name"),
+            uid: string("User").parse::<u32>().unwrap_or(0),
+            seat: optional("Seat"),
+            tty: optional("TTY"),
+            sessionType: string("Type"),
+            class: string("Class"),
+            state: string("State"),
+            remote: string("Remote") == "yes",
+            idle: string("IdleHint") == "yes",
+            idleSince: idleSince
+        });
+    }
+
+    return sessions;
+}
+
+/// Returns the login sessions known to systemd-logind, with their seat and idle state
+///
+/// Sessions are read through `loginctl`
+pub fn sessions() -> io::Result<Vec<Session>> {
+    linuxCheck();
+
+    let list = Command::new("loginctl").args(["list-sessions", "--no-legend", "--no-pager"]).output()?;
+
+    if !list.status.success() {
+        return Err(io::Error::other(String::from_utf8_lossy(&list.stderr).trim().to_string()));
+    }
+
+    let identifiers: Vec<String> = String::from_utf8_lossy(&list.stdout).lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|id| id.to_string())
+        .collect();
+
+    if identifiers.is_empty() {
+        return Ok(Vec::<Session>::new());
+    }
+
+    let output = Command::new("loginctl").arg("show-session").args(&identifiers).output()?;
+    return Ok(parseSessions(&String::from_utf8_lossy(&output.stdout)));
+}
+
+/// Returns `true` if every user session is idle (greeters and background sessions are ignored),
+/// e.g. to decide whether the system can be suspended
+pub fn allSessionsIdle() -> io::Result<bool> {
+    return Ok(sessions()?.iter().filter(|session| session.class == "user").all(|session| session.idle));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readsJournalFields() {
+        let entry = r#"{"PRIORITY":"3","_SYSTEMD_UNIT":"nginx.service","MESSAGE":"bind() to \"0.0.0.0:80\" failed\nè","_PID":"12"}"#;
+
+        assert_eq!(jsonStringField(entry, "_SYSTEMD_UNIT").as_deref(), Some("nginx.service"));
+        assert_eq!(jsonStringField(entry, "PRIORITY").as_deref(), Some("3"));
+        assert_eq!(jsonStringField(entry, "MESSAGE").as_deref(), Some("bind() to \"0.0.0.0:80\" failed\nè"));
+        assert_eq!(jsonStringField(entry, "_COMM"), None);
+    }
+
+    #[test]
+    fn decodesJsonEscapes() {
+        let entry = r#"{"MESSAGE":"a\\b\/c\r\n\t\b\f\u00e8\u20ac\ud83d\ude00\ud83d!\ude00","UNIT":"\u12"}"#;
+
+        assert_eq!(
+            jsonStringField(entry, "MESSAGE").as_deref(),
+            Some("a\\b/c\r\n\t\u{8}\u{c}è€😀\u{FFFD}!\u{FFFD}")
+        );
+        assert_eq!(jsonStringField(entry, "UNIT"), None);
+    }
+
+    #[test]
+    fn parsesSessions() {
+        let content = "Id=2\nUser=1000\nName=alice\nSeat=seat0\nTTY=tty2\nRemote=no\nType=wayland\nClass=user\nState=active\nIdleHint=yes\nIdleSinceHint=1700000000000000\n\n\
+                       Id=c1\nUser=120\nName=gdm\nSeat=\nRemote=no\nType=wayland\nClass=greeter\nState=online\nIdleHint=no\nIdleSinceHint=0\n";
+
+        let sessions = parseSessions(content);
+
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].seat.as_deref(), Some("seat0"));
+        assert_eq!(sessions[0].uid, 1000);
+        assert!(sessions[0].idle);
+        assert_eq!(sessions[0].idleSince, Some(UNIX_EPOCH + Duration::from_secs(1700000000)));
+        assert_eq!(sessions[1].seat, None);
+        assert_eq!(sessions[1].idleTime(), None);
+    }
+}
@@ -0,0 +1,1197 @@
+use std::{fmt, fs};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use rsjson::{Json, Node, NodeContent};
+use crate::{cpu, ram, gpu, network, storage, motherboard, sensors, bus, units};
+use crate::utils::{sysPath, tryReadDir, ByteSize, ErrorCode, Frequency, SysError};
+
+/// Reason a requested section of a `SystemSnapshot` could not be collected
+///
+/// Exported in place of the section as `{"error": {"code": "PermissionDenied", "path": "...", "message": "..."}}`,
+/// so consumers can tell a missing device (`NotPresent`) from a failure to read it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SectionError {
+    pub code: ErrorCode,
+    /// File or directory the section is read from, when the error is tied to one
+    pub path: Option<PathBuf>,
+    pub message: String
+}
+
+impl SectionError {
+    pub fn fromSysError(error: &SysError) -> SectionError {
+        return SectionError {
+            code: error.code(),
+            path: Some(error.path().to_path_buf()),
+            message: error.to_string()
+        };
+    }
+
+    // the device class directory is readable but holds no matching device, or the reason it is not readable
+    fn missingDevice(directory: &str, message: &str) -> SectionError {
+        return match tryReadDir(directory) {
+            Ok(_) => SectionError {
+                code: ErrorCode::NotPresent,
+                path: Some(sysPath(directory)),
+                message: message.to_string()
+            },
+            Err(error) => SectionError::fromSysError(&error)
+        };
+    }
+
+    fn gpuMetrics(error: gpu::GpuMetricsError) -> SectionError {
+        let path = sysPath("/sys/class/drm/card0/device/gpu_metrics");
+
+        return match error {
+            gpu::GpuMetricsError::Unavailable => match fs::File::open(&path) {
+                Err(error) => SectionError::fromSysError(&SysError::fromIo(&path, error)),
+                Ok(_) => SectionError { code: ErrorCode::Io, path: Some(path), message: error.to_string() }
+            },
+            gpu::GpuMetricsError::Truncated { .. } => SectionError { code: ErrorCode::Parse, path: Some(path), message: error.to_string() },
+            gpu::GpuMetricsError::UnsupportedVersion { .. } => {
+                SectionError { code: ErrorCode::Unsupported, path: Some(path), message: error.to_string() }
+            }
+        };
+    }
+
+    /// Returns the `{"error": {...}}` object exported in place of the section
+    pub fn toJson(&self) -> Json {
+        let mut error = Json::new();
+
+        error.addNode(Node::new("code", NodeContent::String(self.code.toString())));
+        error.addNode(Node::new("path", match &self.path {
+            Some(path) => NodeContent::String(path.to_string_lossy().to_string()),
+            None => NodeContent::Null
+        }));
+        error.addNode(Node::new("message", NodeContent::String(self.message.clone())));
+
+        let mut json = Json::new();
+        json.addNode(Node::new("error", NodeContent::Json(error)));
+
+        return json;
+    }
+
+    // reads back a section exported by `toJson()`, `None` for any other content
+    fn fromContent(content: &NodeContent) -> Option<SectionError> {
+        let json = asJson(content)?;
+        if json.getAllNodes().len() != 1 {
+            return None;
+        }
+
+        let error = asJson(&getNodeIfPresent(&json, "error")?)?;
+
+        return Some(SectionError {
+            code: ErrorCode::fromString(&asOptionalString(&getNode(&error, "code"))?),
+            path: asOptionalString(&getNode(&error, "path")).map(PathBuf::from),
+            message: asString(&getNode(&error, "message"))
+        });
+    }
+}
+
+impl fmt::Display for SectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.code, self.message)
+    }
+}
+
+/// Typed representation of the data exported by `exportJson()`
+///
+/// Each section is `None` when it was not collected, when it could not be read (the reason being in `errors`),
+/// or when it was missing (or `null`) in the source document
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::SystemSnapshot;
+///
+/// let snapshot = SystemSnapshot::capture();
+/// println!("{:?}", snapshot.load);
+/// snapshot.toJson().writeToFile("snapshot.json");
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SystemSnapshot {
+    pub cpu: Option<cpu::CPU>,
+    pub clockSource: Option<cpu::ClockSource>,
+    pub load: Option<cpu::Load>,
+    pub ram: Option<ram::RAM>,
+    pub motherboard: Option<motherboard::Motherboard>,
+    pub nvmeDevices: Option<Vec<storage::NvmeDevice>>,
+    pub storageDevices: Option<Vec<storage::StorageDevice>>,
+    pub battery: Option<sensors::Battery>,
+    pub backlight: Option<sensors::Backlight>,
+    pub temperatureSensors: Option<Vec<sensors::TemperatureSensor>>,
+    pub categorizedTemperatures: Option<Vec<sensors::CategorizedTemperature>>,
+    pub thermalZones: Option<Vec<sensors::ThermalZone>>,
+    pub coolingDevices: Option<Vec<sensors::CoolingDevice>>,
+    pub backlights: Option<Vec<sensors::Backlight>>,
+    /// Combined battery of multi-battery laptops, `battery` holds the first one
+    pub aggregateBattery: Option<sensors::Battery>,
+    pub networkRate: Option<network::NetworkRate>,
+    pub networkRoutes: Option<Vec<network::NetworkRoute>>,
+    pub networkInterfaces: Option<Vec<network::NetworkInterface>>,
+    pub ipv4: Option<Vec<network::IPv4>>,
+    pub ipv6: Option<Vec<network::IPv6>>,
+    /// Statistics of each interface, by interface name
+    pub interfaceStatistics: Option<HashMap<String, network::InterfaceStatistics>>,
+    /// `card0` only, collected with the `legacy-gpu-export` feature, use `gpus` otherwise
+    pub vram: Option<gpu::VRAM>,
+    /// `card0` only, collected with the `legacy-gpu-export` feature, use `gpus` otherwise
+    pub gpuMetrics: Option<gpu::GpuMetrics>,
+    /// Every GPU
+    pub gpus: Option<Vec<gpu::Gpu>>,
+    pub busInput: Option<Vec<bus::BusInput>>,
+    /// Why requested sections are missing, by key of the exported document, e.g. `"battery"`
+    pub errors: HashMap<String, SectionError>
+}
+
+/// Alias of `SystemSnapshot`, mostly used together with `SysInfo::builder()`
+pub type SysInfo = SystemSnapshot;
+
+/// Collects only the requested sections of a `SystemSnapshot`, leaving the others to `None`, or exports them
+/// with `export()`, the others being `null`
+///
+/// A full export takes about a second because of the CPU and network sampling, leaving those sections out skips it
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::SysInfo;
+///
+/// let info = SysInfo::builder().cpu().ram().network().collect();
+/// println!("{:?}", info.networkRate);
+///
+/// let json = SysInfo::builder().all().withoutGpu().withoutBus().export();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotBuilder {
+    cpu: bool,
+    ram: bool,
+    gpu: bool,
+    network: bool,
+    storage: bool,
+    sensors: bool,
+    motherboard: bool,
+    bus: bool,
+    units: bool
+}
+
+impl SnapshotBuilder {
+    /// Collects CPU information, usage, clock source and load
+    pub fn cpu(mut self) -> Self {
+        self.cpu = true;
+        self
+    }
+
+    /// Collects RAM information
+    pub fn ram(mut self) -> Self {
+        self.ram = true;
+        self
+    }
+
+    /// Collects every GPU, with its VRAM and metrics; the `card0` only `vram` and `gpuMetrics` sections are
+    /// collected too with the `legacy-gpu-export` feature
+    pub fn gpu(mut self) -> Self {
+        self.gpu = true;
+        self
+    }
+
+    /// Collects network rate, routes, interfaces, their statistics and IPv4 and IPv6 addresses
+    pub fn network(mut self) -> Self {
+        self.network = true;
+        self
+    }
+
+    /// Collects NVME and storage devices
+    pub fn storage(mut self) -> Self {
+        self.storage = true;
+        self
+    }
+
+    /// Collects batteries, backlights, temperatures, thermal zones and cooling devices
+    pub fn sensors(mut self) -> Self {
+        self.sensors = true;
+        self
+    }
+
+    /// Collects motherboard and BIOS information
+    pub fn motherboard(mut self) -> Self {
+        self.motherboard = true;
+        self
+    }
+
+    /// Collects bus input devices
+    pub fn bus(mut self) -> Self {
+        self.bus = true;
+        self
+    }
+
+    /// Collects every section, equivalent to what `exportJson()` contains
+    pub fn all(self) -> Self {
+        self.cpu().ram().gpu().network().storage().sensors().motherboard().bus()
+    }
+
+    /// Leaves the `cpu` section out
+    pub fn withoutCpu(mut self) -> Self {
+        self.cpu = false;
+        self
+    }
+
+    /// Leaves the `ram` section out
+    pub fn withoutRam(mut self) -> Self {
+        self.ram = false;
+        self
+    }
+
+    /// Leaves the `gpu` section out
+    pub fn withoutGpu(mut self) -> Self {
+        self.gpu = false;
+        self
+    }
+
+    /// Leaves the `network` section out
+    pub fn withoutNetwork(mut self) -> Self {
+        self.network = false;
+        self
+    }
+
+    /// Leaves the `storage` section out
+    pub fn withoutStorage(mut self) -> Self {
+        self.storage = false;
+        self
+    }
+
+    /// Leaves the `sensors` section out
+    pub fn withoutSensors(mut self) -> Self {
+        self.sensors = false;
+        self
+    }
+
+    /// Leaves the `motherboard` section out
+    pub fn withoutMotherboard(mut self) -> Self {
+        self.motherboard = false;
+        self
+    }
+
+    /// Leaves the `bus` section out
+    pub fn withoutBus(mut self) -> Self {
+        self.bus = false;
+        self
+    }
+
+    /// Adds to `export()` a `units` object with the unit of every numeric node, see `units::unitsJson()`
+    pub fn withUnits(mut self) -> Self {
+        self.units = true;
+        self
+    }
+
+    /// Collects the requested sections and converts them to the document returned by `exportJson()`
+    pub fn export(&self) -> Json {
+        let mut json = self.collect().toJson();
+
+        if self.units {
+            json.addNode(Node::new("units", NodeContent::Json(units::unitsJson())));
+        }
+
+        return json;
+    }
+
+    /// Reads the requested sections from the system
+    pub fn collect(&self) -> SystemSnapshot {
+        let cpu = self.cpu.then(cpu::CPU::new);
+        let networkRate = self.network.then(network::networkRate);
+
+        return self.collectWith(cpu, networkRate);
+    }
+
+    /// Same as `collect()`, but the CPU and network sampling waits with `tokio::time::sleep`,
+    /// the two running concurrently, and the other sections are read on the blocking thread pool
+    #[cfg(feature = "async")]
+    pub async fn collectAsync(&self) -> SystemSnapshot {
+        let (cpu, networkRate) = tokio::join!(
+            async { if self.cpu { Some(cpu::CPU::newAsync().await) } else { None } },
+            async { if self.network { Some(network::networkRateAsync().await) } else { None } }
+        );
+
+        // storage, SMART and the other sections read many files, some of them slowly, keep them off the executor;
+        // the root in effect is thread local, so it is carried over to the blocking thread
+        let builder = self.clone();
+        let root = crate::utils::SysPaths::current();
+
+        return match tokio::task::spawn_blocking(move || root.scope(|| builder.collectWith(cpu, networkRate))).await {
+            Ok(snapshot) => snapshot,
+            Err(error) => std::panic::resume_unwind(error.into_panic())
+        };
+    }
+
+    // reads the requested sections which do not need sampling, the sampled values are passed in
+    fn collectWith(&self, cpu: Option<cpu::CPU>, networkRate: Option<network::NetworkRate>) -> SystemSnapshot {
+        let mut snapshot = SystemSnapshot::empty();
+
+        if self.cpu {
+            match cpu::tryCpuInfo() {
+                Ok(_) => snapshot.cpu = cpu,
+                Err(error) => snapshot.setError("cpu", SectionError::fromSysError(&error))
+            }
+
+            snapshot.clockSource = Some(cpu::clockSource());
+
+            match cpu::tryGetLoad() {
+                Ok(load) => snapshot.load = Some(load),
+                Err(error) => snapshot.setError("load", SectionError::fromSysError(&error))
+            }
+        }
+
+        if self.ram {
+            match ram::tryRamSize() {
+                Ok(_) => snapshot.ram = Some(ram::RAM::new()),
+                Err(error) => snapshot.setError("ram", SectionError::fromSysError(&error))
+            }
+        }
+
+        if self.gpu {
+            snapshot.gpus = Some(gpu::gpus());
+
+            // the single GPU sections are only exported, and thus only read, with the legacy export
+            #[cfg(feature = "legacy-gpu-export")]
+            {
+                snapshot.vram = Some(gpu::VRAM::new());
+
+                match gpu::tryGpuMetrics() {
+                    Ok(metrics) => snapshot.gpuMetrics = Some(metrics),
+                    Err(error) => snapshot.setError("gpu-metrics", SectionError::gpuMetrics(error))
+                }
+            }
+        }
+
+        if self.network {
+            snapshot.networkRate = networkRate;
+            snapshot.networkRoutes = Some(network::networkRoutes());
+            snapshot.networkInterfaces = Some(network::networkInterfaces());
+            snapshot.ipv4 = Some(network::getIPv4());
+            snapshot.ipv6 = Some(network::getIPv6());
+            snapshot.interfaceStatistics = Some(
+                snapshot.networkInterfaces.iter().flatten()
+                    .filter_map(|interface| Some((interface.name.clone(), network::interfaceStatistics(&interface.name)?)))
+                    .collect()
+            );
+        }
+
+        if self.storage {
+            match storage::tryNvmeDevices() {
+                Ok(devices) => snapshot.nvmeDevices = Some(devices),
+                Err(error) => snapshot.setError("nvme-devices", SectionError::fromSysError(&error))
+            }
+
+            snapshot.storageDevices = Some(storage::storageDevices());
+        }
+
+        if self.sensors {
+            snapshot.battery = sensors::batteryInfo();
+            if snapshot.battery.is_none() {
+                snapshot.setError("battery", SectionError::missingDevice("/sys/class/power_supply", "no battery found"));
+            }
+
+            snapshot.backlight = sensors::getBacklight();
+            if snapshot.backlight.is_none() {
+                snapshot.setError("backlight", SectionError::missingDevice("/sys/class/backlight", "no backlight found"));
+            }
+
+            match sensors::tryTemperatureSensors() {
+                Ok(temperatureSensors) => snapshot.temperatureSensors = Some(temperatureSensors),
+                Err(error) => snapshot.setError("temperature-sensors", SectionError::fromSysError(&error))
+            }
+
+            snapshot.categorizedTemperatures = Some(sensors::categorizedTemperatures());
+            snapshot.thermalZones = Some(sensors::thermalZones());
+            snapshot.coolingDevices = Some(sensors::coolingDevices());
+            snapshot.backlights = Some(sensors::backlights());
+            snapshot.aggregateBattery = sensors::aggregateBattery();
+        }
+
+        if self.motherboard {
+            snapshot.motherboard = Some(motherboard::motherboardInfo());
+        }
+
+        if self.bus {
+            snapshot.busInput = Some(bus::busInput());
+        }
+
+        return snapshot;
+    }
+}
+
+impl SystemSnapshot {
+    /// Returns a builder collecting only the requested sections
+    pub fn builder() -> SnapshotBuilder {
+        SnapshotBuilder::default()
+    }
+
+    /// Reads every section from the system, the data `exportJson()` is built from
+    pub fn capture() -> SystemSnapshot {
+        SnapshotBuilder::default().all().collect()
+    }
+
+    /// Same as `capture()`, sampling CPU and network usage concurrently with `tokio::time::sleep`
+    /// instead of blocking the thread
+    #[cfg(feature = "async")]
+    pub async fn captureAsync() -> SystemSnapshot {
+        SnapshotBuilder::default().all().collectAsync().await
+    }
+
+    /// Converts the snapshot to the document returned by `exportJson()`, sections which were not collected are `null`,
+    /// sections in `errors` are `{"error": {"code": ..., "path": ..., "message": ...}}`
+    pub fn toJson(&self) -> Json {
+        crate::snapshotJson(self.clone())
+    }
+
+    /// Returns the value at the dotted `path` of `toJson()`, e.g. `"cpu.usage.total"` or `"nvme-devices.0.model"`
+    ///
+    /// List elements are selected by their index, `None` if any segment of the path does not exist
+    pub fn value(&self, path: &str) -> Option<NodeContent> {
+        let mut content = NodeContent::Json(self.toJson());
+
+        for segment in path.split('.') {
+            content = match content {
+                NodeContent::Json(json) => getNodeIfPresent(&json, segment)?,
+                NodeContent::List(list) => list.into_iter().nth(segment.parse::<usize>().ok()?)?,
+                _ => return None
+            };
+        }
+
+        return Some(content);
+    }
+
+    fn empty() -> SystemSnapshot {
+        SystemSnapshot {
+            cpu: None,
+            clockSource: None,
+            load: None,
+            ram: None,
+            motherboard: None,
+            nvmeDevices: None,
+            storageDevices: None,
+            battery: None,
+            backlight: None,
+            temperatureSensors: None,
+            categorizedTemperatures: None,
+            thermalZones: None,
+            coolingDevices: None,
+            backlights: None,
+            aggregateBattery: None,
+            networkRate: None,
+            networkRoutes: None,
+            networkInterfaces: None,
+            ipv4: None,
+            ipv6: None,
+            interfaceStatistics: None,
+            vram: None,
+            gpuMetrics: None,
+            gpus: None,
+            busInput: None,
+            errors: HashMap::new()
+        }
+    }
+
+    fn setError(&mut self, section: &str, error: SectionError) {
+        self.errors.insert(section.to_string(), error);
+    }
+
+    /// Parses a snapshot previously generated by `exportJson()`, returns `None` if the text is not valid JSON
+    ///
+    /// Every exported section is read back, values the export leaves out (e.g. IPv4 netmasks) stay empty
+    /// ```rust,no_run
+    /// use sysutil::SystemSnapshot;
+    ///
+    /// let content = std::fs::read_to_string("snapshot.json").unwrap();
+    /// let snapshot = SystemSnapshot::fromJson(&content).unwrap();
+    /// ```
+    pub fn fromJson(json: &str) -> Option<SystemSnapshot> {
+        let document = Json::fromString(json).ok()?;
+
+        // sections exported as errors are read as missing, keeping their reason
+        let mut json = Json::new();
+        let mut errors = HashMap::<String, SectionError>::new();
+
+        for node in document.getAllNodes() {
+            match SectionError::fromContent(&node.getContent()) {
+                Some(error) => {
+                    errors.insert(node.getLabel(), error);
+                    json.addNode(Node::new(node.getLabel(), NodeContent::Null));
+                },
+                None => json.addNode(node)
+            }
+        }
+
+        // documents without the legacy `vram` and `gpu-metrics` nodes describe `card0` in `gpus`
+        let gpus = asList(&getNode(&json, "gpus")).map(|list| parseList(&list, parseGpu));
+        let firstCard = gpus.iter().flatten().find(|gpu| gpu.card == "card0");
+
+        Some(SystemSnapshot {
+            cpu: asJson(&getNode(&json, "cpu")).map(|node| parseCpu(&node)),
+            clockSource: asJson(&getNode(&json, "cpu"))
+                .and_then(|node| asJson(&getNode(&node, "clock-source")))
+                .map(|node| parseClockSource(&node)),
+            load: asJson(&getNode(&json, "load")).map(|node| parseLoad(&node)),
+            ram: asJson(&getNode(&json, "ram")).map(|node| parseRam(&node)),
+            motherboard: asJson(&getNode(&json, "motherboard")).map(|node| parseMotherboard(&node)),
+            nvmeDevices: asList(&getNode(&json, "nvme-devices")).map(|list| parseList(&list, parseNvmeDevice)),
+            storageDevices: asList(&getNode(&json, "storage-devices")).map(|list| parseList(&list, parseStorageDevice)),
+            battery: asJson(&getNode(&json, "battery")).map(|node| parseBattery(&node)),
+            backlight: asJson(&getNode(&json, "backlight")).map(|node| parseBacklight(&node)),
+            temperatureSensors: asList(&getNode(&json, "temperature-sensors")).map(|list| parseList(&list, parseTemperatureSensor)),
+            categorizedTemperatures: asJson(&getNode(&json, "sensors"))
+                .and_then(|node| asList(&getNode(&node, "chips")))
+                .map(|list| parseCategorizedTemperatures(&list)),
+            thermalZones: asJson(&getNode(&json, "sensors"))
+                .and_then(|node| asList(&getNode(&node, "thermal-zones")))
+                .map(|list| parseList(&list, parseThermalZone)),
+            coolingDevices: asJson(&getNode(&json, "sensors"))
+                .and_then(|node| asList(&getNode(&node, "cooling-devices")))
+                .map(|list| parseList(&list, parseCoolingDevice)),
+            backlights: asJson(&getNode(&json, "sensors"))
+                .and_then(|node| asList(&getNode(&node, "backlights")))
+                .map(|list| parseList(&list, parseBacklight)),
+            aggregateBattery: asJson(&getNode(&json, "sensors"))
+                .and_then(|node| asJson(&getNode(&node, "battery")))
+                .map(|node| parseBattery(&node)),
+            networkRate: asJson(&getNode(&json, "network"))
+                .and_then(|node| asJson(&getNode(&node, "rate")))
+                .map(|node| parseNetworkRate(&node)),
+            networkRoutes: asJson(&getNode(&json, "network"))
+                .and_then(|node| asList(&getNode(&node, "routes")))
+                .map(|list| parseList(&list, parseNetworkRoute)),
+            networkInterfaces: asJson(&getNode(&json, "network-interfaces")).map(|node| parseNetworkInterfaces(&node)),
+            ipv4: asList(&getNode(&json, "ipv4")).map(|list| parseList(&list, parseIPv4)),
+            ipv6: asJson(&getNode(&json, "network"))
+                .and_then(|node| asJson(&getNode(&node, "interfaces")))
+                .map(|node| parseIPv6(&node)),
+            interfaceStatistics: asJson(&getNode(&json, "network"))
+                .and_then(|node| asJson(&getNode(&node, "interfaces")))
+                .map(|node| parseInterfaceStatistics(&node)),
+            vram: asJson(&getNode(&json, "vram")).map(|node| parseVram(&node))
+                .or_else(|| firstCard.map(|gpu| gpu.vram.clone())),
+            gpuMetrics: asJson(&getNode(&json, "gpu-metrics")).map(|node| parseGpuMetrics(&node))
+                .or_else(|| firstCard.and_then(|gpu| gpu.metrics.clone())),
+            gpus: gpus.clone(),
+            busInput: asList(&getNode(&json, "bus-input")).map(|list| parseList(&list, parseBusInput)),
+            errors: errors
+        })
+    }
+}
+
+fn getNodeIfPresent(json: &Json, label: &str) -> Option<NodeContent> {
+    return json.getAllNodes().into_iter().find(|node| node.getLabel() == label).map(|node| node.getContent());
+}
+
+// sections collecting the top level key of the exported document
+pub(crate) fn sectionsFor(key: &str) -> Option<SnapshotBuilder> {
+    let sections = SnapshotBuilder::default();
+
+    return match key {
+        "cpu" | "load" => Some(sections.cpu()),
+        "ram" => Some(sections.ram()),
+        "motherboard" => Some(sections.motherboard()),
+        "nvme-devices" | "storage-devices" => Some(sections.storage()),
+        "battery" | "backlight" | "temperature-sensors" | "sensors" => Some(sections.sensors()),
+        "network" | "ipv4" | "network-interfaces" => Some(sections.network()),
+        "gpus" | "vram" | "gpu-metrics" => Some(sections.gpu()),
+        "bus-input" => Some(sections.bus()),
+        _ => None
+    };
+}
+
+fn getNode(json: &Json, label: &str) -> NodeContent {
+    for node in json.getAllNodes() {
+        if node.getLabel() == label {
+            return node.getContent();
+        }
+    }
+
+    return NodeContent::Null;
+}
+
+fn asJson(content: &NodeContent) -> Option<Json> {
+    match content {
+        NodeContent::Json(json) => Some(json.clone()),
+        _ => None
+    }
+}
+
+fn asList(content: &NodeContent) -> Option<Vec<NodeContent>> {
+    match content {
+        NodeContent::List(list) => Some(list.clone()),
+        _ => None
+    }
+}
+
+fn asString(content: &NodeContent) -> String {
+    match content {
+        NodeContent::String(string) => string.clone(),
+        _ => String::new()
+    }
+}
+
+fn asOptionalString(content: &NodeContent) -> Option<String> {
+    match content {
+        NodeContent::String(string) => Some(string.clone()),
+        _ => None
+    }
+}
+
+fn asStringList(content: &NodeContent) -> Vec<String> {
+    asList(content).unwrap_or_default().iter().map(asString).collect()
+}
+
+fn asUsize(content: &NodeContent) -> Option<usize> {
+    match content {
+        NodeContent::Int(value) => Some(*value),
+        NodeContent::Float(value) => Some(*value as usize),
+        _ => None
+    }
+}
+
+fn asFloat(content: &NodeContent) -> Option<f32> {
+    match content {
+        NodeContent::Float(value) => Some(*value),
+        NodeContent::Int(value) => Some(*value as f32),
+        _ => None
+    }
+}
+
+fn asBool(content: &NodeContent) -> Option<bool> {
+    match content {
+        NodeContent::Bool(value) => Some(*value),
+        _ => None
+    }
+}
+
+fn parseList<T>(list: &Vec<NodeContent>, parser: fn(&Json) -> T) -> Vec<T> {
+    let mut parsed = Vec::<T>::new();
+
+    for element in list {
+        if let Some(json) = asJson(element) {
+            parsed.push(parser(&json));
+        }
+    }
+
+    return parsed;
+}
+
+fn gibToBytes(gib: f32) -> u64 {
+    (gib as f64 * 1024_f64.powi(3)) as u64
+}
+
+fn parseProcessorUsage(json: &Json) -> cpu::ProcessorUsage {
+    cpu::ProcessorUsage {
+        total: asFloat(&getNode(json, "total")).unwrap_or(0_f32),
+        user: asFloat(&getNode(json, "user")).unwrap_or(0_f32),
+        nice: asFloat(&getNode(json, "nice")).unwrap_or(0_f32),
+        system: asFloat(&getNode(json, "system")).unwrap_or(0_f32),
+        idle: asFloat(&getNode(json, "idle")).unwrap_or(0_f32),
+        iowait: asFloat(&getNode(json, "iowait")).unwrap_or(0_f32),
+        interrupt: asFloat(&getNode(json, "interrupt")).unwrap_or(0_f32),
+        soft_interrupt: asFloat(&getNode(json, "soft-interrupt")).unwrap_or(0_f32),
+    }
+}
+
+fn parseSchedulerPolicies(json: &Json) -> Vec<cpu::SchedulerPolicy> {
+    let mut policies = Vec::<cpu::SchedulerPolicy>::new();
+
+    for node in json.getAllNodes() {
+        let policy = match asJson(&node.getContent()) {
+            Some(policy) => policy,
+            None => continue
+        };
+
+        policies.push(cpu::SchedulerPolicy {
+            name: node.getLabel(),
+            scalingGovernor: asString(&getNode(&policy, "scaling-governor")),
+            scalingDriver: asString(&getNode(&policy, "scaling-driver")),
+            minimumScaling: Frequency::fromKhz((asFloat(&getNode(&policy, "minimum-scaling-mhz")).unwrap_or(0_f32) * 1000_f32) as usize),
+            maximumScaling: Frequency::fromKhz((asFloat(&getNode(&policy, "maximum-scaling-mhz")).unwrap_or(0_f32) * 1000_f32) as usize),
+        });
+    }
+
+    return policies;
+}
+
+fn parsePerProcessorUsage(json: &Json) -> Vec<cpu::ProcessorUsage> {
+    let mut processors = Vec::<(usize, cpu::ProcessorUsage)>::new();
+
+    for node in json.getAllNodes() {
+        if let (Ok(processorID), Some(usage)) = (node.getLabel().parse::<usize>(), asJson(&node.getContent())) {
+            processors.push((processorID, parseProcessorUsage(&usage)));
+        }
+    }
+
+    processors.sort_by_key(|(processorID, _)| *processorID);
+    return processors.into_iter().map(|(_, usage)| usage).collect();
+}
+
+fn parsePerProcessorFrequency(json: &Json) -> Vec<cpu::ProcessorFrequency> {
+    let mut processors = Vec::<cpu::ProcessorFrequency>::new();
+
+    for node in json.getAllNodes() {
+        if let Some(khz) = asUsize(&node.getContent()) {
+            processors.push(cpu::ProcessorFrequency {
+                processorID: node.getLabel(),
+                frequency: Frequency::fromKhz(khz)
+            });
+        }
+    }
+
+    return processors;
+}
+
+fn parseCpu(json: &Json) -> cpu::CPU {
+    let info = cpu::CpuInfo {
+        modelName: asString(&getNode(json, "model-name")),
+        cores: asUsize(&getNode(json, "cores")).unwrap_or(0),
+        threads: asUsize(&getNode(json, "threads")).unwrap_or(0),
+        dies: asUsize(&getNode(json, "dies")).unwrap_or(0),
+        governors: asStringList(&getNode(json, "governors")).iter().map(|governor| cpu::Governor::fromString(governor)).collect(),
+        maxFrequencyMHz: asFloat(&getNode(json, "max-frequency")).unwrap_or(0_f32),
+        clockBoost: asBool(&getNode(json, "clock-boost")),
+        architecture: cpu::Architecture::fromString(&asString(&getNode(json, "architecture"))),
+        byteOrder: cpu::Endianness::fromString(&asString(&getNode(json, "byte-order"))),
+        performanceCores: asUsize(&getNode(json, "performance-cores")),
+        efficiencyCores: asUsize(&getNode(json, "efficiency-cores")),
+    };
+
+    cpu::CPU {
+        info: info,
+        averageUsage: parseProcessorUsage(&asJson(&getNode(json, "usage")).unwrap_or(Json::new())),
+        perProcessorUsage: parsePerProcessorUsage(&asJson(&getNode(json, "per-processor-usage")).unwrap_or(Json::new())),
+        schedulerPolicies: parseSchedulerPolicies(&asJson(&getNode(json, "scheduler-policies")).unwrap_or(Json::new())),
+        averageFrequency: Frequency::fromKhz(asUsize(&getNode(json, "frequency")).unwrap_or(0)),
+        perProcessorFrequency: parsePerProcessorFrequency(&asJson(&getNode(json, "per-processor-frequency")).unwrap_or(Json::new()))
+    }
+}
+
+fn parseClockSource(json: &Json) -> cpu::ClockSource {
+    cpu::ClockSource {
+        current: asString(&getNode(json, "current")),
+        available: asStringList(&getNode(json, "available"))
+    }
+}
+
+fn parseLoad(json: &Json) -> cpu::Load {
+    cpu::Load {
+        oneMinute: asFloat(&getNode(json, "one-minute")).unwrap_or(0_f32),
+        fiveMinutes: asFloat(&getNode(json, "five-minutes")).unwrap_or(0_f32),
+        fifteenMinutes: asFloat(&getNode(json, "fifteen-minutes")).unwrap_or(0_f32)
+    }
+}
+
+fn parseRam(json: &Json) -> ram::RAM {
+    ram::RAM {
+        size: ByteSize::fromBytes(gibToBytes(asFloat(&getNode(json, "size-gib")).unwrap_or(0_f32))),
+        usage: asFloat(&getNode(json, "usage")).unwrap_or(0_f32),
+        frequency: asUsize(&getNode(json, "frequency")).map(Frequency::fromMhz),
+        busWidth: asUsize(&getNode(json, "width"))
+    }
+}
+
+fn parseMotherboard(json: &Json) -> motherboard::Motherboard {
+    let bios = asJson(&getNode(json, "bios")).unwrap_or(Json::new());
+
+    motherboard::Motherboard {
+        name: asString(&getNode(json, "name")),
+        vendor: asString(&getNode(json, "vendor")),
+        version: asString(&getNode(json, "version")),
+        bios: motherboard::Bios {
+            vendor: asString(&getNode(&bios, "vendor")),
+            release: asString(&getNode(&bios, "release")),
+            version: asString(&getNode(&bios, "version")),
+            date: asString(&getNode(&bios, "date"))
+        }
+    }
+}
+
+fn parsePartition(json: &Json) -> storage::StoragePartition {
+    storage::StoragePartition {
+        device: asString(&getNode(json, "device")),
+        mountPoint: asString(&getNode(json, "mount-point")),
+        fileSystem: asString(&getNode(json, "filesystem")),
+        size: storage::ByteSize::new(asUsize(&getNode(json, "size")).unwrap_or(0) as u64),
+        startPoint: asUsize(&getNode(json, "start-point")).unwrap_or(0)
+    }
+}
+
+fn parseNvmeDevice(json: &Json) -> storage::NvmeDevice {
+    storage::NvmeDevice {
+        device: asString(&getNode(json, "device")),
+        pcieAddress: asString(&getNode(json, "pcie-address")),
+        model: asString(&getNode(json, "model")),
+        linkSpeedGTs: asFloat(&getNode(json, "link-speed-gts")).unwrap_or(0_f32),
+        pcieLanes: asUsize(&getNode(json, "pcie-lanes")).unwrap_or(0),
+        size: storage::ByteSize::new(asUsize(&getNode(json, "size")).unwrap_or(0) as u64),
+        partitions: parseList(&asList(&getNode(json, "partitions")).unwrap_or_default(), parsePartition)
+    }
+}
+
+fn parseStorageDevice(json: &Json) -> storage::StorageDevice {
+    storage::StorageDevice {
+        model: asString(&getNode(json, "model")),
+        device: asString(&getNode(json, "device")),
+        size: storage::ByteSize::new(asUsize(&getNode(json, "size")).unwrap_or(0) as u64),
+        partitions: parseList(&asList(&getNode(json, "partitions")).unwrap_or_default(), parsePartition),
+        transport: storage::StorageTransport::fromString(&asString(&getNode(json, "transport"))),
+        target: asOptionalString(&getNode(json, "target")),
+        host: asOptionalString(&getNode(json, "host"))
+    }
+}
+
+fn parseBattery(json: &Json) -> sensors::Battery {
+    sensors::Battery {
+        capacity: asUsize(&getNode(json, "capacity")).unwrap_or(0) as u8,
+        status: sensors::BatteryStatus::fromString(&asString(&getNode(json, "status")))
+    }
+}
+
+fn parseBacklight(json: &Json) -> sensors::Backlight {
+    sensors::Backlight {
+        name: asString(&getNode(json, "name")),
+        backlightType: sensors::BacklightType::fromString(&asString(&getNode(json, "type"))),
+        brightness: asUsize(&getNode(json, "brightness")).unwrap_or(0) as u32,
+        maxBrightness: asUsize(&getNode(json, "max-brightness")).unwrap_or(0) as u32
+    }
+}
+
+// channels are exported grouped by hwmon chip
+fn parseCategorizedTemperatures(chips: &[NodeContent]) -> Vec<sensors::CategorizedTemperature> {
+    let mut temperatures = Vec::<sensors::CategorizedTemperature>::new();
+
+    for chip in chips.iter().filter_map(asJson) {
+        let device = asString(&getNode(&chip, "device"));
+        let name = asString(&getNode(&chip, "name"));
+
+        for channel in asList(&getNode(&chip, "temperatures")).unwrap_or_default().iter().filter_map(asJson) {
+            temperatures.push(sensors::CategorizedTemperature {
+                category: sensors::SensorCategory::fromString(&asString(&getNode(&channel, "category"))),
+                device: device.clone(),
+                chip: name.clone(),
+                label: asString(&getNode(&channel, "label")),
+                temperature: asFloat(&getNode(&channel, "temperature")).unwrap_or(0_f32)
+            });
+        }
+    }
+
+    return temperatures;
+}
+
+fn parseThermalZone(json: &Json) -> sensors::ThermalZone {
+    sensors::ThermalZone {
+        name: asString(&getNode(json, "name")),
+        zoneType: asString(&getNode(json, "type")),
+        temperature: asFloat(&getNode(json, "temperature")),
+        coolingDevices: asStringList(&getNode(json, "cooling-devices"))
+    }
+}
+
+fn parseCoolingDevice(json: &Json) -> sensors::CoolingDevice {
+    sensors::CoolingDevice {
+        name: asString(&getNode(json, "name")),
+        deviceType: asString(&getNode(json, "type")),
+        currentState: asUsize(&getNode(json, "current-state")).unwrap_or(0) as u64,
+        maxState: asUsize(&getNode(json, "max-state")).unwrap_or(0) as u64
+    }
+}
+
+fn parseTemperatureSensor(json: &Json) -> sensors::TemperatureSensor {
+    sensors::TemperatureSensor {
+        label: asString(&getNode(json, "label")),
+        temperature: asFloat(&getNode(json, "temperature"))
+    }
+}
+
+fn parseNetworkRate(json: &Json) -> network::NetworkRate {
+    network::NetworkRate {
+        download: asFloat(&getNode(json, "download")).unwrap_or(0_f32),
+        upload: asFloat(&getNode(json, "upload")).unwrap_or(0_f32)
+    }
+}
+
+fn parseNetworkRoute(json: &Json) -> network::NetworkRoute {
+    network::NetworkRoute {
+        routeType: match asString(&getNode(json, "type")).as_str() {
+            "TCP6" => network::RouteType::TCP6,
+            "UDP" => network::RouteType::UDP,
+            "UDP6" => network::RouteType::UDP6,
+            _ => network::RouteType::TCP
+        },
+        localAddress: asString(&getNode(json, "local-address")),
+        localPort: asUsize(&getNode(json, "local-port")).unwrap_or(0) as u16,
+        remoteAddress: asString(&getNode(json, "remote-address")),
+        remotePort: asUsize(&getNode(json, "remote-port")).unwrap_or(0) as u16,
+        routeStatus: network::RouteStatus::fromString(&asString(&getNode(json, "route-status"))),
+        txQueue: asUsize(&getNode(json, "tx-queue")).unwrap_or(0) as u64,
+        rxQueue: asUsize(&getNode(json, "rx-queue")).unwrap_or(0) as u64,
+        timer: network::SocketTimer::fromString(&asString(&getNode(json, "timer"))),
+        retransmits: asUsize(&getNode(json, "retransmits")).unwrap_or(0) as u64
+    }
+}
+
+fn parseNetworkInterfaces(json: &Json) -> Vec<network::NetworkInterface> {
+    let mut interfaces = Vec::<network::NetworkInterface>::new();
+
+    for node in json.getAllNodes() {
+        let interface = match asJson(&node.getContent()) {
+            Some(interface) => interface,
+            None => continue
+        };
+
+        interfaces.push(network::NetworkInterface {
+            name: node.getLabel(),
+            macAddress: asString(&getNode(&interface, "mac")),
+            interfaceType: match asString(&getNode(&interface, "interface-type")).as_str() {
+                "virtual" => network::InterfaceType::Virtual,
+                _ => network::InterfaceType::Physical
+            }
+        });
+    }
+
+    return interfaces;
+}
+
+fn parseInterfaceStatistics(json: &Json) -> HashMap<String, network::InterfaceStatistics> {
+    let mut statistics = HashMap::<String, network::InterfaceStatistics>::new();
+
+    for node in json.getAllNodes() {
+        let interface = match asJson(&node.getContent()).and_then(|interface| asJson(&getNode(&interface, "statistics"))) {
+            Some(interface) => interface,
+            None => continue
+        };
+
+        let getUsize = |label: &str| asUsize(&getNode(&interface, label)).unwrap_or(0);
+
+        statistics.insert(node.getLabel(), network::InterfaceStatistics {
+            rxBytes: getUsize("rx-bytes"),
+            txBytes: getUsize("tx-bytes"),
+            rxPackets: getUsize("rx-packets"),
+            txPackets: getUsize("tx-packets"),
+            rxErrors: getUsize("rx-errors"),
+            txErrors: getUsize("tx-errors"),
+            rxDropped: getUsize("rx-dropped"),
+            txDropped: getUsize("tx-dropped")
+        });
+    }
+
+    return statistics;
+}
+
+fn parseIPv4(json: &Json) -> network::IPv4 {
+    network::IPv4 {
+        address: asString(&getNode(json, "address")),
+        interface: asString(&getNode(json, "interface")),
+        broadcast: String::new(),
+        netmask: String::new(),
+        cidr: String::new()
+    }
+}
+
+// addresses are exported as `<address>/<prefix length>` below each interface, the scope follows from the address
+fn parseIPv6(interfaces: &Json) -> Vec<network::IPv6> {
+    let mut addresses = Vec::<network::IPv6>::new();
+
+    for node in interfaces.getAllNodes() {
+        let Some(interface) = asJson(&node.getContent()) else {
+            continue
+        };
+
+        for address in asStringList(&getNode(&interface, "ipv6")) {
+            let Some((address, prefixLength)) = address.split_once('/') else {
+                continue
+            };
+
+            let scope = match address.parse::<std::net::Ipv6Addr>() {
+                Ok(parsed) if parsed.is_loopback() => "host",
+                Ok(parsed) if parsed.segments()[0] & 0xffc0 == 0xfe80 => "link",
+                Ok(parsed) if parsed.segments()[0] & 0xffc0 == 0xfec0 => "site",
+                _ => "global"
+            };
+
+            addresses.push(network::IPv6 {
+                address: address.to_string(),
+                interface: node.getLabel(),
+                prefixLength: prefixLength.parse::<u8>().unwrap_or(0),
+                scope: scope.to_string()
+            });
+        }
+    }
+
+    return addresses;
+}
+
+fn parseVram(json: &Json) -> gpu::VRAM {
+    gpu::VRAM {
+        size: asFloat(&getNode(json, "size-gib")).map(|size| ByteSize::fromBytes(gibToBytes(size))),
+        usage: asFloat(&getNode(json, "usage")),
+        frequency: asUsize(&getNode(json, "frequency")).map(Frequency::fromMhz),
+        busWidth: asUsize(&getNode(json, "bus-width"))
+    }
+}
+
+fn parseGpu(json: &Json) -> gpu::Gpu {
+    gpu::Gpu {
+        card: asString(&getNode(json, "card")),
+        pciAddress: asString(&getNode(json, "pci-address")),
+        driver: asString(&getNode(json, "driver")),
+        usage: asFloat(&getNode(json, "usage")),
+        vram: parseVram(&asJson(&getNode(json, "vram")).unwrap_or_else(Json::new)),
+        metrics: asJson(&getNode(json, "metrics")).map(|node| parseGpuMetrics(&node)),
+        displays: asStringList(&getNode(json, "displays"))
+    }
+}
+
+fn parseGpuMetrics(json: &Json) -> gpu::GpuMetrics {
+    let getU16 = |label: &str| asUsize(&getNode(json, label)).unwrap_or(0) as u16;
+    let getFrequency = |label: &str| Frequency::fromMhz(asUsize(&getNode(json, label)).unwrap_or(0));
+
+    gpu::GpuMetrics {
+        temperatureEdge: getU16("temperature-edge"),
+        temperatureHotspot: getU16("temperature-hotspot"),
+        temperatureMem: getU16("temperature-mem"),
+        temperatureVrgfx: getU16("temperature-vrgfx"),
+        temperatureVrsoc: getU16("temperature-vrsoc"),
+        temperatureVrmem: getU16("temperature-vrmem"),
+        averageSocketPower: getU16("average-socket-power"),
+        averageMultimediaActivity: getU16("average-multimedia-activity"),
+        averageGfxclkFrequency: getFrequency("average-gfxclk-frequency"),
+        averageSockclkFrequency: getFrequency("average-sockclk-frequency"),
+        averageUclkFrequency: getFrequency("average-uclk-frequency"),
+        currentGfxclk: getFrequency("current-gfxclk"),
+        currentSockclk: getFrequency("current-sockclk"),
+        currentUclk: getFrequency("current-uclk"),
+        currentVclk0: getFrequency("current-vclk0"),
+        currentDclk0: getFrequency("current-dclk0"),
+        currentVclk1: getFrequency("current-vclk1"),
+        currentDclk1: getFrequency("current-dclk1"),
+        throttleStatus: asUsize(&getNode(json, "throttle-status")).unwrap_or(0) as u32,
+        currentFanSpeed: getU16("current-fan-speed"),
+        pcieLinkWidth: getU16("pcie-link-width"),
+        pcieLinkSpeed: getU16("pcie-link-speed"),
+    }
+}
+
+fn parseBusInput(json: &Json) -> bus::BusInput {
+    bus::BusInput {
+        bus: asUsize(&getNode(json, "bus")).unwrap_or(0) as u16,
+        vendor: asUsize(&getNode(json, "vendor")).unwrap_or(0) as u16,
+        product: asUsize(&getNode(json, "product")).unwrap_or(0) as u16,
+        version: asUsize(&getNode(json, "version")).unwrap_or(0) as u16,
+        name: asString(&getNode(json, "name")),
+        physicalPath: asString(&getNode(json, "physical-path")),
+        sysfsPath: asString(&getNode(json, "sysfs-path")),
+        uniqueIdentifier: String::new(),
+        handles: asStringList(&getNode(json, "handles")),
+        properties: asUsize(&getNode(json, "properties")).unwrap_or(0),
+        events: asUsize(&getNode(json, "events")).unwrap_or(0),
+        keys: asStringList(&getNode(json, "keys")),
+        miscellaneousEvents: asUsize(&getNode(json, "miscellaneous-events")).unwrap_or(0),
+        led: asUsize(&getNode(json, "led")).unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SysInfo, SysPaths};
+
+    #[test]
+    fn exportsSectionErrors() {
+        let root = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/root/missing");
+        let snapshot = SysPaths::new(root).scope(|| SystemSnapshot::builder().ram().sensors().collect());
+
+        assert!(snapshot.ram.is_none());
+        assert!(matches!(snapshot.value("ram.error.code"), Some(NodeContent::String(code)) if code == "NotFound"));
+        assert!(matches!(snapshot.value("battery.error.code"), Some(NodeContent::String(code)) if code == "NotFound"));
+        assert!(matches!(snapshot.value("temperature-sensors.error.code"), Some(NodeContent::String(code)) if code == "NotFound"));
+
+        let parsed = SystemSnapshot::fromJson(&snapshot.toJson().toString()).unwrap();
+        let error = &parsed.errors["ram"];
+
+        assert!(parsed.ram.is_none());
+        assert_eq!(error.code, ErrorCode::NotFound);
+        assert_eq!(error.path, Some(PathBuf::from(root).join("proc/meminfo")));
+
+        // codes of newer versions are kept as they are rather than reported as another kind
+        assert_eq!(ErrorCode::fromString("Throttled"), ErrorCode::Unknown(String::from("Throttled")));
+        assert_eq!(ErrorCode::fromString("Throttled").toString(), "Throttled");
+    }
+
+    #[test]
+    fn exportsSelectedSections() {
+        let root = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/root/desktop");
+        let json = SysPaths::new(root).scope(|| SysInfo::builder().ram().motherboard().withoutMotherboard().withUnits().export());
+
+        let node = |label: &str| json.getAllNodes().into_iter().find(|node| node.getLabel() == label).map(|node| node.getContent());
+
+        assert!(matches!(node("ram"), Some(NodeContent::Json(_))));
+        assert!(matches!(node("motherboard"), Some(NodeContent::Null)));
+        assert!(matches!(node("units"), Some(NodeContent::Json(_))));
+    }
+
+    #[test]
+    fn readsBackSensorsAndIPv6() {
+        let mut snapshot = SystemSnapshot::empty();
+
+        snapshot.categorizedTemperatures = Some(vec![
+            sensors::CategorizedTemperature {
+                category: sensors::SensorCategory::CpuCcd(1),
+                device: String::from("hwmon2"),
+                chip: String::from("k10temp"),
+                label: String::from("Tccd2"),
+                temperature: 51.5
+            },
+            sensors::CategorizedTemperature {
+                category: sensors::SensorCategory::Other(String::from("acpitz/temp1")),
+                device: String::from("hwmon0"),
+                chip: String::from("acpitz"),
+                label: String::from("temp1"),
+                temperature: 30_f32
+            }
+        ]);
+        snapshot.thermalZones = Some(vec![sensors::ThermalZone {
+            name: String::from("thermal_zone0"),
+            zoneType: String::from("x86_pkg_temp"),
+            temperature: Some(48_f32),
+            coolingDevices: vec![String::from("cooling_device3")]
+        }]);
+        snapshot.coolingDevices = Some(vec![sensors::CoolingDevice {
+            name: String::from("cooling_device3"),
+            deviceType: String::from("Fan"),
+            currentState: 2,
+            maxState: 5
+        }]);
+
+        snapshot.networkRate = Some(network::NetworkRate { download: 0_f32, upload: 0_f32 });
+        snapshot.networkInterfaces = Some(vec![network::NetworkInterface {
+            name: String::from("eth0"),
+            macAddress: String::from("52:54:00:12:34:56"),
+            interfaceType: network::InterfaceType::Physical
+        }]);
+        snapshot.ipv6 = Some(vec![
+            network::IPv6 {
+                address: String::from("fe80::5054:ff:fe12:3456"),
+                interface: String::from("eth0"),
+                prefixLength: 64,
+                scope: String::from("link")
+            },
+            network::IPv6 {
+                address: String::from("2001:db8::2"),
+                interface: String::from("eth0"),
+                prefixLength: 64,
+                scope: String::from("global")
+            }
+        ]);
+
+        let parsed = SystemSnapshot::fromJson(&snapshot.toJson().toString()).unwrap();
+        assert_eq!(parsed.toJson().toString(), snapshot.toJson().toString());
+
+        let temperatures = parsed.categorizedTemperatures.unwrap();
+        assert!(matches!(temperatures[0].category, sensors::SensorCategory::CpuCcd(1)));
+        assert_eq!(temperatures[1].chip, "acpitz");
+        assert_eq!(parsed.coolingDevices.unwrap()[0].maxState, 5);
+
+        let scopes: Vec<String> = parsed.ipv6.unwrap().into_iter().map(|address| address.scope).collect();
+        assert_eq!(scopes, vec!["link", "global"]);
+    }
+}
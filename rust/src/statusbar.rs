@@ -0,0 +1,245 @@
+use std::fmt;
+use rsjson::{Json, Node, NodeContent};
+use crate::{cpu, ram, network, sensors};
+use crate::sensors::{BatteryStatus, SensorCategory};
+use crate::utils::{*};
+
+/// Severity of a status bar block, used as waybar class and mapped to the i3status-rust states
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlockState {
+    Normal,
+    Warning,
+    Critical
+}
+
+impl BlockState {
+    pub fn fromString(state: &str) -> BlockState {
+        match state {
+            "warning" => BlockState::Warning,
+            "critical" => BlockState::Critical,
+            _ => BlockState::Normal
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            BlockState::Normal => String::from("normal"),
+            BlockState::Warning => String::from("warning"),
+            BlockState::Critical => String::from("critical")
+        }
+    }
+
+    // states accepted by the i3status-rust `custom` block
+    fn i3statusState(&self) -> &'static str {
+        match self {
+            BlockState::Normal => "Idle",
+            BlockState::Warning => "Warning",
+            BlockState::Critical => "Critical"
+        }
+    }
+}
+
+impl fmt::Display for BlockState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Values from which a block turns to the warning and critical states
+///
+/// When `warning` is greater than `critical` lower values are worse, e.g. `Thresholds::new(20_f32, 10_f32)` for battery capacity
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Thresholds {
+    pub warning: f32,
+    pub critical: f32
+}
+
+impl Thresholds {
+    pub fn new(warning: f32, critical: f32) -> Thresholds {
+        return Thresholds {
+            warning: warning,
+            critical: critical
+        };
+    }
+
+    /// Returns the state of a block showing `value`
+    pub fn state(&self, value: f32) -> BlockState {
+        let descending = self.warning > self.critical;
+        let reached = |threshold: f32| if descending { value <= threshold } else { value >= threshold };
+
+        if reached(self.critical) {
+            return BlockState::Critical;
+        }
+
+        if reached(self.warning) {
+            return BlockState::Warning;
+        }
+
+        return BlockState::Normal;
+    }
+}
+
+/// Contains the content of a status bar block
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusBlock {
+    pub text: String,
+    pub tooltip: String,
+    /// Value between 0 and 100, used by waybar to pick format icons
+    pub percentage: Option<u8>,
+    pub state: BlockState
+}
+
+impl StatusBlock {
+    /// Renders the block as the JSON line read by a waybar custom module with `"return-type": "json"`
+    pub fn toWaybar(&self) -> String {
+        let mut json = Json::new();
+
+        json.addNode(Node::new("text", NodeContent::String(self.text.clone())));
+        json.addNode(Node::new("tooltip", NodeContent::String(self.tooltip.clone())));
+        json.addNode(Node::new("class", NodeContent::String(self.state.toString())));
+
+        if let Some(percentage) = self.percentage {
+            json.addNode(Node::new("percentage", NodeContent::Int(percentage as usize)));
+        }
+
+        return json.toString();
+    }
+
+    /// Renders the block as the JSON line read by an i3status-rust custom block with `json = true`
+    pub fn toI3status(&self) -> String {
+        let mut json = Json::new();
+
+        json.addNode(Node::new("text", NodeContent::String(self.text.clone())));
+        json.addNode(Node::new("state", NodeContent::String(self.state.i3statusState().to_string())));
+
+        return json.toString();
+    }
+}
+
+impl fmt::Display for StatusBlock {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+fn percentage(value: f32) -> Option<u8> {
+    return Some(value.round().clamp(0_f32, 100_f32) as u8);
+}
+
+/// Returns a block with the average CPU usage, and usage by mode and load in the tooltip
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::statusbar::{cpuBlock, Thresholds};
+///
+/// println!("{}", cpuBlock(Thresholds::new(70_f32, 90_f32)).toWaybar());
+/// ```
+pub fn cpuBlock(thresholds: Thresholds) -> StatusBlock {
+    let usage = cpu::cpuUsage().average;
+    let load = cpu::getLoad();
+
+    return StatusBlock {
+        text: format!("{:.0}%", usage.total),
+        tooltip: format!(
+            "user {:.1}%, system {:.1}%, iowait {:.1}%\nload {}", usage.user, usage.system, usage.iowait, load
+        ),
+        percentage: percentage(usage.total),
+        state: thresholds.state(usage.total)
+    };
+}
+
+/// Returns a block with the RAM usage in percentage, and used and total RAM in the tooltip
+pub fn memoryBlock(thresholds: Thresholds) -> StatusBlock {
+    let usage = ram::ramUsage();
+    let size = ram::ramSize();
+    let used = ByteSize::fromBytes((size.bytes() as f64 * usage as f64 / 100_f64) as u64);
+
+    return StatusBlock {
+        text: format!("{:.0}%", usage),
+        tooltip: format!("{} used of {}", used, size),
+        percentage: percentage(usage),
+        state: thresholds.state(usage)
+    };
+}
+
+/// Returns a block with the download and upload rate, and the IPv4 address of each interface in the tooltip
+pub fn networkBlock() -> StatusBlock {
+    let rate = network::networkRate();
+
+    let tooltip = network::getIPv4().iter()
+        .filter(|address| address.interface != "lo")
+        .map(|address| format!("{}: {}", address.interface, address))
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    return StatusBlock {
+        text: format!("↓ {}/s ↑ {}/s", ByteSize::fromBytes(rate.download as u64), ByteSize::fromBytes(rate.upload as u64)),
+        tooltip: tooltip,
+        percentage: None,
+        state: BlockState::Normal
+    };
+}
+
+/// Returns a block with the capacity of every battery combined, `None` if the system has no battery
+///
+/// Thresholds only apply while discharging, e.g. `Thresholds::new(20_f32, 10_f32)`
+pub fn batteryBlock(thresholds: Thresholds) -> Option<StatusBlock> {
+    let battery = sensors::aggregateBattery()?;
+
+    let state = match battery.status {
+        BatteryStatus::Discharging => thresholds.state(battery.capacity as f32),
+        _ => BlockState::Normal
+    };
+
+    return Some(StatusBlock {
+        text: format!("{}%", battery.capacity),
+        tooltip: battery.status.toString(),
+        percentage: Some(battery.capacity.min(100)),
+        state: state
+    });
+}
+
+/// Returns a block with the CPU temperature, every temperature sensor in the tooltip, `None` if no sensor is found
+///
+/// The CPU package sensor is shown when present, the hottest CPU core or CCD otherwise, then the hottest sensor of any kind
+pub fn temperatureBlock(thresholds: Thresholds) -> Option<StatusBlock> {
+    let temperatures = sensors::categorizedTemperatures();
+
+    let hottest = |filter: fn(&SensorCategory) -> bool| {
+        temperatures.iter()
+            .filter(|temperature| filter(&temperature.category))
+            .map(|temperature| temperature.temperature)
+            .fold(None, |hottest: Option<f32>, temperature| Some(hottest.map_or(temperature, |hottest| hottest.max(temperature))))
+    };
+
+    let temperature = hottest(|category| matches!(category, SensorCategory::CpuPackage))
+        .or_else(|| hottest(|category| matches!(category, SensorCategory::CpuCore(_) | SensorCategory::CpuCcd(_))))
+        .or_else(|| hottest(|_| true))?;
+
+    return Some(StatusBlock {
+        text: format!("{:.0}°C", temperature),
+        tooltip: temperatures.iter().map(|temperature| temperature.to_string()).collect::<Vec<String>>().join("\n"),
+        percentage: None,
+        state: thresholds.state(temperature)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appliesThresholds() {
+        let usage = Thresholds::new(70_f32, 90_f32);
+        assert_eq!(usage.state(12_f32), BlockState::Normal);
+        assert_eq!(usage.state(70_f32), BlockState::Warning);
+        assert_eq!(usage.state(95_f32), BlockState::Critical);
+
+        let capacity = Thresholds::new(20_f32, 10_f32);
+        assert_eq!(capacity.state(80_f32), BlockState::Normal);
+        assert_eq!(capacity.state(15_f32), BlockState::Warning);
+        assert_eq!(capacity.state(10_f32), BlockState::Critical);
+    }
+}
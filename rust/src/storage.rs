@@ -1,8 +1,17 @@
-use std::{fs, path};
+use std::{fmt, fs, io, path};
+#[cfg(feature = "zfs")]
+use std::collections::HashMap;
+#[cfg(feature = "zfs")]
+use std::process::Command;
+use std::time::SystemTime;
+use std::ffi::CString;
+use std::os::fd::AsRawFd;
+use std::os::unix::fs::PermissionsExt;
 use crate::utils::{*};
 
 /// Contains NVME device information
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NvmeDevice {
     pub device: String,
     pub pcieAddress: String,
@@ -13,11 +22,21 @@ pub struct NvmeDevice {
     pub partitions: Vec<StoragePartition>
 }
 
+impl fmt::Display for NvmeDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f, "{} {} (PCIe x{} @ {} GT/s, {} partitions)",
+            self.device, self.model, self.pcieLanes, self.linkSpeedGTs, self.partitions.len()
+        )
+    }
+}
+
 /// Bytes size data structure implementing methods to convert in various size orders
 /// The methods allow the convertion in the various size orders, both in base 1000 and base 1024
 /// ```rust
 /// let byteSize = /* some sysutil function returning ByteSize */;
-/// byteSize.b(); // bytes
+/// byteSize.bytes(); // bytes
+/// byteSize.bits(); // bits
 ///
 /// byteSize.kb(); // 1000 bytes
 /// byteSize.kib(); // 1024 bytes
@@ -32,18 +51,30 @@ pub struct NvmeDevice {
 /// byteSize.tib(); // 1.099.511.627.776 bytes
 /// ```
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct ByteSize {
-    bytes: usize
+    bytes: u64
 }
 
 impl ByteSize {
-    fn new(value: usize) -> ByteSize {
+    pub(crate) fn new(value: u64) -> ByteSize {
         return ByteSize {
             bytes: value
         }
     }
 
-    pub fn b(&self) -> usize {
+    /// Returns the size in bytes
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Returns the size in bits
+    pub fn bits(&self) -> u64 {
+        self.bytes * 8
+    }
+
+    #[deprecated(note = "ambiguous with `sysutil::ByteSize::b()`, use `bytes()` instead")]
+    pub fn b(&self) -> u64 {
         self.bytes
     }
 
@@ -82,6 +113,7 @@ impl ByteSize {
 
 /// Encloses device name, size and startpoint relative to a partition
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StoragePartition {
     pub device: String,
     pub mountPoint: String,
@@ -90,41 +122,412 @@ pub struct StoragePartition {
     pub startPoint: usize
 }
 
+impl fmt::Display for StoragePartition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.mountPoint.is_empty() {
+            write!(f, "{} (not mounted)", self.device)
+        } else {
+            write!(f, "{} on {} ({})", self.device, self.mountPoint, self.fileSystem)
+        }
+    }
+}
+
+/// Represents how a storage device is attached to the system
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StorageTransport {
+    Local,
+    Iscsi,
+    Nbd,
+    Rbd
+}
+
+impl StorageTransport {
+    pub fn fromString(transport: &str) -> StorageTransport {
+        match transport {
+            "iscsi" => StorageTransport::Iscsi,
+            "nbd" => StorageTransport::Nbd,
+            "rbd" => StorageTransport::Rbd,
+            _ => StorageTransport::Local
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            StorageTransport::Local => String::from("local"),
+            StorageTransport::Iscsi => String::from("iscsi"),
+            StorageTransport::Nbd => String::from("nbd"),
+            StorageTransport::Rbd => String::from("rbd")
+        }
+    }
+}
+
+impl fmt::Display for StorageTransport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
 /// Contains information relative to a storage device in the system
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StorageDevice {
     pub model: String,
     pub device: String,
     pub size: ByteSize,
-    pub partitions: Vec<StoragePartition>
+    pub partitions: Vec<StoragePartition>,
+    pub transport: StorageTransport,
+    /// iSCSI target IQN, `pool/image` for rbd, backend for nbd
+    pub target: Option<String>,
+    /// iSCSI portal address or Ceph monitor addresses
+    pub host: Option<String>
 }
 
-/// Returns a vector containing all NVME devices found in the system
-pub fn nvmeDevices() -> Vec<NvmeDevice> {
+impl fmt::Display for StorageDevice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} ({} partitions)", self.device, self.model, self.partitions.len())?;
+
+        match &self.target {
+            Some(target) if self.transport != StorageTransport::Local => write!(f, " via {} {}", self.transport, target),
+            _ => Ok(())
+        }
+    }
+}
+
+/// Contains space usage of a mounted filesystem
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FilesystemUsage {
+    pub mountPoint: String,
+    pub size: ByteSize,
+    pub used: ByteSize,
+    pub available: ByteSize,
+    /// Total inodes, 0 on filesystems allocating inodes dynamically (e.g. btrfs)
+    pub inodes: u64,
+    pub usedInodes: u64,
+    pub freeInodes: u64
+}
+
+impl FilesystemUsage {
+    /// Returns used space in percentage, computed over the space available to unprivileged users
+    pub fn usage(&self) -> f32 {
+        let total = self.used.bytes() + self.available.bytes();
+
+        if total == 0 {
+            return 0_f32;
+        }
+
+        self.used.bytes() as f32 * 100_f32 / total as f32
+    }
+
+    /// Returns used inodes in percentage, 0 when the filesystem does not report a fixed inode count
+    pub fn inodeUsage(&self) -> f32 {
+        if self.inodes == 0 {
+            return 0_f32;
+        }
+
+        self.usedInodes as f32 * 100_f32 / self.inodes as f32
+    }
+}
+
+impl fmt::Display for FilesystemUsage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {:.1}% used of {:.2} GiB", self.mountPoint, self.usage(), self.size.gib())
+    }
+}
+
+/// Returns space usage of the filesystem mounted at `mountPoint`, returns `None` if it's not possible to retrieve data
+pub fn filesystemUsage(mountPoint: &str) -> Option<FilesystemUsage> {
     linuxCheck();
 
-    let mut devices = Vec::<NvmeDevice>::new();
-    let mut deviceNames = Vec::<String>::new();
-    let mut error = false;
+    let path = CString::new(mountPoint).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
 
-    match fs::read_dir(path::Path::new("/sys/class/nvme")) {
-        Err(_) => {
-            error = true
-        },
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
 
-        Ok(devs) => {
-            for device in devs {
-                deviceNames.push(device.unwrap().file_name().to_str().unwrap().to_string());
+    let blockSize = stat.f_frsize as u64;
+    let size = stat.f_blocks as u64 * blockSize;
+    let free = stat.f_bfree as u64 * blockSize;
+
+    Some(FilesystemUsage {
+        mountPoint: mountPoint.to_string(),
+        size: ByteSize::new(size),
+        used: ByteSize::new(size - free),
+        available: ByteSize::new(stat.f_bavail as u64 * blockSize),
+        inodes: stat.f_files as u64,
+        usedInodes: (stat.f_files as u64).saturating_sub(stat.f_ffree as u64),
+        freeInodes: stat.f_ffree as u64
+    })
+}
+
+/// Returns space and inode usage of every mounted filesystem, filesystems reporting no size (e.g. proc, sysfs) are skipped
+pub fn mountedFilesystems() -> Vec<FilesystemUsage> {
+    linuxCheck();
+
+    let mut filesystems = Vec::<FilesystemUsage>::new();
+
+    for mount in readFile("/proc/mounts").split("\n") {
+        let Some(mountPoint) = mount.split(" ").nth(1) else {
+            continue
+        };
+
+        // /proc/mounts escapes spaces and tabs in mount points as octal sequences
+        let mountPoint = mountPoint.replace("\\040", " ").replace("\\011", "\t");
+
+        if filesystems.iter().any(|filesystem| filesystem.mountPoint == mountPoint) {
+            continue
+        }
+
+        if let Some(usage) = filesystemUsage(&mountPoint) {
+            if usage.size.bytes() > 0 {
+                filesystems.push(usage);
             }
         }
     }
 
-    if error {
-        return devices;
+    return filesystems;
+}
+
+/// Contains discard support of a mounted filesystem
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FilesystemTrim {
+    pub device: String,
+    pub mountPoint: String,
+    pub fileSystem: String,
+    /// Whether the underlying block device accepts discard requests
+    pub supported: bool,
+    /// Whether the filesystem is mounted with online discard (`discard` mount option)
+    pub discardEnabled: bool,
+    pub discardGranularity: ByteSize
+}
+
+impl fmt::Display for FilesystemTrim {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = if !self.supported {
+            "unsupported"
+        } else if self.discardEnabled {
+            "online discard"
+        } else {
+            "periodic trim"
+        };
+
+        write!(f, "{} on {}: {}", self.device, self.mountPoint, status)
     }
+}
 
-    let partitions = readFile("/proc/partitions");
-    let mountPoints = readFile("/proc/mounts");
+/// Contains discard support of the mounted filesystems and the last periodic trim
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrimSupport {
+    pub filesystems: Vec<FilesystemTrim>,
+    /// Last run of `fstrim.timer`, `None` if the timer is not used or has never run
+    pub lastFstrim: Option<SystemTime>
+}
+
+// returns the sysfs queue directory of a block device, partitions use the queue of their parent disk
+fn blockQueue(device: &str) -> Option<path::PathBuf> {
+    let resolved = fs::canonicalize(device).ok()?;
+    let name = resolved.file_name()?.to_str()?;
+    let block = fs::canonicalize(sysPath(format!("/sys/class/block/{}", name))).ok()?;
+
+    if block.join("queue").is_dir() {
+        return Some(block.join("queue"));
+    }
+
+    let parent = block.parent()?.join("queue");
+    return if parent.is_dir() { Some(parent) } else { None };
+}
+
+/// Returns discard support of every mounted block device filesystem and the last `fstrim.timer` run
+pub fn trimSupport() -> TrimSupport {
+    linuxCheck();
+
+    let mut filesystems = Vec::<FilesystemTrim>::new();
+
+    for mount in readFile("/proc/mounts").split("\n") {
+        let splitted: Vec<&str> = mount.split(" ").collect();
+        if splitted.len() < 4 || !splitted[0].starts_with("/dev/") {
+            continue
+        }
+
+        let Some(queue) = blockQueue(splitted[0]) else {
+            continue
+        };
+
+        let granularity = readFile(queue.join("discard_granularity")).parse::<u64>().unwrap_or(0);
+        let maxBytes = readFile(queue.join("discard_max_bytes")).parse::<u64>().unwrap_or(0);
+
+        // btrfs uses `discard=async` and `discard=sync`
+        let discardEnabled = splitted[3].split(",").any(|option| option == "discard" || option.starts_with("discard="));
+
+        filesystems.push(FilesystemTrim {
+            device: splitted[0].to_string(),
+            mountPoint: splitted[1].replace("\\040", " ").replace("\\011", "\t"),
+            fileSystem: splitted[2].to_string(),
+            supported: granularity > 0 && maxBytes > 0,
+            discardEnabled: discardEnabled,
+            discardGranularity: ByteSize::new(granularity)
+        });
+    }
+
+    // systemd touches the stamp file every time a persistent timer elapses
+    let lastFstrim = fs::metadata(sysPath("/var/lib/systemd/timers/stamp-fstrim.timer"))
+        .and_then(|metadata| metadata.modified())
+        .ok();
+
+    return TrimSupport {
+        filesystems: filesystems,
+        lastFstrim: lastFstrim
+    };
+}
+
+/// Represents a hardening issue found on a mount
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MountIssue {
+    MissingNodev,
+    MissingNosuid,
+    MissingNoexec,
+    /// The root of the filesystem is writable by every user and lacks the sticky bit
+    WorldWritable
+}
+
+impl MountIssue {
+    pub fn toString(&self) -> String {
+        match self {
+            MountIssue::MissingNodev => String::from("missing nodev"),
+            MountIssue::MissingNosuid => String::from("missing nosuid"),
+            MountIssue::MissingNoexec => String::from("missing noexec"),
+            MountIssue::WorldWritable => String::from("world-writable")
+        }
+    }
+}
+
+impl fmt::Display for MountIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains a hardening issue of a mounted filesystem
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MountFinding {
+    pub device: String,
+    pub mountPoint: String,
+    pub fileSystem: String,
+    pub issue: MountIssue
+}
+
+impl fmt::Display for MountFinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({}): {}", self.mountPoint, self.fileSystem, self.issue)
+    }
+}
+
+// mount points commonly expected to carry restrictive options, and the options expected on them
+const HARDENED_MOUNTS: [(&str, bool, bool, bool); 7] = [
+    // (mount point, nodev, nosuid, noexec)
+    ("/tmp", true, true, true),
+    ("/var/tmp", true, true, true),
+    ("/dev/shm", true, true, true),
+    ("/home", true, true, false),
+    ("/boot", true, true, false),
+    ("/media", true, true, true),
+    ("/run/media", true, true, true)
+];
+
+/// Returns mounts missing `nodev`, `nosuid` or `noexec` where commonly expected (e.g. `/tmp`, `/dev/shm`, removable media)
+/// and world-writable filesystems without the sticky bit, pseudo filesystems under `/proc` and `/sys` are skipped
+pub fn mountAudit() -> Vec<MountFinding> {
+    linuxCheck();
+
+    let mut findings = Vec::<MountFinding>::new();
+
+    for mount in readFile("/proc/mounts").split("\n") {
+        let splitted: Vec<&str> = mount.split(" ").collect();
+        if splitted.len() < 4 {
+            continue
+        }
+
+        let mountPoint = splitted[1].replace("\\040", " ").replace("\\011", "\t");
+        if mountPoint.starts_with("/proc") || mountPoint.starts_with("/sys") {
+            continue
+        }
+
+        let options: Vec<&str> = splitted[3].split(",").collect();
+        let mut issues = Vec::<MountIssue>::new();
+
+        // removable media is mounted below the expected directory, e.g. /run/media/user/usb
+        let expected = HARDENED_MOUNTS.iter().find(|(point, ..)| {
+            mountPoint == *point || ((*point == "/media" || *point == "/run/media") && mountPoint.starts_with(&format!("{}/", point)))
+        });
+
+        if let Some((_, nodev, nosuid, noexec)) = expected {
+            if *nodev && !options.contains(&"nodev") {
+                issues.push(MountIssue::MissingNodev);
+            }
+
+            if *nosuid && !options.contains(&"nosuid") {
+                issues.push(MountIssue::MissingNosuid);
+            }
+
+            if *noexec && !options.contains(&"noexec") {
+                issues.push(MountIssue::MissingNoexec);
+            }
+        }
+
+        if let Ok(metadata) = fs::metadata(&mountPoint) {
+            let mode = metadata.permissions().mode();
+
+            if !options.contains(&"ro") && mode & 0o002 != 0 && mode & 0o1000 == 0 {
+                issues.push(MountIssue::WorldWritable);
+            }
+        }
+
+        for issue in issues {
+            findings.push(MountFinding {
+                device: splitted[0].to_string(),
+                mountPoint: mountPoint.clone(),
+                fileSystem: splitted[2].to_string(),
+                issue: issue
+            });
+        }
+    }
+
+    return findings;
+}
+
+/// Returns a vector containing all NVME devices found in the system, empty if they can not be read
+pub fn nvmeDevices() -> Vec<NvmeDevice> {
+    return tryNvmeDevices().unwrap_or_default();
+}
+
+/// Same as `nvmeDevices()`, but returns an error when the device information can not be read or parsed
+///
+/// A system without NVMe devices is not an error
+pub fn tryNvmeDevices() -> Result<Vec<NvmeDevice>, SysError> {
+    linuxCheck();
+
+    let mut devices = Vec::<NvmeDevice>::new();
+
+    let deviceNames: Vec<String> = match tryReadDir("/sys/class/nvme") {
+        Err(SysError::NotFound(_)) => return Ok(devices),
+        Err(error) => return Err(error),
+        Ok(entries) => entries.iter().map(|entry| entry.file_name().to_string_lossy().to_string()).collect()
+    };
+
+    let partitions = tryReadFile("/proc/partitions")?;
+    let mountPoints = tryReadFile("/proc/mounts")?;
+
+    // `major minor #blocks name`, the size is in 1 KiB blocks
+    let partitionSize = |line: &str| -> Result<u64, SysError> {
+        let collected = line.split_whitespace().collect::<Vec<&str>>();
+        tryParse("/proc/partitions", collected.get(2).unwrap_or(&line))
+    };
 
     for device in deviceNames {
         let path = format!("/sys/class/nvme/{}", device.clone());
@@ -132,21 +535,17 @@ pub fn nvmeDevices() -> Vec<NvmeDevice> {
         let deviceAddress = readFile(format!("{}/address", path));
         let model = readFile(format!("{}/model", path));
 
+        // fabrics controllers (NVMe over TCP, RDMA, ...) have no PCIe link
         let linkSpeed = {
             let tmp = readFile(format!("{}/device/current_link_speed", path));
-            tmp.split(" ").collect::<Vec<&str>>()[0].to_string().parse::<f32>().unwrap()
+            tmp.split(" ").next().and_then(|speed| speed.parse::<f32>().ok()).unwrap_or(0_f32)
         };
-        let pcieLanes: usize = readFile(format!("{}/device/current_link_width", path)).parse().unwrap();
+        let pcieLanes: usize = readFile(format!("{}/device/current_link_width", path)).parse().unwrap_or(0);
 
-        let mut size: usize = 0;
+        let mut size: u64 = 0;
         for partitionLine in partitions.split("\n") {
             if partitionLine.contains(&device) {
-
-                let splitted = partitionLine.split(" ");
-                let collected = splitted.collect::<Vec<&str>>();
-
-                let tempSize = collected[collected.len() - 2];
-                size = tempSize.parse::<usize>().unwrap();
+                size = partitionSize(partitionLine)?;
                 break
             }
         }
@@ -156,11 +555,17 @@ pub fn nvmeDevices() -> Vec<NvmeDevice> {
             if mount.contains(&device) {
 
                 let splitted: Vec<&str> = mount.split(" ").collect();
-                let device = splitted.first().unwrap().to_string();
-                let deviceName: String = device.split("/").collect::<Vec<&str>>().get(2).unwrap().to_string();
+                if splitted.len() < 3 {
+                    return Err(SysError::parse("/proc/mounts", mount));
+                }
 
-                let mountPoint = splitted.get(1).unwrap().to_string();
-                let fileSystem = splitted.get(2).unwrap().to_string();
+                let device = splitted[0].to_string();
+                let Some(deviceName) = device.split("/").nth(2).map(|name| name.to_string()) else {
+                    continue
+                };
+
+                let mountPoint = splitted[1].to_string();
+                let fileSystem = splitted[2].to_string();
 
                 let mut partSize = ByteSize{bytes: 0};
                 let mut startPoint = 0;
@@ -169,15 +574,15 @@ pub fn nvmeDevices() -> Vec<NvmeDevice> {
                     if partition.contains(&deviceName) {
 
                         partSize = ByteSize{
-                            bytes: {
-                                let tmp = partition.split(" ").collect::<Vec<&str>>();
-                                tmp[tmp.len() - 2].parse::<usize>().unwrap()
-                            }
+                            bytes: partitionSize(partition)?
                         };
 
-                        startPoint = {
-                            let tmp = readFile(format!("/sys/class/block/{}/start", deviceName));
-                            tmp.parse::<usize>().unwrap()
+                        // a whole namespace mounted without a partition table has no `start` file
+                        let startPath = format!("/sys/class/block/{}/start", deviceName);
+                        startPoint = match tryReadFile(&startPath) {
+                            Ok(content) => tryParse(&startPath, &content)?,
+                            Err(SysError::NotFound(_)) => 0,
+                            Err(error) => return Err(error)
                         };
                         break
                     }
@@ -206,10 +611,55 @@ pub fn nvmeDevices() -> Vec<NvmeDevice> {
         );
     }
 
-    return devices;
+    return Ok(devices);
 }
 
-/// Returns a vector containing all storage devices (NVME excluded) in the system
+fn nonEmpty(content: String) -> Option<String> {
+    return if content.is_empty() { None } else { Some(content) };
+}
+
+// returns transport, target and host of a block device
+fn deviceTransport(name: &str) -> (StorageTransport, Option<String>, Option<String>) {
+    if name.starts_with("nbd") {
+        return (StorageTransport::Nbd, nonEmpty(readFile(format!("/sys/class/block/{}/backend", name))), None);
+    }
+
+    if let Some(id) = name.strip_prefix("rbd") {
+        let base = format!("/sys/bus/rbd/devices/{}", id);
+        let target = format!("{}/{}", readFile(format!("{}/pool", base)), readFile(format!("{}/name", base)));
+
+        // config_info starts with the comma separated monitor addresses
+        let host = readFile(format!("{}/config_info", base)).split(" ").next().unwrap_or("").to_string();
+
+        return (StorageTransport::Rbd, Some(target), nonEmpty(host));
+    }
+
+    // SCSI disks attached through an iSCSI session live under hostN/sessionM in sysfs
+    let Ok(device) = fs::canonicalize(sysPath(format!("/sys/class/block/{}/device", name))) else {
+        return (StorageTransport::Local, None, None);
+    };
+
+    let Some(session) = device.iter().filter_map(|component| component.to_str()).find(|component| component.starts_with("session")) else {
+        return (StorageTransport::Local, None, None);
+    };
+
+    let target = nonEmpty(readFile(format!("/sys/class/iscsi_session/{}/targetname", session)));
+    let connectionPrefix = format!("connection{}:", session.trim_start_matches("session"));
+
+    let host = fs::read_dir(sysPath("/sys/class/iscsi_connection")).ok().and_then(|connections| {
+        connections.flatten()
+            .find(|connection| connection.file_name().to_string_lossy().starts_with(&connectionPrefix))
+            .map(|connection| format!(
+                "{}:{}",
+                readFile(connection.path().join("persistent_address")),
+                readFile(connection.path().join("persistent_port"))
+            ))
+    });
+
+    return (StorageTransport::Iscsi, target, host);
+}
+
+/// Returns a vector containing all storage devices (NVME excluded) in the system, including iSCSI, nbd and rbd disks
 pub fn storageDevices() -> Vec<StorageDevice> {
     linuxCheck();
 
@@ -218,13 +668,13 @@ pub fn storageDevices() -> Vec<StorageDevice> {
     let mut error = false;
     let mut dirContent = Vec::<String>::new();
 
-    match fs::read_dir(path::Path::new(baseDir)) {
+    match fs::read_dir(sysPath(baseDir)) {
         Err(_) => {
             error = true;
         },
         Ok(content) => {
-            for dir in content {
-                dirContent.push(dir.unwrap().file_name().to_str().unwrap().to_string())
+            for dir in content.flatten() {
+                dirContent.push(dir.file_name().to_string_lossy().to_string())
             }
         }
     }
@@ -238,7 +688,15 @@ pub fn storageDevices() -> Vec<StorageDevice> {
     let mut devices = Vec::<StorageDevice>::new();
     for dir in &dirContent {
 
-        if !dir.contains("sd") || dir.len() != 3 {
+        let isScsi = dir.starts_with("sd") && dir.len() == 3;
+        let isNetwork = (dir.starts_with("nbd") || dir.starts_with("rbd")) && !dir.contains("p");
+
+        if !isScsi && !isNetwork {
+            continue
+        }
+
+        // nbd devices exist even when not connected to a server
+        if dir.starts_with("nbd") && !sysPath(format!("{}/{}/pid", baseDir, dir)).exists() {
             continue
         }
 
@@ -246,7 +704,7 @@ pub fn storageDevices() -> Vec<StorageDevice> {
         let size = ByteSize{
             bytes: {
                 let tmp = readFile(format!("{}/{}/size", baseDir, dir));
-                tmp.parse::<usize>().unwrap_or(0)
+                tmp.parse::<u64>().unwrap_or(0)
             }
         };
 
@@ -254,11 +712,8 @@ pub fn storageDevices() -> Vec<StorageDevice> {
         let mut partitions = Vec::<StoragePartition>::new();
 
         for partitionDir in &dirContent {
-            if ! partitionDir.contains(&dir.clone()) {
-                continue
-            }
-
-            if partitionDir.len() <= 3 {
+            // partitions are listed as children of their disk, e.g. sda/sda1 or nbd1/nbd1p1
+            if partitionDir == dir || !sysPath(format!("{}/{}/{}", baseDir, dir, partitionDir)).exists() {
                 continue
             }
 
@@ -281,8 +736,8 @@ pub fn storageDevices() -> Vec<StorageDevice> {
                 if mount.contains(&format!("/dev/{} ", partitionDir).to_string()) {
                     let splittedLine: Vec<&str> = mount.split(" ").collect();
 
-                    mountPoint = splittedLine.get(1).unwrap().to_string();
-                    filesystem = splittedLine.get(2).unwrap().to_string();
+                    mountPoint = splittedLine.get(1).unwrap_or(&"").to_string();
+                    filesystem = splittedLine.get(2).unwrap_or(&"").to_string();
 
                     break
                 }
@@ -299,15 +754,809 @@ pub fn storageDevices() -> Vec<StorageDevice> {
             );
         }
 
+        let (transport, target, host) = deviceTransport(dir);
+
         devices.push(
             StorageDevice {
                 model: model,
                 device: device,
                 size: size,
-                partitions: partitions
+                partitions: partitions,
+                transport: transport,
+                target: target,
+                host: host
             }
         );
     }
 
     return devices;
 }
+
+/// Represents the health of a ZFS pool
+#[cfg(feature = "zfs")]
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ZfsHealth {
+    Online,
+    Degraded,
+    Faulted,
+    Offline,
+    Unavailable,
+    Removed,
+    Suspended,
+    Unknown
+}
+
+#[cfg(feature = "zfs")]
+impl ZfsHealth {
+    pub fn fromString(health: &str) -> ZfsHealth {
+        match health {
+            "ONLINE" => ZfsHealth::Online,
+            "DEGRADED" => ZfsHealth::Degraded,
+            "FAULTED" => ZfsHealth::Faulted,
+            "OFFLINE" => ZfsHealth::Offline,
+            "UNAVAIL" => ZfsHealth::Unavailable,
+            "REMOVED" => ZfsHealth::Removed,
+            "SUSPENDED" => ZfsHealth::Suspended,
+            _ => ZfsHealth::Unknown
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            ZfsHealth::Online => String::from("ONLINE"),
+            ZfsHealth::Degraded => String::from("DEGRADED"),
+            ZfsHealth::Faulted => String::from("FAULTED"),
+            ZfsHealth::Offline => String::from("OFFLINE"),
+            ZfsHealth::Unavailable => String::from("UNAVAIL"),
+            ZfsHealth::Removed => String::from("REMOVED"),
+            ZfsHealth::Suspended => String::from("SUSPENDED"),
+            ZfsHealth::Unknown => String::from("UNKNOWN")
+        }
+    }
+}
+
+#[cfg(feature = "zfs")]
+impl fmt::Display for ZfsHealth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains I/O counters of a ZFS dataset
+#[cfg(feature = "zfs")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZfsDataset {
+    pub name: String,
+    pub reads: u64,
+    pub writes: u64,
+    pub bytesRead: ByteSize,
+    pub bytesWritten: ByteSize
+}
+
+/// Contains information about an imported ZFS pool
+#[cfg(feature = "zfs")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZfsPool {
+    pub name: String,
+    pub health: ZfsHealth,
+    /// Capacity values are read from `zpool list`, `None` if the command is not available
+    pub size: Option<ByteSize>,
+    pub allocated: Option<ByteSize>,
+    pub free: Option<ByteSize>,
+    /// Free space fragmentation, in percentage
+    pub fragmentation: Option<u8>,
+    pub datasets: Vec<ZfsDataset>
+}
+
+#[cfg(feature = "zfs")]
+impl fmt::Display for ZfsPool {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.name, self.health)?;
+
+        match (&self.allocated, &self.size) {
+            (Some(allocated), Some(size)) => write!(f, ": {:.2} GiB of {:.2} GiB used", allocated.gib(), size.gib()),
+            _ => Ok(())
+        }
+    }
+}
+
+/// Contains ZFS ARC (adaptive replacement cache) statistics
+#[cfg(feature = "zfs")]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZfsArc {
+    pub size: ByteSize,
+    pub targetSize: ByteSize,
+    pub maximumSize: ByteSize,
+    pub hits: u64,
+    pub misses: u64
+}
+
+#[cfg(feature = "zfs")]
+impl ZfsArc {
+    /// Returns the ARC hit ratio, in percentage
+    pub fn hitRatio(&self) -> f32 {
+        let total = self.hits + self.misses;
+
+        if total == 0 {
+            return 0_f32;
+        }
+
+        self.hits as f32 * 100_f32 / total as f32
+    }
+}
+
+// parses a named kstat file, whose lines after the two header lines are `name type data`
+#[cfg(feature = "zfs")]
+fn parseKstat(content: &str) -> HashMap<String, String> {
+    let mut values = HashMap::<String, String>::new();
+
+    for line in content.lines().skip(2) {
+        let splitted: Vec<&str> = line.split_whitespace().collect();
+
+        if splitted.len() >= 3 {
+            values.insert(splitted[0].to_string(), splitted[2..].join(" "));
+        }
+    }
+
+    return values;
+}
+
+// reads size, allocated, free and fragmentation of every pool from `zpool list`
+#[cfg(feature = "zfs")]
+fn zpoolList() -> HashMap<String, (u64, u64, u64, Option<u8>)> {
+    let mut pools = HashMap::<String, (u64, u64, u64, Option<u8>)>::new();
+
+    let Ok(output) = Command::new("zpool").args(["list", "-Hp", "-o", "name,size,alloc,free,frag"]).output() else {
+        return pools;
+    };
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let splitted: Vec<&str> = line.split("\t").collect();
+        if splitted.len() != 5 {
+            continue
+        }
+
+        let value = |index: usize| splitted[index].parse::<u64>().unwrap_or(0);
+        pools.insert(
+            splitted[0].to_string(),
+            (value(1), value(2), value(3), splitted[4].trim_end_matches("%").parse::<u8>().ok())
+        );
+    }
+
+    return pools;
+}
+
+/// Returns every imported ZFS pool with its datasets, empty if ZFS is not loaded
+#[cfg(feature = "zfs")]
+pub fn zfsPools() -> Vec<ZfsPool> {
+    linuxCheck();
+
+    let mut pools = Vec::<ZfsPool>::new();
+
+    let Ok(entries) = fs::read_dir(sysPath("/proc/spl/kstat/zfs")) else {
+        return pools;
+    };
+
+    let capacities = zpoolList();
+
+    for entry in entries.flatten() {
+        let statePath = entry.path().join("state");
+        if !statePath.exists() {
+            continue
+        }
+
+        let name = entry.file_name().to_string_lossy().to_string();
+        let mut datasets = Vec::<ZfsDataset>::new();
+
+        for objset in fs::read_dir(entry.path()).into_iter().flatten().flatten() {
+            if !objset.file_name().to_string_lossy().starts_with("objset-") {
+                continue
+            }
+
+            let stats = parseKstat(&readFile(objset.path()));
+            let value = |key: &str| stats.get(key).and_then(|value| value.parse::<u64>().ok()).unwrap_or(0);
+
+            datasets.push(ZfsDataset {
+                name: stats.get("dataset_name").cloned().unwrap_or_default(),
+                reads: value("reads"),
+                writes: value("writes"),
+                bytesRead: ByteSize::new(value("nread")),
+                bytesWritten: ByteSize::new(value("nwritten"))
+            });
+        }
+
+        datasets.sort_by(|first, second| first.name.cmp(&second.name));
+        let capacity = capacities.get(&name);
+
+        pools.push(ZfsPool {
+            health: ZfsHealth::fromString(&readFile(statePath)),
+            size: capacity.map(|capacity| ByteSize::new(capacity.0)),
+            allocated: capacity.map(|capacity| ByteSize::new(capacity.1)),
+            free: capacity.map(|capacity| ByteSize::new(capacity.2)),
+            fragmentation: capacity.and_then(|capacity| capacity.3),
+            name: name,
+            datasets: datasets
+        });
+    }
+
+    return pools;
+}
+
+/// Returns ZFS ARC statistics, `None` if ZFS is not loaded
+#[cfg(feature = "zfs")]
+pub fn zfsArc() -> Option<ZfsArc> {
+    linuxCheck();
+
+    let stats = parseKstat(&fs::read_to_string(sysPath("/proc/spl/kstat/zfs/arcstats")).ok()?);
+    let value = |key: &str| stats.get(key).and_then(|value| value.parse::<u64>().ok()).unwrap_or(0);
+
+    return Some(ZfsArc {
+        size: ByteSize::new(value("size")),
+        targetSize: ByteSize::new(value("c")),
+        maximumSize: ByteSize::new(value("c_max")),
+        hits: value("hits"),
+        misses: value("misses")
+    });
+}
+
+/// Represents the kind of a SMART self-test
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelfTestKind {
+    Short,
+    /// Extended self-test, which reads the whole surface and can take hours
+    Long,
+    Other(u8)
+}
+
+impl SelfTestKind {
+    pub fn toString(&self) -> String {
+        match self {
+            SelfTestKind::Short => String::from("short"),
+            SelfTestKind::Long => String::from("long"),
+            SelfTestKind::Other(code) => format!("other ({:#x})", code)
+        }
+    }
+}
+
+impl fmt::Display for SelfTestKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Represents the outcome of a SMART self-test
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SelfTestResult {
+    Passed,
+    Aborted,
+    Interrupted,
+    Failed,
+    InProgress,
+    Unknown
+}
+
+impl SelfTestResult {
+    pub fn toString(&self) -> String {
+        match self {
+            SelfTestResult::Passed => String::from("passed"),
+            SelfTestResult::Aborted => String::from("aborted"),
+            SelfTestResult::Interrupted => String::from("interrupted"),
+            SelfTestResult::Failed => String::from("failed"),
+            SelfTestResult::InProgress => String::from("in progress"),
+            SelfTestResult::Unknown => String::from("unknown")
+        }
+    }
+}
+
+impl fmt::Display for SelfTestResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains an entry of the SMART self-test log
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfTestEntry {
+    pub kind: SelfTestKind,
+    pub result: SelfTestResult,
+    /// Power-on hours of the drive when the test ended
+    pub powerOnHours: u64,
+    /// First LBA that failed the test, if reported
+    pub failingLba: Option<u64>
+}
+
+impl fmt::Display for SelfTestEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} test {} at {} hours", self.kind, self.result, self.powerOnHours)
+    }
+}
+
+/// Contains the SMART self-test log of a drive, entries are sorted from the most recent
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfTestLog {
+    /// Self-test currently running, if any
+    pub running: Option<SelfTestKind>,
+    /// Remaining work of the running self-test, in percentage
+    pub remaining: Option<u8>,
+    pub entries: Vec<SelfTestEntry>
+}
+
+// from linux/nvme_ioctl.h and the NVMe base specification
+const NVME_IOCTL_ADMIN_CMD: libc::c_ulong = 0xC0484E41;
+const NVME_ADMIN_GET_LOG_PAGE: u8 = 0x02;
+const NVME_ADMIN_DEVICE_SELF_TEST: u8 = 0x14;
+const NVME_LOG_DEVICE_SELF_TEST: u32 = 0x06;
+const NVME_LOG_SMART: u32 = 0x02;
+const NVME_SELF_TEST_LOG_LENGTH: usize = 564;
+
+// from linux/hdreg.h and the ATA command set
+const HDIO_DRIVE_CMD: libc::c_ulong = 0x031F;
+const ATA_SMART: u8 = 0xB0;
+const ATA_SMART_READ_LOG: u8 = 0xD5;
+const ATA_SMART_READ_DATA: u8 = 0xD0;
+const ATA_SMART_IMMEDIATE_OFFLINE: u8 = 0xD4;
+const ATA_LOG_SELF_TEST: u8 = 0x06;
+const ATA_SECTOR_LENGTH: usize = 512;
+
+#[repr(C)]
+#[derive(Default)]
+struct NvmeAdminCommand {
+    opcode: u8,
+    flags: u8,
+    reserved: u16,
+    nsid: u32,
+    cdw2: u32,
+    cdw3: u32,
+    metadata: u64,
+    address: u64,
+    metadataLength: u32,
+    dataLength: u32,
+    cdw10: u32,
+    cdw11: u32,
+    cdw12: u32,
+    cdw13: u32,
+    cdw14: u32,
+    cdw15: u32,
+    timeoutMs: u32,
+    result: u32
+}
+
+fn isNvme(device: &str) -> bool {
+    return device.trim_start_matches("/dev/").starts_with("nvme");
+}
+
+fn nvmeAdminCommand(device: &str, command: &mut NvmeAdminCommand) -> io::Result<()> {
+    let file = fs::File::open(device)?;
+
+    if unsafe { libc::ioctl(file.as_raw_fd(), NVME_IOCTL_ADMIN_CMD as _, command as *mut NvmeAdminCommand) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    return Ok(());
+}
+
+// `arguments` holds command, sector number, feature and sector count, followed by the returned sectors
+fn ataDriveCommand(device: &str, arguments: &mut [u8]) -> io::Result<()> {
+    let file = fs::File::open(device)?;
+
+    if unsafe { libc::ioctl(file.as_raw_fd(), HDIO_DRIVE_CMD as _, arguments.as_mut_ptr()) } < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    return Ok(());
+}
+
+/// Starts a SMART self-test on `device` (e.g. `/dev/sda`, `/dev/nvme0`) and returns immediately, requires root privileges
+///
+/// Progress and results can be read with `smartSelfTestLog()`
+pub fn runSmartSelfTest(device: &str, kind: SelfTestKind) -> io::Result<()> {
+    linuxCheck();
+
+    let code = match kind {
+        SelfTestKind::Short => 1_u8,
+        SelfTestKind::Long => 2_u8,
+        SelfTestKind::Other(code) => code
+    };
+
+    if isNvme(device) {
+        let mut command = NvmeAdminCommand {
+            opcode: NVME_ADMIN_DEVICE_SELF_TEST,
+            nsid: 0xFFFFFFFF,
+            cdw10: code as u32,
+            ..Default::default()
+        };
+
+        return nvmeAdminCommand(device, &mut command);
+    }
+
+    let mut arguments = [ATA_SMART, code, ATA_SMART_IMMEDIATE_OFFLINE, 0];
+    return ataDriveCommand(device, &mut arguments);
+}
+
+fn selfTestKind(code: u8) -> SelfTestKind {
+    match code {
+        1 => SelfTestKind::Short,
+        2 => SelfTestKind::Long,
+        _ => SelfTestKind::Other(code)
+    }
+}
+
+// parses the NVMe device self-test log page: current operation, completion, then 20 results of 28 bytes
+fn parseNvmeSelfTestLog(log: &[u8]) -> SelfTestLog {
+    let running = match log.first().map(|byte| byte & 0x0F) {
+        None | Some(0) => None,
+        Some(code) => Some(selfTestKind(code))
+    };
+
+    let mut entries = Vec::<SelfTestEntry>::new();
+
+    for entry in log.get(4..).unwrap_or_default().chunks_exact(28) {
+        let result = match entry[0] & 0x0F {
+            0x0 => SelfTestResult::Passed,
+            0x1 | 0x3 | 0x4 | 0x8 | 0x9 => SelfTestResult::Aborted,
+            0x2 => SelfTestResult::Interrupted,
+            0x5..=0x7 => SelfTestResult::Failed,
+            0xF => continue,
+            _ => SelfTestResult::Unknown
+        };
+
+        let read = |start: usize| u64::from_le_bytes(entry[start..start + 8].try_into().unwrap());
+
+        entries.push(SelfTestEntry {
+            kind: selfTestKind(entry[0] >> 4),
+            result: result,
+            powerOnHours: read(4),
+            failingLba: if entry[2] & 0x02 != 0 { Some(read(16)) } else { None }
+        });
+    }
+
+    return SelfTestLog {
+        running: running,
+        remaining: running.map(|_| 100_u8.saturating_sub(log[1] & 0x7F)),
+        entries: entries
+    };
+}
+
+// parses the ATA SMART self-test log: 21 circular descriptors of 24 bytes, byte 508 indexes the most recent one
+fn parseAtaSelfTestLog(log: &[u8]) -> SelfTestLog {
+    let mut entries = Vec::<SelfTestEntry>::new();
+    let mut running = None;
+    let mut remaining = None;
+
+    let latest = log.get(508).copied().unwrap_or(0) as usize;
+
+    for offset in 0..21 {
+        // descriptor indexes are 1-based, walk backwards from the most recent one
+        let index = (latest + 21 - 1 - offset) % 21;
+        let Some(descriptor) = log.get(2 + index * 24..2 + (index + 1) * 24) else {
+            break
+        };
+
+        if descriptor[0] == 0 {
+            continue
+        }
+
+        // bit 7 marks captive tests, which share codes with the offline ones
+        let kind = selfTestKind(descriptor[0] & 0x7F);
+        let status = descriptor[1] >> 4;
+
+        let result = match status {
+            0x0 => SelfTestResult::Passed,
+            0x1 => SelfTestResult::Aborted,
+            0x2 => SelfTestResult::Interrupted,
+            0x3..=0x8 => SelfTestResult::Failed,
+            0xF => SelfTestResult::InProgress,
+            _ => SelfTestResult::Unknown
+        };
+
+        if result == SelfTestResult::InProgress && running.is_none() {
+            running = Some(kind);
+            remaining = Some((descriptor[1] & 0x0F) * 10);
+        }
+
+        let failingLba = u32::from_le_bytes(descriptor[5..9].try_into().unwrap());
+
+        entries.push(SelfTestEntry {
+            kind: kind,
+            result: result,
+            powerOnHours: u16::from_le_bytes([descriptor[2], descriptor[3]]) as u64,
+            failingLba: if result == SelfTestResult::Failed && failingLba != 0xFFFFFFFF { Some(failingLba as u64) } else { None }
+        });
+    }
+
+    return SelfTestLog {
+        running: running,
+        remaining: remaining,
+        entries: entries
+    };
+}
+
+// reads a log page of the whole NVMe controller
+fn nvmeLogPage(device: &str, page: u32, length: usize) -> io::Result<Vec<u8>> {
+    let mut log = vec![0_u8; length];
+    let dwords = (length / 4 - 1) as u32;
+
+    let mut command = NvmeAdminCommand {
+        opcode: NVME_ADMIN_GET_LOG_PAGE,
+        nsid: 0xFFFFFFFF,
+        address: log.as_mut_ptr() as u64,
+        dataLength: length as u32,
+        cdw10: page | (dwords << 16),
+        ..Default::default()
+    };
+
+    nvmeAdminCommand(device, &mut command)?;
+    return Ok(log);
+}
+
+/// Reads the SMART self-test log of `device` (e.g. `/dev/sda`, `/dev/nvme0`), requires root privileges
+pub fn smartSelfTestLog(device: &str) -> io::Result<SelfTestLog> {
+    linuxCheck();
+
+    if isNvme(device) {
+        let log = nvmeLogPage(device, NVME_LOG_DEVICE_SELF_TEST, NVME_SELF_TEST_LOG_LENGTH)?;
+        return Ok(parseNvmeSelfTestLog(&log));
+    }
+
+    let mut arguments = [0_u8; 4 + ATA_SECTOR_LENGTH];
+    arguments[..4].copy_from_slice(&[ATA_SMART, ATA_LOG_SELF_TEST, ATA_SMART_READ_LOG, 1]);
+
+    ataDriveCommand(device, &mut arguments)?;
+    return Ok(parseAtaSelfTestLog(&arguments[4..]));
+}
+
+/// Contains SSD wear normalized across NVMe and SATA drives
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SsdEndurance {
+    pub device: String,
+    /// Estimated life used, from 0 (new) to 100 (rated endurance reached)
+    pub lifeUsed: u8,
+    /// Total data written by the host, `None` if the drive does not report it
+    ///
+    /// SATA drives report it through attribute 241 or 246, read as a count of 512 bytes LBAs; drives counting in
+    /// larger units (often 32 MiB) are reported far below the actual amount
+    pub bytesWritten: Option<ByteSize>
+}
+
+impl fmt::Display for SsdEndurance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}% life used", self.device, self.lifeUsed)?;
+
+        match &self.bytesWritten {
+            Some(written) => write!(f, ", {:.2} TB written", written.tb()),
+            None => Ok(())
+        }
+    }
+}
+
+// SATA attributes whose normalized value counts the remaining life down from 100, in order of preference
+const ATA_WEAR_ATTRIBUTES: [u8; 4] = [
+    231, // SSD_Life_Left
+    233, // Media_Wearout_Indicator
+    202, // Percent_Lifetime_Remain
+    177  // Wear_Leveling_Count
+];
+
+// SATA attributes counting written LBAs, assumed to be 512 bytes; many vendors count 32 MiB units or GiB in them instead,
+// which the raw value does not tell apart
+const ATA_WRITTEN_ATTRIBUTES: [u8; 2] = [
+    241, // Total_LBAs_Written
+    246  // Total_Host_Sector_Write
+];
+
+// parses the SMART data table: 30 attributes of 12 bytes (id, flags, value, worst, raw) starting at byte 2
+fn parseAtaAttributes(data: &[u8]) -> Vec<(u8, u8, u64)> {
+    let mut attributes = Vec::<(u8, u8, u64)>::new();
+
+    for attribute in data.get(2..362).unwrap_or_default().chunks_exact(12) {
+        if attribute[0] == 0 {
+            continue
+        }
+
+        let mut raw = [0_u8; 8];
+        raw[..6].copy_from_slice(&attribute[5..11]);
+
+        attributes.push((attribute[0], attribute[3], u64::from_le_bytes(raw)));
+    }
+
+    return attributes;
+}
+
+/// Returns wear of the SSD `device` (e.g. `/dev/sda`, `/dev/nvme0`) normalized to a single life-used percentage, requires root privileges
+///
+/// NVMe drives report `percentage_used` and data units written, SATA drives the first available wear attribute
+/// (e.g. media wearout indicator, wear leveling count) and total LBAs written
+pub fn ssdEndurance(device: &str) -> io::Result<SsdEndurance> {
+    linuxCheck();
+
+    if isNvme(device) {
+        let log = nvmeLogPage(device, NVME_LOG_SMART, ATA_SECTOR_LENGTH)?;
+
+        // data units are thousands of 512 bytes blocks
+        let unitsWritten = u128::from_le_bytes(log[48..64].try_into().unwrap());
+
+        return Ok(SsdEndurance {
+            device: device.to_string(),
+            lifeUsed: log[5].min(100),
+            bytesWritten: Some(ByteSize::new((unitsWritten * 512_000).min(u64::MAX as u128) as u64))
+        });
+    }
+
+    let mut arguments = [0_u8; 4 + ATA_SECTOR_LENGTH];
+    arguments[..4].copy_from_slice(&[ATA_SMART, 0, ATA_SMART_READ_DATA, 1]);
+
+    ataDriveCommand(device, &mut arguments)?;
+    let attributes = parseAtaAttributes(&arguments[4..]);
+
+    let remaining = ATA_WEAR_ATTRIBUTES.iter().find_map(|id| {
+        attributes.iter().find(|attribute| attribute.0 == *id).map(|attribute| attribute.1)
+    }).ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "no wear indicator reported by the drive"))?;
+
+    let written = ATA_WRITTEN_ATTRIBUTES.iter().find_map(|id| {
+        attributes.iter().find(|attribute| attribute.0 == *id).map(|attribute| attribute.2)
+    });
+
+    return Ok(SsdEndurance {
+        device: device.to_string(),
+        lifeUsed: 100_u8.saturating_sub(remaining.min(100)),
+        bytesWritten: written.map(|lbas| ByteSize::new(lbas * 512))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NVMe device self-test log page: current operation and completion, then 20 results of 28 bytes made of status,
+    // segment, valid diagnostic information, power on hours at 4 and failing LBA at 16; unused results have status 0xF
+    fn nvmeSelfTestLog(operation: u8, completion: u8, results: &[(u8, u8, u64, u64)]) -> Vec<u8> {
+        let mut log = vec![operation, completion, 0, 0];
+
+        for index in 0..20 {
+            let mut result = [0u8; 28];
+            let (status, valid, powerOnHours, failingLba) = results.get(index).copied().unwrap_or((0x0F, 0, 0, 0));
+
+            result[0] = status;
+            result[2] = valid;
+            result[4..12].copy_from_slice(&powerOnHours.to_le_bytes());
+            result[12..16].copy_from_slice(&1_u32.to_le_bytes());
+            result[16..24].copy_from_slice(&failingLba.to_le_bytes());
+            log.extend_from_slice(&result);
+        }
+
+        return log;
+    }
+
+    // ATA SMART self-test log: revision, then 21 descriptors of 24 bytes made of test, status, timestamp in hours,
+    // checkpoint and failing LBA at 5, with the 1-based index of the most recent descriptor at 508
+    fn ataSelfTestLog(latest: u8, descriptors: &[(usize, u8, u8, u16, u32)]) -> Vec<u8> {
+        let mut log = vec![0u8; 512];
+        log[0] = 1;
+        log[508] = latest;
+
+        for (index, test, status, hours, failingLba) in descriptors {
+            let offset = 2 + index * 24;
+
+            log[offset] = *test;
+            log[offset + 1] = *status;
+            log[offset + 2..offset + 4].copy_from_slice(&hours.to_le_bytes());
+            log[offset + 5..offset + 9].copy_from_slice(&failingLba.to_le_bytes());
+        }
+
+        return log;
+    }
+
+    // ATA SMART data: revision, then 30 attributes of 12 bytes made of id, flags, value, worst and a 48 bits raw value
+    fn ataSmartData(attributes: &[(u8, u8, u64)]) -> Vec<u8> {
+        let mut data = vec![0u8; 512];
+        data[0] = 0x10;
+
+        for (index, (id, value, raw)) in attributes.iter().enumerate() {
+            let offset = 2 + index * 12;
+
+            data[offset] = *id;
+            data[offset + 1] = 0x32;
+            data[offset + 3] = *value;
+            data[offset + 4] = *value;
+            data[offset + 5..offset + 11].copy_from_slice(&raw.to_le_bytes()[..6]);
+        }
+
+        return data;
+    }
+
+    type Entry = (SelfTestKind, SelfTestResult, u64, Option<u64>);
+
+    fn entries(log: &SelfTestLog) -> Vec<Entry> {
+        return log.entries.iter().map(|entry| (entry.kind, entry.result, entry.powerOnHours, entry.failingLba)).collect();
+    }
+
+    #[test]
+    fn parsesSelfTestLogs() {
+        let cases: [(SelfTestLog, Option<SelfTestKind>, Option<u8>, Vec<Entry>); 5] = [
+            (parseNvmeSelfTestLog(&nvmeSelfTestLog(0, 0, &[
+                (0x20, 0x01, 1200, 0), (0x17, 0x03, 1100, 0x1234), (0x10, 0x01, 1000, 0)
+            ])), None, None, vec![
+                (SelfTestKind::Long, SelfTestResult::Passed, 1200, None),
+                (SelfTestKind::Short, SelfTestResult::Failed, 1100, Some(0x1234)),
+                (SelfTestKind::Short, SelfTestResult::Passed, 1000, None)
+            ]),
+            (parseNvmeSelfTestLog(&nvmeSelfTestLog(0x02, 25, &[(0x11, 0x01, 900, 0)])), Some(SelfTestKind::Long), Some(75), vec![
+                (SelfTestKind::Short, SelfTestResult::Aborted, 900, None)
+            ]),
+            (parseAtaSelfTestLog(&ataSelfTestLog(3, &[
+                (0, 0x01, 0x00, 500, 0xFFFFFFFF), (1, 0x02, 0x70, 510, 0xABCDEF), (2, 0x01, 0x00, 520, 0xFFFFFFFF)
+            ])), None, None, vec![
+                (SelfTestKind::Short, SelfTestResult::Passed, 520, None),
+                (SelfTestKind::Long, SelfTestResult::Failed, 510, Some(0xABCDEF)),
+                (SelfTestKind::Short, SelfTestResult::Passed, 500, None)
+            ]),
+            // the most recent descriptor is the first one, older ones wrap around to the end of the log
+            (parseAtaSelfTestLog(&ataSelfTestLog(1, &[
+                (0, 0x01, 0xF3, 101, 0xFFFFFFFF), (19, 0x02, 0x00, 90, 0xFFFFFFFF), (20, 0x81, 0x10, 100, 0xFFFFFFFF)
+            ])), Some(SelfTestKind::Short), Some(30), vec![
+                (SelfTestKind::Short, SelfTestResult::InProgress, 101, None),
+                (SelfTestKind::Short, SelfTestResult::Aborted, 100, None),
+                (SelfTestKind::Long, SelfTestResult::Passed, 90, None)
+            ]),
+            (parseAtaSelfTestLog(&[]), None, None, vec![])
+        ];
+
+        for (log, running, remaining, expected) in cases {
+            assert_eq!(log.running, running);
+            assert_eq!(log.remaining, remaining);
+            assert_eq!(entries(&log), expected);
+        }
+    }
+
+    #[test]
+    fn parsesAtaAttributes() {
+        let smartData = ataSmartData(&[(5, 100, 0), (9, 99, 12345), (233, 97, 0), (241, 100, 0x123456789A)]);
+
+        let cases: [(&[u8], Vec<(u8, u8, u64)>); 3] = [
+            (&smartData, vec![(5, 100, 0), (9, 99, 12345), (233, 97, 0), (241, 100, 0x123456789A)]),
+            (&smartData[..14], vec![]),
+            (&[], vec![])
+        ];
+
+        for (data, expected) in cases {
+            assert_eq!(parseAtaAttributes(data), expected);
+        }
+    }
+
+    #[test]
+    fn mountsWholeNamespaces() {
+        let root = std::env::temp_dir().join(format!("sysutil-nvme-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let files = [
+            ("sys/class/nvme/nvme0/model", "Test NVMe"),
+            ("sys/class/block/nvme0n1p1/start", "2048"),
+            ("proc/partitions", "major minor  #blocks  name\n\n 259 0 1000 nvme0n1\n 259 1 500 nvme0n1p1\n 259 2 2000 nvme1n1\n"),
+            ("proc/mounts", "/dev/nvme0n1p1 /boot vfat rw 0 0\n/dev/nvme1n1 /data ext4 rw 0 0\n")
+        ];
+        for (file, content) in files {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::write(root.join(file), content).unwrap();
+        }
+        fs::create_dir_all(root.join("sys/class/nvme/nvme1")).unwrap();
+
+        let devices = crate::SysPaths::new(&root).scope(tryNvmeDevices).unwrap();
+        let _ = fs::remove_dir_all(&root);
+
+        let mut partitions = devices.iter()
+            .flat_map(|device| device.partitions.iter().map(|partition| (partition.mountPoint.clone(), partition.startPoint)))
+            .collect::<Vec<(String, usize)>>();
+        partitions.sort();
+
+        assert_eq!(partitions, vec![(String::from("/boot"), 2048), (String::from("/data"), 0)]);
+    }
+}
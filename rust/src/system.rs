@@ -0,0 +1,94 @@
+use std::{fmt, fs, io};
+use crate::utils::{*};
+
+/// Contains the identifiers of the machine, to be used as a stable host key
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MachineIdentity {
+    /// systemd / D-Bus machine ID, generated at install time
+    pub machineId: Option<String>,
+    /// SMBIOS product UUID, survives reinstalls
+    pub productUuid: Option<String>,
+    pub boardSerial: Option<String>,
+    /// Whether some identifiers could not be read for lack of permissions (DMI serials and UUID require root)
+    pub restricted: bool
+}
+
+impl MachineIdentity {
+    /// Returns the most stable available identifier: product UUID, then board serial, then machine ID
+    pub fn hostKey(&self) -> Option<String> {
+        return self.productUuid.clone()
+            .or_else(|| self.boardSerial.clone())
+            .or_else(|| self.machineId.clone());
+    }
+}
+
+impl fmt::Display for MachineIdentity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.hostKey() {
+            Some(key) => write!(f, "{}", key),
+            None => write!(f, "unknown")
+        }
+    }
+}
+
+// values vendors leave in DMI fields they do not fill
+const PLACEHOLDER_IDENTIFIERS: [&str; 8] = [
+    "To Be Filled By O.E.M.", "Default string", "System Serial Number", "Not Specified",
+    "None", "0123456789", "00000000-0000-0000-0000-000000000000", "03000200-0400-0500-0006-000700080009"
+];
+
+/// Reads the first usable identifier among `paths`, also telling whether any of them was denied
+fn readIdentifier(paths: &[&str]) -> (Option<String>, bool) {
+    let mut denied = false;
+
+    for path in paths {
+        match fs::read_to_string(sysPath(path)) {
+            Ok(content) => {
+                let value = content.trim().trim_end_matches('\0').to_string();
+
+                let placeholder = value.is_empty()
+                    || value.chars().all(|character| character == 'F' || character == 'f' || character == '-')
+                    || PLACEHOLDER_IDENTIFIERS.iter().any(|placeholder| value.eq_ignore_ascii_case(placeholder));
+
+                if !placeholder {
+                    return (Some(value), denied);
+                }
+            },
+
+            Err(error) => {
+                if error.kind() == io::ErrorKind::PermissionDenied {
+                    denied = true;
+                }
+
+                traceEvent!(trace, path = path, error = %error, "identifier not readable");
+            }
+        }
+    }
+
+    return (None, denied);
+}
+
+/// Returns the machine ID, DMI product UUID and board serial of the system
+///
+/// DMI identifiers are only readable by root, when they are denied `restricted` is set
+/// and `hostKey()` falls back to the machine ID. Device tree serial numbers are used on boards without DMI
+pub fn machineIdentity() -> MachineIdentity {
+    linuxCheck();
+
+    let (machineId, _) = readIdentifier(&["/etc/machine-id", "/var/lib/dbus/machine-id"]);
+    let (productUuid, uuidDenied) = readIdentifier(&["/sys/devices/virtual/dmi/id/product_uuid"]);
+
+    let (boardSerial, serialDenied) = readIdentifier(&[
+        "/sys/devices/virtual/dmi/id/board_serial",
+        "/sys/devices/virtual/dmi/id/product_serial",
+        "/sys/firmware/devicetree/base/serial-number"
+    ]);
+
+    return MachineIdentity {
+        machineId: machineId,
+        productUuid: productUuid.map(|uuid| uuid.to_lowercase()),
+        boardSerial: boardSerial,
+        restricted: uuidDenied || serialDenied
+    };
+}
@@ -0,0 +1,176 @@
+use rsjson::{Json, Node, NodeContent};
+use crate::utils::Unit;
+
+/// Unit of every numeric node of `exportJson()`, by dotted path
+///
+/// `*` matches any single segment, a list index or a key such as an interface name.
+/// Units follow the historical names of the nodes, e.g. `cpu.frequency` is in kHz while `cpu.max-frequency` is in MHz
+pub const EXPORT_UNITS: &[(&str, Unit)] = &[
+    ("cpu.cores", Unit::Count),
+    ("cpu.threads", Unit::Count),
+    ("cpu.dies", Unit::Count),
+    ("cpu.performance-cores", Unit::Count),
+    ("cpu.efficiency-cores", Unit::Count),
+    ("cpu.max-frequency", Unit::Megahertz),
+    ("cpu.frequency", Unit::Kilohertz),
+    ("cpu.per-processor-frequency.*", Unit::Kilohertz),
+    ("cpu.usage.*", Unit::Percent),
+    ("cpu.per-processor-usage.*.*", Unit::Percent),
+    ("cpu.scheduler-policies.*.minimum-scaling-mhz", Unit::Megahertz),
+    ("cpu.scheduler-policies.*.maximum-scaling-mhz", Unit::Megahertz),
+    ("load.*", Unit::Unitless),
+    ("ram.usage", Unit::Percent),
+    ("ram.size-gib", Unit::Gibibytes),
+    ("ram.frequency", Unit::Megahertz),
+    ("ram.width", Unit::Bits),
+    // single GPU nodes, only exported with the `legacy-gpu-export` feature
+    ("vram.usage", Unit::Percent),
+    ("vram.size-gib", Unit::Gibibytes),
+    ("vram.frequency", Unit::Megahertz),
+    ("vram.bus-width", Unit::Bits),
+    ("gpu-metrics.temperature-edge", Unit::Celsius),
+    ("gpu-metrics.temperature-hotspot", Unit::Celsius),
+    ("gpu-metrics.temperature-mem", Unit::Celsius),
+    ("gpu-metrics.temperature-vrgfx", Unit::Celsius),
+    ("gpu-metrics.temperature-vrsoc", Unit::Celsius),
+    ("gpu-metrics.temperature-vrmem", Unit::Celsius),
+    ("gpu-metrics.average-socket-power", Unit::Watts),
+    ("gpu-metrics.average-multimedia-activity", Unit::Percent),
+    ("gpu-metrics.average-gfxclk-frequency", Unit::Megahertz),
+    ("gpu-metrics.average-sockclk-frequency", Unit::Megahertz),
+    ("gpu-metrics.average-uclk-frequency", Unit::Megahertz),
+    ("gpu-metrics.current-gfxclk", Unit::Megahertz),
+    ("gpu-metrics.current-sockclk", Unit::Megahertz),
+    ("gpu-metrics.current-fan-speed", Unit::Rpm),
+    // raw driver values, see `GpuMetrics`
+    ("gpu-metrics.throttle-status", Unit::Unitless),
+    ("gpu-metrics.pcie-link-width", Unit::Unitless),
+    ("gpu-metrics.pcie-link-speed", Unit::Unitless),
+    ("gpus.*.usage", Unit::Percent),
+    ("gpus.*.vram.usage", Unit::Percent),
+    ("gpus.*.vram.size-gib", Unit::Gibibytes),
+    ("gpus.*.vram.frequency", Unit::Megahertz),
+    ("gpus.*.vram.bus-width", Unit::Bits),
+    ("gpus.*.metrics.temperature-edge", Unit::Celsius),
+    ("gpus.*.metrics.temperature-hotspot", Unit::Celsius),
+    ("gpus.*.metrics.temperature-mem", Unit::Celsius),
+    ("gpus.*.metrics.temperature-vrgfx", Unit::Celsius),
+    ("gpus.*.metrics.temperature-vrsoc", Unit::Celsius),
+    ("gpus.*.metrics.temperature-vrmem", Unit::Celsius),
+    ("gpus.*.metrics.average-socket-power", Unit::Watts),
+    ("gpus.*.metrics.average-multimedia-activity", Unit::Percent),
+    ("gpus.*.metrics.average-gfxclk-frequency", Unit::Megahertz),
+    ("gpus.*.metrics.average-sockclk-frequency", Unit::Megahertz),
+    ("gpus.*.metrics.average-uclk-frequency", Unit::Megahertz),
+    ("gpus.*.metrics.current-gfxclk", Unit::Megahertz),
+    ("gpus.*.metrics.current-sockclk", Unit::Megahertz),
+    ("gpus.*.metrics.current-fan-speed", Unit::Rpm),
+    // raw driver values, see `GpuMetrics`
+    ("gpus.*.metrics.throttle-status", Unit::Unitless),
+    ("gpus.*.metrics.pcie-link-width", Unit::Unitless),
+    ("gpus.*.metrics.pcie-link-speed", Unit::Unitless),
+    ("nvme-devices.*.link-speed-gts", Unit::GigatransfersPerSecond),
+    ("nvme-devices.*.pcie-lanes", Unit::Count),
+    ("nvme-devices.*.size", Unit::Bytes),
+    ("nvme-devices.*.partitions.*.size", Unit::Bytes),
+    ("nvme-devices.*.partitions.*.start-point", Unit::Sectors),
+    ("storage-devices.*.size", Unit::Bytes),
+    ("storage-devices.*.partitions.*.size", Unit::Bytes),
+    ("storage-devices.*.partitions.*.start-point", Unit::Sectors),
+    ("battery.capacity", Unit::Percent),
+    ("backlight.brightness", Unit::Unitless),
+    ("backlight.max-brightness", Unit::Unitless),
+    ("temperature-sensors.*.temperature", Unit::Celsius),
+    ("sensors.chips.*.temperatures.*.temperature", Unit::Celsius),
+    ("sensors.thermal-zones.*.temperature", Unit::Celsius),
+    ("sensors.cooling-devices.*.current-state", Unit::Unitless),
+    ("sensors.cooling-devices.*.max-state", Unit::Unitless),
+    ("sensors.cooling-devices.*.usage", Unit::Percent),
+    ("sensors.backlights.*.brightness", Unit::Unitless),
+    ("sensors.backlights.*.max-brightness", Unit::Unitless),
+    ("sensors.backlights.*.percentage", Unit::Percent),
+    ("sensors.battery.capacity", Unit::Percent),
+    ("network.rate.download", Unit::BytesPerSecond),
+    ("network.rate.upload", Unit::BytesPerSecond),
+    ("network.routes.*.local-port", Unit::Unitless),
+    ("network.routes.*.remote-port", Unit::Unitless),
+    ("network.routes.*.tx-queue", Unit::Bytes),
+    ("network.routes.*.rx-queue", Unit::Bytes),
+    ("network.routes.*.retransmits", Unit::Count),
+    ("network.interfaces.*.statistics.rx-bytes", Unit::Bytes),
+    ("network.interfaces.*.statistics.tx-bytes", Unit::Bytes),
+    ("network.interfaces.*.statistics.rx-packets", Unit::Count),
+    ("network.interfaces.*.statistics.tx-packets", Unit::Count),
+    ("network.interfaces.*.statistics.rx-errors", Unit::Count),
+    ("network.interfaces.*.statistics.tx-errors", Unit::Count),
+    ("network.interfaces.*.statistics.rx-dropped", Unit::Count),
+    ("network.interfaces.*.statistics.tx-dropped", Unit::Count),
+    // identifiers and bitmasks of the input devices
+    ("bus-input.*.bus", Unit::Unitless),
+    ("bus-input.*.vendor", Unit::Unitless),
+    ("bus-input.*.product", Unit::Unitless),
+    ("bus-input.*.version", Unit::Unitless),
+    ("bus-input.*.properties", Unit::Unitless),
+    ("bus-input.*.events", Unit::Unitless),
+    ("bus-input.*.miscellaneous-events", Unit::Unitless),
+    ("bus-input.*.led", Unit::Unitless)
+];
+
+fn matchesPattern(pattern: &str, path: &str) -> bool {
+    let mut patternSegments = pattern.split('.');
+    let mut pathSegments = path.split('.');
+
+    loop {
+        match (patternSegments.next(), pathSegments.next()) {
+            (None, None) => return true,
+            (Some(expected), Some(segment)) if expected == "*" || expected == segment => {},
+            _ => return false
+        }
+    }
+}
+
+/// Returns the unit of the numeric node at the dotted `path` of `exportJson()`, `None` for non numeric or unknown nodes
+/// ## Example
+/// ```rust
+/// use sysutil::Unit;
+/// use sysutil::units::unitOf;
+///
+/// assert_eq!(unitOf("network.rate.download"), Some(Unit::BytesPerSecond));
+/// assert_eq!(unitOf("sensors.chips.0.temperatures.2.temperature"), Some(Unit::Celsius));
+/// ```
+pub fn unitOf(path: &str) -> Option<Unit> {
+    return EXPORT_UNITS.iter().find(|(pattern, _)| matchesPattern(pattern, path)).map(|(_, unit)| *unit);
+}
+
+/// Returns `EXPORT_UNITS` as a JSON object of unit symbols by path pattern, e.g. `{"cpu.usage.*": "%", ...}`
+///
+/// Unitless values have an empty symbol, `SnapshotBuilder::withUnits()` adds this object to the export as `units`
+pub fn unitsJson() -> Json {
+    let mut json = Json::new();
+
+    for (pattern, unit) in EXPORT_UNITS {
+        json.addNode(Node::new(*pattern, NodeContent::String(unit.toString())));
+    }
+
+    return json;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matchesUnitPatterns() {
+        assert_eq!(unitOf("cpu.per-processor-usage.3.iowait"), Some(Unit::Percent));
+        assert_eq!(unitOf("cpu.frequency"), Some(Unit::Kilohertz));
+        assert_eq!(unitOf("network.interfaces.wlan0.statistics.rx-bytes"), Some(Unit::Bytes));
+        assert_eq!(unitOf("network.interfaces.wlan0.mac"), None);
+        assert_eq!(unitOf("cpu.usage"), None);
+        assert_eq!(unitOf("bus-input.0.led"), Some(Unit::Unitless));
+        assert_eq!(unitOf("bus-input.0.name"), None);
+
+        assert_eq!(Unit::Celsius.format(41.26, 1), "41.3 °C");
+        assert_eq!(Unit::Percent.format(12.0, 0), "12%");
+        assert_eq!(Unit::fromString(&Unit::GigatransfersPerSecond.toString()), Unit::GigatransfersPerSecond);
+    }
+}
@@ -1,8 +1,14 @@
-use std::path;
+use std::{fmt, io, ops, path};
+use std::cell::RefCell;
 use std::fs;
 use std::io::Read;
+use std::os::unix::fs::OpenOptionsExt;
+use std::str::FromStr;
+use std::sync::{OnceLock, RwLock};
 
 /// Byte measure unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ByteUnit {
     B, KB, MB, GB,
     KiB, MiB, GiB
@@ -20,26 +26,350 @@ impl ByteUnit {
             ByteUnit::GiB => "GiB".to_string(),
         }
     }
+
+    /// Returns the amount of bytes represented by one unit
+    pub fn bytes(&self) -> u64 {
+        match self {
+            ByteUnit::B => 1,
+            ByteUnit::KB => 1000,
+            ByteUnit::MB => 1000_u64.pow(2),
+            ByteUnit::GB => 1000_u64.pow(3),
+            ByteUnit::KiB => 1024,
+            ByteUnit::MiB => 1024_u64.pow(2),
+            ByteUnit::GiB => 1024_u64.pow(3),
+        }
+    }
 }
 
-/// Data structure implementing conversion for the various measure units
+impl fmt::Display for ByteUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+impl FromStr for ByteUnit {
+    type Err = ParseByteSizeError;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        match unit.trim().to_lowercase().as_str() {
+            "" | "b" => Ok(ByteUnit::B),
+            "kb" | "k" => Ok(ByteUnit::KB),
+            "mb" | "m" => Ok(ByteUnit::MB),
+            "gb" | "g" => Ok(ByteUnit::GB),
+            "kib" => Ok(ByteUnit::KiB),
+            "mib" => Ok(ByteUnit::MiB),
+            "gib" => Ok(ByteUnit::GiB),
+            _ => Err(ParseByteSizeError::new(unit))
+        }
+    }
+}
+
+/// Error returned when parsing a `ByteSize` or a `ByteUnit` from a string fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseByteSizeError {
+    input: String
+}
+
+impl ParseByteSizeError {
+    fn new(input: &str) -> Self {
+        Self {
+            input: input.to_string()
+        }
+    }
+}
+
+impl fmt::Display for ParseByteSizeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid byte size: \"{}\"", self.input)
+    }
+}
+
+impl std::error::Error for ParseByteSizeError {}
+
+/// Stable, machine readable kind of error, as found in the `code` of the section errors of `exportJson()`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorCode {
+    /// The hardware the section describes is not present, e.g. no battery on a desktop
+    NotPresent,
+    NotFound,
+    PermissionDenied,
+    Io,
+    Parse,
+    /// The data exists but its format is not supported
+    Unsupported,
+    /// A code this version does not know, e.g. read back from the export of a newer version
+    Unknown(String)
+}
+
+impl ErrorCode {
+    pub fn fromString(code: &str) -> ErrorCode {
+        match code {
+            "NotPresent" => ErrorCode::NotPresent,
+            "NotFound" => ErrorCode::NotFound,
+            "PermissionDenied" => ErrorCode::PermissionDenied,
+            "Io" => ErrorCode::Io,
+            "Parse" => ErrorCode::Parse,
+            "Unsupported" => ErrorCode::Unsupported,
+            _ => ErrorCode::Unknown(code.to_string())
+        }
+    }
+
+    pub fn toString(&self) -> String {
+        match self {
+            ErrorCode::NotPresent => String::from("NotPresent"),
+            ErrorCode::NotFound => String::from("NotFound"),
+            ErrorCode::PermissionDenied => String::from("PermissionDenied"),
+            ErrorCode::Io => String::from("Io"),
+            ErrorCode::Parse => String::from("Parse"),
+            ErrorCode::Unsupported => String::from("Unsupported"),
+            ErrorCode::Unknown(code) => code.clone()
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Error returned by the `try*` functions when system information can not be read
 #[derive(Debug)]
+pub enum SysError {
+    /// The file or directory the information comes from does not exist
+    NotFound(path::PathBuf),
+    PermissionDenied(path::PathBuf),
+    /// Any other I/O error while reading the path
+    Io(path::PathBuf, io::Error),
+    /// The content of the path could not be parsed
+    Parse {
+        path: path::PathBuf,
+        content: String
+    }
+}
+
+impl SysError {
+    pub(crate) fn fromIo(path: &path::Path, error: io::Error) -> SysError {
+        match error.kind() {
+            io::ErrorKind::NotFound => SysError::NotFound(path.to_path_buf()),
+            io::ErrorKind::PermissionDenied => SysError::PermissionDenied(path.to_path_buf()),
+            _ => SysError::Io(path.to_path_buf(), error)
+        }
+    }
+
+    pub(crate) fn parse<T: AsRef<path::Path>>(path: T, content: &str) -> SysError {
+        return SysError::Parse {
+            path: path.as_ref().to_path_buf(),
+            content: content.to_string()
+        };
+    }
+
+    /// Returns the stable code of the error kind
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SysError::NotFound(_) => ErrorCode::NotFound,
+            SysError::PermissionDenied(_) => ErrorCode::PermissionDenied,
+            SysError::Io(_, _) => ErrorCode::Io,
+            SysError::Parse { .. } => ErrorCode::Parse
+        }
+    }
+
+    /// Returns the path the information was read from
+    pub fn path(&self) -> &path::Path {
+        match self {
+            SysError::NotFound(path) | SysError::PermissionDenied(path) | SysError::Io(path, _) => path,
+            SysError::Parse { path, .. } => path
+        }
+    }
+}
+
+impl fmt::Display for SysError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SysError::NotFound(path) => write!(f, "{} not found", path.display()),
+            SysError::PermissionDenied(path) => write!(f, "permission denied reading {}", path.display()),
+            SysError::Io(path, error) => write!(f, "failed to read {}: {}", path.display(), error),
+            SysError::Parse { path, content } => write!(f, "unexpected content in {}: \"{}\"", path.display(), content)
+        }
+    }
+}
+
+impl std::error::Error for SysError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SysError::Io(_, error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+static INSTALLED_ROOT: RwLock<Option<path::PathBuf>> = RwLock::new(None);
+static ENVIRONMENT_ROOT: OnceLock<Option<path::PathBuf>> = OnceLock::new();
+
+thread_local! {
+    static SCOPED_ROOT: RefCell<Option<path::PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Root directory `/sys`, `/proc` and the other system files are read from, `/` by default
+///
+/// Pointing it at a captured filesystem tree allows testing against fixture data. The root in effect is, in order,
+/// the one of an enclosing `scope()`, the `install()`ed one, the `SYSUTIL_ROOT` environment variable and `/`.
+/// Every absolute path the crate reads is moved below the root, including the ones passed to `readFile()`, unless it is
+/// already below it; relative paths and files the crate writes (e.g. `appendCsv()`) are used as they are, and values
+/// which do not come from files (sockets, ioctls, external commands) are still read from the running system
+/// ## Example
+/// ```rust,no_run
+/// use sysutil::SysPaths;
+///
+/// let load = SysPaths::new("fixtures/laptop").scope(|| sysutil::cpu::getLoad());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct SysPaths {
+    root: path::PathBuf
+}
+
+impl SysPaths {
+    /// Reads system files below `root`, e.g. `root/proc/stat` instead of `/proc/stat`
+    pub fn new<T: AsRef<path::Path>>(root: T) -> SysPaths {
+        return SysPaths {
+            root: root.as_ref().to_path_buf()
+        };
+    }
+
+    /// Returns the root in effect on the current thread
+    pub fn current() -> SysPaths {
+        if let Some(root) = SCOPED_ROOT.with(|scoped| scoped.borrow().clone()) {
+            return SysPaths::new(root);
+        }
+
+        if let Some(root) = INSTALLED_ROOT.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone() {
+            return SysPaths::new(root);
+        }
+
+        let environment = ENVIRONMENT_ROOT.get_or_init(|| {
+            std::env::var_os("SYSUTIL_ROOT").filter(|root| !root.is_empty()).map(path::PathBuf::from)
+        });
+
+        return SysPaths::new(environment.clone().unwrap_or_else(|| path::PathBuf::from("/")));
+    }
+
+    pub fn root(&self) -> &path::Path {
+        return &self.root;
+    }
+
+    /// Moves an absolute path below the root, relative paths and paths already below the root are returned unchanged
+    pub fn resolve<T: AsRef<path::Path>>(&self, filePath: T) -> path::PathBuf {
+        let filePath = filePath.as_ref();
+
+        if self.root == path::Path::new("/") || !filePath.is_absolute() || filePath.starts_with(&self.root) {
+            return filePath.to_path_buf();
+        }
+
+        return self.root.join(filePath.strip_prefix("/").unwrap_or(filePath));
+    }
+
+    /// Makes every thread read system files below this root, until `reset()` is called
+    pub fn install(self) {
+        *INSTALLED_ROOT.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(self.root);
+    }
+
+    /// Removes the `install()`ed root, going back to `SYSUTIL_ROOT` or `/`
+    pub fn reset() {
+        *INSTALLED_ROOT.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+
+    /// Runs `function` reading system files below this root, on the current thread only
+    ///
+    /// Threads spawned by `function` (e.g. the sampler of a `SharedMonitor`) are not affected, use `install()` for them
+    pub fn scope<T, F: FnOnce() -> T>(&self, function: F) -> T {
+        let previous = SCOPED_ROOT.with(|scoped| scoped.replace(Some(self.root.clone())));
+        let _restore = ScopeRestore { previous: previous };
+
+        return function();
+    }
+}
+
+// puts back the enclosing scope's root, even if the scoped function panics
+struct ScopeRestore {
+    previous: Option<path::PathBuf>
+}
+
+impl Drop for ScopeRestore {
+    fn drop(&mut self) {
+        SCOPED_ROOT.with(|scoped| *scoped.borrow_mut() = self.previous.take());
+    }
+}
+
+/// Resolves a system path hard-coded by the crate against the root of `SysPaths`
+pub(crate) fn sysPath<T: AsRef<path::Path>>(filePath: T) -> path::PathBuf {
+    return SysPaths::current().resolve(filePath);
+}
+
+/// Reads a file like `readFile()`, but reports why it could not be read
+pub(crate) fn tryReadFile<T: AsRef<path::Path>>(filePath: T) -> Result<String, SysError> {
+    let filePath = sysPath(filePath);
+    let filePath = filePath.as_path();
+
+    return fs::read_to_string(filePath)
+        .map(|content| content.trim().to_string())
+        .map_err(|error| SysError::fromIo(filePath, error));
+}
+
+/// Lists a directory, skipping entries which vanish while iterating
+pub(crate) fn tryReadDir<T: AsRef<path::Path>>(directory: T) -> Result<Vec<fs::DirEntry>, SysError> {
+    let directory = sysPath(directory);
+    let directory = directory.as_path();
+
+    return fs::read_dir(directory)
+        .map(|entries| entries.flatten().collect())
+        .map_err(|error| SysError::fromIo(directory, error));
+}
+
+/// Parses a value read from `filePath`, reporting the offending content on failure
+pub(crate) fn tryParse<T: FromStr, P: AsRef<path::Path>>(filePath: P, content: &str) -> Result<T, SysError> {
+    return content.trim().parse::<T>().map_err(|_| SysError::parse(filePath, content));
+}
+
+/// Data structure implementing conversion for the various measure units
+///
+/// `ByteSize` supports arithmetic, ordering and parsing from strings
+/// ```rust
+/// use sysutil::ByteSize;
+///
+/// let size = "512 MiB".parse::<ByteSize>().unwrap() + ByteSize::fromBytes(1024);
+/// println!("{}", size); // 512.00 MiB
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
 pub struct ByteSize {
-    bytes: usize
+    bytes: u64
 }
 
 impl ByteSize {
-    pub fn fromBytes(bytes: usize) -> Self {
+    pub fn fromBytes(bytes: u64) -> Self {
         Self {
             bytes: bytes
         }
     }
 
-    pub fn b(&self) -> usize {
+    /// Returns the size in bytes
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Returns the size in bits
+    pub fn bits(&self) -> u64 {
         self.bytes * 8
     }
 
-    pub fn B(&self) -> usize {
+    #[deprecated(note = "returns bits, use `bits()` or `bytes()` instead")]
+    pub fn b(&self) -> u64 {
+        self.bits()
+    }
+
+    #[deprecated(note = "use `bytes()` instead")]
+    pub fn B(&self) -> u64 {
         self.bytes
     }
 
@@ -68,8 +398,8 @@ impl ByteSize {
     }
 
     pub fn fitBase1024(&self) -> (f32, ByteUnit) {
-        if self.B() < 1024 {
-            (self.B() as f32, ByteUnit::B)
+        if self.bytes < 1024 {
+            (self.bytes as f32, ByteUnit::B)
 
         } else if self.KiB() < 1024_f32 {
             (self.KiB(), ByteUnit::KiB)
@@ -83,8 +413,8 @@ impl ByteSize {
     }
 
     pub fn fitBase1000(&self) -> (f32, ByteUnit) {
-        if self.B() < 1000 {
-            (self.B() as f32, ByteUnit::B)
+        if self.bytes < 1000 {
+            (self.bytes as f32, ByteUnit::B)
 
         } else if self.KB() < 1000_f32 {
             (self.KB(), ByteUnit::KB)
@@ -98,25 +428,353 @@ impl ByteSize {
     }
 }
 
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (value, unit) = self.fitBase1024();
+
+        match unit {
+            ByteUnit::B => write!(f, "{} {}", value, unit),
+            _ => write!(f, "{:.2} {}", value, unit)
+        }
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ParseByteSizeError;
+
+    /// Parses strings like `"512 MiB"`, `"2GB"` or `"1024"` (bytes)
+    fn from_str(size: &str) -> Result<Self, Self::Err> {
+        let trimmed = size.trim();
+        let split = trimmed.find(|chr: char| !chr.is_ascii_digit() && chr != '.').unwrap_or(trimmed.len());
+
+        let (value, unit) = trimmed.split_at(split);
+        let value = value.parse::<f64>().map_err(|_| ParseByteSizeError::new(size))?;
+        let unit = unit.parse::<ByteUnit>().map_err(|_| ParseByteSizeError::new(size))?;
+
+        Ok(ByteSize::fromBytes((value * unit.bytes() as f64).round() as u64))
+    }
+}
+
+/// Addition saturates at `u64::MAX` bytes
+impl ops::Add for ByteSize {
+    type Output = ByteSize;
+
+    fn add(self, other: ByteSize) -> ByteSize {
+        ByteSize::fromBytes(self.bytes.saturating_add(other.bytes))
+    }
+}
+
+impl ops::AddAssign for ByteSize {
+    fn add_assign(&mut self, other: ByteSize) {
+        self.bytes = self.bytes.saturating_add(other.bytes);
+    }
+}
+
+/// Subtraction saturates at zero bytes
+impl ops::Sub for ByteSize {
+    type Output = ByteSize;
+
+    fn sub(self, other: ByteSize) -> ByteSize {
+        ByteSize::fromBytes(self.bytes.saturating_sub(other.bytes))
+    }
+}
+
+impl ops::SubAssign for ByteSize {
+    fn sub_assign(&mut self, other: ByteSize) {
+        self.bytes = self.bytes.saturating_sub(other.bytes);
+    }
+}
+
+impl std::iter::Sum for ByteSize {
+    fn sum<I: Iterator<Item = ByteSize>>(iter: I) -> ByteSize {
+        iter.fold(ByteSize::default(), |total, size| total + size)
+    }
+}
+
+/// Frequency data structure implements direct conversion for frequencies
+/// in various size orders, it is shared by CPU, RAM and GPU related data structures
+/// ```rust
+/// use sysutil::cpu::cpuFrequency;
+/// let frequency = cpuFrequency().average;
+///
+/// frequency.khz(); // returns the frequency in Kilo Hertz
+/// frequency.mhz(); // returns the frequency in Mega Hertz
+/// frequency.ghz(); // return the frequency in Giga Hertz
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Frequency {
+    pub khz: usize
+}
+
+impl Frequency {
+    pub fn fromKhz(khz: usize) -> Frequency {
+        Frequency {
+            khz: khz
+        }
+    }
+
+    pub fn fromMhz(mhz: usize) -> Frequency {
+        Frequency {
+            khz: mhz * 1000
+        }
+    }
+
+    pub fn khz(&self) -> f32 {
+        return self.khz as f32;
+    }
+
+    pub fn mhz(&self) -> f32 {
+        return self.khz as f32 / 1000_f32;
+    }
+
+    pub fn ghz(&self) -> f32 {
+        return self.khz as f32 / 1000_000_f32;
+    }
+
+    /// Returns the frequency in the unit `Display` uses, GHz from 1 GHz, MHz from 1 MHz, kHz otherwise
+    pub fn withUnit(&self) -> (f32, Unit) {
+        if self.khz >= 1_000_000 {
+            return (self.ghz(), Unit::Gigahertz);
+
+        } else if self.khz >= 1000 {
+            return (self.mhz(), Unit::Megahertz);
+        }
+
+        return (self.khz(), Unit::Kilohertz);
+    }
+}
+
+impl fmt::Display for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.khz >= 1000_000 {
+            write!(f, "{:.2} GHz", self.ghz())
+
+        } else if self.khz >= 1000 {
+            write!(f, "{:.0} MHz", self.mhz())
+
+        } else {
+            write!(f, "{} kHz", self.khz)
+        }
+    }
+}
+
+/// Measure unit of a numeric value, as returned by the `withUnit()` helpers and listed by `units::unitOf()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Unit {
+    Percent,
+    Celsius,
+    Kilohertz,
+    Megahertz,
+    Gigahertz,
+    Bytes,
+    /// Binary gigabytes, 1024^3 bytes
+    Gibibytes,
+    BytesPerSecond,
+    GigatransfersPerSecond,
+    Bits,
+    /// Blocks of 512 bytes, whatever the logical block size of the device
+    Sectors,
+    Watts,
+    Rpm,
+    /// Number of items, e.g. cores or packets
+    Count,
+    /// Ratios, identifiers, raw driver values and other numbers without a unit
+    Unitless
+}
+
+impl Unit {
+    pub fn fromString(unit: &str) -> Unit {
+        match unit {
+            "%" => Unit::Percent,
+            "°C" => Unit::Celsius,
+            "kHz" => Unit::Kilohertz,
+            "MHz" => Unit::Megahertz,
+            "GHz" => Unit::Gigahertz,
+            "B" => Unit::Bytes,
+            "GiB" => Unit::Gibibytes,
+            "B/s" => Unit::BytesPerSecond,
+            "GT/s" => Unit::GigatransfersPerSecond,
+            "bit" => Unit::Bits,
+            "sector" => Unit::Sectors,
+            "W" => Unit::Watts,
+            "RPM" => Unit::Rpm,
+            "count" => Unit::Count,
+            _ => Unit::Unitless
+        }
+    }
+
+    /// Returns the symbol of the unit, empty for `Unitless`
+    pub fn toString(&self) -> String {
+        match self {
+            Unit::Percent => String::from("%"),
+            Unit::Celsius => String::from("°C"),
+            Unit::Kilohertz => String::from("kHz"),
+            Unit::Megahertz => String::from("MHz"),
+            Unit::Gigahertz => String::from("GHz"),
+            Unit::Bytes => String::from("B"),
+            Unit::Gibibytes => String::from("GiB"),
+            Unit::BytesPerSecond => String::from("B/s"),
+            Unit::GigatransfersPerSecond => String::from("GT/s"),
+            Unit::Bits => String::from("bit"),
+            Unit::Sectors => String::from("sector"),
+            Unit::Watts => String::from("W"),
+            Unit::Rpm => String::from("RPM"),
+            Unit::Count => String::from("count"),
+            Unit::Unitless => String::new()
+        }
+    }
+
+    /// Formats `value` with `decimals` digits followed by the symbol, e.g. `42.5 °C`
+    ///
+    /// The decimal separator is always `.`, whatever the locale, so the output can be parsed back by any consumer
+    pub fn format(&self, value: f32, decimals: usize) -> String {
+        return match self {
+            Unit::Unitless | Unit::Count => format!("{:.*}", decimals, value),
+            Unit::Percent => format!("{:.*}%", decimals, value),
+            _ => format!("{:.*} {}", decimals, value, self.toString())
+        };
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.toString())
+    }
+}
+
+/// Contains current and maximum link parameters of a PCIe device
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PcieLink {
+    pub currentSpeedGTs: Option<f32>,
+    pub maximumSpeedGTs: Option<f32>,
+    pub currentWidth: Option<usize>,
+    pub maximumWidth: Option<usize>
+}
+
+impl PcieLink {
+    /// Checks whether the link trained below its maximum speed or width
+    ///
+    /// Note that many devices lower their link speed when idle to save power
+    pub fn isDegraded(&self) -> bool {
+        let slower = matches!((self.currentSpeedGTs, self.maximumSpeedGTs), (Some(current), Some(maximum)) if current < maximum);
+        let narrower = matches!((self.currentWidth, self.maximumWidth), (Some(current), Some(maximum)) if current < maximum);
+
+        return slower || narrower;
+    }
+}
+
+impl fmt::Display for PcieLink {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let speed = |speed: Option<f32>| speed.map(|speed| format!("{} GT/s", speed)).unwrap_or(String::from("unknown"));
+        let width = |width: Option<usize>| width.map(|width| format!("x{}", width)).unwrap_or(String::from("x?"));
+
+        write!(
+            f, "{} {} (maximum {} {})",
+            speed(self.currentSpeedGTs), width(self.currentWidth),
+            speed(self.maximumSpeedGTs), width(self.maximumWidth)
+        )
+    }
+}
+
+/// Reads the link status of the PCI device at the specified sysfs path, `None` for conventional PCI devices
+pub(crate) fn readPcieLink(device: &path::Path) -> Option<PcieLink> {
+    let speed = |name: &str| -> Option<f32> {
+        readFile(device.join(name)).split(' ').next()?.parse::<f32>().ok()
+    };
+
+    let currentWidth = readFile(device.join("current_link_width")).parse::<usize>().ok();
+    let maximumWidth = readFile(device.join("max_link_width")).parse::<usize>().ok();
+    let currentSpeed = speed("current_link_speed");
+    let maximumSpeed = speed("max_link_speed");
+
+    return match (currentSpeed, maximumSpeed, currentWidth, maximumWidth) {
+        (None, None, None, None) => None,
+        _ => Some(PcieLink {
+            currentSpeedGTs: currentSpeed,
+            maximumSpeedGTs: maximumSpeed,
+            currentWidth: currentWidth,
+            maximumWidth: maximumWidth
+        })
+    };
+}
+
 pub fn linuxCheck() {
     if !path::Path::new("/sys").exists() || !path::Path::new("/proc").exists() {
         panic!("Detected non-Linux system");
     }
 }
 
+/// Emits a `tracing` event when the `tracing` feature is enabled, expands to nothing otherwise
+#[allow(unused_macros)]
+macro_rules! traceEvent {
+    ($level:ident, $($arg:tt)+) => {
+        #[cfg(feature = "tracing")]
+        tracing::$level!($($arg)+);
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use traceEvent;
+
+/// Enters a `tracing` span lasting until the end of the current block when the `tracing` feature is enabled
+#[allow(unused_macros)]
+macro_rules! traceSpan {
+    ($name:expr) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name).entered();
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use traceSpan;
+
 pub fn readFile<T>(filePath: T) -> String
 where T: AsRef<path::Path>, {
-    if let Ok(mut file) = fs::File::open(filePath) {
-        let mut buffer = String::new();
-
-        if file.read_to_string(&mut buffer).is_ok() {
-            return buffer.trim().to_string();
+    let filePath = sysPath(filePath);
+    let filePath = filePath.as_path();
+
+    match fs::File::open(filePath) {
+        Ok(mut file) => {
+            let mut buffer = String::new();
+
+            match file.read_to_string(&mut buffer) {
+                Ok(_) => return buffer.trim().to_string(),
+                Err(_error) => {
+                    traceEvent!(debug, path = %filePath.display(), error = %_error, "failed to read file");
+                }
+            }
+        },
+        Err(_error) => {
+            traceEvent!(debug, path = %filePath.display(), error = %_error, "failed to open file");
         }
     }
 
     return String::new();
 }
 
+/// Reads every record currently in the kernel log ring buffer through `/dev/kmsg`,
+/// each one formatted as `<level>,<sequence>,<microseconds>,<flags>;<message>`
+pub(crate) fn kmsgRecords() -> io::Result<Vec<String>> {
+    let mut kmsg = fs::OpenOptions::new().read(true).custom_flags(libc::O_NONBLOCK).open(sysPath("/dev/kmsg"))?;
+    let mut records = Vec::<String>::new();
+    let mut buffer = vec![0_u8; 8192];
+
+    // every read returns a single record, until the end of the buffer is reached
+    loop {
+        match kmsg.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(size) => records.push(String::from_utf8_lossy(&buffer[..size]).to_string()),
+            // the record was overwritten while reading, skip it
+            Err(error) if error.raw_os_error() == Some(libc::EPIPE) => continue,
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => break,
+            Err(error) => return Err(error)
+        }
+    }
+
+    return Ok(records);
+}
+
 pub fn bytesToU16(bytes: Vec<u8>) -> u16 {
     let first = bytes[1];
     let second = bytes[0];
@@ -152,3 +810,34 @@ pub fn bytesToU64(bytes: Vec<u8>) -> u64 {
 
     return res;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn readsBelowSysPathsRoot() {
+        let root = SysPaths::new(path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/root/desktop"));
+        let (loadavg, ramUsage) = root.scope(|| (readFile("/proc/loadavg"), crate::ram::ramUsage()));
+
+        assert!(loadavg.starts_with("1.25 0.80 0.42"));
+        assert_eq!(ramUsage, 25_f32);
+
+        // paths read from directory listings are already below the root
+        let stat = root.root().join("proc/stat");
+        assert_eq!(root.resolve(&stat), stat);
+        assert_eq!(root.resolve("/proc/stat"), stat);
+        assert_eq!(root.resolve("proc/stat"), path::PathBuf::from("proc/stat"));
+    }
+
+    #[test]
+    fn saturatesByteSizeArithmetic() {
+        let mut total = ByteSize::fromBytes(u64::MAX - 1) + ByteSize::fromBytes(2);
+        assert_eq!(total.bytes(), u64::MAX);
+
+        total += ByteSize::fromBytes(1);
+        assert_eq!(total.bytes(), u64::MAX);
+        assert_eq!([ByteSize::fromBytes(u64::MAX), ByteSize::fromBytes(1)].into_iter().sum::<ByteSize>().bytes(), u64::MAX);
+        assert_eq!((ByteSize::fromBytes(1) - ByteSize::fromBytes(2)).bytes(), 0);
+    }
+}